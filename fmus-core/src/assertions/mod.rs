@@ -0,0 +1,468 @@
+use std::collections::HashMap;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::http::{ByteRange, HttpResponse};
+use crate::jsonpath::{self, JsonPathOp};
+use crate::markup;
+use crate::schema::JsonSchema;
+
+// Jumlah karakter body yang ditulis sebagai nilai aktual assertion body
+const BODY_EXCERPT_CHARS: usize = 500;
+
+// Assertion deklaratif yang bisa disimpan bersama request di collection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Assertion {
+    StatusEquals { status: u16 },
+    // Rentang inklusif, misalnya 200..=299
+    StatusRange { min: u16, max: u16 },
+    StatusOneOf { statuses: Vec<u16> },
+    HeaderEquals { name: String, value: String },
+    HeaderMatches { name: String, pattern: String },
+    HeaderExists { name: String },
+    BodyContains { text: String },
+    BodyMatches { pattern: String },
+    JsonPath { path: String, check: JsonPathOp },
+    JsonSchema { schema: JsonSchema },
+    // XPath pada body XML, namespaces berisi prefix tambahan
+    #[serde(rename = "xpath")]
+    XPath {
+        expr: String,
+        #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+        namespaces: HashMap<String, String>,
+        check: JsonPathOp,
+    },
+    // CSS selector pada body HTML, mendukung akhiran "::text" dan "::attr(nama)"
+    Css { selector: String, check: JsonPathOp },
+    CssCount { selector: String, count: usize },
+    ResponseTimeBelow { ms: u64 },
+    // Ukuran body dalam byte, batas inklusif
+    BodySize {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        min: Option<usize>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        max: Option<usize>,
+    },
+    // 206 Partial Content untuk Range "bytes=start-end", Content-Range dan panjang body harus cocok
+    Range {
+        start: u64,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        end: Option<u64>,
+        // Ukuran resource lengkap yang diharapkan di Content-Range
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        total: Option<u64>,
+    },
+}
+
+// Hasil evaluasi satu assertion
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssertionResult {
+    pub assertion: Assertion,
+    pub passed: bool,
+    pub expected: Value,
+    pub actual: Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+// Laporan pass/fail untuk semua assertion sebuah request
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AssertionReport {
+    pub results: Vec<AssertionResult>,
+    pub passed: usize,
+    pub failed: usize,
+}
+
+impl AssertionReport {
+    pub fn all_passed(&self) -> bool {
+        self.failed == 0
+    }
+
+    pub fn failures(&self) -> impl Iterator<Item = &AssertionResult> {
+        self.results.iter().filter(|result| !result.passed)
+    }
+
+    pub fn push(&mut self, result: AssertionResult) {
+        if result.passed {
+            self.passed += 1;
+        } else {
+            self.failed += 1;
+        }
+        self.results.push(result);
+    }
+}
+
+// Mengevaluasi daftar assertion terhadap response
+pub fn evaluate(assertions: &[Assertion], response: &HttpResponse) -> AssertionReport {
+    let mut report = AssertionReport::default();
+    for assertion in assertions {
+        report.push(assertion.evaluate(response));
+    }
+    report
+}
+
+impl Assertion {
+    // Deskripsi singkat untuk ditampilkan di laporan
+    pub fn describe(&self) -> String {
+        match self {
+            Assertion::StatusEquals { status } => format!("status is {}", status),
+            Assertion::StatusRange { min, max } => format!("status is between {} and {}", min, max),
+            Assertion::StatusOneOf { statuses } => format!("status is one of {:?}", statuses),
+            Assertion::HeaderEquals { name, value } => format!("header '{}' is '{}'", name, value),
+            Assertion::HeaderMatches { name, pattern } => format!("header '{}' matches /{}/", name, pattern),
+            Assertion::HeaderExists { name } => format!("header '{}' exists", name),
+            Assertion::BodyContains { text } => format!("body contains '{}'", text),
+            Assertion::BodyMatches { pattern } => format!("body matches /{}/", pattern),
+            Assertion::JsonPath { path, check } => format!("{} {}", path, describe_op(check)),
+            Assertion::JsonSchema { .. } => "body matches JSON schema".to_string(),
+            Assertion::XPath { expr, check, .. } => format!("xpath {} {}", expr, describe_op(check)),
+            Assertion::Css { selector, check } => format!("css {} {}", selector, describe_op(check)),
+            Assertion::CssCount { selector, count } => format!("css {} matches {} element(s)", selector, count),
+            Assertion::ResponseTimeBelow { ms } => format!("response time is below {} ms", ms),
+            Assertion::BodySize { min, max } => match (min, max) {
+                (Some(min), Some(max)) => format!("body size between {} and {} bytes", min, max),
+                (Some(min), None) => format!("body size of at least {} bytes", min),
+                (None, Some(max)) => format!("body size of at most {} bytes", max),
+                (None, None) => "body size".to_string(),
+            },
+            Assertion::Range { start, end, total } => {
+                let range = ByteRange { start: *start, end: *end }.header_value();
+                match total {
+                    Some(total) => format!("partial content for {} of {} bytes", range, total),
+                    None => format!("partial content for {}", range),
+                }
+            },
+        }
+    }
+
+    // Mengevaluasi assertion dan mencatat nilai yang diharapkan serta nilai aktual
+    pub fn evaluate(&self, response: &HttpResponse) -> AssertionResult {
+        let (expected, actual, outcome) = match self {
+            Assertion::StatusEquals { status } => (
+                json!(status),
+                json!(response.status),
+                check(response.status == *status, || format!("Expected status {} but got {}", status, response.status)),
+            ),
+            Assertion::StatusRange { min, max } => (
+                json!({ "min": min, "max": max }),
+                json!(response.status),
+                check((*min..=*max).contains(&response.status), || {
+                    format!("Expected status between {} and {} but got {}", min, max, response.status)
+                }),
+            ),
+            Assertion::StatusOneOf { statuses } => (
+                json!(statuses),
+                json!(response.status),
+                check(statuses.contains(&response.status), || {
+                    format!("Expected status to be one of {:?} but got {}", statuses, response.status)
+                }),
+            ),
+            Assertion::HeaderEquals { name, value } => {
+                let actual = response.header(name);
+                (
+                    json!(value),
+                    json!(actual),
+                    match actual {
+                        Some(actual) if actual == value => Ok(()),
+                        Some(actual) => Err(format!("Expected header '{}' to be '{}' but got '{}'", name, value, actual)),
+                        None => Err(format!("Expected header '{}' not found in response", name)),
+                    },
+                )
+            },
+            Assertion::HeaderMatches { name, pattern } => {
+                let actual = response.header(name);
+                (
+                    json!(pattern),
+                    json!(actual),
+                    match actual {
+                        Some(actual) => matches(pattern, actual, || format!("Expected header '{}' to match /{}/ but got '{}'", name, pattern, actual)),
+                        None => Err(format!("Expected header '{}' not found in response", name)),
+                    },
+                )
+            },
+            Assertion::HeaderExists { name } => {
+                let actual = response.header(name);
+                (
+                    json!(true),
+                    json!(actual.is_some()),
+                    check(actual.is_some(), || format!("Expected header '{}' not found in response", name)),
+                )
+            },
+            Assertion::BodyContains { text } => match response.text() {
+                Ok(body) => (
+                    json!(text),
+                    body_excerpt(&body),
+                    check(body.contains(text.as_str()), || format!("Expected body to contain '{}'", text)),
+                ),
+                Err(e) => (json!(text), Value::Null, Err(e)),
+            },
+            Assertion::BodyMatches { pattern } => match response.text() {
+                Ok(body) => (
+                    json!(pattern),
+                    body_excerpt(&body),
+                    matches(pattern, &body, || format!("Expected body to match /{}/", pattern)),
+                ),
+                Err(e) => (json!(pattern), Value::Null, Err(e)),
+            },
+            Assertion::JsonPath { path, check: op } => {
+                let expected = serde_json::to_value(op).unwrap_or(Value::Null);
+                match response.json() {
+                    Ok(body) => match jsonpath::query(&body, path) {
+                        Ok(nodes) => (expected, jsonpath::actual_value(path, &nodes), op.check(path, &nodes)),
+                        Err(e) => (expected, Value::Null, Err(e)),
+                    },
+                    Err(e) => (expected, Value::Null, Err(e)),
+                }
+            },
+            Assertion::JsonSchema { schema } => {
+                match response.assert_json_schema(schema) {
+                    Ok(()) => (json!("valid"), json!([]), Ok(())),
+                    Err(violations) => {
+                        let message = violations
+                            .iter()
+                            .map(|v| format!("{} {}", v.instance_path, v.message).trim().to_string())
+                            .collect::<Vec<_>>()
+                            .join("; ");
+                        (json!("valid"), json!(violations), Err(format!("Response does not match schema: {}", message)))
+                    },
+                }
+            },
+            Assertion::XPath { expr, namespaces, check: op } => {
+                let expected = serde_json::to_value(op).unwrap_or(Value::Null);
+                match response.xpath_with_namespaces(expr, namespaces) {
+                    Ok(values) => (expected, markup::actual_value(&values), markup::check(expr, &values, op)),
+                    Err(e) => (expected, Value::Null, Err(e)),
+                }
+            },
+            Assertion::Css { selector, check: op } => {
+                let expected = serde_json::to_value(op).unwrap_or(Value::Null);
+                match response.select(selector) {
+                    Ok(values) => (expected, markup::actual_value(&values), markup::check(selector, &values, op)),
+                    Err(e) => (expected, Value::Null, Err(e)),
+                }
+            },
+            Assertion::CssCount { selector, count } => match response.select(selector) {
+                Ok(values) => (
+                    json!(count),
+                    json!(values.len()),
+                    check(values.len() == *count, || {
+                        format!("Expected {} element(s) matching '{}' but found {}", count, selector, values.len())
+                    }),
+                ),
+                Err(e) => (json!(count), Value::Null, Err(e)),
+            },
+            Assertion::ResponseTimeBelow { ms } => (
+                json!(ms),
+                json!(response.time as u64),
+                check(response.time < *ms as u128, || format!("Expected response time below {} ms but got {} ms", ms, response.time)),
+            ),
+            Assertion::BodySize { min, max } => {
+                let size = response.body.len();
+                let in_range = min.is_none_or(|min| size >= min) && max.is_none_or(|max| size <= max);
+                (
+                    json!({ "min": min, "max": max }),
+                    json!(size),
+                    check(in_range, || format!("Expected {} but got {} bytes", self.describe(), size)),
+                )
+            },
+            Assertion::Range { start, end, total } => {
+                let range = ByteRange { start: *start, end: *end };
+                let actual = json!({ "status": response.status, "content_range": response.header("content-range") });
+                (
+                    json!({ "status": 206, "range": range.header_value(), "total": total }),
+                    actual,
+                    response.assert_range(&range, *total).map(|_| ()),
+                )
+            },
+        };
+
+        AssertionResult {
+            assertion: self.clone(),
+            passed: outcome.is_ok(),
+            expected,
+            actual,
+            message: outcome.err(),
+        }
+    }
+}
+
+// Body sebagai nilai aktual, dipotong agar laporan tidak memuat body besar
+fn body_excerpt(body: &str) -> Value {
+    match body.char_indices().nth(BODY_EXCERPT_CHARS) {
+        Some((end, _)) => json!(format!("{}... ({} bytes)", &body[..end], body.len())),
+        None => json!(body),
+    }
+}
+
+fn check(passed: bool, message: impl FnOnce() -> String) -> Result<(), String> {
+    if passed {
+        Ok(())
+    } else {
+        Err(message())
+    }
+}
+
+fn matches(pattern: &str, text: &str, message: impl FnOnce() -> String) -> Result<(), String> {
+    let regex = Regex::new(pattern).map_err(|e| format!("Invalid regex '{}': {}", pattern, e))?;
+    check(regex.is_match(text), message)
+}
+
+fn describe_op(op: &JsonPathOp) -> String {
+    match op {
+        JsonPathOp::Equals(value) => format!("equals {}", value),
+        JsonPathOp::NotEquals(value) => format!("does not equal {}", value),
+        JsonPathOp::Exists => "exists".to_string(),
+        JsonPathOp::NotExists => "does not exist".to_string(),
+        JsonPathOp::Contains(value) => format!("contains {}", value),
+        JsonPathOp::Length(length) => format!("has length {}", length),
+        JsonPathOp::Regex(pattern) => format!("matches /{}/", pattern),
+        JsonPathOp::GreaterThan(limit) => format!("> {}", limit),
+        JsonPathOp::GreaterOrEqual(limit) => format!(">= {}", limit),
+        JsonPathOp::LessThan(limit) => format!("< {}", limit),
+        JsonPathOp::LessOrEqual(limit) => format!("<= {}", limit),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(body: &str) -> HttpResponse {
+        HttpResponse { status: 200, headers: HashMap::new(), body: body.as_bytes().to_vec(), time: 1 }
+    }
+
+    fn with_headers(mut response: HttpResponse, headers: &[(&str, &str)]) -> HttpResponse {
+        response.headers = headers.iter().map(|(name, value)| (name.to_string(), value.to_string())).collect();
+        response
+    }
+
+    fn passed(assertion: Assertion, response: &HttpResponse) -> bool {
+        assertion.evaluate(response).passed
+    }
+
+    #[test]
+    fn status_assertions() {
+        let mut response = response("");
+        response.status = 204;
+
+        assert!(passed(Assertion::StatusEquals { status: 204 }, &response));
+        assert!(passed(Assertion::StatusRange { min: 200, max: 204 }, &response));
+        assert!(!passed(Assertion::StatusRange { min: 200, max: 203 }, &response));
+        assert!(passed(Assertion::StatusOneOf { statuses: vec![200, 204] }, &response));
+
+        let result = Assertion::StatusEquals { status: 200 }.evaluate(&response);
+        assert_eq!((result.expected, result.actual), (json!(200), json!(204)));
+        assert_eq!(result.message.as_deref(), Some("Expected status 200 but got 204"));
+    }
+
+    #[test]
+    fn header_assertions_ignore_name_case() {
+        let response = with_headers(response(""), &[("Content-Type", "application/json; charset=utf-8")]);
+
+        assert!(passed(Assertion::HeaderExists { name: "content-type".to_string() }, &response));
+        assert!(!passed(Assertion::HeaderExists { name: "etag".to_string() }, &response));
+        assert!(passed(Assertion::HeaderMatches { name: "CONTENT-TYPE".to_string(), pattern: "^application/json".to_string() }, &response));
+
+        let result = Assertion::HeaderEquals { name: "content-type".to_string(), value: "text/html".to_string() }.evaluate(&response);
+        assert!(!result.passed);
+        assert_eq!(result.actual, json!("application/json; charset=utf-8"));
+
+        let result = Assertion::HeaderEquals { name: "etag".to_string(), value: "x".to_string() }.evaluate(&response);
+        assert_eq!(result.actual, Value::Null);
+        assert_eq!(result.message.as_deref(), Some("Expected header 'etag' not found in response"));
+
+        let result = Assertion::HeaderMatches { name: "content-type".to_string(), pattern: "(".to_string() }.evaluate(&response);
+        assert!(result.message.unwrap().starts_with("Invalid regex '('"));
+    }
+
+    #[test]
+    fn json_path_assertions_record_actual_values() {
+        let body = response(r#"{"items": [{"id": 7}], "total": 1}"#);
+
+        let result = Assertion::JsonPath { path: "$.items[*].id".to_string(), check: JsonPathOp::Equals(json!([7])) }.evaluate(&body);
+        assert!(result.passed);
+        assert_eq!(result.actual, json!([7]));
+        assert_eq!(result.expected, json!({"op": "equals", "value": [7]}));
+
+        let result = Assertion::JsonPath { path: "total".to_string(), check: JsonPathOp::GreaterThan(1.0) }.evaluate(&body);
+        assert!(!result.passed);
+        assert_eq!(result.actual, json!(1));
+
+        let result = Assertion::JsonPath { path: "$.missing".to_string(), check: JsonPathOp::Exists }.evaluate(&body);
+        assert_eq!((result.passed, result.actual), (false, Value::Null));
+
+        let result = Assertion::JsonPath { path: "$.total".to_string(), check: JsonPathOp::Exists }.evaluate(&response("not json"));
+        assert!(!result.passed);
+        assert!(result.message.is_some());
+    }
+
+    #[test]
+    fn time_and_size_assertions() {
+        let mut response = response("12345");
+        response.time = 250;
+
+        assert!(passed(Assertion::ResponseTimeBelow { ms: 251 }, &response));
+        assert!(!passed(Assertion::ResponseTimeBelow { ms: 250 }, &response));
+
+        assert!(passed(Assertion::BodySize { min: Some(5), max: Some(5) }, &response));
+        assert!(passed(Assertion::BodySize { min: None, max: None }, &response));
+        assert!(!passed(Assertion::BodySize { min: Some(6), max: None }, &response));
+
+        let result = Assertion::BodySize { min: None, max: Some(4) }.evaluate(&response);
+        assert_eq!(result.actual, json!(5));
+        assert_eq!(result.message.as_deref(), Some("Expected body size of at most 4 bytes but got 5 bytes"));
+    }
+
+    #[test]
+    fn range_assertions_check_status_and_content_range() {
+        let mut partial = with_headers(response("01234"), &[("Content-Range", "bytes 10-14/100")]);
+        partial.status = 206;
+        let range = Assertion::Range { start: 10, end: Some(14), total: Some(100) };
+
+        let result = range.evaluate(&partial);
+        assert!(result.passed, "{:?}", result.message);
+        assert_eq!(result.actual, json!({"status": 206, "content_range": "bytes 10-14/100"}));
+
+        assert!(!passed(Assertion::Range { start: 10, end: Some(14), total: Some(99) }, &partial));
+        assert!(!passed(Assertion::Range { start: 0, end: Some(4), total: None }, &partial));
+
+        let full = response("0123456789");
+        let result = range.evaluate(&full);
+        assert_eq!(result.message.as_deref(), Some("Expected status 206 for range bytes=10-14 but got 200"));
+    }
+
+    #[test]
+    fn report_counts_passes_and_failures() {
+        let assertions = [
+            Assertion::StatusEquals { status: 200 },
+            Assertion::StatusEquals { status: 201 },
+            Assertion::BodyContains { text: "ok".to_string() },
+        ];
+        let report = evaluate(&assertions, &response("ok"));
+        assert_eq!((report.passed, report.failed), (2, 1));
+        assert!(!report.all_passed());
+        assert_eq!(report.failures().count(), 1);
+    }
+
+    #[test]
+    fn body_assertions_report_the_body() {
+        let assertions = [Assertion::BodyContains { text: "missing".to_string() }, Assertion::BodyMatches { pattern: "^he".to_string() }];
+        let report = evaluate(&assertions, &response("hello"));
+        assert_eq!(report.results[0].actual, json!("hello"));
+        assert!(!report.results[0].passed);
+        assert_eq!(report.results[1].actual, json!("hello"));
+        assert!(report.results[1].passed);
+    }
+
+    #[test]
+    fn long_bodies_are_cut_on_a_character_boundary() {
+        let body = "é".repeat(BODY_EXCERPT_CHARS + 1);
+        let excerpt = body_excerpt(&body);
+        let text = excerpt.as_str().unwrap();
+        assert!(text.starts_with(&"é".repeat(BODY_EXCERPT_CHARS)));
+        assert!(text.ends_with(&format!("... ({} bytes)", body.len())));
+    }
+}
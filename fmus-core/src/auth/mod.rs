@@ -1,6 +1,8 @@
 use serde::{Serialize, Deserialize};
 use serde_json::Value;
 
+use crate::vault::{Secret, Vault};
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum AuthType {
     None,
@@ -24,29 +26,29 @@ pub enum ApiKeyLocation {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ApiKeyAuth {
     pub key: String,
-    pub value: String,
+    pub value: Secret,
     pub location: ApiKeyLocation,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BasicAuth {
     pub username: String,
-    pub password: String,
+    pub password: Secret,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BearerAuth {
-    pub token: String,
+    pub token: Secret,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OAuth2Auth {
     pub client_id: String,
-    pub client_secret: String,
+    pub client_secret: Secret,
     pub token_url: String,
     pub scopes: Vec<String>,
-    pub access_token: Option<String>,
-    pub refresh_token: Option<String>,
+    pub access_token: Option<Secret>,
+    pub refresh_token: Option<Secret>,
     pub expires_at: Option<u64>,
 }
 
@@ -70,20 +72,20 @@ impl Auth {
     pub fn basic(username: &str, password: &str) -> Self {
         Auth::Basic(BasicAuth {
             username: username.to_string(),
-            password: password.to_string(),
+            password: Secret::plain(password),
         })
     }
 
     pub fn bearer(token: &str) -> Self {
         Auth::Bearer(BearerAuth {
-            token: token.to_string(),
+            token: Secret::plain(token),
         })
     }
 
     pub fn api_key(key: &str, value: &str, location: ApiKeyLocation) -> Self {
         Auth::ApiKey(ApiKeyAuth {
             key: key.to_string(),
-            value: value.to_string(),
+            value: Secret::plain(value),
             location,
         })
     }
@@ -96,7 +98,7 @@ impl Auth {
     ) -> Self {
         Auth::OAuth2(OAuth2Auth {
             client_id: client_id.to_string(),
-            client_secret: client_secret.to_string(),
+            client_secret: Secret::plain(client_secret),
             token_url: token_url.to_string(),
             scopes,
             access_token: None,
@@ -112,25 +114,49 @@ impl Auth {
         })
     }
 
-    pub fn to_http_auth(&self) -> Option<crate::http::AuthConfig> {
+    // Memindahkan semua nilai rahasia ke vault, menyisakan referensi saja
+    pub fn seal_secrets(&mut self, vault: &mut Vault, name: &str) -> Result<(), String> {
         match self {
+            Auth::None | Auth::Custom(_) => Ok(()),
+            Auth::Basic(basic) => basic.password.seal(vault, &format!("{}.password", name)),
+            Auth::Bearer(bearer) => bearer.token.seal(vault, &format!("{}.token", name)),
+            Auth::ApiKey(api_key) => api_key.value.seal(vault, &format!("{}.value", name)),
+            Auth::OAuth2(oauth2) => {
+                oauth2.client_secret.seal(vault, &format!("{}.client_secret", name))?;
+                if let Some(token) = oauth2.access_token.as_mut() {
+                    token.seal(vault, &format!("{}.access_token", name))?;
+                }
+                if let Some(token) = oauth2.refresh_token.as_mut() {
+                    token.seal(vault, &format!("{}.refresh_token", name))?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    // Auth untuk dikirim. Referensi vault dibuka di sini, jadi vault yang terkunci atau tidak ada
+    // menghasilkan error dan bukan kredensial kosong.
+    pub fn to_http_auth(&self, vault: Option<&Vault>) -> Result<Option<crate::http::AuthConfig>, String> {
+        let plain = |secret: &Secret| secret.reveal(vault);
+
+        Ok(match self {
             Auth::None => None,
             Auth::Basic(basic) => Some(crate::http::AuthConfig {
                 auth_type: "basic".to_string(),
                 credentials: serde_json::json!({
                     "username": basic.username,
-                    "password": basic.password
+                    "password": plain(&basic.password)?
                 }),
             }),
             Auth::Bearer(bearer) => Some(crate::http::AuthConfig {
                 auth_type: "bearer".to_string(),
-                credentials: serde_json::json!(bearer.token),
+                credentials: serde_json::json!(plain(&bearer.token)?),
             }),
             Auth::ApiKey(api_key) => Some(crate::http::AuthConfig {
                 auth_type: "api_key".to_string(),
                 credentials: serde_json::json!({
                     "key": api_key.key,
-                    "value": api_key.value,
+                    "value": plain(&api_key.value)?,
                     "location": match api_key.location {
                         ApiKeyLocation::Header => "header",
                         ApiKeyLocation::Query => "query",
@@ -142,18 +168,38 @@ impl Auth {
                 auth_type: "oauth2".to_string(),
                 credentials: serde_json::json!({
                     "client_id": oauth2.client_id,
-                    "client_secret": oauth2.client_secret,
+                    "client_secret": plain(&oauth2.client_secret)?,
                     "token_url": oauth2.token_url,
                     "scopes": oauth2.scopes,
-                    "access_token": oauth2.access_token,
-                    "refresh_token": oauth2.refresh_token,
+                    "access_token": oauth2.access_token.as_ref().map(plain).transpose()?,
+                    "refresh_token": oauth2.refresh_token.as_ref().map(plain).transpose()?,
                     "expires_at": oauth2.expires_at,
                 }),
             }),
             Auth::Custom(custom) => Some(crate::http::AuthConfig {
                 auth_type: custom.auth_type.clone(),
-                credentials: custom.data.clone(),
+                credentials: crate::vault::reveal_json(&custom.data, vault)?,
             }),
-        }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn sealed_auth_needs_an_unlocked_vault() {
+        let mut vault = Vault::create("passphrase").unwrap();
+        let mut auth = Auth::basic("ann", "s3cret");
+        auth.seal_secrets(&mut vault, "login").unwrap();
+
+        let config = auth.to_http_auth(Some(&vault)).unwrap().unwrap();
+        assert_eq!(config.credentials, json!({"username": "ann", "password": "s3cret"}));
+
+        vault.lock();
+        assert_eq!(auth.to_http_auth(Some(&vault)).unwrap_err(), "Vault is locked");
+        assert!(auth.to_http_auth(None).is_err());
     }
 }
@@ -0,0 +1,214 @@
+// Menjalankan monitor tanpa aplikasi desktop.
+//
+//   fmus-monitor monitors.json          jalankan sesuai jadwal sampai Ctrl+C
+//   fmus-monitor monitors.json --once   jalankan semua monitor aktif sekali, exit 1 jika ada yang gagal
+//
+// Format monitors.json (path relatif terhadap file ini):
+//   {
+//     "history_dir": "monitor-history",
+//     "vault": "vault.json",
+//     "collections": ["api.collection.json"],
+//     "environments": ["staging.env.json"],
+//     "monitors": [{"id": "api-health", "name": "API health", "collection_id": "...",
+//                   "environment_id": "staging", "schedule": {"type": "interval", "minutes": 5}}]
+//   }
+// File collection memakai format export JSON aplikasi desktop (collection, folders, requests).
+// File environment berupa export environment aplikasi desktop atau object {"nama": "nilai"} biasa.
+// Secret dibuka lewat vault dari --vault <vault.json>, FMUS_VAULT atau key "vault", dengan
+// passphrase dari FMUS_VAULT_PASSPHRASE, sama seperti CLI fmus.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::Arc;
+
+use fmus_core::collections::{CollectionBundle, CollectionStore};
+use fmus_core::environment::Environment;
+use fmus_core::monitor::{Monitor, MonitorHistory, MonitorRun, MonitorStatus, PreparedMonitor, Scheduler};
+use fmus_core::{HttpClient, RunPlan, Vault};
+use serde::Deserialize;
+
+// Allocator yang mencatat memori per thread agar batas memori script berlaku
+#[global_allocator]
+static ALLOCATOR: fmus_core::scripting::ScriptAllocator = fmus_core::scripting::ScriptAllocator;
+
+fn default_history_dir() -> PathBuf {
+    PathBuf::from("monitor-history")
+}
+
+#[derive(Deserialize)]
+struct MonitorConfig {
+    #[serde(default = "default_history_dir")]
+    history_dir: PathBuf,
+    #[serde(default)]
+    collections: Vec<PathBuf>,
+    #[serde(default)]
+    environments: Vec<PathBuf>,
+    #[serde(default)]
+    vault: Option<PathBuf>,
+    monitors: Vec<Monitor>,
+}
+
+const USAGE: &str = "Usage: fmus-monitor <monitors.json> [--once] [--vault <vault.json>]";
+
+struct Args {
+    config_path: PathBuf,
+    once: bool,
+    vault: Option<PathBuf>,
+}
+
+fn parse_args(raw: &[String]) -> Result<Args, String> {
+    let mut config_path = None;
+    let mut once = false;
+    let mut vault = None;
+    let mut raw = raw.iter();
+    while let Some(arg) = raw.next() {
+        match arg.as_str() {
+            "--once" => once = true,
+            "--vault" => vault = Some(raw.next().map(PathBuf::from).ok_or_else(|| format!("Missing value for --vault\n{}", USAGE))?),
+            option if option.starts_with("--") => return Err(format!("Unknown option: {}\n{}", option, USAGE)),
+            path if config_path.is_none() => config_path = Some(PathBuf::from(path)),
+            extra => return Err(format!("Unexpected argument: {}\n{}", extra, USAGE)),
+        }
+    }
+
+    let config_path = config_path.ok_or_else(|| USAGE.to_string())?;
+    Ok(Args { config_path, once, vault })
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let raw: Vec<String> = std::env::args().skip(1).collect();
+    let args = match parse_args(&raw) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::from(2);
+        },
+    };
+
+    match run(&args).await {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::from(2)
+        },
+    }
+}
+
+// Return false jika mode --once dan ada monitor yang gagal
+async fn run(args: &Args) -> Result<bool, String> {
+    let config_path = args.config_path.as_path();
+    let content = std::fs::read_to_string(config_path)
+        .map_err(|e| format!("Failed to read {}: {}", config_path.display(), e))?;
+    let config: MonitorConfig = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse {}: {}", config_path.display(), e))?;
+
+    let base = config_path.parent().unwrap_or(Path::new("."));
+    let collections: Vec<PathBuf> = config.collections.iter().map(|path| base.join(path)).collect();
+    let environments: Vec<PathBuf> = config.environments.iter().map(|path| base.join(path)).collect();
+    // --vault dan FMUS_VAULT lebih diutamakan daripada key "vault" di monitors.json
+    let vault_path = args.vault.clone()
+        .or_else(|| std::env::var_os("FMUS_VAULT").map(PathBuf::from))
+        .or_else(|| config.vault.as_ref().map(|path| base.join(path)));
+    let vault = vault_path.map(|path| Vault::load_unlocked(&path)).transpose()?;
+    let names: HashMap<String, String> = config.monitors.iter()
+        .map(|monitor| (monitor.id.clone(), monitor.name.clone()))
+        .collect();
+    let enabled: Vec<String> = config.monitors.iter()
+        .filter(|monitor| monitor.enabled)
+        .map(|monitor| monitor.id.clone())
+        .collect();
+
+    // File dibaca ulang setiap run agar perubahan collection dan environment langsung terpakai
+    let scheduler = Arc::new(Scheduler::new(
+        HttpClient::new(None),
+        MonitorHistory::new(base.join(&config.history_dir)),
+        move |monitor| prepare(monitor, &collections, &environments, vault.as_ref()),
+    ));
+    scheduler.set_monitors(config.monitors)?;
+
+    if args.once {
+        let mut passed = true;
+        for id in &enabled {
+            let run = scheduler.run_now(id).await?;
+            print_run(&run, &names);
+            passed &= run.status == MonitorStatus::Passed;
+        }
+        return Ok(passed);
+    }
+
+    println!("Running {} monitor(s), press Ctrl+C to stop", enabled.len());
+    tokio::select! {
+        _ = scheduler.run(move |run| print_run(run, &names)) => {},
+        _ = tokio::signal::ctrl_c() => {},
+    }
+    Ok(true)
+}
+
+fn prepare(
+    monitor: &Monitor,
+    collections: &[PathBuf],
+    environments: &[PathBuf],
+    vault: Option<&Vault>,
+) -> Result<PreparedMonitor, String> {
+    let mut store = CollectionStore::new();
+    for path in collections {
+        store.import_bundle(read_json::<CollectionBundle>(path)?);
+    }
+
+    let mut plan = match &monitor.folder_id {
+        Some(folder_id) => RunPlan::for_folder(&store, folder_id, vault)?,
+        None => RunPlan::for_collection(&store, &monitor.collection_id, vault)?,
+    };
+    let environment = match &monitor.environment_id {
+        Some(environment_id) => {
+            let environment = find_environment(environment_id, environments)?;
+            plan.secrets.extend(environment.secret_values(vault)?);
+            environment.resolve_variables(vault)?
+        },
+        None => HashMap::new(),
+    };
+
+    Ok(PreparedMonitor { plan, environment })
+}
+
+// Environment dicari berdasarkan id atau nama di file export, atau nama file tanpa ekstensi
+fn find_environment(environment_id: &str, environments: &[PathBuf]) -> Result<Environment, String> {
+    for path in environments {
+        let environment = Environment::load(path)?;
+        let stem = path.file_stem().and_then(|stem| stem.to_str()).map(|stem| stem.trim_end_matches(".env"));
+        if environment.matches(environment_id) || stem == Some(environment_id) {
+            return Ok(environment);
+        }
+    }
+    Err(format!("Environment not found: {}", environment_id))
+}
+
+fn read_json<T: serde::de::DeserializeOwned>(path: &Path) -> Result<T, String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse {}: {}", path.display(), e))
+}
+
+fn print_run(run: &MonitorRun, names: &HashMap<String, String>) {
+    let name = names.get(&run.monitor_id).unwrap_or(&run.monitor_id);
+    let mut line = format!("{} {} {}", run.started_at.format("%Y-%m-%d %H:%M:%S"), name, run.status.as_str());
+    if let Some(summary) = &run.summary {
+        line.push_str(&format!(" ({}/{} passed, {} ms)", summary.passed, summary.executed, run.duration));
+    }
+    if let Some(error) = &run.error {
+        line.push_str(&format!(": {}", error));
+    }
+    if !run.regressions.is_empty() {
+        line.push_str(&format!(", newly failing: {}", run.regressions.join(", ")));
+    }
+    if let Some(event) = run.event {
+        line.push_str(&format!(" [{}]", event.as_str()));
+    }
+    println!("{}", line);
+    for error in &run.hook_errors {
+        eprintln!("  hook error: {}", error);
+    }
+}
@@ -0,0 +1,158 @@
+// Parser argumen sederhana: opsi dengan nilai (`-H nilai`, `--header=nilai`), flag tanpa nilai
+// dan argumen posisi. Setelah `--` semua argumen dianggap posisi.
+// `-h`/`--help` di posisi opsi menghentikan parsing, lihat Args::help.
+
+#[derive(Debug)]
+pub struct Args {
+    positional: Vec<String>,
+    values: Vec<(String, String)>,
+    flags: Vec<String>,
+    help: bool,
+}
+
+impl Args {
+    // options berisi semua nama opsi yang butuh nilai, flags semua nama flag
+    pub fn parse(raw: &[String], options: &[&str], flags: &[&str]) -> Result<Args, String> {
+        let mut args = Args { positional: Vec::new(), values: Vec::new(), flags: Vec::new(), help: false };
+        let mut iter = raw.iter();
+
+        while let Some(arg) = iter.next() {
+            if arg == "--" {
+                args.positional.extend(iter.by_ref().cloned());
+                break;
+            }
+            if !arg.starts_with('-') || arg == "-" {
+                args.positional.push(arg.clone());
+                continue;
+            }
+            // Argumen lain boleh tidak valid karena yang diminta hanya usage
+            if arg == "-h" || arg == "--help" {
+                args.help = true;
+                break;
+            }
+
+            let (name, inline) = match arg.split_once('=') {
+                Some((name, value)) if arg.starts_with("--") => (name, Some(value.to_string())),
+                _ => (arg.as_str(), None),
+            };
+
+            if options.contains(&name) {
+                let value = match inline {
+                    Some(value) => value,
+                    None => iter.next().cloned().ok_or_else(|| format!("Missing value for {}", name))?,
+                };
+                args.values.push((name.to_string(), value));
+            } else if flags.contains(&name) && inline.is_none() {
+                args.flags.push(name.to_string());
+            } else {
+                return Err(format!("Unknown option: {}", arg));
+            }
+        }
+
+        Ok(args)
+    }
+
+    // Usage diminta; nilai opsi seperti `-d --help` tidak dihitung
+    pub fn help(&self) -> bool {
+        self.help
+    }
+
+    pub fn positionals(&self) -> &[String] {
+        &self.positional
+    }
+
+    // Nilai terakhir dari salah satu nama opsi
+    pub fn value(&self, names: &[&str]) -> Option<&str> {
+        self.values.iter().rev()
+            .find(|(name, _)| names.contains(&name.as_str()))
+            .map(|(_, value)| value.as_str())
+    }
+
+    // Semua nilai opsi yang boleh diulang, sesuai urutan
+    pub fn values(&self, names: &[&str]) -> Vec<&str> {
+        self.values.iter()
+            .filter(|(name, _)| names.contains(&name.as_str()))
+            .map(|(_, value)| value.as_str())
+            .collect()
+    }
+
+    pub fn flag(&self, names: &[&str]) -> bool {
+        self.flags.iter().any(|flag| names.contains(&flag.as_str()))
+    }
+
+    // Nilai opsi yang harus berupa angka
+    pub fn number<T: std::str::FromStr>(&self, names: &[&str]) -> Result<Option<T>, String> {
+        self.value(names)
+            .map(|value| value.parse().map_err(|_| format!("Invalid number for {}: {}", names[names.len() - 1], value)))
+            .transpose()
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    pub(crate) fn strings(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    fn parse(raw: &[&str]) -> Result<Args, String> {
+        Args::parse(&strings(raw), &["-H", "--header", "-o", "--output", "-n"], &["-v", "--verbose"])
+    }
+
+    #[test]
+    fn options_take_the_next_or_inline_value() {
+        let args = parse(&["get", "-H", "A: 1", "--header=B: 2", "-v", "url", "-o", "a.txt", "--output", "b.txt"]).unwrap();
+        assert_eq!(args.positionals(), ["get", "url"]);
+        assert_eq!(args.values(&["-H", "--header"]), ["A: 1", "B: 2"]);
+        assert_eq!(args.value(&["-o", "--output"]), Some("b.txt"));
+        assert!(args.flag(&["-v", "--verbose"]));
+        assert!(!args.help());
+
+        // Nilai boleh diawali '-' dan boleh kosong
+        let args = parse(&["-H", "-v", "--output="]).unwrap();
+        assert_eq!(args.value(&["-H"]), Some("-v"));
+        assert_eq!(args.value(&["--output"]), Some(""));
+        assert!(!args.flag(&["-v"]));
+    }
+
+    #[test]
+    fn unknown_options_and_missing_values_are_errors() {
+        assert_eq!(parse(&["--bogus"]).unwrap_err(), "Unknown option: --bogus");
+        assert_eq!(parse(&["-x", "url"]).unwrap_err(), "Unknown option: -x");
+        assert_eq!(parse(&["--verbose=yes"]).unwrap_err(), "Unknown option: --verbose=yes");
+        // Nilai inline hanya untuk opsi panjang
+        assert_eq!(parse(&["-o=a.txt"]).unwrap_err(), "Unknown option: -o=a.txt");
+        assert_eq!(parse(&["url", "-H"]).unwrap_err(), "Missing value for -H");
+    }
+
+    #[test]
+    fn double_dash_and_single_dash_are_positional() {
+        let args = parse(&["-", "--", "-v", "--help", "--bogus"]).unwrap();
+        assert_eq!(args.positionals(), ["-", "-v", "--help", "--bogus"]);
+        assert!(!args.flag(&["-v"]));
+        assert!(!args.help());
+    }
+
+    #[test]
+    fn help_is_only_recognized_where_an_option_is_expected() {
+        assert!(parse(&["--help"]).unwrap().help());
+        assert!(parse(&["url", "-h"]).unwrap().help());
+        // Argumen setelah --help tidak diperiksa lagi
+        assert!(parse(&["-h", "--bogus", "-H"]).unwrap().help());
+
+        let args = parse(&["-H", "--help", "--output", "-h"]).unwrap();
+        assert!(!args.help());
+        assert_eq!(args.value(&["-H"]), Some("--help"));
+        assert_eq!(args.value(&["--output"]), Some("-h"));
+    }
+
+    #[test]
+    fn numbers_are_parsed_from_the_last_value() {
+        let args = parse(&["-n", "x", "-n", "3"]).unwrap();
+        assert_eq!(args.number::<u32>(&["-n"]), Ok(Some(3)));
+        assert_eq!(args.number::<u32>(&["-o"]), Ok(None));
+        let args = parse(&["-n", "-1"]).unwrap();
+        assert_eq!(args.number::<u32>(&["-n"]).unwrap_err(), "Invalid number for -n: -1");
+    }
+}
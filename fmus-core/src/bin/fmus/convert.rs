@@ -0,0 +1,163 @@
+use std::path::Path;
+
+use fmus_core::collections::{curl, postman, CollectionBundle, CollectionStore};
+
+use crate::args::Args;
+
+pub const USAGE: &str = "Usage: fmus import <file> [options]
+       fmus export <collection.json> [options]
+
+import converts a Postman collection v2.1 (or an FMUS collection file) to an FMUS collection file,
+or a curl command (file or - for stdin) to a request.
+export converts an FMUS collection file (or a Postman collection) to another format.
+Plain secret values in request auth are never exported. On import they are sealed into the vault
+when a vault is given, otherwise kept as they are.
+
+Options:
+      --from curl              Import a curl command instead of a collection
+      --into <collection.json> Add the curl request to this collection, written back as an FMUS collection file unless -o is given
+      --name <name>            Name of the curl request (default METHOD /path)
+      --vault <vault.json>     Vault for secrets in request auth, unlocked with FMUS_VAULT_PASSPHRASE
+      --to <format>            Export format: postman (default) or json
+  -o, --output <file>          Write to a file instead of stdout";
+
+const OPTIONS: &[&str] = &["--to", "-o", "--output"];
+const IMPORT_OPTIONS: &[&str] = &["--from", "--into", "--name", "--vault", "-o", "--output"];
+
+pub fn import(raw: &[String]) -> Result<bool, String> {
+    let args = Args::parse(raw, IMPORT_OPTIONS, &[])?;
+    if args.help() {
+        println!("{}", USAGE);
+        return Ok(true);
+    }
+    let mut bundle = match args.value(&["--from"]) {
+        Some("curl") => import_curl(&args)?,
+        Some(other) => return Err(format!("Unsupported import format: {} (collections are detected automatically)", other)),
+        None => load(&args)?,
+    };
+
+    if let (Some(mut vault), Some(path)) = (crate::load_vault(&args)?, crate::vault_path(&args)) {
+        let mut sealed = false;
+        for request in bundle.requests.iter_mut() {
+            sealed |= request.seal_secrets(&mut vault)?;
+        }
+        if sealed {
+            vault.save(&path)?;
+        }
+    }
+
+    let content = serde_json::to_string_pretty(&bundle)
+        .map_err(|e| format!("Failed to serialize collection: {}", e))?;
+    let output = args.value(&["-o", "--output"]).or(args.value(&["--into"]));
+    crate::write_output(output, &content)?;
+    Ok(true)
+}
+
+// Request dari perintah curl, ditambahkan ke collection --into atau ke collection baru
+fn import_curl(args: &Args) -> Result<CollectionBundle, String> {
+    let command = match args.positionals() {
+        [path] if path == "-" => std::io::read_to_string(std::io::stdin())
+            .map_err(|e| format!("Failed to read stdin: {}", e))?,
+        [path] => crate::read_file(Path::new(path))?,
+        _ => return Err(format!("Expected one file with a curl command\n\n{}", USAGE)),
+    };
+
+    let curl = curl::parse(&command, true)?;
+    if curl.insecure {
+        eprintln!("warning: -k/--insecure is ignored, TLS certificates are always verified");
+    }
+    let request = curl.into_request(args.value(&["--name"]).map(String::from));
+
+    let mut bundle = match args.value(&["--into"]) {
+        Some(path) => crate::load_collection(Path::new(path))?,
+        None => {
+            let mut store = CollectionStore::new();
+            let collection = store.create_collection(request.name.clone(), None);
+            CollectionBundle { collection, folders: Vec::new(), requests: Vec::new() }
+        },
+    };
+    bundle.collection.requests.push(request.id.clone());
+    bundle.requests.push(request);
+    Ok(bundle)
+}
+
+pub fn export(raw: &[String]) -> Result<bool, String> {
+    let args = Args::parse(raw, OPTIONS, &[])?;
+    if args.help() {
+        println!("{}", USAGE);
+        return Ok(true);
+    }
+    let bundle = load(&args)?.redacted();
+    let content = match args.value(&["--to"]).unwrap_or("postman").to_lowercase().as_str() {
+        "json" => serde_json::to_string_pretty(&bundle)
+            .map_err(|e| format!("Failed to serialize collection: {}", e))?,
+        "postman" => postman::export(&bundle)?,
+        other => return Err(format!("Unsupported format: {} (expected postman or json)", other)),
+    };
+
+    crate::write_output(args.value(&["-o", "--output"]), &content)?;
+    Ok(true)
+}
+
+fn load(args: &Args) -> Result<CollectionBundle, String> {
+    match args.positionals() {
+        [path] => crate::load_collection(Path::new(path)),
+        _ => Err(format!("Expected one collection file\n\n{}", USAGE)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::args::tests::strings;
+
+    #[test]
+    fn import_arguments_are_checked() {
+        assert_eq!(import(&strings(&["c.json", "--to", "json"])).unwrap_err(), "Unknown option: --to");
+        assert_eq!(import(&strings(&["c.json", "--from"])).unwrap_err(), "Missing value for --from");
+        assert_eq!(
+            import(&strings(&["c.json", "--from", "har"])).unwrap_err(),
+            "Unsupported import format: har (collections are detected automatically)",
+        );
+        assert!(import(&strings(&["--from", "curl"])).unwrap_err().starts_with("Expected one file with a curl command"));
+        assert!(import(&strings(&[])).unwrap_err().starts_with("Expected one collection file"));
+        assert_eq!(import(&strings(&["--help"])), Ok(true));
+    }
+
+    #[test]
+    fn export_arguments_are_checked() {
+        assert_eq!(export(&strings(&["c.json", "--from", "curl"])).unwrap_err(), "Unknown option: --from");
+        assert_eq!(export(&strings(&["c.json", "-o"])).unwrap_err(), "Missing value for -o");
+        assert!(export(&strings(&["a.json", "b.json"])).unwrap_err().starts_with("Expected one collection file"));
+        // -o --help menulis ke file bernama --help, bukan mencetak usage
+        assert!(export(&strings(&["-o", "--help"])).unwrap_err().starts_with("Expected one collection file"));
+    }
+
+    #[test]
+    fn export_writes_the_requested_format() {
+        let dir = std::env::temp_dir().join(format!("fmus-convert-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut store = CollectionStore::new();
+        let collection = store.create_collection("Demo".to_string(), None);
+        let bundle = CollectionBundle { collection, folders: Vec::new(), requests: Vec::new() };
+        let input = dir.join("c.json");
+        std::fs::write(&input, serde_json::to_string(&bundle).unwrap()).unwrap();
+        let input = input.to_str().unwrap();
+
+        let output = dir.join("postman.json");
+        assert_eq!(export(&strings(&[input, "--output", output.to_str().unwrap()])), Ok(true));
+        let postman: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&output).unwrap()).unwrap();
+        assert_eq!(postman["info"]["name"], "Demo");
+
+        let output = dir.join("fmus.json");
+        assert_eq!(export(&strings(&[input, "--to=JSON", "-o", output.to_str().unwrap()])), Ok(true));
+        let exported: CollectionBundle = serde_json::from_str(&std::fs::read_to_string(&output).unwrap()).unwrap();
+        assert_eq!(exported.collection.name, "Demo");
+
+        assert_eq!(
+            export(&strings(&[input, "--to", "yaml"])).unwrap_err(),
+            "Unsupported format: yaml (expected postman or json)",
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,151 @@
+use std::path::Path;
+
+use fmus_core::environment::{EnvVariable, Environment};
+
+use crate::args::Args;
+
+pub const USAGE: &str = "Usage: fmus env <command> <env.json> [arguments] [options]
+
+Environment files use the desktop app export format.
+
+Commands:
+  create <env.json> <name>         Create an empty environment file
+  list <env.json>                  List variables, secret values are hidden
+  get <env.json> <key>             Print the value of a variable
+  set <env.json> <key> <value>     Add or replace a variable
+  unset <env.json> <key>           Remove a variable
+
+Options:
+      --secret               Store the value in the vault (set only)
+      --disabled             Add the variable as disabled (set only)
+      --vault <vault.json>   Vault for secrets, unlocked with FMUS_VAULT_PASSPHRASE";
+
+pub fn run(raw: &[String]) -> Result<bool, String> {
+    let args = Args::parse(raw, &["--vault"], &["--secret", "--disabled"])?;
+    if args.help() {
+        println!("{}", USAGE);
+        return Ok(true);
+    }
+    let (command, path, rest) = match args.positionals() {
+        [command, path, rest @ ..] => (command.as_str(), Path::new(path), rest),
+        _ => return Err(USAGE.to_string()),
+    };
+
+    match (command, rest) {
+        ("create", [name]) => {
+            if path.exists() {
+                return Err(format!("{} already exists", path.display()));
+            }
+            Environment::new(name, None).save(path)?;
+        },
+        ("list", []) => {
+            for variable in &Environment::load(path)?.variables {
+                let value = if variable.is_secret { "********" } else { variable.value.as_str() };
+                let disabled = if variable.enabled { "" } else { "  # disabled" };
+                println!("{}={}{}", variable.key, value, disabled);
+            }
+        },
+        ("get", [key]) => {
+            let environment = Environment::load(path)?;
+            let variable = environment.variable(key)
+                .ok_or_else(|| format!("Variable not found: {}", key))?;
+            let vault = crate::load_vault(&args)?;
+            println!("{}", variable.resolve(vault.as_ref())?);
+        },
+        ("set", [key, value]) => set(&args, path, key, value)?,
+        ("unset", [key]) => {
+            let mut environment = Environment::load(path)?;
+            let variable = environment.remove_variable(key)
+                .ok_or_else(|| format!("Variable not found: {}", key))?;
+            if let Some(secret_ref) = variable.secret_ref {
+                remove_secret(&args, &secret_ref)?;
+            }
+            environment.save(path)?;
+        },
+        _ => return Err(USAGE.to_string()),
+    }
+
+    Ok(true)
+}
+
+// Secret langsung disegel ke vault sehingga nilai polosnya tidak pernah ditulis ke file
+fn set(args: &Args, path: &Path, key: &str, value: &str) -> Result<(), String> {
+    let mut environment = Environment::load(path)?;
+    let mut variable = EnvVariable {
+        enabled: !args.flag(&["--disabled"]),
+        is_secret: args.flag(&["--secret"]),
+        ..EnvVariable::new(key, value)
+    };
+
+    if variable.is_secret {
+        let mut vault = crate::load_vault(args)?
+            .ok_or_else(|| "--secret requires --vault <vault.json> or FMUS_VAULT".to_string())?;
+        variable.seal(&mut vault, &environment.name)?;
+        if let Some(previous) = environment.set_variable(variable) {
+            vault.remove_secret(&previous);
+        }
+        save_vault(args, &vault)?;
+    } else if let Some(previous) = environment.set_variable(variable) {
+        remove_secret(args, &previous)?;
+    }
+
+    environment.save(path)
+}
+
+// Hapus secret lama dari vault; tanpa vault entry-nya dibiarkan
+fn remove_secret(args: &Args, secret_ref: &fmus_core::SecretRef) -> Result<(), String> {
+    if let Some(mut vault) = crate::load_vault(args)? {
+        if vault.remove_secret(secret_ref) {
+            save_vault(args, &vault)?;
+        }
+    }
+    Ok(())
+}
+
+fn save_vault(args: &Args, vault: &fmus_core::Vault) -> Result<(), String> {
+    match crate::vault_path(args) {
+        Some(path) => vault.save(&path),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::args::tests::strings;
+
+    #[test]
+    fn commands_edit_the_environment_file() {
+        let dir = std::env::temp_dir().join(format!("fmus-env-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("env.json");
+        let file = path.to_str().unwrap();
+
+        assert_eq!(run(&strings(&["create", file, "Dev"])), Ok(true));
+        assert!(run(&strings(&["create", file, "Dev"])).unwrap_err().ends_with("already exists"));
+        assert_eq!(run(&strings(&["set", file, "host", "--help"])), Ok(true));
+        assert_eq!(run(&strings(&["set", "--disabled", file, "token", "--", "-abc"])), Ok(true));
+
+        let environment = Environment::load(&path).unwrap();
+        assert_eq!(environment.name, "Dev");
+        assert!(environment.variable("host").is_none());
+        let token = environment.variable("token").unwrap();
+        assert_eq!(token.value, "-abc");
+        assert!(!token.enabled);
+
+        assert_eq!(run(&strings(&["unset", file, "token"])), Ok(true));
+        assert_eq!(run(&strings(&["unset", file, "token"])).unwrap_err(), "Variable not found: token");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn invalid_arguments_are_errors() {
+        assert_eq!(run(&strings(&["list", "env.json", "--all"])).unwrap_err(), "Unknown option: --all");
+        assert_eq!(run(&strings(&["list", "env.json", "--secret=yes"])).unwrap_err(), "Unknown option: --secret=yes");
+        assert_eq!(run(&strings(&["get", "env.json", "key", "--vault"])).unwrap_err(), "Missing value for --vault");
+        assert_eq!(run(&strings(&["list"])).unwrap_err(), USAGE);
+        assert_eq!(run(&strings(&["rename", "env.json", "a", "b"])).unwrap_err(), USAGE);
+        assert_eq!(run(&strings(&["set", "env.json", "key"])).unwrap_err(), USAGE);
+        assert_eq!(run(&strings(&["--help"])), Ok(true));
+    }
+}
@@ -0,0 +1,122 @@
+// CLI FMUS-POST di atas fmus-core. Membaca file collection dan environment yang sama dengan
+// aplikasi desktop (export JSON), sehingga collection bisa dijalankan di CI.
+//
+//   fmus request [METHOD] <url> [opsi]       kirim satu request
+//   fmus run <collection.json> [opsi]        jalankan collection, exit 1 jika ada yang gagal
+//   fmus import <file> [opsi]                Postman collection ke file collection FMUS
+//   fmus export <collection.json> [opsi]     file collection FMUS ke Postman
+//   fmus env <perintah> <env.json> [...]     kelola file environment
+//
+// Exit code: 0 berhasil, 1 ada request/test yang gagal, 2 argumen atau file tidak valid.
+// Secret di collection dan environment dibuka lewat --vault <vault.json> (atau FMUS_VAULT)
+// dengan passphrase dari FMUS_VAULT_PASSPHRASE.
+
+mod args;
+mod convert;
+mod env;
+mod request;
+mod run;
+
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use fmus_core::collections::{postman, CollectionBundle};
+use fmus_core::vault::Vault;
+
+use args::Args;
+
+// Allocator yang mencatat memori per thread agar batas memori script berlaku
+#[global_allocator]
+static ALLOCATOR: fmus_core::scripting::ScriptAllocator = fmus_core::scripting::ScriptAllocator;
+
+const USAGE: &str = "Usage: fmus <command> [options]
+
+Commands:
+  request [METHOD] <url>     Send a single request
+  run <collection.json>      Run a collection, exit 1 when a request or test fails
+  import <file>              Convert a Postman collection to an FMUS collection file
+  export <collection.json>   Convert an FMUS collection file to another format
+  env <command> <env.json>   Manage environment files (list, get, set, unset, create)
+
+Run `fmus <command> --help` for the options of a command.";
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let Some(command) = args.first() else {
+        eprintln!("{}", USAGE);
+        return ExitCode::from(2);
+    };
+    // -h/--help setelah perintah ditangani parser argumen perintah itu
+    let rest = &args[1..];
+
+    let result = match command.as_str() {
+        "request" => request::run(rest).await,
+        "run" => run::run(rest).await,
+        "import" => convert::import(rest),
+        "export" => convert::export(rest),
+        "env" => env::run(rest),
+        "-h" | "--help" | "help" => {
+            println!("{}", USAGE);
+            Ok(true)
+        },
+        "-V" | "--version" => {
+            println!("fmus {}", fmus_core::version());
+            Ok(true)
+        },
+        other => Err(format!("Unknown command: {}\n\n{}", other, USAGE)),
+    };
+
+    match result {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::from(2)
+        },
+    }
+}
+
+fn read_file(path: &Path) -> Result<String, String> {
+    std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+}
+
+// Tulis ke file, atau ke stdout jika path tidak diisi
+fn write_output(output: Option<&str>, content: &str) -> Result<(), String> {
+    match output {
+        Some(path) => std::fs::write(path, content).map_err(|e| format!("Failed to write {}: {}", path, e)),
+        None => {
+            println!("{}", content);
+            Ok(())
+        },
+    }
+}
+
+// File collection FMUS (export JSON aplikasi desktop) atau Postman collection v2.1
+fn load_collection(path: &Path) -> Result<CollectionBundle, String> {
+    let content = read_file(path)?;
+    let value: serde_json::Value = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?;
+
+    if value.get("info").is_some() && value.get("item").is_some() {
+        return postman::import(&content).map_err(|e| format!("{}: {}", path.display(), e));
+    }
+    serde_json::from_value(value).map_err(|e| format!("Invalid collection file {}: {}", path.display(), e))
+}
+
+// Lokasi vault dari --vault atau FMUS_VAULT
+fn vault_path(args: &Args) -> Option<PathBuf> {
+    args.value(&["--vault"]).map(PathBuf::from).or_else(|| std::env::var_os("FMUS_VAULT").map(PathBuf::from))
+}
+
+// Vault yang sudah di-unlock dengan FMUS_VAULT_PASSPHRASE, None jika lokasi vault tidak diisi
+fn load_vault(args: &Args) -> Result<Option<Vault>, String> {
+    vault_path(args).map(|path| Vault::load_unlocked(&path)).transpose()
+}
+
+// Pasangan "nama=nilai" dari opsi seperti --var dan --query
+fn parse_pair(pair: &str, option: &str) -> Result<(String, String), String> {
+    pair.split_once('=')
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .ok_or_else(|| format!("Expected name=value for {}: {}", option, pair))
+}
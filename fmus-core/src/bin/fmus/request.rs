@@ -0,0 +1,268 @@
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::Path;
+
+use fmus_core::codec::BodyFormat;
+use fmus_core::environment::Environment;
+use fmus_core::http::{AuthConfig, HttpClient, HttpResponse, PreparedRequest, RequestParams};
+use fmus_core::scripting::ScriptScope;
+use serde_json::{json, Value};
+
+use crate::args::Args;
+
+pub const USAGE: &str = "Usage: fmus request [METHOD] <url> [options]
+
+Options:
+  -X, --method <method>      HTTP method (default GET, or POST when a body is given)
+  -H, --header <name: value> Request header, repeatable
+  -q, --query <name=value>   Query parameter, repeatable
+  -d, --data <body|@file>    Request body; JSON is sent as JSON, anything else as a string
+      --format <format>      Encode the body as json, msgpack or cbor
+  -u, --user <user:pass>     Basic auth
+      --bearer <token>       Bearer token auth
+      --auth <json>          Auth in fmus-core format, e.g. {\"type\":\"api_key\",\"credentials\":{...}}
+  -e, --env <env.json>       Replace {{variables}} from an environment file
+      --var <name=value>     Set a variable, repeatable, overrides the environment
+      --vault <vault.json>   Vault for secret environment variables
+  -t, --timeout <seconds>    Request timeout
+  -i, --include              Print the status line and response headers
+  -v, --verbose              Print the request and response headers to stderr
+      --raw                  Print the body as received instead of pretty-printed
+      --json                 Print status, headers, time and body as one JSON object
+  -o, --output <file>        Write the raw body to a file
+  -f, --fail                 Exit 1 when the status is 400 or above";
+
+const OPTIONS: &[&str] = &[
+    "-X", "--method", "-H", "--header", "-q", "--query", "-d", "--data", "--format", "-u", "--user",
+    "--bearer", "--auth", "-e", "--env", "--var", "--vault", "-t", "--timeout", "-o", "--output",
+];
+const FLAGS: &[&str] = &["-i", "--include", "-v", "--verbose", "--raw", "--json", "-f", "--fail"];
+
+pub async fn run(raw: &[String]) -> Result<bool, String> {
+    let args = Args::parse(raw, OPTIONS, FLAGS)?;
+    if args.help() {
+        println!("{}", USAGE);
+        return Ok(true);
+    }
+    let params = request_params(&args)?;
+
+    // Variabel dari environment lalu --var, diterapkan ke URL, header, query dan body
+    let mut scope = ScriptScope::default();
+    if let Some(path) = args.value(&["-e", "--env"]) {
+        let vault = crate::load_vault(&args)?;
+        scope.environment = Environment::load(Path::new(path))?.resolve_variables(vault.as_ref())?;
+    }
+    for pair in args.values(&["--var"]) {
+        let (name, value) = crate::parse_pair(pair, "--var")?;
+        scope.environment.insert(name, value);
+    }
+    let params = scope.apply_to(params);
+
+    let client = HttpClient::new(None);
+    let verbose = args.flag(&["-v", "--verbose"]);
+    if verbose {
+        print_request(&client.prepare(params.clone())?);
+    }
+
+    let response = client.request(params).await?;
+    if verbose {
+        print_response_head(&response, "< ", &mut std::io::stderr());
+        eprintln!("< ({} ms, {} bytes)", response.time, response.body.len());
+    }
+
+    print_response(&args, &response)?;
+    Ok(!args.flag(&["-f", "--fail"]) || response.status < 400)
+}
+
+fn request_params(args: &Args) -> Result<RequestParams, String> {
+    let (method, url) = match args.positionals() {
+        [url] => (None, url.clone()),
+        [method, url] => (Some(method.to_uppercase()), url.clone()),
+        [] => return Err(format!("Missing URL\n\n{}", USAGE)),
+        _ => return Err(format!("Too many arguments\n\n{}", USAGE)),
+    };
+
+    let body = args.value(&["-d", "--data"]).map(read_body).transpose()?;
+    let method = args.value(&["-X", "--method"]).map(str::to_uppercase)
+        .or(method)
+        .unwrap_or_else(|| if body.is_some() { "POST" } else { "GET" }.to_string());
+
+    let mut headers = HashMap::new();
+    for header in args.values(&["-H", "--header"]) {
+        let (name, value) = header.split_once(':')
+            .ok_or_else(|| format!("Expected 'Name: value' for --header: {}", header))?;
+        headers.insert(name.trim().to_string(), value.trim().to_string());
+    }
+
+    let mut query = HashMap::new();
+    for pair in args.values(&["-q", "--query"]) {
+        let (name, value) = crate::parse_pair(pair, "--query")?;
+        query.insert(name, value);
+    }
+
+    let body_format = match args.value(&["--format"]) {
+        Some(format) => serde_json::from_value::<BodyFormat>(json!({ "format": format }))
+            .map(Some)
+            .map_err(|_| format!("Unsupported body format: {} (expected json, msgpack or cbor)", format))?,
+        None => None,
+    };
+
+    Ok(RequestParams {
+        url,
+        method,
+        headers: if headers.is_empty() { None } else { Some(headers) },
+        params: if query.is_empty() { None } else { Some(query) },
+        body,
+        timeout: args.number(&["-t", "--timeout"])?,
+        auth: auth(args)?,
+        body_format,
+    })
+}
+
+// Body dari argumen atau dari file jika diawali '@'. JSON yang valid dikirim sebagai JSON.
+fn read_body(data: &str) -> Result<Value, String> {
+    let text = match data.strip_prefix('@') {
+        Some(path) => crate::read_file(Path::new(path))?,
+        None => data.to_string(),
+    };
+    Ok(serde_json::from_str(&text).unwrap_or(Value::String(text)))
+}
+
+fn auth(args: &Args) -> Result<Option<AuthConfig>, String> {
+    if let Some(user) = args.value(&["-u", "--user"]) {
+        let (username, password) = user.split_once(':').unwrap_or((user, ""));
+        return Ok(Some(AuthConfig {
+            auth_type: "basic".to_string(),
+            credentials: json!({ "username": username, "password": password }),
+        }));
+    }
+    if let Some(token) = args.value(&["--bearer"]) {
+        return Ok(Some(AuthConfig { auth_type: "bearer".to_string(), credentials: json!({ "token": token }) }));
+    }
+
+    args.value(&["--auth"])
+        .map(|auth| serde_json::from_str(auth).map_err(|e| format!("Invalid --auth: {}", e)))
+        .transpose()
+}
+
+fn print_request(request: &PreparedRequest) {
+    eprintln!("> {} {}", request.method, request.url);
+    for (name, value) in &request.headers {
+        eprintln!("> {}: {}", name, value);
+    }
+    eprintln!(">");
+    if let Some(body) = &request.body {
+        eprintln!("{}", String::from_utf8_lossy(body));
+    }
+}
+
+// Baris status dan header response, header diurutkan agar output stabil
+fn print_response_head(response: &HttpResponse, prefix: &str, out: &mut dyn Write) {
+    let reason = reqwest::StatusCode::from_u16(response.status).ok()
+        .and_then(|status| status.canonical_reason())
+        .unwrap_or_default();
+    let _ = writeln!(out, "{}HTTP {} {}", prefix, response.status, reason);
+
+    let mut headers: Vec<(&String, &String)> = response.headers.iter().collect();
+    headers.sort();
+    for (name, value) in headers {
+        // Set-Cookie yang digabung dengan newline ditulis satu per baris
+        for value in value.split('\n') {
+            let _ = writeln!(out, "{}{}: {}", prefix, name, value);
+        }
+    }
+}
+
+fn print_response(args: &Args, response: &HttpResponse) -> Result<(), String> {
+    if let Some(path) = args.value(&["-o", "--output"]) {
+        return std::fs::write(path, &response.body).map_err(|e| format!("Failed to write {}: {}", path, e));
+    }
+
+    if args.flag(&["--json"]) {
+        let body = response.json().unwrap_or_else(|_| Value::String(response.text_lossy()));
+        let output = json!({
+            "status": response.status,
+            "headers": response.headers,
+            "time": response.time,
+            "size": response.body.len(),
+            "body": body,
+        });
+        println!("{}", serde_json::to_string_pretty(&output).unwrap_or_default());
+        return Ok(());
+    }
+
+    let mut stdout = std::io::stdout().lock();
+    if args.flag(&["-i", "--include"]) {
+        print_response_head(response, "", &mut stdout);
+        let _ = writeln!(stdout);
+    }
+
+    let written = if args.flag(&["--raw"]) {
+        stdout.write_all(&response.body)
+    } else {
+        writeln!(stdout, "{}", response.pretty())
+    };
+    written.map_err(|e| format!("Failed to write response: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::args::tests::strings;
+
+    fn params(raw: &[&str]) -> Result<RequestParams, String> {
+        request_params(&Args::parse(&strings(raw), OPTIONS, FLAGS)?)
+    }
+
+    #[test]
+    fn method_comes_from_option_position_or_body() {
+        assert_eq!(params(&["http://a.test"]).unwrap().method, "GET");
+        assert_eq!(params(&["delete", "http://a.test"]).unwrap().method, "DELETE");
+        assert_eq!(params(&["-X", "put", "post", "http://a.test"]).unwrap().method, "PUT");
+        assert_eq!(params(&["http://a.test", "-d", "{\"a\": 1}"]).unwrap().method, "POST");
+    }
+
+    #[test]
+    fn option_values_are_applied() {
+        let request = params(&[
+            "http://a.test", "-H", "Accept: text/plain", "--header=X-Id:  7 ", "-q", "page=2", "--query", "q=a=b",
+            "-d", "{\"a\": 1}", "--format", "cbor", "-t", "5", "--bearer", "abc",
+        ]).unwrap();
+        let headers = request.headers.unwrap();
+        assert_eq!(headers["Accept"], "text/plain");
+        assert_eq!(headers["X-Id"], "7");
+        let query = request.params.unwrap();
+        assert_eq!(query["page"], "2");
+        assert_eq!(query["q"], "a=b");
+        assert_eq!(request.body, Some(json!({"a": 1})));
+        assert_eq!(request.body_format, Some(BodyFormat::Cbor));
+        assert_eq!(request.timeout, Some(5));
+        assert_eq!(request.auth.unwrap().credentials, json!({"token": "abc"}));
+
+        // Body yang bukan JSON dikirim sebagai string, termasuk nilai yang mirip flag
+        assert_eq!(params(&["http://a.test", "-d", "--help"]).unwrap().body, Some(json!("--help")));
+        let auth = params(&["http://a.test", "-u", "user"]).unwrap().auth.unwrap();
+        assert_eq!(auth.credentials, json!({"username": "user", "password": ""}));
+    }
+
+    #[test]
+    fn invalid_arguments_are_errors() {
+        assert!(params(&[]).unwrap_err().starts_with("Missing URL"));
+        assert!(params(&["get", "http://a.test", "extra"]).unwrap_err().starts_with("Too many arguments"));
+        assert_eq!(params(&["http://a.test", "--bogus"]).unwrap_err(), "Unknown option: --bogus");
+        assert_eq!(params(&["http://a.test", "-H"]).unwrap_err(), "Missing value for -H");
+        assert_eq!(params(&["http://a.test", "--raw=1"]).unwrap_err(), "Unknown option: --raw=1");
+        assert_eq!(params(&["http://a.test", "-H", "Accept"]).unwrap_err(), "Expected 'Name: value' for --header: Accept");
+        assert_eq!(params(&["http://a.test", "-q", "page"]).unwrap_err(), "Expected name=value for --query: page");
+        assert_eq!(params(&["http://a.test", "-t", "soon"]).unwrap_err(), "Invalid number for --timeout: soon");
+        assert!(params(&["http://a.test", "--format", "xml"]).unwrap_err().starts_with("Unsupported body format: xml"));
+        assert!(params(&["http://a.test", "--auth", "{"]).unwrap_err().starts_with("Invalid --auth"));
+    }
+
+    #[test]
+    fn help_is_not_taken_from_option_values() {
+        let args = Args::parse(&strings(&["http://a.test", "-H", "--help"]), OPTIONS, FLAGS).unwrap();
+        assert!(!args.help());
+        assert!(Args::parse(&strings(&["-v", "--help"]), OPTIONS, FLAGS).unwrap().help());
+    }
+}
@@ -0,0 +1,280 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use fmus_core::collections::CollectionStore;
+use fmus_core::environment::{EnvVariable, Environment};
+use fmus_core::report::{self, ReporterConfig, ReporterKind};
+use fmus_core::runner::{load_data_file, RunEvent};
+use fmus_core::snapshot::{SnapshotMode, SnapshotStore};
+use fmus_core::{ClientConfig, HttpClient, RunOptions, RunPlan, Runner};
+use fmus_core::vault::Vault;
+
+use crate::args::Args;
+
+pub const USAGE: &str = "Usage: fmus run <collection.json> [options]
+
+The collection is an FMUS collection file (desktop JSON export) or a Postman collection v2.1.
+
+Options:
+  -e, --env <env.json>           Environment file (desktop export or {\"name\": \"value\"})
+      --var <name=value>         Set an environment variable, repeatable
+      --vault <vault.json>       Vault for secrets, unlocked with FMUS_VAULT_PASSPHRASE
+  -d, --data <file>              CSV or JSON data file, one iteration per row
+  -n, --iterations <count>       Number of iterations
+      --folder <id|name>         Run only one folder
+  -r, --reporter <type[:file]>   console, json, junit or html; repeatable (default console)
+      --bail                     Stop after the first failing request
+      --parallel                 Run folders without an execution mode in parallel
+      --max-concurrency <count>  Requests running at the same time in parallel folders
+      --delay <ms>               Delay between requests
+      --iteration-delay <ms>     Delay between iterations
+  -t, --timeout <seconds>        Timeout for requests that do not set their own
+      --snapshots <dir>          Compare responses with snapshots stored in this directory
+      --update-snapshots         Overwrite snapshots with the current responses
+      --export-environment <file>  Write the environment with variables set by scripts and captures
+      --upload-dir <dir>         Allow multipart file fields to read files inside this directory
+  -q, --quiet                    Do not print progress";
+
+const OPTIONS: &[&str] = &[
+    "-e", "--env", "--var", "--vault", "-d", "--data", "-n", "--iterations", "--folder", "-r", "--reporter",
+    "--max-concurrency", "--delay", "--iteration-delay", "-t", "--timeout", "--snapshots", "--export-environment",
+    "--upload-dir",
+];
+const FLAGS: &[&str] = &["--bail", "--parallel", "--update-snapshots", "-q", "--quiet"];
+
+// Return false jika ada request yang gagal
+pub async fn run(raw: &[String]) -> Result<bool, String> {
+    let args = Args::parse(raw, OPTIONS, FLAGS)?;
+    if args.help() {
+        println!("{}", USAGE);
+        return Ok(true);
+    }
+    let [path] = args.positionals() else {
+        return Err(format!("Expected one collection file\n\n{}", USAGE));
+    };
+
+    let bundle = crate::load_collection(Path::new(path))?;
+    let mut vault = crate::load_vault(&args)?;
+    let mut environment = match args.value(&["-e", "--env"]) {
+        Some(path) => Some(Environment::load(Path::new(path))?),
+        None => None,
+    };
+    let mut variables = match &environment {
+        Some(environment) => environment.resolve_variables(vault.as_ref())?,
+        None => HashMap::new(),
+    };
+    for pair in args.values(&["--var"]) {
+        let (name, value) = crate::parse_pair(pair, "--var")?;
+        variables.insert(name, value);
+    }
+
+    let folder_id = match args.value(&["--folder"]) {
+        Some(folder) => Some(bundle.folders.iter()
+            .find(|f| f.id == folder || f.name == folder)
+            .map(|f| f.id.clone())
+            .ok_or_else(|| format!("Folder not found: {}", folder))?),
+        None => None,
+    };
+    let collection_id = bundle.collection.id.clone();
+    let mut store = CollectionStore::new();
+    store.import_bundle(bundle);
+    let mut plan = match &folder_id {
+        Some(folder_id) => RunPlan::for_folder(&store, folder_id, vault.as_ref())?,
+        None => RunPlan::for_collection(&store, &collection_id, vault.as_ref())?,
+    };
+    if let Some(environment) = &environment {
+        plan.secrets.extend(environment.secret_values(vault.as_ref())?);
+    }
+
+    let options = run_options(&args)?;
+    let reporters = reporters(&args)?;
+    // Field file multipart hanya boleh membaca file dari --upload-dir
+    let client = HttpClient::new(args.value(&["--upload-dir"]).map(|dir| ClientConfig {
+        base_url: None,
+        timeout: Some(30),
+        headers: None,
+        rate_limit: None,
+        upload_dir: Some(PathBuf::from(dir)),
+    }));
+    let runner = Runner::new(&client, options);
+    let runner = match args.value(&["--snapshots"]) {
+        Some(dir) => runner.with_snapshots(SnapshotStore::new(PathBuf::from(dir).join(&collection_id))),
+        None => runner,
+    };
+
+    let quiet = args.flag(&["-q", "--quiet"]);
+    let result = runner.run(&plan, variables, |event| {
+        if let (false, RunEvent::RequestStarted { name, .. }) = (quiet, event) {
+            eprintln!("→ {}", name);
+        }
+    }).await;
+
+    report::write_reports(&result, &reporters)?;
+
+    if let (Some(environment), Some(output)) = (environment.as_mut(), args.value(&["--export-environment"])) {
+        export_environment(environment, &result.changes.environment, vault.as_mut(), &args)?;
+        environment.redacted().save(Path::new(output))?;
+    }
+
+    Ok(result.passed())
+}
+
+// Terapkan perubahan pm.environment ke environment yang akan diekspor. Secret yang tidak berubah
+// tetap memakai referensi vault-nya; nilai secret baru disegel sebagai entry baru karena entry lama
+// masih dipakai file environment asal. Tanpa vault nilai secret baru tidak ikut diekspor.
+fn export_environment(
+    environment: &mut Environment,
+    changes: &HashMap<String, Option<String>>,
+    mut vault: Option<&mut Vault>,
+    args: &Args,
+) -> Result<(), String> {
+    let mut sealed = false;
+    for (key, value) in changes {
+        let Some(value) = value else {
+            environment.remove_variable(key);
+            continue;
+        };
+
+        let existing = environment.variable(key);
+        let mut variable = EnvVariable {
+            enabled: existing.is_none_or(|v| v.enabled),
+            is_secret: existing.is_some_and(|v| v.is_secret),
+            ..EnvVariable::new(key, value)
+        };
+        if !variable.is_secret {
+            environment.set_variable(variable);
+            continue;
+        }
+
+        let existing = existing.expect("secret variable exists");
+        if existing.resolve(vault.as_deref()).is_ok_and(|current| current == *value) {
+            continue;
+        }
+        match vault.as_deref_mut() {
+            Some(vault) => sealed |= variable.seal(vault, &environment.name)?,
+            None => eprintln!("warning: secret {} was changed but is exported without a value (no vault to seal it)", key),
+        }
+        environment.set_variable(variable);
+    }
+
+    if let (true, Some(vault), Some(path)) = (sealed, vault, crate::vault_path(args)) {
+        vault.save(&path)?;
+    }
+    Ok(())
+}
+
+fn run_options(args: &Args) -> Result<RunOptions, String> {
+    let data = match args.value(&["-d", "--data"]) {
+        Some(path) => load_data_file(path)?,
+        None => Vec::new(),
+    };
+    let snapshot_mode = match (args.value(&["--snapshots"]), args.flag(&["--update-snapshots"])) {
+        (Some(_), true) => Some(SnapshotMode::Update),
+        (Some(_), false) => Some(SnapshotMode::Compare),
+        (None, true) => return Err("--update-snapshots requires --snapshots <dir>".to_string()),
+        (None, false) => None,
+    };
+
+    Ok(RunOptions {
+        bail: args.flag(&["--bail"]),
+        delay_ms: args.number(&["--delay"])?.unwrap_or(0),
+        parallel: args.flag(&["--parallel"]),
+        max_concurrency: args.number(&["--max-concurrency"])?,
+        iterations: args.number(&["-n", "--iterations"])?,
+        iteration_delay_ms: args.number(&["--iteration-delay"])?.unwrap_or(0),
+        data,
+        timeout: args.number(&["-t", "--timeout"])?,
+        snapshot_mode,
+        ..RunOptions::default()
+    })
+}
+
+// "junit" ditulis ke stdout, "junit:report.xml" ke file
+fn reporters(args: &Args) -> Result<Vec<ReporterConfig>, String> {
+    let values = args.values(&["-r", "--reporter"]);
+    if values.is_empty() {
+        return Ok(vec![ReporterConfig { kind: ReporterKind::Console, output: None }]);
+    }
+
+    values.into_iter()
+        .map(|value| {
+            let (kind, output) = match value.split_once(':') {
+                Some((kind, output)) => (kind, Some(PathBuf::from(output))),
+                None => (value, None),
+            };
+            let kind = serde_json::from_value(serde_json::Value::String(kind.to_string()))
+                .map_err(|_| format!("Unknown reporter: {} (expected console, json, junit or html)", kind))?;
+            Ok(ReporterConfig { kind, output })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::args::tests::strings;
+
+    fn parse(raw: &[&str]) -> Result<Args, String> {
+        Args::parse(&strings(raw), OPTIONS, FLAGS)
+    }
+
+    #[test]
+    fn run_options_follow_option_values() {
+        let args = parse(&[
+            "c.json", "--bail", "--parallel", "--max-concurrency", "4", "-n", "3", "--delay=100",
+            "--iteration-delay", "50", "-t", "10",
+        ]).unwrap();
+        let options = run_options(&args).unwrap();
+        assert!(options.bail && options.parallel);
+        assert_eq!(options.max_concurrency, Some(4));
+        assert_eq!(options.iterations, Some(3));
+        assert_eq!(options.delay_ms, 100);
+        assert_eq!(options.iteration_delay_ms, 50);
+        assert_eq!(options.timeout, Some(10));
+        assert!(options.snapshot_mode.is_none());
+
+        let options = run_options(&parse(&["c.json"]).unwrap()).unwrap();
+        assert!(!options.bail && !options.parallel);
+        assert_eq!(options.delay_ms, 0);
+
+        let args = parse(&["c.json", "--snapshots", "snaps", "--update-snapshots"]).unwrap();
+        assert!(matches!(run_options(&args).unwrap().snapshot_mode, Some(SnapshotMode::Update)));
+    }
+
+    #[test]
+    fn reporters_default_to_console() {
+        let console = reporters(&parse(&["c.json"]).unwrap()).unwrap();
+        assert!(matches!(console[..], [ReporterConfig { kind: ReporterKind::Console, output: None }]));
+
+        let configured = reporters(&parse(&["c.json", "-r", "junit:out/report.xml", "--reporter", "json"]).unwrap()).unwrap();
+        assert!(matches!(configured[0].kind, ReporterKind::Junit));
+        assert_eq!(configured[0].output, Some(PathBuf::from("out/report.xml")));
+        assert!(matches!(configured[1].kind, ReporterKind::Json));
+        assert_eq!(configured[1].output, None);
+    }
+
+    #[test]
+    fn invalid_arguments_are_errors() {
+        assert_eq!(parse(&["c.json", "--folders", "a"]).unwrap_err(), "Unknown option: --folders");
+        assert_eq!(parse(&["c.json", "--bail=true"]).unwrap_err(), "Unknown option: --bail=true");
+        assert_eq!(parse(&["c.json", "--reporter"]).unwrap_err(), "Missing value for --reporter");
+        assert_eq!(
+            run_options(&parse(&["c.json", "-n", "many"]).unwrap()).unwrap_err(),
+            "Invalid number for --iterations: many",
+        );
+        assert_eq!(
+            run_options(&parse(&["c.json", "--update-snapshots"]).unwrap()).unwrap_err(),
+            "--update-snapshots requires --snapshots <dir>",
+        );
+        assert!(reporters(&parse(&["c.json", "-r", "xml"]).unwrap()).unwrap_err().starts_with("Unknown reporter: xml"));
+    }
+
+    #[tokio::test]
+    async fn collection_file_is_required() {
+        assert!(run(&strings(&[])).await.unwrap_err().starts_with("Expected one collection file"));
+        assert!(run(&strings(&["a.json", "b.json"])).await.unwrap_err().starts_with("Expected one collection file"));
+        // --help tanpa file collection hanya mencetak usage
+        assert_eq!(run(&strings(&["--help"])).await, Ok(true));
+        assert!(run(&strings(&["-d", "--help"])).await.unwrap_err().starts_with("Expected one collection file"));
+    }
+}
@@ -0,0 +1,217 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::http::HttpResponse;
+use crate::jsonpath;
+use crate::scripting::ScriptScope;
+
+// Sumber nilai yang diambil dari response
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "from", rename_all = "snake_case")]
+pub enum CaptureSource {
+    // Node pertama yang cocok dengan JSONPath, string diambil tanpa tanda kutip
+    JsonPath { path: String },
+    Header { name: String },
+    // Cookie dari header Set-Cookie
+    Cookie { name: String },
+    // Regex pada body teks, default group 1 jika ada, selain itu seluruh match
+    Regex {
+        pattern: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        group: Option<usize>,
+    },
+    Status,
+}
+
+// Tempat nilai capture disimpan
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CaptureScope {
+    #[default]
+    Environment,
+    Collection,
+    // Variabel lokal yang hanya berlaku selama satu run
+    Run,
+}
+
+// Aturan capture, misalnya {"variable": "token", "from": "json_path", "path": "$.access_token"}
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Capture {
+    pub variable: String,
+    #[serde(flatten)]
+    pub source: CaptureSource,
+    #[serde(default)]
+    pub scope: CaptureScope,
+}
+
+// Hasil satu capture, value None jika nilainya tidak ditemukan
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CaptureResult {
+    pub variable: String,
+    pub scope: CaptureScope,
+    pub value: Option<String>,
+    pub error: Option<String>,
+}
+
+impl Capture {
+    // Ambil nilai dari response sesuai sumbernya
+    pub fn extract(&self, response: &HttpResponse) -> Result<String, String> {
+        match &self.source {
+            CaptureSource::JsonPath { path } => {
+                let json = response.json()?;
+                let nodes = jsonpath::query(&json, path)?;
+                match nodes.first() {
+                    Some(Value::String(text)) => Ok(text.clone()),
+                    Some(value) => Ok(value.to_string()),
+                    None => Err(format!("JSONPath '{}' matched nothing", path)),
+                }
+            },
+            CaptureSource::Header { name } => response.header(name)
+                .map(|value| value.to_string())
+                .ok_or_else(|| format!("Header '{}' not found in response", name)),
+            CaptureSource::Cookie { name } => response.header("set-cookie")
+                .and_then(|cookies| find_cookie(cookies, name))
+                .ok_or_else(|| format!("Cookie '{}' not found in response", name)),
+            CaptureSource::Regex { pattern, group } => {
+                let regex = regex::Regex::new(pattern)
+                    .map_err(|e| format!("Invalid regex '{}': {}", pattern, e))?;
+                let text = response.text_lossy();
+                let caps = regex.captures(&text)
+                    .ok_or_else(|| format!("Regex '{}' did not match the response body", pattern))?;
+                let index = group.unwrap_or(if caps.len() > 1 { 1 } else { 0 });
+                caps.get(index)
+                    .map(|m| m.as_str().to_string())
+                    .ok_or_else(|| format!("Regex '{}' has no group {}", pattern, index))
+            },
+            CaptureSource::Status => Ok(response.status.to_string()),
+        }
+    }
+}
+
+impl CaptureResult {
+    pub fn passed(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+// Jalankan semua capture terhadap response. Capture yang gagal tidak menghentikan capture lainnya.
+pub fn capture_all(response: &HttpResponse, captures: &[Capture]) -> Vec<CaptureResult> {
+    captures.iter()
+        .map(|capture| {
+            let (value, error) = match capture.extract(response) {
+                Ok(value) => (Some(value), None),
+                Err(e) => (None, Some(e)),
+            };
+            CaptureResult {
+                variable: capture.variable.clone(),
+                scope: capture.scope,
+                value,
+                error,
+            }
+        })
+        .collect()
+}
+
+// Simpan nilai capture yang berhasil ke scope variabel, sehingga bisa dipakai request berikutnya
+pub fn apply(results: &[CaptureResult], scope: &mut ScriptScope) {
+    for result in results {
+        if let Some(value) = &result.value {
+            let variables = match result.scope {
+                CaptureScope::Environment => &mut scope.environment,
+                CaptureScope::Collection => &mut scope.collection_variables,
+                CaptureScope::Run => &mut scope.variables,
+            };
+            variables.insert(result.variable.clone(), value.clone());
+        }
+    }
+}
+
+// Cari cookie di header Set-Cookie (satu cookie per baris), atribut seperti Path diabaikan
+fn find_cookie(cookies: &str, name: &str) -> Option<String> {
+    cookies.lines().find_map(|cookie| {
+        let pair = cookie.split(';').next()?;
+        let (key, value) = pair.split_once('=')?;
+        (key.trim() == name).then(|| value.trim().trim_matches('"').to_string())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use serde_json::json;
+
+    fn response() -> HttpResponse {
+        let headers = HashMap::from([
+            ("Content-Type".to_string(), "application/json".to_string()),
+            ("X-Request-Id".to_string(), "req-42".to_string()),
+            ("Set-Cookie".to_string(), "theme=dark; Path=/\nsession=\"abc123\"; HttpOnly".to_string()),
+        ]);
+        let body = json!({"access_token": "t0k3n", "user": {"id": 7, "roles": ["admin", "dev"]}});
+        HttpResponse { status: 201, headers, body: body.to_string().into_bytes(), time: 1 }
+    }
+
+    fn capture(source: Value) -> Capture {
+        let mut capture = json!({"variable": "v"});
+        capture.as_object_mut().unwrap().extend(source.as_object().unwrap().clone());
+        serde_json::from_value(capture).unwrap()
+    }
+
+    fn extract(source: Value) -> Result<String, String> {
+        capture(source).extract(&response())
+    }
+
+    #[test]
+    fn json_path_takes_the_first_node() {
+        assert_eq!(extract(json!({"from": "json_path", "path": "$.access_token"})).unwrap(), "t0k3n");
+        assert_eq!(extract(json!({"from": "json_path", "path": "user.id"})).unwrap(), "7");
+        assert_eq!(extract(json!({"from": "json_path", "path": "$.user.roles[*]"})).unwrap(), "admin");
+        assert_eq!(extract(json!({"from": "json_path", "path": "$.user.roles"})).unwrap(), r#"["admin","dev"]"#);
+    }
+
+    #[test]
+    fn header_cookie_regex_and_status() {
+        assert_eq!(extract(json!({"from": "header", "name": "x-request-id"})).unwrap(), "req-42");
+        assert_eq!(extract(json!({"from": "cookie", "name": "session"})).unwrap(), "abc123");
+        assert_eq!(extract(json!({"from": "cookie", "name": "theme"})).unwrap(), "dark");
+        assert_eq!(extract(json!({"from": "regex", "pattern": r#""id":(\d+)"#})).unwrap(), "7");
+        assert_eq!(extract(json!({"from": "regex", "pattern": r#""roles":\["(\w+)","(\w+)"#, "group": 2})).unwrap(), "dev");
+        assert_eq!(extract(json!({"from": "regex", "pattern": "t0k\\d+n"})).unwrap(), "t0k3n");
+        assert_eq!(extract(json!({"from": "status"})).unwrap(), "201");
+    }
+
+    #[test]
+    fn missing_values_are_errors() {
+        let error = |source: Value| extract(source).unwrap_err();
+
+        assert_eq!(error(json!({"from": "json_path", "path": "$.missing"})), "JSONPath '$.missing' matched nothing");
+        assert!(error(json!({"from": "json_path", "path": "$["})).starts_with("Invalid JSONPath"));
+        assert_eq!(error(json!({"from": "header", "name": "etag"})), "Header 'etag' not found in response");
+        assert_eq!(error(json!({"from": "cookie", "name": "missing"})), "Cookie 'missing' not found in response");
+        assert_eq!(error(json!({"from": "regex", "pattern": "nope"})), "Regex 'nope' did not match the response body");
+        assert_eq!(error(json!({"from": "regex", "pattern": "t0k", "group": 1})), "Regex 't0k' has no group 1");
+        assert!(error(json!({"from": "regex", "pattern": "("})).starts_with("Invalid regex '('"));
+
+        let mut text = response();
+        text.body = b"not json".to_vec();
+        assert!(capture(json!({"from": "json_path", "path": "$.a"})).extract(&text).is_err());
+    }
+
+    #[test]
+    fn failed_captures_do_not_stop_the_rest() {
+        let captures = vec![
+            capture(json!({"from": "header", "name": "missing"})),
+            Capture { variable: "token".to_string(), source: CaptureSource::JsonPath { path: "$.access_token".to_string() }, scope: CaptureScope::Collection },
+            Capture { variable: "status".to_string(), source: CaptureSource::Status, scope: CaptureScope::Run },
+        ];
+        let results = capture_all(&response(), &captures);
+        assert!(!results[0].passed());
+        assert!(results[1].passed() && results[2].passed());
+
+        let mut scope = ScriptScope::default();
+        apply(&results, &mut scope);
+        assert!(scope.environment.is_empty());
+        assert_eq!(scope.collection_variables.get("token").map(String::as_str), Some("t0k3n"));
+        assert_eq!(scope.variables.get("status").map(String::as_str), Some("201"));
+    }
+}
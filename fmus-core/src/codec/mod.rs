@@ -0,0 +1,267 @@
+mod proto;
+pub mod protobuf;
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Number, Value};
+
+pub use protobuf::{ProtoSchema, ProtoRegistry};
+
+// Format body request/response. Body selalu ditulis dan dibaca sebagai JSON,
+// lalu di-encode atau di-decode sesuai format ini.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "format", rename_all = "snake_case")]
+pub enum BodyFormat {
+    Json,
+    #[serde(rename = "msgpack")]
+    MsgPack,
+    Cbor,
+    // Pesan protobuf dengan nama lengkap (misalnya "shop.v1.Order") dari file .proto atau descriptor set
+    Protobuf {
+        schema: ProtoSchema,
+        message: String,
+    },
+    // String dikirim apa adanya
+    Text,
+    // Object {"nama": "nilai"} sebagai application/x-www-form-urlencoded, nilai array menjadi field berulang
+    Form,
+    // Object {"nama": "nilai"} sebagai multipart/form-data. Nilai berupa {"file": "path", "filename": ..., "content_type": ...}
+    // dibaca dari file saat request dikirim.
+    Multipart,
+}
+
+impl BodyFormat {
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            BodyFormat::Json => "application/json",
+            BodyFormat::MsgPack => "application/msgpack",
+            BodyFormat::Cbor => "application/cbor",
+            BodyFormat::Protobuf { .. } => "application/x-protobuf",
+            BodyFormat::Text => "text/plain; charset=utf-8",
+            BodyFormat::Form => "application/x-www-form-urlencoded",
+            BodyFormat::Multipart => "multipart/form-data",
+        }
+    }
+
+    // Format dari Content-Type response. Protobuf tidak bisa dikenali tanpa schema.
+    pub fn from_content_type(content_type: &str) -> Option<BodyFormat> {
+        let essence = content_type.split(';').next().unwrap_or_default().trim().to_ascii_lowercase();
+        match essence.as_str() {
+            "application/msgpack" | "application/x-msgpack" | "application/vnd.msgpack" => Some(BodyFormat::MsgPack),
+            "application/cbor" => Some(BodyFormat::Cbor),
+            _ if essence == "application/json" || essence.ends_with("+json") => Some(BodyFormat::Json),
+            _ => None,
+        }
+    }
+}
+
+// Encode nilai JSON ke format body
+pub fn encode(value: &Value, format: &BodyFormat) -> Result<Vec<u8>, String> {
+    match format {
+        BodyFormat::Json => serde_json::to_vec(value).map_err(|e| format!("Failed to encode JSON body: {}", e)),
+        BodyFormat::MsgPack => rmp_serde::to_vec_named(value).map_err(|e| format!("Failed to encode MessagePack body: {}", e)),
+        BodyFormat::Cbor => {
+            let mut bytes = Vec::new();
+            ciborium::ser::into_writer(value, &mut bytes).map_err(|e| format!("Failed to encode CBOR body: {}", e))?;
+            Ok(bytes)
+        },
+        BodyFormat::Protobuf { schema, message } => schema.load()?.encode(message, value),
+        BodyFormat::Text => Ok(text_value(value).into_bytes()),
+        BodyFormat::Form => {
+            let mut serializer = url::form_urlencoded::Serializer::new(String::new());
+            for (key, value) in form_fields(value)? {
+                serializer.append_pair(key, &text_value(value));
+            }
+            Ok(serializer.finish().into_bytes())
+        },
+        // Boundary dan isi file disusun oleh HttpClient
+        BodyFormat::Multipart => Err("Multipart bodies can only be sent by the HTTP client".to_string()),
+    }
+}
+
+// Decode body ke JSON. Byte string menjadi base64, key map yang bukan string diubah menjadi string.
+pub fn decode(bytes: &[u8], format: &BodyFormat) -> Result<Value, String> {
+    match format {
+        BodyFormat::Json => serde_json::from_slice(bytes).map_err(|e| format!("Failed to parse response body as JSON: {}", e)),
+        BodyFormat::MsgPack => {
+            let value = rmpv::decode::read_value(&mut &bytes[..])
+                .map_err(|e| format!("Failed to decode MessagePack body: {}", e))?;
+            Ok(msgpack_to_json(value))
+        },
+        BodyFormat::Cbor => {
+            let value: ciborium::Value = ciborium::de::from_reader(bytes)
+                .map_err(|e| format!("Failed to decode CBOR body: {}", e))?;
+            Ok(cbor_to_json(value))
+        },
+        BodyFormat::Protobuf { schema, message } => schema.load()?.decode(message, bytes),
+        BodyFormat::Text => String::from_utf8(bytes.to_vec())
+            .map(Value::String)
+            .map_err(|e| format!("Response body is not valid UTF-8: {}", e)),
+        BodyFormat::Form => {
+            let mut fields = Map::new();
+            for (key, value) in url::form_urlencoded::parse(bytes) {
+                match fields.get_mut(key.as_ref()) {
+                    Some(Value::Array(values)) => values.push(Value::String(value.into_owned())),
+                    Some(existing) => *existing = Value::Array(vec![existing.take(), Value::String(value.into_owned())]),
+                    None => {
+                        fields.insert(key.into_owned(), Value::String(value.into_owned()));
+                    },
+                }
+            }
+            Ok(Value::Object(fields))
+        },
+        BodyFormat::Multipart => Err("Decoding multipart bodies is not supported".to_string()),
+    }
+}
+
+// Nilai sebagai teks: string apa adanya, nilai lain sebagai JSON
+pub(crate) fn text_value(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+// Pasangan field dari body form/multipart (object), nilai array menjadi field berulang
+pub(crate) fn form_fields(value: &Value) -> Result<Vec<(&str, &Value)>, String> {
+    let fields = value.as_object().ok_or("Form body must be a JSON object")?;
+    Ok(fields.iter()
+        .flat_map(|(key, value)| match value {
+            Value::Array(values) => values.iter().map(|value| (key.as_str(), value)).collect(),
+            value => vec![(key.as_str(), value)],
+        })
+        .collect())
+}
+
+fn float_to_json(value: f64) -> Value {
+    // NaN dan Infinity tidak ada di JSON
+    Number::from_f64(value).map(Value::Number).unwrap_or(Value::Null)
+}
+
+fn key_to_string(key: Value) -> String {
+    match key {
+        Value::String(text) => text,
+        other => other.to_string(),
+    }
+}
+
+fn msgpack_to_json(value: rmpv::Value) -> Value {
+    match value {
+        rmpv::Value::Nil => Value::Null,
+        rmpv::Value::Boolean(b) => Value::Bool(b),
+        rmpv::Value::Integer(n) => n.as_i64()
+            .map(Value::from)
+            .or_else(|| n.as_u64().map(Value::from))
+            .unwrap_or(Value::Null),
+        rmpv::Value::F32(f) => float_to_json(f as f64),
+        rmpv::Value::F64(f) => float_to_json(f),
+        rmpv::Value::String(text) => match text.into_str() {
+            Some(text) => Value::String(text),
+            None => Value::Null,
+        },
+        rmpv::Value::Binary(bytes) => Value::String(BASE64.encode(bytes)),
+        rmpv::Value::Array(items) => Value::Array(items.into_iter().map(msgpack_to_json).collect()),
+        rmpv::Value::Map(entries) => Value::Object(
+            entries.into_iter()
+                .map(|(key, value)| (key_to_string(msgpack_to_json(key)), msgpack_to_json(value)))
+                .collect::<Map<_, _>>(),
+        ),
+        // Tipe extension ditampilkan sebagai tipe dan data base64
+        rmpv::Value::Ext(kind, data) => serde_json::json!({ "ext": kind, "data": BASE64.encode(data) }),
+    }
+}
+
+fn cbor_to_json(value: ciborium::Value) -> Value {
+    match value {
+        ciborium::Value::Null => Value::Null,
+        ciborium::Value::Bool(b) => Value::Bool(b),
+        ciborium::Value::Integer(n) => {
+            let n = i128::from(n);
+            i64::try_from(n)
+                .map(Value::from)
+                .or_else(|_| u64::try_from(n).map(Value::from))
+                .unwrap_or_else(|_| Value::String(n.to_string()))
+        },
+        ciborium::Value::Float(f) => float_to_json(f),
+        ciborium::Value::Text(text) => Value::String(text),
+        ciborium::Value::Bytes(bytes) => Value::String(BASE64.encode(bytes)),
+        ciborium::Value::Array(items) => Value::Array(items.into_iter().map(cbor_to_json).collect()),
+        ciborium::Value::Map(entries) => Value::Object(
+            entries.into_iter()
+                .map(|(key, value)| (key_to_string(cbor_to_json(key)), cbor_to_json(value)))
+                .collect::<Map<_, _>>(),
+        ),
+        // Tag semantik (misalnya tanggal) diabaikan, hanya nilainya yang dipakai
+        ciborium::Value::Tag(_, value) => cbor_to_json(*value),
+        _ => Value::Null,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn binary_formats_round_trip() {
+        let value = json!({"id": 7, "name": "fmus", "tags": ["a", "b"], "price": 1.5, "active": true, "note": null, "big": u64::MAX});
+        for format in [BodyFormat::Json, BodyFormat::MsgPack, BodyFormat::Cbor] {
+            let bytes = encode(&value, &format).unwrap();
+            assert_eq!(decode(&bytes, &format).unwrap(), value, "{:?}", format);
+        }
+    }
+
+    #[test]
+    fn binary_values_become_base64_and_keys_become_strings() {
+        let mut bytes = Vec::new();
+        let map = rmpv::Value::Map(vec![(rmpv::Value::from(1), rmpv::Value::Binary(vec![1, 2, 3]))]);
+        rmpv::encode::write_value(&mut bytes, &map).unwrap();
+        assert_eq!(decode(&bytes, &BodyFormat::MsgPack).unwrap(), json!({"1": "AQID"}));
+
+        let mut bytes = Vec::new();
+        ciborium::ser::into_writer(&ciborium::Value::Tag(1, Box::new(ciborium::Value::Float(f64::NAN))), &mut bytes).unwrap();
+        assert_eq!(decode(&bytes, &BodyFormat::Cbor).unwrap(), Value::Null);
+    }
+
+    #[test]
+    fn form_fields_repeat_for_arrays() {
+        let value = json!({"a": "x y", "b": ["1", 2]});
+        let bytes = encode(&value, &BodyFormat::Form).unwrap();
+        assert_eq!(bytes, b"a=x+y&b=1&b=2");
+        assert_eq!(decode(&bytes, &BodyFormat::Form).unwrap(), json!({"a": "x y", "b": ["1", "2"]}));
+        assert!(encode(&json!([1]), &BodyFormat::Form).is_err());
+    }
+
+    #[test]
+    fn text_is_sent_as_is() {
+        assert_eq!(encode(&json!("plain"), &BodyFormat::Text).unwrap(), b"plain");
+        assert_eq!(encode(&json!({"a": 1}), &BodyFormat::Text).unwrap(), br#"{"a":1}"#);
+        assert!(decode(&[0xff], &BodyFormat::Text).is_err());
+    }
+
+    #[test]
+    fn protobuf_uses_proto_files() {
+        let dir = std::env::temp_dir().join(format!("fmus-codec-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("shop.proto");
+        std::fs::write(&path, r#"
+            syntax = "proto3";
+            package shop.v1;
+            message Order {
+                string order_id = 1;
+                repeated int32 quantities = 2;
+            }
+        "#).unwrap();
+
+        let format = BodyFormat::Protobuf {
+            schema: ProtoSchema { path, include_paths: Vec::new() },
+            message: "shop.v1.Order".to_string(),
+        };
+        let value = json!({"orderId": "A-1", "quantities": [1, 2]});
+        let bytes = encode(&json!({"order_id": "A-1", "quantities": [1, 2]}), &format).unwrap();
+        assert_eq!(decode(&bytes, &format).unwrap(), value);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
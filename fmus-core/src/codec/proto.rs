@@ -0,0 +1,606 @@
+// Parser file .proto (proto2 dan proto3) menjadi FileDescriptorProto.
+// Yang dibaca hanya yang dibutuhkan untuk encode/decode: package, import, message, enum,
+// field (termasuk map dan oneof) serta opsi field packed, default dan json_name.
+// Service, extend dan opsi lainnya dilewati.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use prost_reflect::prost_types::field_descriptor_proto::{Label, Type};
+use prost_reflect::prost_types::{
+    DescriptorProto, EnumDescriptorProto, EnumValueDescriptorProto, FieldDescriptorProto, FieldOptions,
+    FileDescriptorProto, MessageOptions, OneofDescriptorProto,
+};
+use prost_reflect::DescriptorPool;
+
+// Membaca file .proto beserta semua import-nya. Import dicari relatif terhadap direktori file utama
+// lalu include_paths. Import yang tidak ada di disk tapi sudah ada di pool (tipe well-known) dilewati.
+pub(super) fn parse_files(
+    path: &Path,
+    include_paths: &[PathBuf],
+    pool: &DescriptorPool,
+) -> Result<Vec<FileDescriptorProto>, String> {
+    let mut roots = Vec::new();
+    if let Some(parent) = path.parent() {
+        roots.push(parent.to_path_buf());
+    }
+    roots.extend(include_paths.iter().cloned());
+
+    let name = path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .ok_or_else(|| format!("Invalid proto path: {}", path.display()))?;
+
+    let mut files = Vec::new();
+    let mut seen = HashSet::new();
+    load_file(&name, Some(path.to_path_buf()), &roots, pool, &mut seen, &mut files)?;
+    Ok(files)
+}
+
+fn load_file(
+    name: &str,
+    path: Option<PathBuf>,
+    roots: &[PathBuf],
+    pool: &DescriptorPool,
+    seen: &mut HashSet<String>,
+    files: &mut Vec<FileDescriptorProto>,
+) -> Result<(), String> {
+    if !seen.insert(name.to_string()) {
+        return Ok(());
+    }
+
+    let path = match path.or_else(|| roots.iter().map(|root| root.join(name)).find(|path| path.is_file())) {
+        Some(path) => path,
+        None if pool.get_file_by_name(name).is_some() => return Ok(()),
+        None => return Err(format!("Imported proto file not found: {}", name)),
+    };
+
+    let source = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read proto file {}: {}", path.display(), e))?;
+    let file = parse(name, &source).map_err(|e| format!("{}: {}", path.display(), e))?;
+
+    for dependency in &file.dependency {
+        load_file(dependency, None, roots, pool, seen, files)?;
+    }
+    files.push(file);
+
+    Ok(())
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    // Identifier (boleh bertitik seperti "google.protobuf.Any") atau angka
+    Word(String),
+    Str(String),
+    Symbol(char),
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = source.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            },
+            '/' => {
+                chars.next();
+                match chars.next() {
+                    Some('/') => {
+                        for c in chars.by_ref() {
+                            if c == '\n' {
+                                break;
+                            }
+                        }
+                    },
+                    Some('*') => {
+                        let mut previous = ' ';
+                        loop {
+                            match chars.next() {
+                                Some('/') if previous == '*' => break,
+                                Some(c) => previous = c,
+                                None => return Err("Unterminated block comment".to_string()),
+                            }
+                        }
+                    },
+                    _ => return Err("Unexpected '/'".to_string()),
+                }
+            },
+            '"' | '\'' => {
+                chars.next();
+                let mut text = String::new();
+                loop {
+                    match chars.next() {
+                        Some(q) if q == c => break,
+                        Some('\\') => match chars.next() {
+                            Some('n') => text.push('\n'),
+                            Some('t') => text.push('\t'),
+                            Some('r') => text.push('\r'),
+                            Some('0') => text.push('\0'),
+                            Some(other) => text.push(other),
+                            None => return Err("Unterminated string".to_string()),
+                        },
+                        Some(other) => text.push(other),
+                        None => return Err("Unterminated string".to_string()),
+                    }
+                }
+                // String yang berdampingan digabung, seperti di C
+                match tokens.last_mut() {
+                    Some(Token::Str(previous)) => previous.push_str(&text),
+                    _ => tokens.push(Token::Str(text)),
+                }
+            },
+            c if c.is_alphanumeric() || c == '_' || c == '.' || c == '-' || c == '+' => {
+                let mut word = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_alphanumeric() || c == '_' || c == '.' || ((c == '-' || c == '+') && (word.is_empty() || word.ends_with(['e', 'E']))) {
+                        word.push(c);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                tokens.push(Token::Word(word));
+            },
+            '=' | ';' | '{' | '}' | '[' | ']' | '<' | '>' | ',' | '(' | ')' | ':' => {
+                tokens.push(Token::Symbol(c));
+                chars.next();
+            },
+            other => return Err(format!("Unexpected character '{}'", other)),
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    proto3: bool,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Result<Token, String> {
+        let token = self.tokens.get(self.pos).cloned().ok_or("Unexpected end of file")?;
+        self.pos += 1;
+        Ok(token)
+    }
+
+    fn is_symbol(&self, symbol: char) -> bool {
+        self.peek() == Some(&Token::Symbol(symbol))
+    }
+
+    fn is_word(&self, word: &str) -> bool {
+        matches!(self.peek(), Some(Token::Word(w)) if w == word)
+    }
+
+    fn expect_symbol(&mut self, symbol: char) -> Result<(), String> {
+        match self.next()? {
+            Token::Symbol(c) if c == symbol => Ok(()),
+            other => Err(format!("Expected '{}' but found {}", symbol, describe(&other))),
+        }
+    }
+
+    fn word(&mut self) -> Result<String, String> {
+        match self.next()? {
+            Token::Word(word) => Ok(word),
+            other => Err(format!("Expected identifier but found {}", describe(&other))),
+        }
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        match self.next()? {
+            Token::Str(text) => Ok(text),
+            other => Err(format!("Expected string but found {}", describe(&other))),
+        }
+    }
+
+    fn number(&mut self) -> Result<i32, String> {
+        let word = self.word()?;
+        parse_int(&word).ok_or_else(|| format!("Invalid number: {}", word))
+    }
+
+    // Lewati statement sampai ';', termasuk nilai opsi berupa blok { ... }
+    fn skip_statement(&mut self) -> Result<(), String> {
+        loop {
+            match self.next()? {
+                Token::Symbol(';') => return Ok(()),
+                Token::Symbol('{') => {
+                    self.pos -= 1;
+                    self.skip_block()?;
+                },
+                _ => {},
+            }
+        }
+    }
+
+    // Lewati deklarasi sampai blok { ... } pasangannya selesai
+    fn skip_block(&mut self) -> Result<(), String> {
+        while !self.is_symbol('{') {
+            self.next()?;
+        }
+        let mut depth = 0;
+        loop {
+            match self.next()? {
+                Token::Symbol('{') => depth += 1,
+                Token::Symbol('}') => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Ok(());
+                    }
+                },
+                _ => {},
+            }
+        }
+    }
+
+    fn parse_file(&mut self, name: &str) -> Result<FileDescriptorProto, String> {
+        let mut file = FileDescriptorProto {
+            name: Some(name.to_string()),
+            ..FileDescriptorProto::default()
+        };
+
+        while let Some(token) = self.peek().cloned() {
+            match token {
+                Token::Symbol(';') => {
+                    self.next()?;
+                },
+                Token::Word(word) => match word.as_str() {
+                    "syntax" => {
+                        self.next()?;
+                        self.expect_symbol('=')?;
+                        let syntax = self.string()?;
+                        match syntax.as_str() {
+                            "proto2" | "proto3" => {},
+                            other => return Err(format!("Unsupported syntax: {}", other)),
+                        }
+                        self.proto3 = syntax == "proto3";
+                        file.syntax = Some(syntax);
+                        self.expect_symbol(';')?;
+                    },
+                    "edition" => return Err("Protobuf editions are not supported".to_string()),
+                    "package" => {
+                        self.next()?;
+                        file.package = Some(self.word()?);
+                        self.expect_symbol(';')?;
+                    },
+                    "import" => {
+                        self.next()?;
+                        let public = self.is_word("public");
+                        if public || self.is_word("weak") {
+                            self.next()?;
+                        }
+                        if public {
+                            file.public_dependency.push(file.dependency.len() as i32);
+                        }
+                        file.dependency.push(self.string()?);
+                        self.expect_symbol(';')?;
+                    },
+                    "option" => self.skip_statement()?,
+                    "message" => {
+                        self.next()?;
+                        let name = self.word()?;
+                        file.message_type.push(self.parse_message(name)?);
+                    },
+                    "enum" => {
+                        self.next()?;
+                        let name = self.word()?;
+                        file.enum_type.push(self.parse_enum(name)?);
+                    },
+                    "service" | "extend" => self.skip_block()?,
+                    other => return Err(format!("Unexpected '{}' at top level", other)),
+                },
+                other => return Err(format!("Unexpected {} at top level", describe(&other))),
+            }
+        }
+
+        Ok(file)
+    }
+
+    fn parse_message(&mut self, name: String) -> Result<DescriptorProto, String> {
+        let mut message = DescriptorProto {
+            name: Some(name),
+            ..DescriptorProto::default()
+        };
+        self.expect_symbol('{')?;
+
+        while !self.is_symbol('}') {
+            let word = match self.next()? {
+                Token::Symbol(';') => continue,
+                Token::Word(word) => word,
+                other => return Err(format!("Unexpected {} in message", describe(&other))),
+            };
+
+            match word.as_str() {
+                "message" => {
+                    let name = self.word()?;
+                    message.nested_type.push(self.parse_message(name)?);
+                },
+                "enum" => {
+                    let name = self.word()?;
+                    message.enum_type.push(self.parse_enum(name)?);
+                },
+                "oneof" => {
+                    let index = message.oneof_decl.len() as i32;
+                    message.oneof_decl.push(OneofDescriptorProto {
+                        name: Some(self.word()?),
+                        ..OneofDescriptorProto::default()
+                    });
+                    self.expect_symbol('{')?;
+                    while !self.is_symbol('}') {
+                        if self.is_symbol(';') {
+                            self.next()?;
+                        } else if self.is_word("option") {
+                            self.skip_statement()?;
+                        } else {
+                            let type_name = self.word()?;
+                            self.parse_field(&mut message, None, type_name, Some(index))?;
+                        }
+                    }
+                    self.expect_symbol('}')?;
+                },
+                "option" | "reserved" | "extensions" => self.skip_statement()?,
+                "extend" => self.skip_block()?,
+                "group" => return Err("Proto2 groups are not supported".to_string()),
+                "optional" | "required" | "repeated" => {
+                    let label = match word.as_str() {
+                        "optional" => Label::Optional,
+                        "required" => Label::Required,
+                        _ => Label::Repeated,
+                    };
+                    let type_name = self.word()?;
+                    self.parse_field(&mut message, Some(label), type_name, None)?;
+                },
+                _ => self.parse_field(&mut message, None, word, None)?,
+            }
+        }
+        self.expect_symbol('}')?;
+
+        // Field proto3 "optional" memakai oneof sintetis, diletakkan setelah oneof biasa seperti protoc
+        for index in 0..message.field.len() {
+            if message.field[index].proto3_optional == Some(true) {
+                let field_name = message.field[index].name().to_string();
+                message.field[index].oneof_index = Some(message.oneof_decl.len() as i32);
+                message.oneof_decl.push(OneofDescriptorProto {
+                    name: Some(format!("_{}", field_name)),
+                    ..OneofDescriptorProto::default()
+                });
+            }
+        }
+
+        Ok(message)
+    }
+
+    fn parse_field(
+        &mut self,
+        message: &mut DescriptorProto,
+        label: Option<Label>,
+        type_name: String,
+        oneof_index: Option<i32>,
+    ) -> Result<(), String> {
+        let mut field = FieldDescriptorProto::default();
+
+        if type_name == "map" && self.is_symbol('<') {
+            // map<K, V> menjadi field repeated berisi pesan entry {key = 1; value = 2}
+            self.expect_symbol('<')?;
+            let key_type = self.word()?;
+            self.expect_symbol(',')?;
+            let value_type = self.word()?;
+            self.expect_symbol('>')?;
+
+            let name = self.word()?;
+            let entry_name = format!("{}Entry", to_pascal_case(&name));
+            let mut entry = DescriptorProto {
+                name: Some(entry_name.clone()),
+                options: Some(MessageOptions { map_entry: Some(true), ..MessageOptions::default() }),
+                ..DescriptorProto::default()
+            };
+            for (entry_field, entry_type, number) in [("key", key_type, 1), ("value", value_type, 2)] {
+                let mut entry_field = FieldDescriptorProto {
+                    name: Some(entry_field.to_string()),
+                    number: Some(number),
+                    label: Some(Label::Optional as i32),
+                    ..FieldDescriptorProto::default()
+                };
+                set_field_type(&mut entry_field, &entry_type);
+                entry.field.push(entry_field);
+            }
+            message.nested_type.push(entry);
+
+            field.name = Some(name);
+            field.label = Some(Label::Repeated as i32);
+            field.type_name = Some(entry_name);
+        } else {
+            field.name = Some(self.word()?);
+            field.label = Some(match label {
+                Some(Label::Optional) if self.proto3 => {
+                    field.proto3_optional = Some(true);
+                    Label::Optional
+                },
+                Some(label) => label,
+                None => Label::Optional,
+            } as i32);
+            set_field_type(&mut field, &type_name);
+        }
+
+        self.expect_symbol('=')?;
+        field.number = Some(self.number()?);
+        field.oneof_index = oneof_index;
+
+        if self.is_symbol('[') {
+            self.parse_field_options(&mut field)?;
+        }
+        self.expect_symbol(';')?;
+
+        message.field.push(field);
+        Ok(())
+    }
+
+    // Opsi field [packed = true, default = 1, json_name = "x"], opsi lain dilewati
+    fn parse_field_options(&mut self, field: &mut FieldDescriptorProto) -> Result<(), String> {
+        self.expect_symbol('[')?;
+        loop {
+            let name = match self.next()? {
+                Token::Word(word) => word,
+                // Opsi ekstensi seperti (validate.rules).string
+                Token::Symbol('(') => {
+                    while !self.is_symbol(')') {
+                        self.next()?;
+                    }
+                    self.next()?;
+                    if let Some(Token::Word(_)) = self.peek() {
+                        self.next()?;
+                    }
+                    String::new()
+                },
+                other => return Err(format!("Unexpected {} in field options", describe(&other))),
+            };
+            self.expect_symbol('=')?;
+
+            let value = match self.next()? {
+                Token::Word(word) => word,
+                Token::Str(text) => text,
+                Token::Symbol('{') => {
+                    self.pos -= 1;
+                    self.skip_block()?;
+                    String::new()
+                },
+                other => return Err(format!("Unexpected {} in field options", describe(&other))),
+            };
+
+            match name.as_str() {
+                "packed" => {
+                    field.options.get_or_insert_with(FieldOptions::default).packed = Some(value == "true");
+                },
+                "default" => field.default_value = Some(value),
+                "json_name" => field.json_name = Some(value),
+                _ => {},
+            }
+
+            match self.next()? {
+                Token::Symbol(',') => continue,
+                Token::Symbol(']') => return Ok(()),
+                other => return Err(format!("Expected ',' or ']' but found {}", describe(&other))),
+            }
+        }
+    }
+
+    fn parse_enum(&mut self, name: String) -> Result<EnumDescriptorProto, String> {
+        let mut descriptor = EnumDescriptorProto {
+            name: Some(name),
+            ..EnumDescriptorProto::default()
+        };
+        self.expect_symbol('{')?;
+
+        while !self.is_symbol('}') {
+            if self.is_symbol(';') {
+                self.next()?;
+                continue;
+            }
+            if self.is_word("option") || self.is_word("reserved") {
+                self.skip_statement()?;
+                continue;
+            }
+
+            let name = self.word()?;
+            self.expect_symbol('=')?;
+            let number = self.number()?;
+            if self.is_symbol('[') {
+                while !self.is_symbol(']') {
+                    self.next()?;
+                }
+                self.next()?;
+            }
+            self.expect_symbol(';')?;
+
+            descriptor.value.push(EnumValueDescriptorProto {
+                name: Some(name),
+                number: Some(number),
+                ..EnumValueDescriptorProto::default()
+            });
+        }
+        self.expect_symbol('}')?;
+
+        Ok(descriptor)
+    }
+}
+
+fn parse(name: &str, source: &str) -> Result<FileDescriptorProto, String> {
+    let mut parser = Parser {
+        tokens: tokenize(source)?,
+        pos: 0,
+        proto3: false,
+    };
+    parser.parse_file(name)
+}
+
+// Tipe skalar langsung diisi, tipe pesan/enum diisi namanya dan di-resolve oleh DescriptorPool
+fn set_field_type(field: &mut FieldDescriptorProto, type_name: &str) {
+    let scalar = match type_name {
+        "double" => Type::Double,
+        "float" => Type::Float,
+        "int32" => Type::Int32,
+        "int64" => Type::Int64,
+        "uint32" => Type::Uint32,
+        "uint64" => Type::Uint64,
+        "sint32" => Type::Sint32,
+        "sint64" => Type::Sint64,
+        "fixed32" => Type::Fixed32,
+        "fixed64" => Type::Fixed64,
+        "sfixed32" => Type::Sfixed32,
+        "sfixed64" => Type::Sfixed64,
+        "bool" => Type::Bool,
+        "string" => Type::String,
+        "bytes" => Type::Bytes,
+        _ => {
+            field.type_name = Some(type_name.to_string());
+            return;
+        },
+    };
+    field.set_type(scalar);
+}
+
+fn parse_int(word: &str) -> Option<i32> {
+    let (negative, digits) = match word.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, word.trim_start_matches('+')),
+    };
+    let value = if let Some(hex) = digits.strip_prefix("0x").or_else(|| digits.strip_prefix("0X")) {
+        i64::from_str_radix(hex, 16).ok()?
+    } else if digits.len() > 1 && digits.starts_with('0') {
+        i64::from_str_radix(&digits[1..], 8).ok()?
+    } else {
+        digits.parse().ok()?
+    };
+    i32::try_from(if negative { -value } else { value }).ok()
+}
+
+// "user_id" -> "UserId" untuk nama pesan entry map
+fn to_pascal_case(name: &str) -> String {
+    let mut result = String::with_capacity(name.len());
+    let mut capitalize = true;
+    for c in name.chars() {
+        if c == '_' {
+            capitalize = true;
+        } else if capitalize {
+            result.extend(c.to_uppercase());
+            capitalize = false;
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+fn describe(token: &Token) -> String {
+    match token {
+        Token::Word(word) => format!("'{}'", word),
+        Token::Str(text) => format!("\"{}\"", text),
+        Token::Symbol(c) => format!("'{}'", c),
+    }
+}
@@ -0,0 +1,78 @@
+use std::fs;
+use std::path::PathBuf;
+
+use prost_reflect::prost::Message;
+use prost_reflect::{DescriptorPool, DynamicMessage, MessageDescriptor};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::proto;
+
+// Sumber definisi pesan protobuf, tanpa langkah codegen
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProtoSchema {
+    // File .proto, atau descriptor set hasil `protoc --descriptor_set_out` untuk ekstensi lain
+    pub path: PathBuf,
+    // Direktori tambahan untuk mencari file yang di-import oleh file .proto
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub include_paths: Vec<PathBuf>,
+}
+
+impl ProtoSchema {
+    // Membaca schema menjadi registry pesan. Tipe well-known (google/protobuf/*.proto) sudah tersedia.
+    pub fn load(&self) -> Result<ProtoRegistry, String> {
+        let mut pool = DescriptorPool::global();
+
+        if self.path.extension().is_some_and(|ext| ext == "proto") {
+            let files = proto::parse_files(&self.path, &self.include_paths, &pool)?;
+            pool.add_file_descriptor_protos(files)
+                .map_err(|e| format!("Invalid proto file {}: {}", self.path.display(), e))?;
+        } else {
+            let bytes = fs::read(&self.path)
+                .map_err(|e| format!("Failed to read descriptor set {}: {}", self.path.display(), e))?;
+            pool.decode_file_descriptor_set(bytes.as_slice())
+                .map_err(|e| format!("Invalid descriptor set {}: {}", self.path.display(), e))?;
+        }
+
+        Ok(ProtoRegistry { pool })
+    }
+}
+
+// Kumpulan definisi pesan yang sudah dimuat, bisa dipakai ulang untuk banyak request
+#[derive(Debug, Clone)]
+pub struct ProtoRegistry {
+    pool: DescriptorPool,
+}
+
+impl ProtoRegistry {
+    // Nama lengkap semua pesan yang tersedia, tanpa tipe well-known
+    pub fn messages(&self) -> Vec<String> {
+        self.pool.all_messages()
+            .map(|message| message.full_name().to_string())
+            .filter(|name| !name.starts_with("google.protobuf."))
+            .collect()
+    }
+
+    // Encode JSON (mapping JSON protobuf, nama field camelCase maupun aslinya diterima) ke byte protobuf
+    pub fn encode(&self, message: &str, value: &Value) -> Result<Vec<u8>, String> {
+        let descriptor = self.descriptor(message)?;
+        let dynamic = DynamicMessage::deserialize(descriptor, value)
+            .map_err(|e| format!("Body does not match protobuf message {}: {}", message, e))?;
+        Ok(dynamic.encode_to_vec())
+    }
+
+    // Decode byte protobuf menjadi JSON sesuai mapping JSON protobuf
+    pub fn decode(&self, message: &str, bytes: &[u8]) -> Result<Value, String> {
+        let descriptor = self.descriptor(message)?;
+        let dynamic = DynamicMessage::decode(descriptor, bytes)
+            .map_err(|e| format!("Failed to decode protobuf message {}: {}", message, e))?;
+        serde_json::to_value(&dynamic)
+            .map_err(|e| format!("Failed to convert protobuf message {} to JSON: {}", message, e))
+    }
+
+    fn descriptor(&self, message: &str) -> Result<MessageDescriptor, String> {
+        let name = message.trim_start_matches('.');
+        self.pool.get_message_by_name(name)
+            .ok_or_else(|| format!("Protobuf message not found: {}", name))
+    }
+}
@@ -0,0 +1,589 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::codec::{self, BodyFormat};
+use crate::http::{AuthConfig, RequestParams};
+use crate::scripting::Scripts;
+
+use super::Request;
+
+// Hasil parse perintah curl. Opsi yang tidak punya padanan di RequestParams dicatat sebagai flag.
+#[derive(Debug, Clone, Serialize)]
+pub struct CurlCommand {
+    pub params: RequestParams,
+    // -k/--insecure: curl tidak memverifikasi sertifikat TLS, HttpClient selalu memverifikasi
+    pub insecure: bool,
+    // -L/--location. HttpClient selalu mengikuti redirect.
+    pub follow_redirects: bool,
+    // --compressed. Response yang dikompresi tidak di-decode oleh HttpClient, jadi Accept-Encoding tidak ditambahkan.
+    pub compressed: bool,
+}
+
+impl CurlCommand {
+    // Request collection dari perintah curl, nama default "METHOD /path"
+    pub fn into_request(self, name: Option<String>) -> Request {
+        let params = self.params;
+        let name = name.unwrap_or_else(|| {
+            let path = url::Url::parse(&params.url)
+                .map(|url| url.path().to_string())
+                .unwrap_or_else(|_| params.url.clone());
+            format!("{} {}", params.method, path)
+        });
+
+        Request {
+            id: Uuid::new_v4().to_string(),
+            name,
+            description: None,
+            method: params.method,
+            url: params.url,
+            headers: params.headers.unwrap_or_default(),
+            params: params.params.unwrap_or_default(),
+            body: params.body,
+            auth: params.auth.map(|auth| json!({ "type": auth.auth_type, "credentials": auth.credentials })),
+            schema: None,
+            assertions: Vec::new(),
+            scripts: Scripts::default(),
+            snapshot: None,
+            captures: Vec::new(),
+            body_format: params.body_format,
+            response_format: None,
+            flow: Vec::new(),
+        }
+    }
+}
+
+// Opsi curl yang butuh nilai tapi tidak berpengaruh pada request
+const IGNORED_OPTIONS: &[&str] = &[
+    "-o", "--output", "-w", "--write-out", "-c", "--cookie-jar", "--connect-timeout", "--retry", "--retry-delay",
+    "--max-redirs", "--cacert", "--capath", "--cert", "--key", "-x", "--proxy", "--resolve", "--limit-rate",
+];
+// Flag curl yang tidak berpengaruh pada request
+const IGNORED_FLAGS: &[&str] = &[
+    "-s", "--silent", "-S", "--show-error", "-v", "--verbose", "-i", "--include", "-f", "--fail", "-N", "--no-buffer",
+    "-g", "--globoff", "-#", "--progress-bar", "--http1.0", "--http1.1", "--http2", "--http2-prior-knowledge",
+];
+const VALUE_OPTIONS: &[&str] = &[
+    "-X", "--request", "-H", "--header", "-d", "--data", "--data-ascii", "--data-raw", "--data-binary",
+    "--data-urlencode", "--json", "-F", "--form", "--form-string", "-u", "--user", "--oauth2-bearer", "-b", "--cookie",
+    "-A", "--user-agent", "-e", "--referer", "-m", "--max-time", "--url",
+];
+
+// Parse perintah curl dari dokumentasi atau "Copy as cURL" browser (bash). Mendukung kutip shell,
+// $'...' dan baris lanjutan dengan backslash. Jika read_files, data dari file (@file) langsung dibaca
+// dan file -F tetap dirujuk; jika tidak, perintah yang memakai file lokal ditolak.
+pub fn parse(command: &str, read_files: bool) -> Result<CurlCommand, String> {
+    let words = split_command(command)?;
+    let mut words = words.into_iter().peekable();
+    if words.peek().is_some_and(|word| word == "curl" || word.ends_with("/curl") || word.eq_ignore_ascii_case("curl.exe")) {
+        words.next();
+    }
+
+    let mut url = None;
+    let mut method = None;
+    let mut headers: Vec<(String, String)> = Vec::new();
+    let mut data: Vec<String> = Vec::new();
+    let mut form: Vec<(String, Value)> = Vec::new();
+    let mut auth = None;
+    let mut timeout = None;
+    let mut json_data = false;
+    let mut get = false;
+    let mut head = false;
+    let mut insecure = false;
+    let mut follow_redirects = false;
+    let mut compressed = false;
+
+    while let Some(word) = words.next() {
+        if !word.starts_with('-') || word == "-" {
+            if url.replace(word.clone()).is_some() {
+                return Err(format!("Only one URL is supported, found another: {}", word));
+            }
+            continue;
+        }
+
+        for (option, attached) in expand_option(&word)? {
+            let mut value = || -> Result<String, String> {
+                match attached.clone() {
+                    Some(value) => Ok(value),
+                    None => words.next().ok_or_else(|| format!("Missing value for {}", option)),
+                }
+            };
+
+            match option.as_str() {
+                "-X" | "--request" => method = Some(value()?.to_uppercase()),
+                "-H" | "--header" => {
+                    if let Some(header) = parse_header(&value()?) {
+                        headers.push(header);
+                    }
+                },
+                "-d" | "--data" | "--data-ascii" => data.push(read_data(&value()?, true, read_files)?),
+                "--data-raw" => data.push(value()?),
+                "--data-binary" => data.push(read_data(&value()?, false, read_files)?),
+                "--data-urlencode" => data.push(url_encode_data(&value()?, read_files)?),
+                "--json" => {
+                    data.push(read_data(&value()?, false, read_files)?);
+                    json_data = true;
+                },
+                "-F" | "--form" => form.push(parse_form_field(&value()?, true, read_files)?),
+                "--form-string" => form.push(parse_form_field(&value()?, false, false)?),
+                "-u" | "--user" => {
+                    let user = value()?;
+                    let (username, password) = user.split_once(':').unwrap_or((&user, ""));
+                    auth = Some(AuthConfig {
+                        auth_type: "basic".to_string(),
+                        credentials: json!({ "username": username, "password": password }),
+                    });
+                },
+                "--oauth2-bearer" => {
+                    auth = Some(AuthConfig { auth_type: "bearer".to_string(), credentials: json!({ "token": value()? }) });
+                },
+                // Tanpa '=' nilainya adalah file cookie jar, yang tidak diimpor
+                "-b" | "--cookie" => {
+                    let cookie = value()?;
+                    if cookie.contains('=') {
+                        headers.push(("Cookie".to_string(), cookie));
+                    }
+                },
+                "-A" | "--user-agent" => headers.push(("User-Agent".to_string(), value()?)),
+                "-e" | "--referer" => headers.push(("Referer".to_string(), value()?)),
+                "-m" | "--max-time" => {
+                    let seconds = value()?;
+                    let seconds: f64 = seconds.parse().map_err(|_| format!("Invalid --max-time: {}", seconds))?;
+                    timeout = Some(seconds.ceil().max(1.0) as u64);
+                },
+                "--url" => {
+                    if url.replace(value()?).is_some() {
+                        return Err("Only one URL is supported".to_string());
+                    }
+                },
+                "-G" | "--get" => get = true,
+                "-I" | "--head" => head = true,
+                "-k" | "--insecure" => insecure = true,
+                "-L" | "--location" | "--location-trusted" => follow_redirects = true,
+                "--compressed" => compressed = true,
+                other if IGNORED_OPTIONS.contains(&other) => {
+                    value()?;
+                },
+                other if IGNORED_FLAGS.contains(&other) => {},
+                other => return Err(format!("Unsupported curl option: {}", other)),
+            }
+        }
+    }
+
+    let url = url.ok_or("No URL found in curl command")?;
+
+    if json_data {
+        for (name, value) in [("Content-Type", "application/json"), ("Accept", "application/json")] {
+            if !headers.iter().any(|(key, _)| key.eq_ignore_ascii_case(name)) {
+                headers.push((name.to_string(), value.to_string()));
+            }
+        }
+    }
+
+    let has_body = !form.is_empty() || (!data.is_empty() && !get);
+    let method = method.unwrap_or_else(|| {
+        match (head, has_body) {
+            (true, _) => "HEAD",
+            (false, true) => "POST",
+            (false, false) => "GET",
+        }.to_string()
+    });
+
+    let mut query = None;
+    let (body, body_format) = if !form.is_empty() {
+        // Boundary dibuat ulang saat dikirim, jadi Content-Type multipart lama dibuang
+        headers.retain(|(key, value)| !(key.eq_ignore_ascii_case("content-type") && value.to_ascii_lowercase().starts_with("multipart/")));
+        (Some(form_body(form)), Some(BodyFormat::Multipart))
+    } else if data.is_empty() {
+        (None, None)
+    } else if get {
+        query = Some(url::form_urlencoded::parse(data.join("&").as_bytes()).into_owned().collect());
+        (None, None)
+    } else {
+        let (body, body_format) = data_body(data.join("&"), &mut headers);
+        (Some(body), body_format)
+    };
+
+    Ok(CurlCommand {
+        params: RequestParams {
+            // curl memakai http:// untuk URL tanpa scheme
+            url: if url.contains("://") { url } else { format!("http://{}", url) },
+            method,
+            headers: merge_headers(headers),
+            params: query,
+            body,
+            timeout,
+            auth,
+            body_format,
+        },
+        insecure,
+        follow_redirects,
+        compressed,
+    })
+}
+
+// Opsi dengan nilai yang menempel (-XPOST) dan gabungan flag pendek (-sSL) dipecah menjadi satu per opsi
+fn expand_option(word: &str) -> Result<Vec<(String, Option<String>)>, String> {
+    if word.starts_with("--") || word.len() <= 2 {
+        return Ok(vec![(word.to_string(), None)]);
+    }
+
+    let mut options = Vec::new();
+    for (index, short) in word[1..].char_indices() {
+        let option = format!("-{}", short);
+        if VALUE_OPTIONS.contains(&option.as_str()) || IGNORED_OPTIONS.contains(&option.as_str()) {
+            let rest = &word[1 + index + short.len_utf8()..];
+            options.push((option, if rest.is_empty() { None } else { Some(rest.to_string()) }));
+            return Ok(options);
+        }
+        options.push((option, None));
+    }
+    Ok(options)
+}
+
+// "Nama: nilai". "Nama;" berarti header kosong; "Nama:" (menghapus header bawaan curl) diabaikan.
+fn parse_header(header: &str) -> Option<(String, String)> {
+    if let Some(name) = header.strip_suffix(';').filter(|name| !name.contains(':')) {
+        return Some((name.trim().to_string(), String::new()));
+    }
+
+    let (name, value) = header.split_once(':')?;
+    let value = value.trim();
+    if value.is_empty() {
+        return None;
+    }
+    Some((name.trim().to_string(), value.to_string()))
+}
+
+// Header dengan nama yang sama digabung seperti saat dikirim; Cookie dengan "; "
+fn merge_headers(headers: Vec<(String, String)>) -> Option<HashMap<String, String>> {
+    let mut merged: HashMap<String, String> = HashMap::new();
+    for (name, value) in headers {
+        let existing = merged.keys().find(|key| key.eq_ignore_ascii_case(&name)).cloned();
+        match existing {
+            Some(key) => {
+                let separator = if name.eq_ignore_ascii_case("cookie") { "; " } else { ", " };
+                let combined = merged.get_mut(&key).expect("header key exists");
+                combined.push_str(separator);
+                combined.push_str(&value);
+            },
+            None => {
+                merged.insert(name, value);
+            },
+        }
+    }
+
+    if merged.is_empty() { None } else { Some(merged) }
+}
+
+// Data "-d @file" dibaca dari file; -d membuang baris baru seperti curl, --data-binary tidak
+fn read_data(value: &str, strip_newlines: bool, read_files: bool) -> Result<String, String> {
+    let Some(path) = value.strip_prefix('@') else {
+        return Ok(value.to_string());
+    };
+    if path == "-" {
+        return Err("Reading data from stdin (@-) is not supported".to_string());
+    }
+    check_file_access(path, read_files)?;
+
+    let content = std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    Ok(if strip_newlines { content.replace(['\r', '\n'], "") } else { content })
+}
+
+// --data-urlencode: "isi", "=isi", "nama=isi", "@file" atau "nama@file"
+fn url_encode_data(value: &str, read_files: bool) -> Result<String, String> {
+    let encode = |content: &str| url::form_urlencoded::byte_serialize(content.as_bytes()).collect::<String>();
+
+    if let Some((name, content)) = value.split_once('=') {
+        return Ok(if name.is_empty() { encode(content) } else { format!("{}={}", name, encode(content)) });
+    }
+    if let Some((name, path)) = value.split_once('@') {
+        let content = read_data(&format!("@{}", path), false, read_files)?;
+        return Ok(if name.is_empty() { encode(&content) } else { format!("{}={}", name, encode(&content)) });
+    }
+    Ok(encode(value))
+}
+
+// -F "nama=nilai", "nama=@file;type=...;filename=..." atau "nama=<file" (isi file sebagai field teks).
+// --form-string selalu teks apa adanya.
+fn parse_form_field(field: &str, special: bool, read_files: bool) -> Result<(String, Value), String> {
+    let (name, value) = field.split_once('=')
+        .ok_or_else(|| format!("Expected name=value for form field: {}", field))?;
+
+    if special {
+        if let Some(spec) = value.strip_prefix('@') {
+            let mut parts = spec.split(';');
+            let path = parts.next().unwrap_or_default();
+            check_file_access(path, read_files)?;
+            let mut file = json!({ "file": path });
+            for part in parts {
+                match part.split_once('=') {
+                    Some(("type", content_type)) => file["content_type"] = json!(content_type),
+                    Some(("filename", filename)) => file["filename"] = json!(filename.trim_matches('"')),
+                    _ => {},
+                }
+            }
+            return Ok((name.to_string(), file));
+        }
+        if let Some(path) = value.strip_prefix('<') {
+            let path = path.split(';').next().unwrap_or_default();
+            check_file_access(path, read_files)?;
+            return Ok((name.to_string(), json!({ "file": path, "filename": null })));
+        }
+    }
+
+    Ok((name.to_string(), Value::String(value.to_string())))
+}
+
+fn check_file_access(path: &str, read_files: bool) -> Result<(), String> {
+    if read_files {
+        Ok(())
+    } else {
+        Err(format!("curl command refers to local file '{}', which is not allowed here; paste its content instead", path))
+    }
+}
+
+// Field dengan nama yang sama menjadi array
+fn form_body(fields: Vec<(String, Value)>) -> Value {
+    let mut body = serde_json::Map::new();
+    for (name, value) in fields {
+        match body.get_mut(&name) {
+            Some(Value::Array(values)) => values.push(value),
+            Some(existing) => *existing = Value::Array(vec![existing.take(), value]),
+            None => {
+                body.insert(name, value);
+            },
+        }
+    }
+    Value::Object(body)
+}
+
+// Body dari data -d. JSON object/array dikirim sebagai JSON kecuali Content-Type menyatakan lain
+// (dokumentasi API sering lupa menulis header-nya). Data berbentuk nama=nilai menjadi form yang bisa diedit
+// jika form itu di-encode ulang persis sama, karena field form dikirim urut nama.
+// Selain itu data dikirim apa adanya dengan Content-Type dari perintah, atau default curl (form urlencoded).
+fn data_body(data: String, headers: &mut Vec<(String, String)>) -> (Value, Option<BodyFormat>) {
+    let content_type = headers.iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case("content-type"))
+        .map(|(_, value)| value.split(';').next().unwrap_or_default().trim().to_ascii_lowercase());
+
+    let is_json_type = content_type.as_deref().is_some_and(|essence| essence == "application/json" || essence.ends_with("+json"));
+    if is_json_type || content_type.is_none() {
+        match serde_json::from_str::<Value>(&data) {
+            Ok(json) if is_json_type || json.is_object() || json.is_array() => return (json, None),
+            _ => {},
+        }
+    }
+
+    let is_form_type = content_type.as_deref().is_none_or(|essence| essence == "application/x-www-form-urlencoded");
+    let is_form_like = !data.is_empty() && data.split('&').all(|pair| pair.contains('='));
+    if is_form_type && is_form_like {
+        if let Ok(form) = codec::decode(data.as_bytes(), &BodyFormat::Form) {
+            if codec::encode(&form, &BodyFormat::Form).is_ok_and(|encoded| encoded == data.as_bytes()) {
+                return (form, Some(BodyFormat::Form));
+            }
+        }
+    }
+
+    if content_type.is_none() {
+        headers.push(("Content-Type".to_string(), "application/x-www-form-urlencoded".to_string()));
+    }
+    (Value::String(data), Some(BodyFormat::Text))
+}
+
+// Memecah perintah shell (sh/bash) menjadi argumen: kutip tunggal, kutip ganda, $'...' (ANSI-C, dipakai
+// "Copy as cURL" di browser), escape backslash, baris lanjutan (backslash di akhir baris) dan komentar #
+pub fn split_command(command: &str) -> Result<Vec<String>, String> {
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+    let mut chars = command.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            ' ' | '\t' | '\n' | '\r' => {
+                words.extend(word.take());
+            },
+            '#' if word.is_none() => {
+                while chars.next_if(|&c| c != '\n').is_some() {}
+            },
+            '\\' => match chars.next() {
+                Some('\n') => {},
+                Some('\r') => {
+                    chars.next_if_eq(&'\n');
+                },
+                Some(escaped) => word.get_or_insert_with(String::new).push(escaped),
+                None => word.get_or_insert_with(String::new).push('\\'),
+            },
+            '\'' => {
+                let text = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => text.push(c),
+                        None => return Err("Unterminated single quote".to_string()),
+                    }
+                }
+            },
+            '"' => {
+                let text = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(escaped @ ('"' | '\\' | '$' | '`')) => text.push(escaped),
+                            Some('\n') => {},
+                            Some(other) => {
+                                text.push('\\');
+                                text.push(other);
+                            },
+                            None => return Err("Unterminated double quote".to_string()),
+                        },
+                        Some(c) => text.push(c),
+                        None => return Err("Unterminated double quote".to_string()),
+                    }
+                }
+            },
+            '$' if chars.peek() == Some(&'\'') => {
+                chars.next();
+                let text = word.get_or_insert_with(String::new);
+                read_ansi_c(&mut chars, text)?;
+            },
+            c => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+
+    words.extend(word);
+    Ok(words)
+}
+
+// Isi $'...' sampai kutip penutup, dengan escape seperti \n, \t, \', \xHH dan \uHHHH
+fn read_ansi_c(chars: &mut std::iter::Peekable<std::str::Chars>, text: &mut String) -> Result<(), String> {
+    loop {
+        let c = chars.next().ok_or("Unterminated $'...' quote")?;
+        match c {
+            '\'' => return Ok(()),
+            '\\' => {
+                let escaped = chars.next().ok_or("Unterminated $'...' quote")?;
+                let decoded = match escaped {
+                    'n' => Some('\n'),
+                    't' => Some('\t'),
+                    'r' => Some('\r'),
+                    'a' => Some('\x07'),
+                    'b' => Some('\x08'),
+                    'e' | 'E' => Some('\x1b'),
+                    'f' => Some('\x0c'),
+                    'v' => Some('\x0b'),
+                    '0'..='7' => {
+                        let mut octal = escaped.to_string();
+                        while octal.len() < 3 {
+                            match chars.next_if(|c| ('0'..='7').contains(c)) {
+                                Some(digit) => octal.push(digit),
+                                None => break,
+                            }
+                        }
+                        u32::from_str_radix(&octal, 8).ok().and_then(char::from_u32)
+                    },
+                    'x' => read_hex(chars, 2),
+                    'u' => read_hex(chars, 4),
+                    'U' => read_hex(chars, 8),
+                    '\'' | '"' | '\\' | '?' => Some(escaped),
+                    // Escape lain tidak dikenal bash, backslash-nya tetap ada
+                    _ => None,
+                };
+                match decoded {
+                    Some(decoded) => text.push(decoded),
+                    None => {
+                        text.push('\\');
+                        text.push(escaped);
+                    },
+                }
+            },
+            c => text.push(c),
+        }
+    }
+}
+
+// Sampai max digit heksadesimal sebagai satu karakter
+fn read_hex(chars: &mut std::iter::Peekable<std::str::Chars>, max: usize) -> Option<char> {
+    let mut hex = String::new();
+    while hex.len() < max {
+        match chars.next_if(char::is_ascii_hexdigit) {
+            Some(digit) => hex.push(digit),
+            None => break,
+        }
+    }
+    u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collections::{CollectionBundle, CollectionStore};
+
+    #[test]
+    fn splits_shell_quoting() {
+        let words = split_command(r#"curl 'a b' "c \"d\" \$e \x" f\ g 'it'\''s' # komentar"#).unwrap();
+        assert_eq!(words, ["curl", "a b", r#"c "d" $e \x"#, "f g", "it's"]);
+        assert!(split_command("curl 'open").is_err());
+        assert!(split_command("curl \"open").is_err());
+    }
+
+    #[test]
+    fn splits_ansi_c_quotes() {
+        let words = split_command(r"curl $'a\nb\t\'c\' \x41é\101\q'").unwrap();
+        assert_eq!(words, ["curl", "a\nb\t'c' Aé\u{41}\\q"]);
+        assert!(split_command("curl $'open").is_err());
+    }
+
+    #[test]
+    fn joins_continued_lines() {
+        let command = "curl https://example.com/items \\\n  -H 'Accept: application/json' \\\r\n  --compressed";
+        let curl = parse(command, false).unwrap();
+        assert_eq!(curl.params.url, "https://example.com/items");
+        assert_eq!(curl.params.headers.unwrap()["Accept"], "application/json");
+        assert!(curl.compressed);
+    }
+
+    #[test]
+    fn parses_browser_copy_as_curl() {
+        let command = r#"curl 'https://api.example.com/login' -X 'POST' -H 'content-type: application/json' --data-raw $'{"user":"ann\'s"}' -sSL"#;
+        let curl = parse(command, false).unwrap();
+        assert_eq!(curl.params.method, "POST");
+        assert_eq!(curl.params.body, Some(json!({"user": "ann's"})));
+        assert_eq!(curl.params.body_format, None);
+        assert!(curl.follow_redirects);
+    }
+
+    #[test]
+    fn keeps_form_data_order() {
+        let curl = parse("curl example.com -d 'z=1&a=2'", false).unwrap();
+        assert_eq!(curl.params.url, "http://example.com");
+        assert_eq!(curl.params.body, Some(json!("z=1&a=2")));
+        assert_eq!(curl.params.body_format, Some(BodyFormat::Text));
+        assert_eq!(curl.params.headers.unwrap()["Content-Type"], "application/x-www-form-urlencoded");
+
+        let curl = parse("curl example.com -d a=1 -d b=x+y", false).unwrap();
+        assert_eq!(curl.params.body, Some(json!({"a": "1", "b": "x y"})));
+        assert_eq!(curl.params.body_format, Some(BodyFormat::Form));
+    }
+
+    #[test]
+    fn rejects_local_files_unless_allowed() {
+        for command in ["curl example.com -d @body.json", "curl example.com -F f=@photo.png", "curl example.com --data-urlencode q@query.txt"] {
+            let error = parse(command, false).unwrap_err();
+            assert!(error.contains("local file"), "{}", error);
+        }
+        let curl = parse("curl example.com -F 'f=@photo.png;type=image/png'", true).unwrap();
+        assert_eq!(curl.params.body, Some(json!({"f": {"file": "photo.png", "content_type": "image/png"}})));
+
+        // Collection yang sudah berisi field file juga ditolak saat di-import
+        let request = curl.into_request(Some("upload".to_string()));
+        assert_eq!(request.local_files(), vec!["photo.png"]);
+        let collection = CollectionStore::new().create_collection("c".to_string(), None);
+        let bundle = CollectionBundle { collection, folders: Vec::new(), requests: vec![request] };
+        let error = bundle.reject_local_files().unwrap_err();
+        assert!(error.contains("'upload' uploads local file 'photo.png'"), "{}", error);
+    }
+}
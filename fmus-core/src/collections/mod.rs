@@ -0,0 +1,455 @@
+pub mod curl;
+pub mod postman;
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::assertions::Assertion;
+use crate::capture::Capture;
+use crate::codec::{self, BodyFormat};
+use crate::flow::FlowRule;
+use crate::schema::JsonSchema;
+use crate::scripting::Scripts;
+use crate::snapshot::SnapshotConfig;
+use crate::vault::{self, SecretRef, Vault};
+
+// Struktur untuk request dalam collection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Request {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub method: String,
+    pub url: String,
+    pub headers: HashMap<String, String>,
+    pub params: HashMap<String, String>,
+    pub body: Option<Value>,
+    // Field rahasia auth disimpan sebagai referensi vault ({"vault_ref": "..."})
+    pub auth: Option<Value>,
+    // Schema kontrak response yang dicek otomatis saat request dijalankan
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema: Option<JsonSchema>,
+    // Ekspektasi terhadap response yang dievaluasi menjadi laporan pass/fail
+    #[serde(default)]
+    pub assertions: Vec<Assertion>,
+    // Pre-request dan test script (API pm kompatibel Postman)
+    #[serde(default, skip_serializing_if = "Scripts::is_empty")]
+    pub scripts: Scripts,
+    // Jika diisi, response dibandingkan dengan snapshot "golden" yang tersimpan
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snapshot: Option<SnapshotConfig>,
+    // Nilai yang diambil dari response dan disimpan sebagai variabel untuk request berikutnya
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub captures: Vec<Capture>,
+    // Body ditulis sebagai JSON lalu dikirim sebagai MessagePack, CBOR atau protobuf
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body_format: Option<BodyFormat>,
+    // Format body response yang di-decode ke JSON, wajib untuk protobuf karena butuh schema
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response_format: Option<BodyFormat>,
+    // Alur collection runner: skip, goto, repeat atau stop berdasarkan kondisi
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub flow: Vec<FlowRule>,
+}
+
+impl Request {
+    // Segel field rahasia auth ke vault, return true jika ada perubahan
+    pub fn seal_secrets(&mut self, vault: &mut Vault) -> Result<bool, String> {
+        let name = format!("request.{}.auth", self.name);
+        match self.auth.as_mut() {
+            Some(auth) => {
+                let before = auth.clone();
+                vault::seal_json(auth, vault, &name)?;
+                Ok(*auth != before)
+            },
+            None => Ok(false),
+        }
+    }
+
+    // Auth dengan nilai rahasia yang sudah dibuka, untuk dikirim
+    pub fn resolved_auth(&self, vault: Option<&Vault>) -> Result<Option<Value>, String> {
+        self.auth.as_ref().map(|auth| vault::reveal_json(auth, vault)).transpose()
+    }
+
+    // Salinan untuk ekspor, nilai rahasia polos dikosongkan
+    pub fn redacted(&self) -> Request {
+        let mut request = self.clone();
+        request.auth = self.auth.as_ref().map(vault::redact_json);
+        request
+    }
+
+    pub fn secret_refs(&self) -> Vec<SecretRef> {
+        let mut refs = Vec::new();
+        if let Some(auth) = &self.auth {
+            vault::collect_refs(auth, &mut refs);
+        }
+        refs
+    }
+
+    // Path file lokal yang dibaca field multipart ({"file": "path"}) saat request dikirim
+    pub fn local_files(&self) -> Vec<&str> {
+        let Some((body, Some(BodyFormat::Multipart))) = self.body.as_ref().map(|body| (body, &self.body_format)) else {
+            return Vec::new();
+        };
+        codec::form_fields(body).unwrap_or_default()
+            .into_iter()
+            .filter_map(|(_, value)| value.get("file").and_then(Value::as_str))
+            .collect()
+    }
+}
+
+// Cara runner menjalankan isi collection atau folder. Folder tanpa mode mengikuti induknya.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecutionMode {
+    // Satu per satu sesuai urutan
+    Sequential,
+    // Bersamaan, dibatasi max_concurrency di RunOptions
+    Parallel,
+}
+
+// Struktur untuk folder dalam collection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Folder {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub requests: Vec<String>, // Request IDs
+    pub folders: Vec<String>,  // Sub-folder IDs
+    // Script folder dijalankan untuk setiap request di dalamnya
+    #[serde(default, skip_serializing_if = "Scripts::is_empty")]
+    pub scripts: Scripts,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub execution: Option<ExecutionMode>,
+}
+
+// Struktur untuk collection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Collection {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub requests: Vec<String>, // Request IDs
+    pub folders: Vec<String>,  // Folder IDs
+    pub variables: HashMap<String, String>,
+    // Script collection dijalankan sebelum script folder dan request
+    #[serde(default, skip_serializing_if = "Scripts::is_empty")]
+    pub scripts: Scripts,
+    // None berarti mengikuti mode default run
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub execution: Option<ExecutionMode>,
+}
+
+// Collection beserta semua folder dan request-nya, format file collection FMUS (.json)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CollectionBundle {
+    pub collection: Collection,
+    #[serde(default)]
+    pub folders: Vec<Folder>,
+    #[serde(default)]
+    pub requests: Vec<Request>,
+}
+
+impl CollectionBundle {
+    // Salinan untuk ekspor, nilai rahasia polos di auth request dikosongkan
+    pub fn redacted(&self) -> CollectionBundle {
+        CollectionBundle {
+            requests: self.requests.iter().map(Request::redacted).collect(),
+            ..self.clone()
+        }
+    }
+
+    // Tolak collection hasil import yang mengunggah file lokal, sama seperti perintah curl yang ditempel
+    pub fn reject_local_files(&self) -> Result<(), String> {
+        for request in &self.requests {
+            if let Some(path) = request.local_files().first() {
+                return Err(format!(
+                    "Request '{}' uploads local file '{}', which is not allowed in imported collections",
+                    request.name, path,
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+// Store untuk semua collections, requests, dan folders
+#[derive(Debug, Default)]
+pub struct CollectionStore {
+    collections: HashMap<String, Collection>,
+    requests: HashMap<String, Request>,
+    folders: HashMap<String, Folder>,
+}
+
+impl CollectionStore {
+    pub fn new() -> Self {
+        CollectionStore {
+            collections: HashMap::new(),
+            requests: HashMap::new(),
+            folders: HashMap::new(),
+        }
+    }
+
+    // Membuat collection baru
+    pub fn create_collection(&mut self, name: String, description: Option<String>) -> Collection {
+        let id = Uuid::new_v4().to_string();
+        let collection = Collection {
+            id: id.clone(),
+            name,
+            description,
+            requests: Vec::new(),
+            folders: Vec::new(),
+            variables: HashMap::new(),
+            scripts: Scripts::default(),
+            execution: None,
+        };
+
+        self.collections.insert(id, collection.clone());
+        collection
+    }
+
+    // Menyimpan collection hasil import beserta folder dan request-nya
+    pub fn import_collection(&mut self, collection: Collection, folders: Vec<Folder>, requests: Vec<Request>) -> Collection {
+        for folder in folders {
+            self.folders.insert(folder.id.clone(), folder);
+        }
+
+        for request in requests {
+            self.requests.insert(request.id.clone(), request);
+        }
+
+        self.collections.insert(collection.id.clone(), collection.clone());
+        collection
+    }
+
+    // Mendapatkan collection berdasarkan ID
+    pub fn get_collection(&self, id: &str) -> Option<&Collection> {
+        self.collections.get(id)
+    }
+
+    // Mendapatkan semua collections
+    pub fn list_collections(&self) -> Vec<Collection> {
+        self.collections.values().cloned().collect()
+    }
+
+    // Update collection
+    pub fn update_collection(&mut self, id: &str, name: Option<String>, description: Option<String>) -> Option<Collection> {
+        if let Some(collection) = self.collections.get_mut(id) {
+            if let Some(new_name) = name {
+                collection.name = new_name;
+            }
+
+            if description.is_some() {
+                collection.description = description;
+            }
+
+            return Some(collection.clone());
+        }
+
+        None
+    }
+
+    // Menghapus collection
+    pub fn delete_collection(&mut self, id: &str) -> bool {
+        if self.collections.contains_key(id) {
+            // Hapus requests dan folders yang terkait
+            if let Some(collection) = self.collections.get(id).cloned() {
+                for request_id in &collection.requests {
+                    self.requests.remove(request_id);
+                }
+
+                for folder_id in &collection.folders {
+                    self.remove_folder_recursively(folder_id);
+                }
+            }
+
+            self.collections.remove(id);
+            return true;
+        }
+
+        false
+    }
+
+    // Hapus folder dan semua kontennya secara rekursif
+    fn remove_folder_recursively(&mut self, folder_id: &str) {
+        if let Some(folder) = self.folders.get(folder_id) {
+            let request_ids = folder.requests.clone();
+            let folder_ids = folder.folders.clone();
+
+            for request_id in request_ids {
+                self.requests.remove(&request_id);
+            }
+
+            for sub_folder_id in folder_ids {
+                self.remove_folder_recursively(&sub_folder_id);
+            }
+
+            self.folders.remove(folder_id);
+        }
+    }
+
+    // Menambahkan request ke collection
+    pub fn add_request(&mut self, collection_id: &str, request: Request) -> Option<String> {
+        if !self.collections.contains_key(collection_id) {
+            return None;
+        }
+
+        let request_id = request.id.clone();
+        self.requests.insert(request_id.clone(), request);
+
+        if let Some(collection) = self.collections.get_mut(collection_id) {
+            collection.requests.push(request_id.clone());
+            return Some(request_id);
+        }
+
+        None
+    }
+
+    // Mendapatkan request berdasarkan ID
+    pub fn get_request(&self, id: &str) -> Option<&Request> {
+        self.requests.get(id)
+    }
+
+    // Mendapatkan folder berdasarkan ID
+    pub fn get_folder(&self, id: &str) -> Option<&Folder> {
+        self.folders.get(id)
+    }
+
+    // Collection pemilik request dan folder-folder di atasnya, urut dari luar ke dalam
+    pub fn request_context(&self, request_id: &str) -> Option<(&Collection, Vec<&Folder>)> {
+        self.collections.values().find_map(|collection| {
+            if collection.requests.iter().any(|id| id == request_id) {
+                return Some((collection, Vec::new()));
+            }
+
+            collection.folders.iter().find_map(|folder_id| {
+                self.folder_path(folder_id, &|folder| folder.requests.iter().any(|id| id == request_id))
+                    .map(|path| (collection, path))
+            })
+        })
+    }
+
+    // Collection pemilik folder dan jalur folder dari luar sampai folder itu sendiri
+    pub fn folder_context(&self, folder_id: &str) -> Option<(&Collection, Vec<&Folder>)> {
+        self.collections.values().find_map(|collection| {
+            collection.folders.iter().find_map(|id| {
+                self.folder_path(id, &|folder| folder.id == folder_id).map(|path| (collection, path))
+            })
+        })
+    }
+
+    // Jalur dari folder_id ke folder pertama yang memenuhi target, termasuk folder itu
+    fn folder_path(&self, folder_id: &str, target: &dyn Fn(&Folder) -> bool) -> Option<Vec<&Folder>> {
+        let folder = self.folders.get(folder_id)?;
+        if target(folder) {
+            return Some(vec![folder]);
+        }
+
+        folder.folders.iter().find_map(|sub_folder_id| {
+            self.folder_path(sub_folder_id, target).map(|mut path| {
+                path.insert(0, folder);
+                path
+            })
+        })
+    }
+
+    // Atur mode eksekusi collection atau folder berdasarkan ID, None untuk mengikuti induknya
+    pub fn set_execution(&mut self, id: &str, mode: Option<ExecutionMode>) -> bool {
+        if let Some(collection) = self.collections.get_mut(id) {
+            collection.execution = mode;
+            return true;
+        }
+
+        if let Some(folder) = self.folders.get_mut(id) {
+            folder.execution = mode;
+            return true;
+        }
+
+        false
+    }
+
+    // Terapkan perubahan pm.collectionVariables dari script (None berarti unset)
+    pub fn apply_variable_changes(&mut self, collection_id: &str, changes: &HashMap<String, Option<String>>) {
+        if let Some(collection) = self.collections.get_mut(collection_id) {
+            for (key, value) in changes {
+                match value {
+                    Some(value) => collection.variables.insert(key.clone(), value.clone()),
+                    None => collection.variables.remove(key),
+                };
+            }
+        }
+    }
+
+    // Segel semua auth yang masih menyimpan secret polos
+    pub fn seal_secrets(&mut self, vault: &mut Vault) -> Result<usize, String> {
+        let mut sealed = 0;
+        for request in self.requests.values_mut() {
+            if request.seal_secrets(vault)? {
+                sealed += 1;
+            }
+        }
+
+        Ok(sealed)
+    }
+
+    // Semua referensi vault yang dipakai collection, termasuk folder di dalamnya
+    pub fn collection_secret_refs(&self, collection_id: &str) -> Vec<SecretRef> {
+        let mut refs = Vec::new();
+        if let Some(collection) = self.collections.get(collection_id) {
+            self.collect_secret_refs(&collection.requests, &collection.folders, &mut refs);
+        }
+        refs
+    }
+
+    // Collection beserta semua folder dan request di dalamnya, untuk disimpan ke file
+    pub fn export_bundle(&self, collection_id: &str) -> Option<CollectionBundle> {
+        let collection = self.collections.get(collection_id)?;
+        let mut bundle = CollectionBundle { collection: collection.clone(), folders: Vec::new(), requests: Vec::new() };
+        self.collect_items(&collection.requests, &collection.folders, &mut bundle);
+        Some(bundle)
+    }
+
+    fn collect_items(&self, request_ids: &[String], folder_ids: &[String], bundle: &mut CollectionBundle) {
+        bundle.requests.extend(request_ids.iter().filter_map(|id| self.requests.get(id)).cloned());
+
+        for folder_id in folder_ids {
+            if let Some(folder) = self.folders.get(folder_id) {
+                bundle.folders.push(folder.clone());
+                self.collect_items(&folder.requests, &folder.folders, bundle);
+            }
+        }
+    }
+
+    // Menyimpan collection dari file collection FMUS
+    pub fn import_bundle(&mut self, bundle: CollectionBundle) -> Collection {
+        self.import_collection(bundle.collection, bundle.folders, bundle.requests)
+    }
+
+    fn collect_secret_refs(&self, request_ids: &[String], folder_ids: &[String], refs: &mut Vec<SecretRef>) {
+        for request_id in request_ids {
+            if let Some(request) = self.requests.get(request_id) {
+                refs.extend(request.secret_refs());
+            }
+        }
+
+        for folder_id in folder_ids {
+            if let Some(folder) = self.folders.get(folder_id) {
+                self.collect_secret_refs(&folder.requests, &folder.folders, refs);
+            }
+        }
+    }
+
+    // Menghapus request dari collection
+    pub fn remove_request(&mut self, collection_id: &str, request_id: &str) -> bool {
+        if let Some(collection) = self.collections.get_mut(collection_id) {
+            collection.requests.retain(|id| id != request_id);
+            self.requests.remove(request_id);
+            return true;
+        }
+
+        false
+    }
+}
@@ -0,0 +1,463 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::scripting::Scripts;
+
+use super::{Collection, CollectionBundle, Folder, Request};
+
+const SCHEMA: &str = "https://schema.getpostman.com/json/collection/v2.1.0/collection.json";
+
+// Format Postman collection v2.1
+#[derive(Debug, Deserialize, Serialize)]
+struct PostmanCollection {
+    info: PostmanInfo,
+    item: Vec<PostmanItem>,
+    variable: Option<Vec<PostmanVariable>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    event: Option<Vec<PostmanEvent>>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct PostmanInfo {
+    name: String,
+    description: Option<String>,
+    #[serde(rename = "_postman_id")]
+    postman_id: Option<String>,
+    schema: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct PostmanItem {
+    name: String,
+    description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    item: Option<Vec<PostmanItem>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    request: Option<PostmanRequest>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    event: Option<Vec<PostmanEvent>>,
+}
+
+// Script Postman, listen berisi "prerequest" atau "test"
+#[derive(Debug, Deserialize, Serialize)]
+struct PostmanEvent {
+    listen: String,
+    script: PostmanScript,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct PostmanScript {
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    script_type: Option<String>,
+    #[serde(default)]
+    exec: PostmanExec,
+}
+
+// exec bisa berupa array baris atau satu string
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(untagged)]
+enum PostmanExec {
+    Lines(Vec<String>),
+    Text(String),
+}
+
+impl Default for PostmanExec {
+    fn default() -> Self {
+        PostmanExec::Lines(Vec::new())
+    }
+}
+
+impl PostmanExec {
+    fn source(&self) -> String {
+        match self {
+            PostmanExec::Lines(lines) => lines.join("\n"),
+            PostmanExec::Text(text) => text.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct PostmanRequest {
+    method: String,
+    url: PostmanUrl,
+    header: Option<Vec<PostmanHeader>>,
+    body: Option<PostmanBody>,
+    auth: Option<Value>,
+}
+
+// URL bisa ditulis sebagai string biasa atau object dengan raw, host, path dan query
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(untagged)]
+enum PostmanUrl {
+    Raw(String),
+    Parts {
+        raw: String,
+        #[serde(default)]
+        host: Vec<String>,
+        #[serde(default)]
+        path: Vec<String>,
+        #[serde(default)]
+        query: Option<Vec<PostmanQueryParam>>,
+    },
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct PostmanQueryParam {
+    key: String,
+    value: String,
+    disabled: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct PostmanHeader {
+    key: String,
+    value: String,
+    disabled: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct PostmanBody {
+    mode: String,
+    raw: Option<String>,
+    formdata: Option<Vec<PostmanFormData>>,
+    urlencoded: Option<Vec<PostmanUrlEncoded>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    options: Option<Value>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct PostmanFormData {
+    key: String,
+    value: String,
+    #[serde(rename = "type")]
+    form_type: String,
+    disabled: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct PostmanUrlEncoded {
+    key: String,
+    value: String,
+    disabled: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct PostmanVariable {
+    key: String,
+    value: String,
+    #[serde(rename = "type")]
+    var_type: Option<String>,
+    disabled: Option<bool>,
+}
+
+// Import Postman collection v2.1 beserta folder, request dan script-nya. Semua ID dibuat baru.
+pub fn import(content: &str) -> Result<CollectionBundle, String> {
+    let postman_collection: PostmanCollection = serde_json::from_str(content)
+        .map_err(|e| format!("Failed to parse Postman collection: {}", e))?;
+
+    let mut folders = Vec::new();
+    let mut requests = Vec::new();
+    let (request_ids, folder_ids) = import_items(postman_collection.item, &mut folders, &mut requests);
+
+    let variables = postman_collection.variable.unwrap_or_default()
+        .into_iter()
+        .filter(|var| !var.disabled.unwrap_or(false))
+        .map(|var| (var.key, var.value))
+        .collect();
+
+    let collection = Collection {
+        id: Uuid::new_v4().to_string(),
+        name: postman_collection.info.name,
+        description: postman_collection.info.description,
+        requests: request_ids,
+        folders: folder_ids,
+        variables,
+        scripts: convert_events(&postman_collection.event),
+        execution: None,
+    };
+
+    Ok(CollectionBundle { collection, folders, requests })
+}
+
+// Export ke Postman collection v2.1. Assertion, capture, snapshot dan aturan flow tidak punya padanan di Postman
+// sehingga tidak ikut; script tetap ikut.
+pub fn export(bundle: &CollectionBundle) -> Result<String, String> {
+    let folders: HashMap<&str, &Folder> = bundle.folders.iter().map(|folder| (folder.id.as_str(), folder)).collect();
+    let requests: HashMap<&str, &Request> = bundle.requests.iter().map(|request| (request.id.as_str(), request)).collect();
+    let collection = &bundle.collection;
+
+    let variables: Vec<PostmanVariable> = collection.variables.iter()
+        .map(|(key, value)| PostmanVariable {
+            key: key.clone(),
+            value: value.clone(),
+            var_type: Some("string".to_string()),
+            disabled: None,
+        })
+        .collect();
+
+    let postman_collection = PostmanCollection {
+        info: PostmanInfo {
+            name: collection.name.clone(),
+            description: collection.description.clone(),
+            postman_id: Some(collection.id.clone()),
+            schema: Some(SCHEMA.to_string()),
+        },
+        item: export_items(&collection.requests, &collection.folders, &folders, &requests),
+        variable: if variables.is_empty() { None } else { Some(variables) },
+        event: convert_scripts(&collection.scripts),
+    };
+
+    serde_json::to_string_pretty(&postman_collection)
+        .map_err(|e| format!("Failed to serialize collection: {}", e))
+}
+
+// Konversi event Postman ke script fmus
+fn convert_events(events: &Option<Vec<PostmanEvent>>) -> Scripts {
+    let mut scripts = Scripts::default();
+    for event in events.iter().flatten() {
+        let source = event.script.exec.source();
+        if source.trim().is_empty() {
+            continue;
+        }
+
+        match event.listen.as_str() {
+            "prerequest" => scripts.pre_request = Some(source),
+            "test" => scripts.test = Some(source),
+            _ => {},
+        }
+    }
+    scripts
+}
+
+// Konversi script fmus ke event Postman
+fn convert_scripts(scripts: &Scripts) -> Option<Vec<PostmanEvent>> {
+    let events: Vec<PostmanEvent> = [("prerequest", &scripts.pre_request), ("test", &scripts.test)]
+        .into_iter()
+        .filter_map(|(listen, source)| {
+            source.as_ref().map(|source| PostmanEvent {
+                listen: listen.to_string(),
+                script: PostmanScript {
+                    script_type: Some("text/javascript".to_string()),
+                    exec: PostmanExec::Lines(source.lines().map(|line| line.to_string()).collect()),
+                },
+            })
+        })
+        .collect();
+
+    if events.is_empty() { None } else { Some(events) }
+}
+
+// Konversi auth Postman ({"type": "bearer", "bearer": [{key, value}]}) ke format fmus-core
+fn convert_auth(auth: &Value) -> Option<Value> {
+    let auth_type = auth.get("type")?.as_str()?;
+    let mut credentials = serde_json::Map::new();
+    for entry in auth.get(auth_type).and_then(|v| v.as_array()).into_iter().flatten() {
+        if let (Some(key), Some(value)) = (entry.get("key").and_then(|k| k.as_str()), entry.get("value")) {
+            credentials.insert(key.to_string(), value.clone());
+        }
+    }
+
+    let (auth_type, credentials) = match auth_type {
+        "noauth" => return None,
+        "apikey" => ("api_key", json!({
+            "key": credentials.get("key").cloned().unwrap_or(Value::Null),
+            "value": credentials.get("value").cloned().unwrap_or(Value::Null),
+            "location": credentials.get("in").cloned().unwrap_or(json!("header")),
+        })),
+        "oauth2" => ("oauth2", json!({
+            "access_token": credentials.get("accessToken").cloned().unwrap_or(Value::Null),
+        })),
+        other => (other, Value::Object(credentials)),
+    };
+
+    Some(json!({ "type": auth_type, "credentials": credentials }))
+}
+
+// Kebalikan convert_auth: auth fmus-core ke format Postman
+fn export_auth(auth: &Value) -> Option<Value> {
+    let auth_type = auth.get("type").or_else(|| auth.get("auth_type"))?.as_str()?;
+    let credentials = auth.get("credentials").cloned().unwrap_or(Value::Null);
+
+    let (postman_type, entries): (&str, Vec<(&str, Value)>) = match auth_type {
+        "none" => return None,
+        "api_key" => ("apikey", vec![
+            ("key", credentials.get("key").cloned().unwrap_or(Value::Null)),
+            ("value", credentials.get("value").cloned().unwrap_or(Value::Null)),
+            ("in", credentials.get("location").cloned().unwrap_or(json!("header"))),
+        ]),
+        "oauth2" => ("oauth2", vec![("accessToken", credentials.get("access_token").cloned().unwrap_or(Value::Null))]),
+        // Token bearer boleh ditulis langsung sebagai string
+        "bearer" if credentials.is_string() => ("bearer", vec![("token", credentials.clone())]),
+        other => (other, credentials.as_object().into_iter().flatten().map(|(key, value)| (key.as_str(), value.clone())).collect()),
+    };
+
+    let entries: Vec<Value> = entries.into_iter()
+        .map(|(key, value)| json!({ "key": key, "value": value, "type": "string" }))
+        .collect();
+    Some(json!({ "type": postman_type, postman_type: entries }))
+}
+
+// Konversi request Postman ke request fmus
+fn convert_request(name: String, description: Option<String>, request: PostmanRequest, scripts: Scripts) -> Request {
+    let headers = request.header.unwrap_or_default()
+        .into_iter()
+        .filter(|h| !h.disabled.unwrap_or(false))
+        .map(|h| (h.key, h.value))
+        .collect();
+
+    let (raw, query) = match request.url {
+        PostmanUrl::Raw(raw) => (raw, None),
+        PostmanUrl::Parts { raw, query, .. } => (raw, query),
+    };
+
+    // Query string di URL string biasa dipisah ke params seperti query pada URL object
+    let params = match query {
+        Some(query) => query.into_iter()
+            .filter(|q| !q.disabled.unwrap_or(false))
+            .map(|q| (q.key, q.value))
+            .collect(),
+        None => raw.split_once('?')
+            .map(|(_, query)| url::form_urlencoded::parse(query.as_bytes()).into_owned().collect())
+            .unwrap_or_default(),
+    };
+
+    // Hanya body raw yang didukung, JSON disimpan sebagai objek
+    let body = request.body
+        .filter(|body| body.mode == "raw")
+        .and_then(|body| body.raw)
+        .filter(|raw| !raw.is_empty())
+        .map(|raw| serde_json::from_str(&raw).unwrap_or(Value::String(raw)));
+
+    // Query sudah dipisah ke params, jadi dibuang dari URL
+    let url = raw.split('?').next().unwrap_or_default().to_string();
+
+    Request {
+        id: Uuid::new_v4().to_string(),
+        name,
+        description,
+        method: request.method,
+        url,
+        headers,
+        params,
+        body,
+        auth: request.auth.as_ref().and_then(convert_auth),
+        schema: None,
+        assertions: Vec::new(),
+        scripts,
+        snapshot: None,
+        captures: Vec::new(),
+        body_format: None,
+        response_format: None,
+        flow: Vec::new(),
+    }
+}
+
+fn export_request(request: &Request) -> PostmanItem {
+    let mut query: Vec<PostmanQueryParam> = request.params.iter()
+        .map(|(key, value)| PostmanQueryParam { key: key.clone(), value: value.clone(), disabled: None })
+        .collect();
+    query.sort_by(|a, b| a.key.cmp(&b.key));
+
+    let mut raw = request.url.clone();
+    if !query.is_empty() {
+        let pairs: Vec<String> = query.iter().map(|q| format!("{}={}", q.key, q.value)).collect();
+        raw = format!("{}?{}", raw, pairs.join("&"));
+    }
+
+    let mut header: Vec<PostmanHeader> = request.headers.iter()
+        .map(|(key, value)| PostmanHeader { key: key.clone(), value: value.clone(), disabled: None })
+        .collect();
+    header.sort_by(|a, b| a.key.cmp(&b.key));
+
+    let body = request.body.as_ref().map(|body| {
+        let (raw, language) = match body {
+            Value::String(text) => (text.clone(), "text"),
+            other => (serde_json::to_string_pretty(other).unwrap_or_default(), "json"),
+        };
+        PostmanBody {
+            mode: "raw".to_string(),
+            raw: Some(raw),
+            formdata: None,
+            urlencoded: None,
+            options: Some(json!({ "raw": { "language": language } })),
+        }
+    });
+
+    PostmanItem {
+        name: request.name.clone(),
+        description: request.description.clone(),
+        item: None,
+        request: Some(PostmanRequest {
+            method: request.method.clone(),
+            url: PostmanUrl::Parts {
+                raw,
+                host: Vec::new(),
+                path: Vec::new(),
+                query: if query.is_empty() { None } else { Some(query) },
+            },
+            header: Some(header),
+            body,
+            auth: request.auth.as_ref().and_then(export_auth),
+        }),
+        event: convert_scripts(&request.scripts),
+    }
+}
+
+// Import item Postman secara rekursif, return ID request dan folder pada level ini
+fn import_items(items: Vec<PostmanItem>, folders: &mut Vec<Folder>, requests: &mut Vec<Request>) -> (Vec<String>, Vec<String>) {
+    let mut request_ids = Vec::new();
+    let mut folder_ids = Vec::new();
+
+    for item in items {
+        let scripts = convert_events(&item.event);
+
+        if let Some(request) = item.request {
+            let request = convert_request(item.name, item.description, request, scripts);
+            request_ids.push(request.id.clone());
+            requests.push(request);
+        } else {
+            let (sub_request_ids, sub_folder_ids) = import_items(item.item.unwrap_or_default(), folders, requests);
+            let folder = Folder {
+                id: Uuid::new_v4().to_string(),
+                name: item.name,
+                description: item.description,
+                requests: sub_request_ids,
+                folders: sub_folder_ids,
+                scripts,
+                execution: None,
+            };
+            folder_ids.push(folder.id.clone());
+            folders.push(folder);
+        }
+    }
+
+    (request_ids, folder_ids)
+}
+
+// Request lebih dulu lalu sub-folder, sama dengan urutan collection runner
+fn export_items(
+    request_ids: &[String],
+    folder_ids: &[String],
+    folders: &HashMap<&str, &Folder>,
+    requests: &HashMap<&str, &Request>,
+) -> Vec<PostmanItem> {
+    let mut items: Vec<PostmanItem> = request_ids.iter()
+        .filter_map(|id| requests.get(id.as_str()))
+        .map(|request| export_request(request))
+        .collect();
+
+    for folder in folder_ids.iter().filter_map(|id| folders.get(id.as_str())) {
+        items.push(PostmanItem {
+            name: folder.name.clone(),
+            description: folder.description.clone(),
+            item: Some(export_items(&folder.requests, &folder.folders, folders, requests)),
+            request: None,
+            event: convert_scripts(&folder.scripts),
+        });
+    }
+
+    items
+}
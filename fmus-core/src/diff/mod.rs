@@ -0,0 +1,476 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use similar::TextDiff;
+
+use crate::http::HttpResponse;
+use crate::jsonpath;
+
+static UUID_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}$").unwrap()
+});
+
+// ISO 8601 / RFC 3339, misalnya 2024-01-31T10:00:00Z atau 2024-01-31 10:00:00.123+07:00
+static TIMESTAMP_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^\d{4}-\d{2}-\d{2}([T ]\d{2}:\d{2}(:\d{2}(\.\d+)?)?(Z|[+-]\d{2}:?\d{2})?)?$").unwrap()
+});
+
+// Matcher untuk field yang nilainya selalu berubah: nilai aktual cukup memenuhi matcher
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ValueMatcher {
+    Uuid,
+    // String ISO 8601 atau angka epoch
+    Timestamp,
+    Regex { pattern: String },
+    // Nilai boleh berbeda asalkan tipe JSON-nya sama
+    Type,
+    // Field harus ada, nilainya bebas
+    Any,
+}
+
+impl ValueMatcher {
+    pub fn check(&self, value: &Value) -> Result<(), String> {
+        let text = value.as_str();
+        let passed = match self {
+            ValueMatcher::Uuid => text.is_some_and(|text| UUID_PATTERN.is_match(text)),
+            ValueMatcher::Timestamp => match value {
+                Value::Number(_) => true,
+                Value::String(text) => TIMESTAMP_PATTERN.is_match(text),
+                _ => false,
+            },
+            ValueMatcher::Regex { pattern } => {
+                let regex = Regex::new(pattern).map_err(|e| format!("Invalid regex '{}': {}", pattern, e))?;
+                match value {
+                    Value::String(text) => regex.is_match(text),
+                    other => regex.is_match(&other.to_string()),
+                }
+            },
+            ValueMatcher::Type | ValueMatcher::Any => true,
+        };
+
+        if passed {
+            Ok(())
+        } else {
+            Err(format!("{} does not match {}", value, self.describe()))
+        }
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            ValueMatcher::Uuid => "uuid".to_string(),
+            ValueMatcher::Timestamp => "timestamp".to_string(),
+            ValueMatcher::Regex { pattern } => format!("/{}/", pattern),
+            ValueMatcher::Type => "type".to_string(),
+            ValueMatcher::Any => "any".to_string(),
+        }
+    }
+}
+
+// Matcher untuk semua node yang cocok dengan JSONPath
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FieldMatcher {
+    pub path: String,
+    pub matcher: ValueMatcher,
+}
+
+// Aturan perbandingan: path yang diabaikan dan matcher untuk field yang berubah-ubah
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CompareRules {
+    // Ekspresi JSONPath, misalnya "$.meta.requestId" atau "$..updatedAt"
+    #[serde(default)]
+    pub ignore: Vec<String>,
+    #[serde(default)]
+    pub matchers: Vec<FieldMatcher>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffKind {
+    // Ada di nilai aktual, tidak ada di nilai yang diharapkan
+    Added,
+    // Ada di nilai yang diharapkan, tidak ada di nilai aktual
+    Removed,
+    Changed,
+    // Nilai aktual tidak memenuhi matcher
+    Mismatch,
+}
+
+// Satu perbedaan, path dalam format normalized JSONPath seperti $['items'][0]['id']
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Difference {
+    pub path: String,
+    pub kind: DiffKind,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub actual: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+impl Difference {
+    pub fn changed(path: String, expected: Value, actual: Value) -> Self {
+        Self { path, kind: DiffKind::Changed, expected: Some(expected), actual: Some(actual), message: None }
+    }
+
+    // Satu baris yang mudah dibaca, misalnya "~ $['id']: 1 -> 2"
+    pub fn describe(&self) -> String {
+        let show = |value: &Option<Value>| value.as_ref().map(|v| v.to_string()).unwrap_or_default();
+        match self.kind {
+            DiffKind::Added => format!("+ {}: {}", self.path, show(&self.actual)),
+            DiffKind::Removed => format!("- {}: {}", self.path, show(&self.expected)),
+            DiffKind::Changed => match &self.message {
+                Some(message) => format!("~ {}: {}", self.path, message),
+                None => format!("~ {}: {} -> {}", self.path, show(&self.expected), show(&self.actual)),
+            },
+            DiffKind::Mismatch => format!(
+                "! {}: {}",
+                self.path,
+                self.message.clone().unwrap_or_else(|| show(&self.actual))
+            ),
+        }
+    }
+}
+
+// Perbandingan struktural dua nilai JSON dengan aturan ignore dan matcher
+pub fn diff_json(expected: &Value, actual: &Value, rules: &CompareRules) -> Result<Vec<Difference>, String> {
+    let mut ignored = HashSet::new();
+    for path in &rules.ignore {
+        for value in [expected, actual] {
+            ignored.extend(jsonpath::query_located(value, path)?.into_iter().map(|(location, _)| location));
+        }
+    }
+
+    let mut matchers = HashMap::new();
+    for field in &rules.matchers {
+        for value in [expected, actual] {
+            for (location, _) in jsonpath::query_located(value, &field.path)? {
+                matchers.entry(location).or_insert(&field.matcher);
+            }
+        }
+    }
+
+    let context = DiffContext { ignored, matchers };
+    let mut differences = Vec::new();
+    context.compare("$".to_string(), Some(expected), Some(actual), &mut differences);
+    Ok(differences)
+}
+
+struct DiffContext<'a> {
+    ignored: HashSet<String>,
+    matchers: HashMap<String, &'a ValueMatcher>,
+}
+
+impl DiffContext<'_> {
+    fn compare(&self, path: String, expected: Option<&Value>, actual: Option<&Value>, differences: &mut Vec<Difference>) {
+        if self.ignored.contains(&path) {
+            return;
+        }
+
+        let (expected, actual) = match (expected, actual) {
+            (Some(expected), Some(actual)) => (expected, actual),
+            (None, Some(actual)) => {
+                differences.push(Difference { path, kind: DiffKind::Added, expected: None, actual: Some(actual.clone()), message: None });
+                return;
+            },
+            (Some(expected), None) => {
+                differences.push(Difference { path, kind: DiffKind::Removed, expected: Some(expected.clone()), actual: None, message: None });
+                return;
+            },
+            (None, None) => return,
+        };
+
+        if let Some(matcher) = self.matchers.get(&path) {
+            let result = match matcher {
+                ValueMatcher::Type if json_type(expected) != json_type(actual) => {
+                    Err(format!("expected type {} but got {}", json_type(expected), json_type(actual)))
+                },
+                matcher => matcher.check(actual),
+            };
+
+            if let Err(message) = result {
+                differences.push(Difference {
+                    path,
+                    kind: DiffKind::Mismatch,
+                    expected: Some(expected.clone()),
+                    actual: Some(actual.clone()),
+                    message: Some(message),
+                });
+            }
+            return;
+        }
+
+        match (expected, actual) {
+            (Value::Object(expected_obj), Value::Object(actual_obj)) => {
+                for (key, expected_value) in expected_obj {
+                    self.compare(format!("{}{}", path, jsonpath::quote_name(key)), Some(expected_value), actual_obj.get(key), differences);
+                }
+                for (key, actual_value) in actual_obj.iter().filter(|(key, _)| !expected_obj.contains_key(*key)) {
+                    self.compare(format!("{}{}", path, jsonpath::quote_name(key)), None, Some(actual_value), differences);
+                }
+            },
+            (Value::Array(expected_items), Value::Array(actual_items)) => {
+                for index in 0..expected_items.len().max(actual_items.len()) {
+                    self.compare(format!("{}[{}]", path, index), expected_items.get(index), actual_items.get(index), differences);
+                }
+            },
+            (expected, actual) if expected != actual => {
+                differences.push(Difference::changed(path, expected.clone(), actual.clone()));
+            },
+            _ => {},
+        }
+    }
+}
+
+fn json_type(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+// Unified diff baris per baris untuk body teks
+pub fn text_diff(expected: &str, actual: &str) -> String {
+    TextDiff::from_lines(expected, actual)
+        .unified_diff()
+        .context_radius(3)
+        .header("expected", "actual")
+        .to_string()
+}
+
+// Daftar perbedaan dalam bentuk teks, satu baris per perbedaan
+pub fn render(differences: &[Difference]) -> String {
+    differences.iter().map(|difference| difference.describe()).collect::<Vec<_>>().join("\n")
+}
+
+// Perbedaan header, nama header dibandingkan dalam huruf kecil
+pub fn diff_headers(expected: &BTreeMap<String, String>, actual: &BTreeMap<String, String>) -> Vec<Difference> {
+    let names: std::collections::BTreeSet<_> = expected.keys().chain(actual.keys()).collect();
+    names
+        .into_iter()
+        .filter_map(|name| {
+            let (expected, actual) = (expected.get(name), actual.get(name));
+            let kind = match (expected, actual) {
+                (None, Some(_)) => DiffKind::Added,
+                (Some(_), None) => DiffKind::Removed,
+                (Some(expected), Some(actual)) if expected != actual => DiffKind::Changed,
+                _ => return None,
+            };
+
+            Some(Difference {
+                path: format!("headers{}", jsonpath::quote_name(name)),
+                kind,
+                expected: expected.map(|value| Value::String(value.clone())),
+                actual: actual.map(|value| Value::String(value.clone())),
+                message: None,
+            })
+        })
+        .collect()
+}
+
+// Opsi perbandingan response
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DiffOptions {
+    // Aturan ignore dan matcher untuk body JSON
+    #[serde(default)]
+    pub rules: CompareRules,
+    // Header yang selalu berbeda dan tidak perlu dibandingkan, misalnya date atau x-request-id
+    #[serde(default)]
+    pub ignore_headers: Vec<String>,
+}
+
+// Perubahan status code
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StatusChange {
+    pub before: u16,
+    pub after: u16,
+}
+
+// Hasil perbandingan dua response: a dianggap "sebelum" dan b "sesudah"
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ResponseDiff {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<StatusChange>,
+    #[serde(default)]
+    pub headers: Vec<Difference>,
+    // Perbedaan struktural body JSON, atau satu perubahan di "$" untuk body teks/biner
+    #[serde(default)]
+    pub body: Vec<Difference>,
+    // Line diff jika body bukan JSON
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text_diff: Option<String>,
+}
+
+impl ResponseDiff {
+    pub fn is_empty(&self) -> bool {
+        self.status.is_none() && self.headers.is_empty() && self.body.is_empty()
+    }
+
+    // Ringkasan yang mudah dibaca
+    pub fn render(&self) -> String {
+        let mut sections = Vec::new();
+        if let Some(status) = &self.status {
+            sections.push(format!("~ status: {} -> {}", status.before, status.after));
+        }
+        if !self.headers.is_empty() {
+            sections.push(render(&self.headers));
+        }
+        match &self.text_diff {
+            Some(text_diff) => sections.push(text_diff.clone()),
+            None if !self.body.is_empty() => sections.push(render(&self.body)),
+            None => {},
+        }
+        sections.join("\n")
+    }
+}
+
+// Membandingkan dua response tanpa aturan tambahan
+pub fn diff(a: &HttpResponse, b: &HttpResponse) -> ResponseDiff {
+    diff_with_options(a, b, &DiffOptions::default()).unwrap_or_default()
+}
+
+// Membandingkan dua response: status, header, lalu body JSON secara struktural atau teks per baris
+pub fn diff_with_options(a: &HttpResponse, b: &HttpResponse, options: &DiffOptions) -> Result<ResponseDiff, String> {
+    let headers = |response: &HttpResponse| -> BTreeMap<String, String> {
+        response.headers
+            .iter()
+            .map(|(name, value)| (name.to_lowercase(), value.clone()))
+            .filter(|(name, _)| !options.ignore_headers.iter().any(|ignored| ignored.eq_ignore_ascii_case(name)))
+            .collect()
+    };
+
+    let mut result = ResponseDiff {
+        status: (a.status != b.status).then_some(StatusChange { before: a.status, after: b.status }),
+        headers: diff_headers(&headers(a), &headers(b)),
+        ..ResponseDiff::default()
+    };
+
+    if a.body == b.body {
+        return Ok(result);
+    }
+
+    match (a.json(), b.json(), a.text(), b.text()) {
+        (Ok(before), Ok(after), _, _) => result.body = diff_json(&before, &after, &options.rules)?,
+        (_, _, Ok(before), Ok(after)) => {
+            result.text_diff = Some(text_diff(&before, &after));
+            result.body.push(Difference::changed("$".to_string(), Value::String(before), Value::String(after)));
+        },
+        _ => result.body.push(Difference {
+            path: "$".to_string(),
+            kind: DiffKind::Changed,
+            expected: None,
+            actual: None,
+            message: Some(format!("binary body changed ({} bytes -> {} bytes)", a.body.len(), b.body.len())),
+        }),
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn ignore_paths_match_keys_that_need_escaping() {
+        let expected = json!({"it's": 1, "a\\b": 1, "plain": 1});
+        let actual = json!({"it's": 2, "a\\b": 2, "plain": 2});
+        let rules = CompareRules { ignore: vec!["$[\"it's\"]".to_string(), "a\\b".to_string()], matchers: Vec::new() };
+
+        let differences = diff_json(&expected, &actual, &rules).unwrap();
+        assert_eq!(differences.iter().map(|difference| difference.path.as_str()).collect::<Vec<_>>(), ["$['plain']"]);
+
+        let differences = diff_json(&expected, &actual, &CompareRules::default()).unwrap();
+        assert!(differences.iter().any(|difference| difference.path == "$['it\\'s']"));
+    }
+
+    fn paths(differences: &[Difference]) -> Vec<(&str, DiffKind)> {
+        differences.iter().map(|difference| (difference.path.as_str(), difference.kind)).collect()
+    }
+
+    #[test]
+    fn arrays_are_compared_by_index() {
+        let expected = json!({"items": [1, 2, 3]});
+        let actual = json!({"items": [1, 5]});
+
+        let differences = diff_json(&expected, &actual, &CompareRules::default()).unwrap();
+        assert_eq!(paths(&differences), [("$['items'][1]", DiffKind::Changed), ("$['items'][2]", DiffKind::Removed)]);
+        assert_eq!(differences[1].expected, Some(json!(3)));
+
+        let differences = diff_json(&actual, &expected, &CompareRules::default()).unwrap();
+        assert_eq!(paths(&differences), [("$['items'][1]", DiffKind::Changed), ("$['items'][2]", DiffKind::Added)]);
+    }
+
+    #[test]
+    fn type_changes_replace_the_whole_value() {
+        let expected = json!({"id": 1, "tags": ["a"], "meta": {"a": 1}});
+        let actual = json!({"id": "1", "tags": {"0": "a"}, "meta": null});
+
+        let differences = diff_json(&expected, &actual, &CompareRules::default()).unwrap();
+        assert_eq!(paths(&differences), [
+            ("$['id']", DiffKind::Changed),
+            ("$['meta']", DiffKind::Changed),
+            ("$['tags']", DiffKind::Changed),
+        ]);
+        assert_eq!(differences[0].describe(), "~ $['id']: 1 -> \"1\"");
+
+        // Matcher type hanya menerima nilai dengan tipe JSON yang sama
+        let rules = CompareRules {
+            ignore: Vec::new(),
+            matchers: vec![FieldMatcher { path: "$.id".to_string(), matcher: ValueMatcher::Type }],
+        };
+        let differences = diff_json(&json!({"id": 1}), &json!({"id": 2}), &rules).unwrap();
+        assert!(differences.is_empty());
+        let differences = diff_json(&json!({"id": 1}), &json!({"id": "2"}), &rules).unwrap();
+        assert_eq!(differences[0].message.as_deref(), Some("expected type number but got string"));
+    }
+
+    #[test]
+    fn keys_are_quoted_in_paths() {
+        let expected = json!({"a.b": {"it's": 1}, "[0]": 1});
+        let actual = json!({"a.b": {"it's": 2}, "x\ny": 1});
+
+        let differences = diff_json(&expected, &actual, &CompareRules::default()).unwrap();
+        assert_eq!(paths(&differences), [
+            ("$['[0]']", DiffKind::Removed),
+            ("$['a.b']['it\\'s']", DiffKind::Changed),
+            ("$['x\\ny']", DiffKind::Added),
+        ]);
+        // Path perbedaan bisa dipakai lagi sebagai JSONPath
+        assert_eq!(jsonpath::query(&actual, &differences[1].path).unwrap(), vec![&json!(2)]);
+    }
+
+    #[test]
+    fn response_diff_covers_status_headers_and_text() {
+        let response = |status: u16, etag: &str, body: &str| HttpResponse {
+            status,
+            headers: HashMap::from([
+                ("Content-Type".to_string(), "text/plain".to_string()),
+                ("ETag".to_string(), etag.to_string()),
+                ("Date".to_string(), status.to_string()),
+            ]),
+            body: body.as_bytes().to_vec(),
+            time: 1,
+        };
+        let options = DiffOptions { ignore_headers: vec!["date".to_string()], ..DiffOptions::default() };
+
+        let result = diff_with_options(&response(200, "a", "one\ntwo\n"), &response(500, "b", "one\nthree\n"), &options).unwrap();
+        assert_eq!(result.status, Some(StatusChange { before: 200, after: 500 }));
+        assert_eq!(paths(&result.headers), [("headers['etag']", DiffKind::Changed)]);
+        assert!(result.text_diff.as_deref().unwrap().contains("+three"));
+
+        let mut later = response(200, "a", "x");
+        later.headers.insert("Date".to_string(), "later".to_string());
+        assert_eq!(paths(&diff(&response(200, "a", "x"), &later).headers), [("headers['date']", DiffKind::Changed)]);
+        assert!(diff_with_options(&response(200, "a", "x"), &later, &options).unwrap().is_empty());
+    }
+}
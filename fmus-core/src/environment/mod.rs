@@ -0,0 +1,168 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::vault::{Secret, SecretRef, Vault};
+
+// Variable environment. Nilai secret disimpan di vault dan hanya referensinya yang ada di file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnvVariable {
+    pub key: String,
+    pub value: String,
+    pub enabled: bool,
+    #[serde(default)]
+    pub is_secret: bool,
+    // Referensi ke vault untuk secret, value dikosongkan setelah disegel
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secret_ref: Option<SecretRef>,
+}
+
+impl EnvVariable {
+    pub fn new(key: &str, value: &str) -> Self {
+        EnvVariable {
+            key: key.to_string(),
+            value: value.to_string(),
+            enabled: true,
+            is_secret: false,
+            secret_ref: None,
+        }
+    }
+
+    // Nilai sebenarnya dari variable, secret dibuka lewat vault
+    pub fn resolve(&self, vault: Option<&Vault>) -> Result<String, String> {
+        match &self.secret_ref {
+            Some(secret_ref) => Secret::Ref(secret_ref.clone()).reveal(vault),
+            None => Ok(self.value.clone()),
+        }
+    }
+
+    // Pindahkan nilai secret ke vault, return true jika ada yang disegel
+    pub fn seal(&mut self, vault: &mut Vault, env_name: &str) -> Result<bool, String> {
+        if !self.is_secret || self.secret_ref.is_some() {
+            return Ok(false);
+        }
+
+        let name = format!("env.{}.{}", env_name, self.key);
+        self.secret_ref = Some(vault.put_secret(&name, &self.value)?);
+        self.value.clear();
+        Ok(true)
+    }
+}
+
+// Environment dengan format yang sama dengan export aplikasi desktop
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Environment {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub variables: Vec<EnvVariable>,
+    pub is_active: bool,
+}
+
+impl Environment {
+    pub fn new(name: &str, description: Option<String>) -> Self {
+        Environment {
+            id: Uuid::new_v4().to_string(),
+            name: name.to_string(),
+            description,
+            variables: Vec::new(),
+            is_active: false,
+        }
+    }
+
+    // Membaca file environment: export aplikasi desktop, atau object {"nama": "nilai"} biasa
+    // yang diberi nama dari nama file tanpa ekstensi (dan tanpa akhiran .env)
+    pub fn load(path: &Path) -> Result<Environment, String> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let value: serde_json::Value = serde_json::from_str(&content)
+            .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?;
+
+        if value.get("variables").is_some_and(|variables| variables.is_array()) {
+            return serde_json::from_value(value)
+                .map_err(|e| format!("Invalid environment file {}: {}", path.display(), e));
+        }
+
+        let values: HashMap<String, String> = serde_json::from_value(value)
+            .map_err(|e| format!("Environment file {} must map names to string values: {}", path.display(), e))?;
+        let name = path.file_stem()
+            .and_then(|stem| stem.to_str())
+            .map(|stem| stem.trim_end_matches(".env"))
+            .unwrap_or_default();
+
+        let mut environment = Environment::new(name, None);
+        environment.id = name.to_string();
+        let mut keys: Vec<&String> = values.keys().collect();
+        keys.sort();
+        environment.variables = keys.into_iter().map(|key| EnvVariable::new(key, &values[key])).collect();
+        Ok(environment)
+    }
+
+    // Simpan dalam format export aplikasi desktop
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize environment: {}", e))?;
+        fs::write(path, content).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+
+    // true jika id atau nama cocok
+    pub fn matches(&self, id_or_name: &str) -> bool {
+        self.id == id_or_name || self.name == id_or_name
+    }
+
+    pub fn variable(&self, key: &str) -> Option<&EnvVariable> {
+        self.variables.iter().find(|v| v.key == key)
+    }
+
+    // Tambah atau ganti variable dengan key yang sama, return referensi vault lama jika ada
+    pub fn set_variable(&mut self, variable: EnvVariable) -> Option<SecretRef> {
+        match self.variables.iter_mut().find(|v| v.key == variable.key) {
+            Some(existing) => std::mem::replace(existing, variable).secret_ref,
+            None => {
+                self.variables.push(variable);
+                None
+            },
+        }
+    }
+
+    // Hapus variable, return variable yang dihapus
+    pub fn remove_variable(&mut self, key: &str) -> Option<EnvVariable> {
+        let index = self.variables.iter().position(|v| v.key == key)?;
+        Some(self.variables.remove(index))
+    }
+
+    // Nilai semua variable aktif, secret dibuka lewat vault
+    pub fn resolve_variables(&self, vault: Option<&Vault>) -> Result<HashMap<String, String>, String> {
+        let mut variables = HashMap::new();
+        for variable in self.variables.iter().filter(|v| v.enabled) {
+            variables.insert(variable.key.clone(), variable.resolve(vault)?);
+        }
+
+        Ok(variables)
+    }
+
+    // Nilai variable secret yang aktif, untuk disensor dari laporan
+    pub fn secret_values(&self, vault: Option<&Vault>) -> Result<Vec<String>, String> {
+        self.variables.iter()
+            .filter(|v| v.enabled && v.is_secret)
+            .map(|v| v.resolve(vault))
+            .collect()
+    }
+
+    // Salinan untuk ekspor, nilai secret tidak pernah ikut
+    pub fn redacted(&self) -> Environment {
+        let mut environment = self.clone();
+        for variable in environment.variables.iter_mut().filter(|v| v.is_secret) {
+            variable.value.clear();
+        }
+        environment
+    }
+
+    // Semua referensi vault yang dipakai environment ini
+    pub fn secret_refs(&self) -> Vec<SecretRef> {
+        self.variables.iter().filter_map(|v| v.secret_ref.clone()).collect()
+    }
+}
@@ -0,0 +1,313 @@
+use regex::Regex;
+use serde_json::Value;
+
+// Ekspresi kondisi flow, misalnya:
+//   status == 200 && json.state != "done"
+//   vars.attempts < 5 || headers["retry-after"]
+//   !token && body contains "login"
+// Nama di awal path: status, time, size, headers, body, json dan vars. Nama lain dibaca sebagai variabel.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Expr {
+    Literal(Value),
+    Path(Vec<Segment>),
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Compare(Box<Expr>, CompareOp, Box<Expr>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Contains,
+    Matches,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Str(String),
+    Num(f64),
+    Op(&'static str),
+    Dot,
+    LBracket,
+    RBracket,
+    LParen,
+    RParen,
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            c if c.is_whitespace() => i += 1,
+            '.' => { tokens.push(Token::Dot); i += 1; },
+            '[' => { tokens.push(Token::LBracket); i += 1; },
+            ']' => { tokens.push(Token::RBracket); i += 1; },
+            '(' => { tokens.push(Token::LParen); i += 1; },
+            ')' => { tokens.push(Token::RParen); i += 1; },
+            '"' | '\'' => {
+                let mut text = String::new();
+                i += 1;
+                loop {
+                    match chars.get(i) {
+                        None => return Err("Unterminated string in condition".to_string()),
+                        Some(&'\\') => {
+                            text.push(*chars.get(i + 1).ok_or("Unterminated string in condition")?);
+                            i += 2;
+                        },
+                        Some(&quote) if quote == c => break,
+                        Some(&other) => { text.push(other); i += 1; },
+                    }
+                }
+                tokens.push(Token::Str(text));
+                i += 1;
+            },
+            c if c.is_ascii_digit() || (c == '-' && chars.get(i + 1).is_some_and(char::is_ascii_digit)) => {
+                let start = i;
+                i += 1;
+                while chars.get(i).is_some_and(|c| c.is_ascii_digit() || *c == '.') {
+                    i += 1;
+                }
+                let text: String = chars[start..i].iter().collect();
+                let number = text.parse().map_err(|_| format!("Invalid number in condition: {}", text))?;
+                tokens.push(Token::Num(number));
+            },
+            c if c.is_alphabetic() || c == '_' || c == '$' => {
+                let start = i;
+                while chars.get(i).is_some_and(|c| c.is_alphanumeric() || *c == '_' || *c == '$' || *c == '-') {
+                    i += 1;
+                }
+                tokens.push(Token::Ident(chars[start..i].iter().collect()));
+            },
+            _ => {
+                let rest: String = chars[i..chars.len().min(i + 2)].iter().collect();
+                let op = ["==", "!=", "<=", ">=", "&&", "||"].into_iter()
+                    .find(|op| rest.starts_with(op))
+                    .or_else(|| ["<", ">", "!"].into_iter().find(|op| rest.starts_with(op)))
+                    .ok_or_else(|| format!("Unexpected character '{}' in condition", c))?;
+                tokens.push(Token::Op(op));
+                i += op.len();
+            },
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn eat(&mut self, token: &Token) -> bool {
+        if self.peek() == Some(token) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn or(&mut self) -> Result<Expr, String> {
+        let mut left = self.and()?;
+        while self.eat(&Token::Op("||")) {
+            left = Expr::Or(Box::new(left), Box::new(self.and()?));
+        }
+        Ok(left)
+    }
+
+    fn and(&mut self) -> Result<Expr, String> {
+        let mut left = self.not()?;
+        while self.eat(&Token::Op("&&")) {
+            left = Expr::And(Box::new(left), Box::new(self.not()?));
+        }
+        Ok(left)
+    }
+
+    fn not(&mut self) -> Result<Expr, String> {
+        if self.eat(&Token::Op("!")) {
+            return Ok(Expr::Not(Box::new(self.not()?)));
+        }
+        self.compare()
+    }
+
+    fn compare(&mut self) -> Result<Expr, String> {
+        let left = self.operand()?;
+        let op = match self.peek() {
+            Some(Token::Op("==")) => CompareOp::Eq,
+            Some(Token::Op("!=")) => CompareOp::Ne,
+            Some(Token::Op("<")) => CompareOp::Lt,
+            Some(Token::Op("<=")) => CompareOp::Le,
+            Some(Token::Op(">")) => CompareOp::Gt,
+            Some(Token::Op(">=")) => CompareOp::Ge,
+            Some(Token::Ident(word)) if word == "contains" => CompareOp::Contains,
+            Some(Token::Ident(word)) if word == "matches" => CompareOp::Matches,
+            _ => return Ok(left),
+        };
+        self.position += 1;
+        Ok(Expr::Compare(Box::new(left), op, Box::new(self.operand()?)))
+    }
+
+    fn operand(&mut self) -> Result<Expr, String> {
+        match self.next() {
+            Some(Token::LParen) => {
+                let expr = self.or()?;
+                if !self.eat(&Token::RParen) {
+                    return Err("Expected ')' in condition".to_string());
+                }
+                Ok(expr)
+            },
+            Some(Token::Str(text)) => Ok(Expr::Literal(Value::String(text))),
+            Some(Token::Num(number)) => Ok(Expr::Literal(serde_json::Number::from_f64(number).map(Value::Number).unwrap_or(Value::Null))),
+            Some(Token::Ident(word)) => match word.as_str() {
+                "true" => Ok(Expr::Literal(Value::Bool(true))),
+                "false" => Ok(Expr::Literal(Value::Bool(false))),
+                "null" => Ok(Expr::Literal(Value::Null)),
+                _ => self.path(word),
+            },
+            Some(token) => Err(format!("Unexpected {:?} in condition", token)),
+            None => Err("Unexpected end of condition".to_string()),
+        }
+    }
+
+    fn path(&mut self, root: String) -> Result<Expr, String> {
+        let mut segments = vec![Segment::Key(root)];
+        loop {
+            if self.eat(&Token::Dot) {
+                match self.next() {
+                    Some(Token::Ident(key)) => segments.push(Segment::Key(key)),
+                    Some(Token::Num(index)) if index >= 0.0 && index.fract() == 0.0 => segments.push(Segment::Index(index as usize)),
+                    _ => return Err("Expected a name after '.' in condition".to_string()),
+                }
+            } else if self.eat(&Token::LBracket) {
+                match self.next() {
+                    Some(Token::Str(key)) => segments.push(Segment::Key(key)),
+                    Some(Token::Num(index)) if index >= 0.0 && index.fract() == 0.0 => segments.push(Segment::Index(index as usize)),
+                    _ => return Err("Expected a string or index inside [] in condition".to_string()),
+                }
+                if !self.eat(&Token::RBracket) {
+                    return Err("Expected ']' in condition".to_string());
+                }
+            } else {
+                return Ok(Expr::Path(segments));
+            }
+        }
+    }
+}
+
+pub(crate) fn parse(source: &str) -> Result<Expr, String> {
+    let mut parser = Parser { tokens: tokenize(source)?, position: 0 };
+    let expr = parser.or()?;
+    match parser.peek() {
+        None => Ok(expr),
+        Some(token) => Err(format!("Unexpected {:?} in condition", token)),
+    }
+}
+
+impl Expr {
+    // resolve dipanggil dengan path lengkap dan mengembalikan Null jika tidak ada
+    pub(crate) fn eval(&self, resolve: &dyn Fn(&[Segment]) -> Value) -> Result<Value, String> {
+        Ok(match self {
+            Expr::Literal(value) => value.clone(),
+            Expr::Path(segments) => resolve(segments),
+            Expr::Not(expr) => Value::Bool(!truthy(&expr.eval(resolve)?)),
+            Expr::And(left, right) => Value::Bool(truthy(&left.eval(resolve)?) && truthy(&right.eval(resolve)?)),
+            Expr::Or(left, right) => Value::Bool(truthy(&left.eval(resolve)?) || truthy(&right.eval(resolve)?)),
+            Expr::Compare(left, op, right) => Value::Bool(compare(&left.eval(resolve)?, *op, &right.eval(resolve)?)?),
+        })
+    }
+}
+
+// Sama seperti JavaScript: null, false, 0, "" dan path yang tidak ada bernilai false
+pub(crate) fn truthy(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(flag) => *flag,
+        Value::Number(number) => number.as_f64().is_some_and(|number| number != 0.0),
+        Value::String(text) => !text.is_empty(),
+        Value::Array(_) | Value::Object(_) => true,
+    }
+}
+
+// Variabel selalu berupa string, jadi "5" dibandingkan sebagai angka jika sisi lain juga angka
+fn number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(number) => number.as_f64(),
+        Value::String(text) => text.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+fn text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn compare(left: &Value, op: CompareOp, right: &Value) -> Result<bool, String> {
+    let numbers = number(left).zip(number(right));
+    Ok(match op {
+        CompareOp::Eq | CompareOp::Ne => {
+            let equal = match numbers {
+                Some((left, right)) => left == right,
+                None if left.is_null() || right.is_null() => left.is_null() && right.is_null(),
+                None => left == right || text(left) == text(right),
+            };
+            equal == (op == CompareOp::Eq)
+        },
+        CompareOp::Lt | CompareOp::Le | CompareOp::Gt | CompareOp::Ge => {
+            let ordering = match numbers {
+                Some((left, right)) => left.partial_cmp(&right),
+                None if left.is_null() || right.is_null() => None,
+                None => Some(text(left).cmp(&text(right))),
+            };
+            match ordering {
+                Some(ordering) => match op {
+                    CompareOp::Lt => ordering.is_lt(),
+                    CompareOp::Le => ordering.is_le(),
+                    CompareOp::Gt => ordering.is_gt(),
+                    _ => ordering.is_ge(),
+                },
+                None => false,
+            }
+        },
+        CompareOp::Contains => match left {
+            Value::Array(items) => items.iter().any(|item| compare(item, CompareOp::Eq, right).unwrap_or(false)),
+            Value::Object(map) => map.contains_key(&text(right)),
+            Value::Null => false,
+            other => text(other).contains(&text(right)),
+        },
+        CompareOp::Matches => {
+            let pattern = Regex::new(&text(right)).map_err(|e| format!("Invalid regex in condition: {}", e))?;
+            !left.is_null() && pattern.is_match(&text(left))
+        },
+    })
+}
@@ -0,0 +1,213 @@
+mod expr;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::http::HttpResponse;
+use crate::scripting::ScriptScope;
+
+use expr::Segment;
+
+// Batas percobaan repeat jika max_attempts tidak diisi
+const DEFAULT_MAX_ATTEMPTS: usize = 10;
+
+fn default_max_attempts() -> usize {
+    DEFAULT_MAX_ATTEMPTS
+}
+
+// Aturan alur sebuah request di collection runner, misalnya
+// {"when": "json.state != 'done'", "action": "repeat", "max_attempts": 20, "delay_ms": 1000}
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FlowRule {
+    // Ekspresi kondisi atas response dan variabel; kosong berarti selalu
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub when: Option<String>,
+    #[serde(flatten)]
+    pub action: FlowAction,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum FlowAction {
+    // Dicek sebelum request dikirim, jadi kondisinya hanya bisa membaca variabel
+    Skip,
+    // Lanjut ke request lain dalam run berdasarkan ID atau nama
+    Goto { request: String },
+    // Kirim ulang request ini selama kondisi terpenuhi, paling banyak max_attempts kali
+    Repeat {
+        #[serde(default = "default_max_attempts")]
+        max_attempts: usize,
+        #[serde(default)]
+        delay_ms: u64,
+    },
+    // Hentikan iterasi saat ini
+    Stop,
+}
+
+// Apa yang dilakukan runner setelah sebuah request, dicatat di hasil request
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum FlowDecision {
+    Skip,
+    Goto { request: String },
+    // attempt dimulai dari 1 untuk percobaan pertama
+    Repeat { attempt: usize, max_attempts: usize },
+    Stop,
+}
+
+// Cek sintaks semua kondisi, dipakai saat menyimpan request
+pub fn validate(rules: &[FlowRule]) -> Result<(), String> {
+    for condition in rules.iter().filter_map(|rule| rule.when.as_deref()) {
+        expr::parse(condition).map_err(|e| format!("Invalid flow condition '{}': {}", condition, e))?;
+    }
+    Ok(())
+}
+
+// Evaluasi satu kondisi. Tanpa response, status/time/size/headers/body/json bernilai null.
+pub fn evaluate(condition: &str, scope: &ScriptScope, response: Option<&HttpResponse>) -> Result<bool, String> {
+    let expr = expr::parse(condition).map_err(|e| format!("Invalid flow condition '{}': {}", condition, e))?;
+    let value = expr.eval(&|segments| resolve(segments, scope, response))?;
+    Ok(expr::truthy(&value))
+}
+
+// true jika ada aturan skip yang kondisinya terpenuhi
+pub fn should_skip(rules: &[FlowRule], scope: &ScriptScope) -> Result<bool, String> {
+    for rule in rules.iter().filter(|rule| rule.action == FlowAction::Skip) {
+        if matches(rule, scope, None)? {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+// Aturan pertama (selain skip) yang kondisinya terpenuhi setelah response diterima
+pub fn next_action<'a>(rules: &'a [FlowRule], scope: &ScriptScope, response: Option<&HttpResponse>) -> Result<Option<&'a FlowAction>, String> {
+    for rule in rules.iter().filter(|rule| rule.action != FlowAction::Skip) {
+        if matches(rule, scope, response)? {
+            return Ok(Some(&rule.action));
+        }
+    }
+    Ok(None)
+}
+
+fn matches(rule: &FlowRule, scope: &ScriptScope, response: Option<&HttpResponse>) -> Result<bool, String> {
+    match &rule.when {
+        Some(condition) => evaluate(condition, scope, response),
+        None => Ok(true),
+    }
+}
+
+fn resolve(segments: &[Segment], scope: &ScriptScope, response: Option<&HttpResponse>) -> Value {
+    let Some(Segment::Key(root)) = segments.first() else { return Value::Null };
+    let rest = &segments[1..];
+
+    match (root.as_str(), response) {
+        ("status", Some(response)) => Value::from(response.status),
+        ("time", Some(response)) => Value::from(response.time as u64),
+        ("size", Some(response)) => Value::from(response.body.len()),
+        ("body", Some(response)) => Value::String(response.text_lossy()),
+        ("json", Some(response)) => get(response.json().unwrap_or(Value::Null), rest),
+        // Nama header tidak case-sensitive
+        ("headers", Some(response)) => match rest.first() {
+            Some(Segment::Key(name)) => response.headers.iter()
+                .find(|(key, _)| key.eq_ignore_ascii_case(name))
+                .map(|(_, value)| Value::String(value.clone()))
+                .unwrap_or(Value::Null),
+            _ => Value::Null,
+        },
+        ("status" | "time" | "size" | "body" | "json" | "headers", None) => Value::Null,
+        ("vars", _) => match rest.first() {
+            Some(Segment::Key(name)) => variable(scope, name, &rest[1..]),
+            _ => Value::Null,
+        },
+        (name, _) => variable(scope, name, rest),
+    }
+}
+
+// Variabel selalu string; path lanjutan (vars.user.id) dibaca dari isi variabel yang berupa JSON
+fn variable(scope: &ScriptScope, name: &str, rest: &[Segment]) -> Value {
+    match scope.resolve(name) {
+        Some(value) if rest.is_empty() => Value::String(value),
+        Some(value) => get(serde_json::from_str(&value).unwrap_or(Value::Null), rest),
+        None => Value::Null,
+    }
+}
+
+fn get(value: Value, segments: &[Segment]) -> Value {
+    segments.iter().fold(value, |value, segment| match (value, segment) {
+        (Value::Object(mut map), Segment::Key(key)) => map.remove(key).unwrap_or(Value::Null),
+        (Value::Array(mut items), Segment::Index(index)) if *index < items.len() => items.swap_remove(*index),
+        _ => Value::Null,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn response(status: u16, body: &str) -> HttpResponse {
+        let headers = HashMap::from([("Retry-After".to_string(), "3".to_string())]);
+        HttpResponse { status, headers, body: body.as_bytes().to_vec(), time: 12 }
+    }
+
+    fn scope(variables: &[(&str, &str)]) -> ScriptScope {
+        ScriptScope {
+            variables: variables.iter().map(|(key, value)| (key.to_string(), value.to_string())).collect(),
+            ..ScriptScope::default()
+        }
+    }
+
+    fn rule(when: Option<&str>, action: FlowAction) -> FlowRule {
+        FlowRule { when: when.map(str::to_string), action }
+    }
+
+    #[test]
+    fn conditions_read_response_and_variables() {
+        let response = response(202, r#"{"state": "pending", "items": [{"id": 1}]}"#);
+        let scope = scope(&[("attempts", "3"), ("user", r#"{"id": 9}"#)]);
+        let eval = |condition: &str| evaluate(condition, &scope, Some(&response)).unwrap();
+
+        assert!(eval("status == 202 && json.state != 'done'"));
+        assert!(eval("json.items[0].id == 1 && headers['retry-after'] >= 3"));
+        assert!(eval("vars.attempts < 5 && attempts > 2 && vars.user.id == 9"));
+        assert!(eval("body contains 'pending' && json.state matches '^pen'"));
+        assert!(eval("!missing && !(time > 100)"));
+        assert!(!evaluate("status == 202", &scope, None).unwrap());
+    }
+
+    #[test]
+    fn invalid_conditions_are_reported() {
+        let rules = [rule(Some("status =="), FlowAction::Stop)];
+        assert!(validate(&rules).unwrap_err().contains("Invalid flow condition 'status =='"));
+        assert!(evaluate("body matches '('", &ScriptScope::default(), Some(&response(200, ""))).is_err());
+    }
+
+    #[test]
+    fn skip_rules_are_separate_from_other_actions() {
+        let rules = [
+            rule(Some("vars.skip"), FlowAction::Skip),
+            rule(Some("status == 500"), FlowAction::Goto { request: "cleanup".to_string() }),
+            rule(Some("status >= 500"), FlowAction::Stop),
+            rule(None, FlowAction::Repeat { max_attempts: 3, delay_ms: 0 }),
+        ];
+
+        assert!(should_skip(&rules, &scope(&[("skip", "1")])).unwrap());
+        assert!(!should_skip(&rules, &scope(&[])).unwrap());
+        assert_eq!(
+            next_action(&rules, &scope(&[]), Some(&response(500, ""))).unwrap(),
+            Some(&FlowAction::Goto { request: "cleanup".to_string() }),
+        );
+        assert_eq!(next_action(&rules, &scope(&[]), Some(&response(503, ""))).unwrap(), Some(&FlowAction::Stop));
+        assert_eq!(
+            next_action(&rules, &scope(&[]), Some(&response(200, ""))).unwrap(),
+            Some(&FlowAction::Repeat { max_attempts: 3, delay_ms: 0 }),
+        );
+    }
+
+    #[test]
+    fn rules_use_the_action_tag() {
+        let rule: FlowRule = serde_json::from_str(r#"{"when": "status == 202", "action": "repeat", "delay_ms": 10}"#).unwrap();
+        assert_eq!(rule.action, FlowAction::Repeat { max_attempts: DEFAULT_MAX_ATTEMPTS, delay_ms: 10 });
+    }
+}
@@ -0,0 +1,229 @@
+use encoding_rs::{DecoderResult, UTF_8};
+use serde::{Deserialize, Serialize};
+
+pub use encoding_rs::Encoding;
+
+// Batas awal body yang diperiksa untuk <meta charset> atau deklarasi XML
+const SNIFF_LIMIT: usize = 1024;
+
+// Asal encoding yang dipakai untuk decode body
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EncodingSource {
+    // Parameter charset dari header Content-Type
+    ContentType,
+    // Byte order mark di awal body
+    Bom,
+    // <meta charset> HTML atau <?xml encoding="..."?>
+    Document,
+    // Tidak ada petunjuk, dianggap UTF-8
+    Default,
+}
+
+// Teks hasil decode beserta encoding yang dipakai
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DecodedText {
+    pub text: String,
+    // Nama encoding WHATWG, misalnya "UTF-8", "windows-1252" atau "Shift_JIS"
+    pub encoding: String,
+    pub source: EncodingSource,
+    // true jika ada byte tidak valid yang diganti U+FFFD (hanya pada mode lossy)
+    pub had_errors: bool,
+}
+
+// Menentukan encoding body: charset Content-Type, lalu BOM, lalu sniffing meta HTML/deklarasi XML
+pub fn detect_encoding(body: &[u8], content_type: Option<&str>) -> (&'static Encoding, EncodingSource) {
+    if let Some(encoding) = content_type.and_then(charset_param).and_then(|label| Encoding::for_label(label.as_bytes())) {
+        return (encoding, EncodingSource::ContentType);
+    }
+
+    if let Some((encoding, _)) = Encoding::for_bom(body) {
+        return (encoding, EncodingSource::Bom);
+    }
+
+    if let Some(encoding) = sniff_document(body) {
+        return (encoding, EncodingSource::Document);
+    }
+
+    (UTF_8, EncodingSource::Default)
+}
+
+// Decode body menjadi teks. Mode strict gagal jika ada byte tidak valid,
+// mode lossy mengganti byte tersebut dengan U+FFFD.
+pub fn decode(body: &[u8], content_type: Option<&str>, lossy: bool) -> Result<DecodedText, String> {
+    let (encoding, source) = detect_encoding(body, content_type);
+    let bytes = strip_bom(body, encoding);
+
+    let (text, had_errors) = if lossy {
+        let (text, _, had_errors) = encoding.decode(bytes);
+        (text.into_owned(), had_errors)
+    } else {
+        let text = encoding
+            .decode_without_bom_handling_and_without_replacement(bytes)
+            .ok_or_else(|| format!("Failed to decode response body as {}: invalid byte sequence", encoding.name()))?;
+        (text.into_owned(), false)
+    };
+
+    Ok(DecodedText {
+        text,
+        encoding: encoding.name().to_string(),
+        source,
+        had_errors,
+    })
+}
+
+// Decode potongan body tanpa penggantian karakter, BOM di awal potongan dibuang.
+// Jika bukan potongan terakhir, karakter multibyte yang terpotong di akhir tidak ikut di-decode.
+// Return teks dan jumlah byte yang terpakai, potongan berikutnya dimulai dari sana.
+// Return None jika potongan berisi byte yang tidak valid untuk encoding tersebut.
+pub fn decode_chunk(chunk: &[u8], encoding: &'static Encoding, last: bool) -> Option<(String, usize)> {
+    if last {
+        return decode_complete(chunk, encoding).map(|text| (text, chunk.len()));
+    }
+
+    // Karakter terpanjang (UTF-8, GB18030, pasangan surrogate UTF-16) 4 byte,
+    // jadi paling banyak 3 byte di akhir potongan milik karakter yang terpotong
+    (0..=chunk.len().min(3)).find_map(|cut| {
+        let end = chunk.len() - cut;
+        decode_complete(&chunk[..end], encoding).map(|text| (text, end))
+    })
+}
+
+fn decode_complete(bytes: &[u8], encoding: &'static Encoding) -> Option<String> {
+    let mut decoder = encoding.new_decoder_with_bom_removal();
+    let capacity = decoder.max_utf8_buffer_length_without_replacement(bytes.len())?;
+    let mut text = String::with_capacity(capacity);
+
+    match decoder.decode_to_string_without_replacement(bytes, &mut text, true) {
+        (DecoderResult::InputEmpty, _) => Some(text),
+        _ => None,
+    }
+}
+
+// Nilai parameter charset dari Content-Type, misalnya "text/html; charset=Shift_JIS"
+fn charset_param(content_type: &str) -> Option<&str> {
+    content_type.split(';').skip(1).find_map(|param| {
+        let (key, value) = param.split_once('=')?;
+        if key.trim().eq_ignore_ascii_case("charset") {
+            Some(value.trim().trim_matches(|c| c == '"' || c == '\''))
+        } else {
+            None
+        }
+    })
+}
+
+fn strip_bom<'a>(body: &'a [u8], encoding: &'static Encoding) -> &'a [u8] {
+    match Encoding::for_bom(body) {
+        Some((bom_encoding, length)) if bom_encoding == encoding => &body[length..],
+        _ => body,
+    }
+}
+
+// Mencari <meta charset="...">, <meta http-equiv="Content-Type" content="...; charset=...">
+// atau <?xml encoding="..."?> di awal dokumen
+fn sniff_document(body: &[u8]) -> Option<&'static Encoding> {
+    let head = String::from_utf8_lossy(&body[..body.len().min(SNIFF_LIMIT)]).to_ascii_lowercase();
+
+    let label = if head.trim_start().starts_with("<?xml") {
+        let declaration = &head[..head.find("?>")?];
+        attribute_value(declaration, "encoding")
+    } else {
+        head.match_indices("<meta").find_map(|(start, _)| {
+            let tag = &head[start..start + head[start..].find('>')?];
+            attribute_value(tag, "charset").or_else(|| {
+                attribute_value(tag, "content").and_then(|content| {
+                    let charset = &content[content.find("charset=")? + "charset=".len()..];
+                    Some(charset.split(';').next().unwrap_or(charset).trim().to_string())
+                })
+            })
+        })
+    }?;
+
+    // Sama seperti browser, UTF-16 di meta tidak bisa benar (dokumennya terbaca sebagai ASCII)
+    match Encoding::for_label(label.as_bytes())? {
+        encoding if encoding == encoding_rs::UTF_16LE || encoding == encoding_rs::UTF_16BE => Some(UTF_8),
+        encoding => Some(encoding),
+    }
+}
+
+// Nilai atribut dalam tag yang sudah di-lowercase, dengan atau tanpa tanda kutip.
+// Teks di dalam nilai atribut lain (misalnya "charset=" di dalam content) tidak ikut dicocokkan.
+fn attribute_value(tag: &str, name: &str) -> Option<String> {
+    let mut rest = tag.trim_start_matches(|c: char| c != ' ' && c != '\t' && c != '\n' && c != '\r');
+
+    loop {
+        rest = rest.trim_start_matches(|c: char| c.is_ascii_whitespace() || c == '/');
+        if rest.is_empty() {
+            return None;
+        }
+
+        let name_end = rest.find(|c: char| c == '=' || c.is_ascii_whitespace()).unwrap_or(rest.len());
+        let attribute = &rest[..name_end];
+        rest = rest[name_end..].trim_start();
+
+        let value = match rest.strip_prefix('=') {
+            Some(after) => {
+                let after = after.trim_start();
+                match after.chars().next() {
+                    Some(quote @ ('"' | '\'')) => {
+                        let end = after[1..].find(quote).map(|i| i + 1).unwrap_or(after.len());
+                        rest = after.get(end + 1..).unwrap_or("");
+                        &after[1..end]
+                    },
+                    _ => {
+                        let end = after.find(|c: char| c.is_ascii_whitespace()).unwrap_or(after.len());
+                        rest = &after[end..];
+                        &after[..end]
+                    },
+                }
+            },
+            None => "",
+        };
+
+        if attribute == name {
+            return Some(value.trim().to_string());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use encoding_rs::{SHIFT_JIS, UTF_16LE, UTF_8};
+
+    // Membaca body potongan demi potongan lewat next offset, seperti yang dilakukan UI
+    fn read_in_chunks(bytes: &[u8], encoding: &'static Encoding, size: usize) -> String {
+        let mut text = String::new();
+        let mut offset = 0;
+        while offset < bytes.len() {
+            let end = (offset + size).min(bytes.len());
+            let (chunk, consumed) = decode_chunk(&bytes[offset..end], encoding, end == bytes.len()).unwrap();
+            assert!(consumed > 0);
+            text.push_str(&chunk);
+            offset += consumed;
+        }
+        text
+    }
+
+    #[test]
+    fn chunks_split_inside_characters_are_resumed() {
+        let text = "héllo wörld, こんにちは 🎉!";
+        for size in 4..12 {
+            assert_eq!(read_in_chunks(text.as_bytes(), UTF_8, size), text);
+        }
+
+        let (shift_jis, _, _) = SHIFT_JIS.encode("テスト abc テスト");
+        assert_eq!(read_in_chunks(&shift_jis, SHIFT_JIS, 5), "テスト abc テスト");
+
+        let utf16: Vec<u8> = "a🎉b".encode_utf16().flat_map(u16::to_le_bytes).collect();
+        assert_eq!(read_in_chunks(&utf16, UTF_16LE, 5), "a🎉b");
+    }
+
+    #[test]
+    fn invalid_bytes_are_rejected() {
+        assert_eq!(decode_chunk(b"ok \xff ok", UTF_8, true), None);
+        assert_eq!(decode_chunk(b"ok \xff ok", UTF_8, false), None);
+        assert_eq!(decode_chunk("é".as_bytes(), UTF_8, true).map(|(_, consumed)| consumed), Some(2));
+        assert_eq!(decode_chunk(&"é".as_bytes()[..1], UTF_8, false), Some((String::new(), 0)));
+    }
+}
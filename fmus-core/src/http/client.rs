@@ -1,16 +1,32 @@
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
 use std::time::Duration;
 
+use reqwest::header::{HeaderName, HeaderValue};
+use reqwest::multipart::{Form, Part};
 use reqwest::{header, Client as ReqwestClient, Method, RequestBuilder, Response};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+use crate::codec::{self, BodyFormat};
+
+use super::ratelimit::{RateLimitConfig, RateLimiter};
+
 // Tipe untuk konfigurasi HTTP client
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ClientConfig {
     pub base_url: Option<String>,
+    // Timeout dalam detik
     pub timeout: Option<u64>,
     pub headers: Option<HashMap<String, String>>,
+    // Rate limit global/per host dan batas koneksi per host, tanpa batas jika kosong
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rate_limit: Option<RateLimitConfig>,
+    // Direktori yang boleh dibaca oleh field file multipart ({"file": "path"}), path relatif dihitung
+    // dari direktori ini. Tanpa ini field file ditolak agar collection tidak bisa mengirim file lokal diam-diam.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub upload_dir: Option<PathBuf>,
 }
 
 // Tipe untuk parameter request
@@ -21,14 +37,21 @@ pub struct RequestParams {
     pub headers: Option<HashMap<String, String>>,
     pub params: Option<HashMap<String, String>>,
     pub body: Option<Value>,
+    // Timeout dalam detik, menimpa timeout dari ClientConfig
     pub timeout: Option<u64>,
     pub auth: Option<AuthConfig>,
+    // Format body yang dikirim (MessagePack, CBOR, Protobuf), default JSON
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body_format: Option<BodyFormat>,
 }
 
 // Tipe untuk konfigurasi authentication
+// auth_type: "none", "basic", "bearer", "api_key" atau "oauth2"
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AuthConfig {
+    #[serde(alias = "type")]
     pub auth_type: String,
+    #[serde(default)]
     pub credentials: Value,
 }
 
@@ -41,10 +64,23 @@ pub struct HttpResponse {
     pub time: u128,
 }
 
+// Request yang sudah disusun tapi belum dikirim: URL lengkap dengan query, header dan body yang sudah di-encode
+#[derive(Debug, Clone, Serialize)]
+pub struct PreparedRequest {
+    pub method: String,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Option<Vec<u8>>,
+}
+
+const USER_AGENT: &str = "fmus-post/0.0.1";
+
 // HTTP Client utama
 pub struct HttpClient {
     client: ReqwestClient,
     config: ClientConfig,
+    // Bisa diganti saat client dipakai bersama, lihat set_rate_limit
+    limiter: RwLock<Option<Arc<RateLimiter>>>,
 }
 
 impl HttpClient {
@@ -53,7 +89,7 @@ impl HttpClient {
         let mut headers = header::HeaderMap::new();
         headers.insert(
             header::USER_AGENT,
-            header::HeaderValue::from_static("fmus-post/0.0.1"),
+            header::HeaderValue::from_static(USER_AGENT),
         );
 
         let client = ReqwestClient::builder()
@@ -61,20 +97,136 @@ impl HttpClient {
             .build()
             .expect("Failed to create HTTP client");
 
+        let config = config.unwrap_or(ClientConfig {
+            base_url: None,
+            timeout: Some(30),
+            headers: None,
+            rate_limit: None,
+            upload_dir: None,
+        });
+        // Konstruktor tidak bisa gagal, jadi rate limit yang tidak valid diabaikan; set_rate_limit mengembalikan error
+        let rate_limit = config.rate_limit.clone().filter(|rate_limit| match rate_limit.validate() {
+            Ok(()) => true,
+            Err(e) => {
+                log::error!("Ignoring invalid rate limit: {}", e);
+                false
+            },
+        });
+        let limiter = rate_limit.map(|rate_limit| Arc::new(RateLimiter::new(rate_limit)));
+
         Self {
             client,
-            config: config.unwrap_or_else(|| ClientConfig {
-                base_url: None,
-                timeout: Some(30),
-                headers: None,
-            }),
+            config,
+            limiter: RwLock::new(limiter),
+        }
+    }
+
+    // Mengganti rate limit; request yang sedang menunggu tetap memakai limiter lama.
+    // Rate limit yang tidak valid ditolak dan limiter yang aktif tidak berubah.
+    pub fn set_rate_limit(&self, rate_limit: Option<RateLimitConfig>) -> Result<(), String> {
+        if let Some(rate_limit) = &rate_limit {
+            rate_limit.validate()?;
         }
+        *self.limiter.write().unwrap() = rate_limit.map(|rate_limit| Arc::new(RateLimiter::new(rate_limit)));
+        Ok(())
+    }
+
+    pub fn rate_limit(&self) -> Option<RateLimitConfig> {
+        self.limiter.read().unwrap().as_ref().map(|limiter| limiter.config().clone())
     }
 
     // Fungsi untuk mengirim request HTTP
     pub async fn request(&self, params: RequestParams) -> Result<HttpResponse, String> {
-        let start = std::time::Instant::now();
+        let timeout = self.timeout_for(&params);
+        let limited = self.limiter_for(&params);
+        let mut retries = limited.as_ref().map(|(limiter, _)| limiter.config().retry_on_429).unwrap_or(0);
+
+        loop {
+            // Slot koneksi host dipegang sampai body selesai dibaca
+            let permit = match &limited {
+                Some((limiter, host)) => Some(limiter.acquire(host).await),
+                None => None,
+            };
+
+            let start = std::time::Instant::now();
+            let req_builder = self.build_request(params.clone())?.timeout(timeout);
+
+            // Mengirim request
+            let response = match req_builder.send().await {
+                Ok(resp) => resp,
+                Err(e) => return Err(format!("Request failed: {}", e)),
+            };
+
+            // Membuat HttpResponse dari response reqwest
+            let http_response = self.create_response(response, start.elapsed().as_millis()).await?;
+
+            if let Some((limiter, host)) = &limited {
+                if let Some(wait) = limiter.observe(host, http_response.status, &http_response.headers) {
+                    if retries > 0 {
+                        retries -= 1;
+                        // Slot koneksi dilepas selama menunggu agar request lain ke host ini tidak ikut tertahan
+                        drop(permit);
+                        // Mode adaptive sudah menahan host, acquire berikutnya yang menunggu
+                        if !limiter.config().adaptive {
+                            tokio::time::sleep(wait).await;
+                        }
+                        continue;
+                    }
+                }
+            }
+
+            return Ok(http_response);
+        }
+    }
+
+    // Susun request tanpa mengirimnya, misalnya untuk menampilkan request yang akan dikirim.
+    // User-Agent default ikut dicantumkan; header yang ditambahkan saat koneksi (Host, Content-Length) tidak.
+    pub fn prepare(&self, params: RequestParams) -> Result<PreparedRequest, String> {
+        let request = self.build_request(params)?
+            .build()
+            .map_err(|e| format!("Invalid request: {}", e))?;
+
+        let mut headers: Vec<(String, String)> = request.headers().iter()
+            .map(|(key, value)| (key.as_str().to_string(), String::from_utf8_lossy(value.as_bytes()).into_owned()))
+            .collect();
+        if !request.headers().contains_key(header::USER_AGENT) {
+            headers.insert(0, (header::USER_AGENT.as_str().to_string(), USER_AGENT.to_string()));
+        }
+
+        Ok(PreparedRequest {
+            method: request.method().to_string(),
+            url: request.url().to_string(),
+            headers,
+            body: request.body().and_then(|body| body.as_bytes()).map(|bytes| bytes.to_vec()),
+        })
+    }
+
+    // Limiter aktif dan host tujuan request, None jika tidak ada rate limit
+    pub(crate) fn limiter_for(&self, params: &RequestParams) -> Option<(Arc<RateLimiter>, String)> {
+        let limiter = self.limiter.read().unwrap().clone()?;
+        let url = url::Url::parse(&self.resolve_url(&params.url)).ok()?;
+        let host = match url.port() {
+            Some(port) => format!("{}:{}", url.host_str()?, port),
+            None => url.host_str()?.to_string(),
+        };
+        Some((limiter, host.to_ascii_lowercase()))
+    }
+
+    // URL lengkap, URL relatif digabung dengan base_url
+    fn resolve_url(&self, url: &str) -> String {
+        match &self.config.base_url {
+            Some(base_url) if !url.starts_with("http://") && !url.starts_with("https://") => format!("{}{}", base_url, url),
+            _ => url.to_string(),
+        }
+    }
+
+    // Timeout request: dari RequestParams, lalu ClientConfig, default 30 detik
+    pub(crate) fn timeout_for(&self, params: &RequestParams) -> Duration {
+        Duration::from_secs(params.timeout.or(self.config.timeout).unwrap_or(30))
+    }
 
+    // Menyusun request reqwest (URL, query, header, body, auth) tanpa timeout
+    pub(crate) fn build_request(&self, params: RequestParams) -> Result<RequestBuilder, String> {
         let method = match params.method.to_uppercase().as_str() {
             "GET" => Method::GET,
             "POST" => Method::POST,
@@ -88,19 +240,14 @@ impl HttpClient {
             _ => return Err(format!("Unsupported HTTP method: {}", params.method)),
         };
 
-        let url = match &self.config.base_url {
-            Some(base_url) => {
-                if params.url.starts_with("http://") || params.url.starts_with("https://") {
-                    params.url
-                } else {
-                    format!("{}{}", base_url, params.url)
-                }
-            }
-            None => params.url,
-        };
+        let url = self.resolve_url(&params.url);
 
         let mut req_builder = self.client.request(method, &url);
 
+        // Content-Type dari header request tidak ditimpa oleh format body
+        let has_content_type = params.headers.iter().flatten()
+            .any(|(key, _)| key.eq_ignore_ascii_case("content-type"));
+
         // Set query parameters
         if let Some(query_params) = params.params {
             req_builder = req_builder.query(&query_params);
@@ -109,56 +256,45 @@ impl HttpClient {
         // Set global headers
         if let Some(global_headers) = &self.config.headers {
             for (key, value) in global_headers {
-                req_builder = req_builder.header(key, value);
+                let (name, value) = parse_header(key, value)?;
+                req_builder = req_builder.header(name, value);
             }
         }
 
         // Set request-specific headers
         if let Some(req_headers) = params.headers {
             for (key, value) in req_headers {
-                req_builder = req_builder.header(key, value);
+                let (name, value) = parse_header(&key, &value)?;
+                req_builder = req_builder.header(name, value);
             }
         }
 
-        // Set request body
+        // Set request body, ditulis sebagai JSON lalu di-encode sesuai format body
         if let Some(body) = params.body {
-            req_builder = req_builder.json(&body);
+            req_builder = match &params.body_format {
+                None | Some(BodyFormat::Json) => req_builder.json(&body),
+                Some(BodyFormat::Multipart) => req_builder.multipart(multipart_form(&body, self.config.upload_dir.as_deref())?),
+                Some(format) => {
+                    if !has_content_type {
+                        req_builder = req_builder.header(header::CONTENT_TYPE, format.content_type());
+                    }
+                    req_builder.body(codec::encode(&body, format)?)
+                },
+            };
         }
 
-        // Set timeout
-        let timeout = params.timeout
-            .or(self.config.timeout)
-            .unwrap_or(30);
-        req_builder = req_builder.timeout(Duration::from_secs(timeout));
-
         // Menambahkan autentikasi jika ada
         if let Some(auth) = params.auth {
-            req_builder = self.apply_auth(req_builder, auth);
+            req_builder = self.apply_auth(req_builder, auth)?;
         }
 
-        // Mengirim request
-        let response = match req_builder.send().await {
-            Ok(resp) => resp,
-            Err(e) => return Err(format!("Request failed: {}", e)),
-        };
-
-        // Membuat HttpResponse dari response reqwest
-        let http_response = self.create_response(response, start.elapsed().as_millis()).await?;
-
-        Ok(http_response)
+        Ok(req_builder)
     }
 
     // Membuat HttpResponse dari response reqwest
     async fn create_response(&self, response: Response, elapsed: u128) -> Result<HttpResponse, String> {
         let status = response.status().as_u16();
-
-        // Mengambil headers
-        let mut headers = HashMap::new();
-        for (key, value) in response.headers() {
-            if let Ok(v) = value.to_str() {
-                headers.insert(key.as_str().to_string(), v.to_string());
-            }
-        }
+        let headers = collect_headers(response.headers());
 
         // Mengambil response body
         let body = match response.bytes().await {
@@ -175,29 +311,117 @@ impl HttpClient {
     }
 
     // Menerapkan autentikasi ke request
-    fn apply_auth(&self, builder: RequestBuilder, auth: AuthConfig) -> RequestBuilder {
+    fn apply_auth(&self, builder: RequestBuilder, auth: AuthConfig) -> Result<RequestBuilder, String> {
+        let field = |name: &str| {
+            auth.credentials
+                .get(name)
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| format!("Missing '{}' for {} auth", name, auth.auth_type))
+        };
+
         match auth.auth_type.as_str() {
-            "basic" => {
-                if let Some(obj) = auth.credentials.as_object() {
-                    if let (Some(username), Some(password)) = (
-                        obj.get("username").and_then(|u| u.as_str()),
-                        obj.get("password").and_then(|p| p.as_str()),
-                    ) {
-                        return builder.basic_auth(username, Some(password));
-                    }
-                }
-                builder
-            },
+            "none" => Ok(builder),
+            "basic" => Ok(builder.basic_auth(field("username")?, Some(field("password")?))),
             "bearer" => {
-                if let Some(token) = auth.credentials.as_str() {
-                    return builder.bearer_auth(token);
+                let token = match auth.credentials.as_str() {
+                    Some(token) => token,
+                    None => field("token")?,
+                };
+                Ok(builder.bearer_auth(token))
+            },
+            "api_key" => {
+                let key = field("key")?;
+                let value = field("value")?;
+                let location = auth.credentials
+                    .get("location")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("header");
+
+                match location {
+                    "header" => {
+                        let (name, value) = parse_header(key, value)?;
+                        Ok(builder.header(name, value))
+                    },
+                    "query" => Ok(builder.query(&[(key, value)])),
+                    "cookie" => {
+                        let (name, value) = parse_header("cookie", &format!("{}={}", key, value))?;
+                        Ok(builder.header(name, value))
+                    },
+                    other => Err(format!("Unsupported API key location: {}", other)),
                 }
-                builder
             },
-            // Dukungan untuk tipe auth lainnya bisa ditambahkan di sini
-            _ => builder,
+            // Token harus sudah diperoleh sebelumnya, belum ada alur pengambilan token otomatis
+            "oauth2" => Ok(builder.bearer_auth(field("access_token")?)),
+            other => Err(format!("Unsupported auth type: {}", other)),
+        }
+    }
+}
+
+// Mengambil headers, header yang muncul berkali-kali digabung.
+// Set-Cookie digabung dengan newline karena nilainya bisa mengandung koma (Expires).
+pub(crate) fn collect_headers(header_map: &header::HeaderMap) -> HashMap<String, String> {
+    let mut headers: HashMap<String, String> = HashMap::new();
+    for (key, value) in header_map {
+        if let Ok(v) = value.to_str() {
+            let separator = if key == header::SET_COOKIE { "\n" } else { ", " };
+            headers.entry(key.as_str().to_string())
+                .and_modify(|existing| {
+                    existing.push_str(separator);
+                    existing.push_str(v);
+                })
+                .or_insert_with(|| v.to_string());
+        }
+    }
+    headers
+}
+
+// Form multipart dari body object. Field file dibaca dari upload_dir; nama file default nama file di path.
+fn multipart_form(body: &Value, upload_dir: Option<&Path>) -> Result<Form, String> {
+    let mut form = Form::new();
+    for (name, value) in codec::form_fields(body)? {
+        let Some(path) = value.get("file").and_then(Value::as_str) else {
+            form = form.text(name.to_string(), codec::text_value(value));
+            continue;
+        };
+
+        let bytes = std::fs::read(upload_path(path, upload_dir)?).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+        let filename = match value.get("filename") {
+            Some(Value::String(filename)) => Some(filename.clone()),
+            Some(Value::Null) => None,
+            _ => std::path::Path::new(path).file_name().map(|name| name.to_string_lossy().into_owned()),
+        };
+        let mut part = Part::bytes(bytes);
+        if let Some(filename) = filename {
+            part = part.file_name(filename);
         }
+        if let Some(content_type) = value.get("content_type").and_then(Value::as_str) {
+            part = part.mime_str(content_type)
+                .map_err(|e| format!("Invalid content type for field '{}': {}", name, e))?;
+        }
+        form = form.part(name.to_string(), part);
+    }
+    Ok(form)
+}
+
+// Path file multipart setelah symlink dan ".." di-resolve, harus tetap berada di dalam upload_dir
+fn upload_path(path: &str, upload_dir: Option<&Path>) -> Result<PathBuf, String> {
+    let dir = upload_dir
+        .ok_or_else(|| format!("Multipart field refers to local file '{}', but file uploads are not enabled (set upload_dir)", path))?;
+    let dir = dir.canonicalize().map_err(|e| format!("Invalid upload directory {}: {}", dir.display(), e))?;
+    let resolved = dir.join(path).canonicalize().map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    if !resolved.starts_with(&dir) {
+        return Err(format!("File '{}' is outside the upload directory {}", path, dir.display()));
     }
+    Ok(resolved)
+}
+
+// Validasi header, header yang tidak valid menjadi error alih-alih diabaikan
+fn parse_header(key: &str, value: &str) -> Result<(HeaderName, HeaderValue), String> {
+    let name = HeaderName::from_bytes(key.as_bytes())
+        .map_err(|e| format!("Invalid header name '{}': {}", key, e))?;
+    let value = HeaderValue::from_str(value)
+        .map_err(|e| format!("Invalid value for header '{}': {}", key, e))?;
+    Ok((name, value))
 }
 
 // Fungsi helper untuk membuat clients dengan HTTP methods umum
@@ -211,6 +435,7 @@ pub async fn get(url: &str, params: Option<RequestParams>) -> Result<HttpRespons
         body: None,
         timeout: None,
         auth: None,
+        body_format: None,
     });
 
     client.request(params).await
@@ -226,6 +451,7 @@ pub async fn post(url: &str, params: Option<RequestParams>) -> Result<HttpRespon
         body: None,
         timeout: None,
         auth: None,
+        body_format: None,
     });
 
     client.request(params).await
@@ -241,6 +467,7 @@ pub async fn put(url: &str, params: Option<RequestParams>) -> Result<HttpRespons
         body: None,
         timeout: None,
         auth: None,
+        body_format: None,
     });
 
     client.request(params).await
@@ -256,6 +483,7 @@ pub async fn delete(url: &str, params: Option<RequestParams>) -> Result<HttpResp
         body: None,
         timeout: None,
         auth: None,
+        body_format: None,
     });
 
     client.request(params).await
@@ -271,7 +499,108 @@ pub async fn patch(url: &str, params: Option<RequestParams>) -> Result<HttpRespo
         body: None,
         timeout: None,
         auth: None,
+        body_format: None,
     });
 
     client.request(params).await
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::RateLimit;
+    use crate::test_server;
+    use serde_json::json;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::time::Instant;
+
+    #[test]
+    fn multipart_files_must_be_inside_upload_dir() {
+        let dir = std::env::temp_dir().join(format!("fmus-upload-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join("uploads")).unwrap();
+        std::fs::write(dir.join("uploads/photo.png"), b"png").unwrap();
+        std::fs::write(dir.join("secret.txt"), b"secret").unwrap();
+        let uploads = dir.join("uploads");
+
+        let body = json!({"name": "fmus", "photo": {"file": "photo.png"}});
+        assert!(multipart_form(&body, Some(&uploads)).is_ok());
+        let absolute = json!({"photo": {"file": uploads.join("photo.png")}});
+        assert!(multipart_form(&absolute, Some(&uploads)).is_ok());
+
+        // Tanpa upload_dir field file ditolak, field teks tetap boleh
+        let error = multipart_form(&body, None).unwrap_err();
+        assert!(error.contains("file uploads are not enabled"), "{}", error);
+        assert!(multipart_form(&json!({"name": "fmus"}), None).is_ok());
+
+        for path in [json!("../secret.txt"), json!(dir.join("secret.txt"))] {
+            let error = multipart_form(&json!({"f": {"file": path}}), Some(&uploads)).unwrap_err();
+            assert!(error.contains("outside the upload directory"), "{}", error);
+        }
+        assert!(multipart_form(&json!({"f": {"file": "missing.png"}}), Some(&uploads)).unwrap_err().starts_with("Failed to read missing.png"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    fn get(url: String) -> RequestParams {
+        RequestParams {
+            url,
+            method: "GET".to_string(),
+            headers: None,
+            params: None,
+            body: None,
+            timeout: None,
+            auth: None,
+            body_format: None,
+        }
+    }
+
+    #[test]
+    fn invalid_rate_limits_are_rejected() {
+        let invalid = RateLimitConfig {
+            global: Some(RateLimit { requests_per_second: 0.0, burst: None }),
+            ..RateLimitConfig::default()
+        };
+        let config = ClientConfig { base_url: None, timeout: None, headers: None, rate_limit: Some(invalid.clone()), upload_dir: None };
+        let client = HttpClient::new(Some(config));
+        assert!(client.rate_limit().is_none());
+
+        let valid = RateLimitConfig { max_connections_per_host: Some(2), ..RateLimitConfig::default() };
+        client.set_rate_limit(Some(valid)).unwrap();
+        assert!(client.set_rate_limit(Some(invalid)).unwrap_err().contains("must be a positive number"));
+        // Limiter lama tetap aktif setelah penggantian yang ditolak
+        assert_eq!(client.rate_limit().unwrap().max_connections_per_host, Some(2));
+    }
+
+    #[tokio::test]
+    async fn retry_after_429_releases_the_host_connection() {
+        let limited = AtomicBool::new(false);
+        let server = test_server::start_with(move |request| {
+            if request.path == "/limited" && !limited.swap(true, Ordering::SeqCst) {
+                return test_server::response(429, &[("Retry-After", "1")], b"");
+            }
+            test_server::response(200, &[], b"ok")
+        }).await;
+        let client = HttpClient::new(None);
+        client.set_rate_limit(Some(RateLimitConfig {
+            max_connections_per_host: Some(1),
+            adaptive: false,
+            retry_on_429: 1,
+            ..RateLimitConfig::default()
+        })).unwrap();
+
+        let limited = client.request(get(format!("{}/limited", server.url)));
+        let other = async {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            let start = Instant::now();
+            let response = client.request(get(format!("{}/other", server.url))).await.unwrap();
+            (response, start.elapsed())
+        };
+        let (limited, (other, elapsed)) = tokio::join!(limited, other);
+
+        assert_eq!(limited.unwrap().status, 200);
+        assert_eq!(other.status, 200);
+        // Request lain tidak menunggu Retry-After milik request yang kena 429
+        assert!(elapsed < Duration::from_millis(600), "{:?}", elapsed);
+        assert_eq!(server.requests(), 3);
+    }
+}
@@ -0,0 +1,507 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::fs::{self, File, OpenOptions};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+use super::client::{collect_headers, HttpClient, RequestParams};
+use super::range::{ByteRange, ContentRange};
+
+// Opsi download ke file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DownloadOptions {
+    // Jumlah percobaan ulang setelah koneksi putus atau server error
+    #[serde(default = "default_max_retries")]
+    pub max_retries: u32,
+    #[serde(default = "default_retry_delay_ms")]
+    pub retry_delay_ms: u64,
+    // Ukuran akhir yang diharapkan dalam byte
+    #[serde(default)]
+    pub expected_size: Option<u64>,
+    // Checksum SHA-256 (hex) yang diharapkan
+    #[serde(default)]
+    pub sha256: Option<String>,
+    // Lanjutkan file .part sisa download sebelumnya jika ada
+    #[serde(default = "default_resume")]
+    pub resume: bool,
+}
+
+fn default_max_retries() -> u32 {
+    5
+}
+
+fn default_retry_delay_ms() -> u64 {
+    1_000
+}
+
+fn default_resume() -> bool {
+    true
+}
+
+impl Default for DownloadOptions {
+    fn default() -> Self {
+        Self {
+            max_retries: default_max_retries(),
+            retry_delay_ms: default_retry_delay_ms(),
+            expected_size: None,
+            sha256: None,
+            resume: default_resume(),
+        }
+    }
+}
+
+// Progress download, dilaporkan setiap potongan body diterima
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DownloadProgress {
+    pub downloaded: u64,
+    // None jika server tidak mengirim ukuran
+    pub total: Option<u64>,
+    pub attempt: u32,
+}
+
+// Hasil download yang sudah diverifikasi
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DownloadResult {
+    pub path: PathBuf,
+    pub size: u64,
+    pub sha256: String,
+    pub attempts: u32,
+    // Berapa kali download dilanjutkan dengan Range
+    pub resumes: u32,
+    // Server mengirim "Accept-Ranges: bytes"
+    pub accept_ranges: bool,
+    pub time: u128,
+}
+
+// Keadaan download yang belum selesai, disimpan di samping file .part agar bisa dilanjutkan
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct PartialDownload {
+    url: String,
+    // ETag (strong) atau Last-Modified untuk If-Range
+    validator: Option<String>,
+    total: Option<u64>,
+    #[serde(skip)]
+    accept_ranges: bool,
+    #[serde(skip)]
+    resumes: u32,
+}
+
+enum AttemptError {
+    // Bisa dicoba lagi, sisa body dilanjutkan dengan Range
+    Retry(String),
+    Fatal(String),
+}
+
+impl HttpClient {
+    // Download body response ke file. Body ditulis ke "<path>.part" lalu di-rename setelah
+    // ukuran dan checksum cocok. Jika koneksi putus, download dilanjutkan dengan Range dan If-Range;
+    // jika server tidak mendukung range atau file di server berubah, download diulang dari awal.
+    // Timeout request berlaku sebagai batas waktu tanpa data, bukan untuk seluruh download.
+    pub async fn download(
+        &self,
+        params: RequestParams,
+        path: impl AsRef<Path>,
+        options: &DownloadOptions,
+        mut on_progress: impl FnMut(&DownloadProgress),
+    ) -> Result<DownloadResult, String> {
+        let start = Instant::now();
+        let path = path.as_ref();
+        let part_path = with_suffix(path, ".part");
+        let state_path = with_suffix(path, ".part.json");
+
+        let saved = if options.resume { load_partial(&state_path, &part_path, &params.url).await } else { None };
+        let mut partial = match saved {
+            Some(partial) => partial,
+            None => {
+                remove_if_exists(&part_path).await?;
+                PartialDownload { url: params.url.clone(), ..PartialDownload::default() }
+            },
+        };
+
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            match self.download_attempt(&params, &part_path, &mut partial, attempts, &mut on_progress).await {
+                Ok(()) => break,
+                Err(AttemptError::Fatal(e)) => return Err(e),
+                Err(AttemptError::Retry(e)) => {
+                    save_partial(&state_path, &partial).await?;
+                    if attempts > options.max_retries {
+                        return Err(format!("Download failed after {} attempts: {}", attempts, e));
+                    }
+                    log::warn!("Download attempt {} failed, retrying: {}", attempts, e);
+                    tokio::time::sleep(Duration::from_millis(options.retry_delay_ms)).await;
+                },
+            }
+        }
+
+        let size = file_len(&part_path).await;
+        let expected_size = options.expected_size.or(partial.total);
+        if let Some(expected) = expected_size.filter(|expected| *expected != size) {
+            discard(&part_path, &state_path).await?;
+            return Err(format!("Downloaded {} bytes but expected {} bytes", size, expected));
+        }
+
+        let sha256 = sha256_file(&part_path).await?;
+        if let Some(expected) = options.sha256.as_deref().filter(|expected| !expected.eq_ignore_ascii_case(&sha256)) {
+            discard(&part_path, &state_path).await?;
+            return Err(format!("SHA-256 mismatch: expected {} but got {}", expected, sha256));
+        }
+
+        fs::rename(&part_path, path).await
+            .map_err(|e| format!("Failed to move download to {}: {}", path.display(), e))?;
+        remove_if_exists(&state_path).await?;
+
+        Ok(DownloadResult {
+            path: path.to_path_buf(),
+            size,
+            sha256,
+            attempts,
+            resumes: partial.resumes,
+            accept_ranges: partial.accept_ranges,
+            time: start.elapsed().as_millis(),
+        })
+    }
+
+    async fn download_attempt(
+        &self,
+        params: &RequestParams,
+        part_path: &Path,
+        partial: &mut PartialDownload,
+        attempt: u32,
+        on_progress: &mut impl FnMut(&DownloadProgress),
+    ) -> Result<(), AttemptError> {
+        let stall_timeout = self.timeout_for(params);
+        let offset = file_len(part_path).await;
+
+        let mut params = params.clone();
+        if offset > 0 {
+            let headers = params.headers.get_or_insert_with(HashMap::new);
+            headers.insert("Range".to_string(), ByteRange { start: offset, end: None }.header_value());
+            if let Some(validator) = &partial.validator {
+                headers.insert("If-Range".to_string(), validator.clone());
+            }
+        }
+
+        // Slot koneksi host dipegang selama file diunduh, 429 menahan percobaan berikutnya
+        let limited = self.limiter_for(&params);
+        let _permit = match &limited {
+            Some((limiter, host)) => Some(limiter.acquire(host).await),
+            None => None,
+        };
+
+        let request = self.build_request(params).map_err(AttemptError::Fatal)?;
+        let mut response = match tokio::time::timeout(stall_timeout, request.send()).await {
+            Ok(Ok(response)) => response,
+            Ok(Err(e)) => return Err(AttemptError::Retry(format!("Request failed: {}", e))),
+            Err(_) => return Err(AttemptError::Retry(format!("No response within {} s", stall_timeout.as_secs()))),
+        };
+
+        let status = response.status().as_u16();
+        let headers = collect_headers(response.headers());
+        if let Some((limiter, host)) = &limited {
+            limiter.observe(host, status, &headers);
+        }
+        let header = |name: &str| headers.get(name).map(|value| value.as_str());
+        let content_range = header("content-range").and_then(ContentRange::parse);
+
+        let resume = match status {
+            206 => match content_range {
+                Some(ContentRange::Bytes { start, total, .. }) if start == offset => {
+                    partial.total = total.or(partial.total);
+                    true
+                },
+                _ => return Err(AttemptError::Fatal(format!(
+                    "Server returned 206 with Content-Range {:?} for a request starting at byte {}",
+                    header("content-range").unwrap_or(""),
+                    offset,
+                ))),
+            },
+            // Server tidak mendukung range, atau If-Range tidak cocok karena file berubah
+            200 => {
+                partial.total = header("content-length").and_then(|length| length.parse().ok());
+                false
+            },
+            416 if offset > 0 => {
+                // File .part mungkin sudah lengkap dari percobaan sebelumnya
+                if content_range.and_then(|range| range.total()) == Some(offset) {
+                    partial.total = Some(offset);
+                    return Ok(());
+                }
+                remove_if_exists(part_path).await.map_err(AttemptError::Fatal)?;
+                return Err(AttemptError::Retry("Range not satisfiable, restarting download".to_string()));
+            },
+            408 | 429 | 500..=599 => return Err(AttemptError::Retry(format!("Server returned status {}", status))),
+            _ => return Err(AttemptError::Fatal(format!("Download failed with status {}", status))),
+        };
+
+        partial.accept_ranges |= header("accept-ranges").is_some_and(|value| value.eq_ignore_ascii_case("bytes"));
+        if resume {
+            partial.resumes += 1;
+        } else {
+            // Weak ETag tidak boleh dipakai untuk If-Range
+            partial.validator = header("etag")
+                .filter(|etag| !etag.starts_with("W/"))
+                .or_else(|| header("last-modified"))
+                .map(|value| value.to_string());
+        }
+
+        let mut file = if resume {
+            OpenOptions::new().append(true).open(part_path).await
+        } else {
+            File::create(part_path).await
+        }
+        .map_err(|e| AttemptError::Fatal(format!("Failed to open {}: {}", part_path.display(), e)))?;
+
+        let mut downloaded = if resume { offset } else { 0 };
+        loop {
+            let chunk = match tokio::time::timeout(stall_timeout, response.chunk()).await {
+                Ok(Ok(Some(chunk))) => chunk,
+                Ok(Ok(None)) => break,
+                Ok(Err(e)) => {
+                    flush(&mut file, part_path).await?;
+                    return Err(AttemptError::Retry(format!("Connection interrupted: {}", e)));
+                },
+                Err(_) => {
+                    flush(&mut file, part_path).await?;
+                    return Err(AttemptError::Retry(format!("No data received for {} s", stall_timeout.as_secs())));
+                },
+            };
+
+            file.write_all(&chunk).await
+                .map_err(|e| AttemptError::Fatal(format!("Failed to write {}: {}", part_path.display(), e)))?;
+            downloaded += chunk.len() as u64;
+            on_progress(&DownloadProgress { downloaded, total: partial.total, attempt });
+        }
+        flush(&mut file, part_path).await?;
+
+        match partial.total {
+            Some(total) if downloaded < total => Err(AttemptError::Retry(format!(
+                "Connection closed after {} of {} bytes",
+                downloaded, total
+            ))),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+async fn file_len(path: &Path) -> u64 {
+    fs::metadata(path).await.map(|metadata| metadata.len()).unwrap_or(0)
+}
+
+async fn flush(file: &mut File, path: &Path) -> Result<(), AttemptError> {
+    file.flush().await
+        .map_err(|e| AttemptError::Fatal(format!("Failed to write {}: {}", path.display(), e)))
+}
+
+async fn remove_if_exists(path: &Path) -> Result<(), String> {
+    match fs::remove_file(path).await {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(format!("Failed to remove {}: {}", path.display(), e)),
+    }
+}
+
+// Hapus file .part yang rusak agar percobaan berikutnya mulai dari awal
+async fn discard(part_path: &Path, state_path: &Path) -> Result<(), String> {
+    remove_if_exists(part_path).await?;
+    remove_if_exists(state_path).await
+}
+
+// Keadaan download sebelumnya, hanya dipakai jika URL-nya sama dan file .part masih ada
+async fn load_partial(state_path: &Path, part_path: &Path, url: &str) -> Option<PartialDownload> {
+    let content = fs::read(state_path).await.ok()?;
+    let partial: PartialDownload = serde_json::from_slice(&content).ok()?;
+    (partial.url == url && file_len(part_path).await > 0).then_some(partial)
+}
+
+async fn save_partial(state_path: &Path, partial: &PartialDownload) -> Result<(), String> {
+    let content = serde_json::to_vec(partial).map_err(|e| format!("Failed to serialize download state: {}", e))?;
+    fs::write(state_path, content).await
+        .map_err(|e| format!("Failed to write {}: {}", state_path.display(), e))
+}
+
+async fn sha256_file(path: &Path) -> Result<String, String> {
+    let mut file = File::open(path).await
+        .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 64 * 1024];
+
+    loop {
+        let read = file.read(&mut buffer).await
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+
+    Ok(hasher.finalize().iter().map(|byte| format!("{:02x}", byte)).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+    use crate::test_server::{self, response};
+
+    const CONTENT: &[u8] = b"0123456789";
+
+    fn params(url: &str) -> RequestParams {
+        RequestParams {
+            url: url.to_string(),
+            method: "GET".to_string(),
+            headers: None,
+            params: None,
+            body: None,
+            timeout: Some(5),
+            auth: None,
+            body_format: None,
+        }
+    }
+
+    fn options() -> DownloadOptions {
+        DownloadOptions { max_retries: 2, retry_delay_ms: 10, ..DownloadOptions::default() }
+    }
+
+    fn temp_path() -> PathBuf {
+        std::env::temp_dir().join(format!("fmus-download-{}", uuid::Uuid::new_v4()))
+    }
+
+    // Response 200 yang terputus: Content-Length penuh tapi body hanya sebagian
+    fn truncated(sent: usize) -> Vec<u8> {
+        let mut bytes = format!(
+            "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nAccept-Ranges: bytes\r\nETag: \"v1\"\r\nConnection: close\r\n\r\n",
+            CONTENT.len(),
+        )
+        .into_bytes();
+        bytes.extend_from_slice(&CONTENT[..sent]);
+        bytes
+    }
+
+    #[tokio::test]
+    async fn interrupted_download_resumes_with_range() {
+        let ranges = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&ranges);
+        let server = test_server::start_with(move |request| {
+            let range = request.header("range").map(str::to_string);
+            seen.lock().unwrap().push((range.clone(), request.header("if-range").map(str::to_string)));
+            match range.as_deref() {
+                None => truncated(4),
+                Some("bytes=4-") => response(206, &[("Content-Range", "bytes 4-9/10")], &CONTENT[4..]),
+                Some(other) => panic!("unexpected range {}", other),
+            }
+        })
+        .await;
+        let path = temp_path();
+
+        let result = HttpClient::new(None).download(params(&server.url), &path, &options(), |_| {}).await.unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), CONTENT);
+        assert_eq!((result.size, result.attempts, result.resumes, result.accept_ranges), (10, 2, 1, true));
+        assert_eq!(result.sha256, "84d89877f0d4041efb6bf91a16f0248f2fd573e6af05c19f96bedb9f882f7882");
+        // Bagian kedua diminta mulai dari akhir file .part, dengan ETag sebagai If-Range
+        assert_eq!(
+            *ranges.lock().unwrap(),
+            vec![(None, None), (Some("bytes=4-".to_string()), Some("\"v1\"".to_string()))],
+        );
+        assert!(!with_suffix(&path, ".part").exists() && !with_suffix(&path, ".part.json").exists());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn full_response_to_range_request_restarts_file() {
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&attempts);
+        // Server yang tidak mendukung range selalu mengirim body penuh dengan status 200
+        let server = test_server::start_with(move |_| match counter.fetch_add(1, Ordering::SeqCst) {
+            0 => truncated(6),
+            _ => response(200, &[], CONTENT),
+        })
+        .await;
+        let path = temp_path();
+
+        let result = HttpClient::new(None).download(params(&server.url), &path, &options(), |_| {}).await.unwrap();
+        // Body penuh menimpa file .part, bukan ditambahkan di belakangnya
+        assert_eq!(std::fs::read(&path).unwrap(), CONTENT);
+        assert_eq!((result.attempts, result.resumes), (2, 0));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn saved_partial_file_is_appended() {
+        let server = test_server::start_with(|request| match request.header("range") {
+            Some("bytes=3-") => response(206, &[("Content-Range", "bytes 3-9/10")], &CONTENT[3..]),
+            _ => response(200, &[], CONTENT),
+        })
+        .await;
+        let path = temp_path();
+        std::fs::write(with_suffix(&path, ".part"), &CONTENT[..3]).unwrap();
+        let state = serde_json::json!({"url": server.url, "validator": null, "total": 10});
+        std::fs::write(with_suffix(&path, ".part.json"), state.to_string()).unwrap();
+
+        let mut progress = Vec::new();
+        let result = HttpClient::new(None)
+            .download(params(&server.url), &path, &options(), |p| progress.push(p.downloaded))
+            .await
+            .unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), CONTENT);
+        assert_eq!((result.attempts, result.resumes), (1, 1));
+        // Progress dihitung dari byte yang sudah ada di file .part
+        assert_eq!(progress.last(), Some(&10));
+
+        // Tanpa resume file .part lama dibuang dan download mulai dari awal
+        std::fs::write(with_suffix(&path, ".part"), b"xxx").unwrap();
+        let options = DownloadOptions { resume: false, ..options() };
+        let result = HttpClient::new(None).download(params(&server.url), &path, &options, |_| {}).await.unwrap();
+        assert_eq!(result.resumes, 0);
+        assert_eq!(std::fs::read(&path).unwrap(), CONTENT);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn mismatched_content_range_fails() {
+        // Server menjawab range dari awal file, padahal yang diminta mulai byte 4
+        let server = test_server::start_with(|request| match request.header("range") {
+            None => truncated(4),
+            Some(_) => response(206, &[("Content-Range", "bytes 0-9/10")], CONTENT),
+        })
+        .await;
+        let path = temp_path();
+
+        let error = HttpClient::new(None).download(params(&server.url), &path, &options(), |_| {}).await.unwrap_err();
+        assert_eq!(error, "Server returned 206 with Content-Range \"bytes 0-9/10\" for a request starting at byte 4");
+        assert!(!path.exists());
+
+        let _ = std::fs::remove_file(with_suffix(&path, ".part"));
+    }
+
+    #[tokio::test]
+    async fn size_and_checksum_are_verified() {
+        let server = test_server::start_with(|_| response(200, &[], CONTENT)).await;
+        let path = temp_path();
+
+        let options = DownloadOptions { expected_size: Some(11), ..options() };
+        let error = HttpClient::new(None).download(params(&server.url), &path, &options, |_| {}).await.unwrap_err();
+        assert_eq!(error, "Downloaded 10 bytes but expected 11 bytes");
+
+        let options = DownloadOptions { expected_size: None, sha256: Some("00".to_string()), ..options };
+        let error = HttpClient::new(None).download(params(&server.url), &path, &options, |_| {}).await.unwrap_err();
+        assert!(error.starts_with("SHA-256 mismatch"), "{}", error);
+        // File yang gagal diverifikasi tidak ditinggalkan
+        assert!(!path.exists() && !with_suffix(&path, ".part").exists());
+    }
+}
@@ -0,0 +1,387 @@
+use std::fmt::Write;
+
+use serde::Deserialize;
+
+use super::response::ResponseBodyType;
+
+const INDENT: &str = "  ";
+
+// Batas default byte yang ditampilkan pada hex dump
+pub const HEX_DUMP_LIMIT: usize = 64 * 1024;
+
+// Elemen HTML tanpa tag penutup
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track", "wbr",
+];
+
+// Elemen HTML yang otomatis ditutup oleh elemen berikutnya, misalnya <li> oleh <li>
+const IMPLIED_END: &[(&str, &[&str])] = &[
+    ("li", &["li"]),
+    ("p", &["p"]),
+    ("option", &["option"]),
+    ("dt", &["dt", "dd"]),
+    ("dd", &["dt", "dd"]),
+    ("tr", &["tr", "td", "th"]),
+    ("td", &["td", "th"]),
+    ("th", &["td", "th"]),
+];
+
+// Elemen HTML yang isinya tidak di-parse dan tidak diubah
+const RAW_TEXT_ELEMENTS: &[&str] = &["script", "style", "pre", "textarea"];
+
+// Format body teks sesuai tipenya. Tipe tanpa formatter (teks, CSV, JavaScript) dikembalikan apa adanya.
+pub fn pretty(body_type: &ResponseBodyType, text: &str) -> Result<String, String> {
+    match body_type {
+        ResponseBodyType::Json => format_json(text),
+        ResponseBodyType::Xml => format_xml(text),
+        ResponseBodyType::Html => format_html(text),
+        ResponseBodyType::Yaml => format_yaml(text),
+        _ => Ok(text.to_string()),
+    }
+}
+
+// Indentasi JSON tanpa mengubah urutan key maupun penulisan angka
+pub fn format_json(text: &str) -> Result<String, String> {
+    serde_json::from_str::<serde::de::IgnoredAny>(text)
+        .map_err(|e| format!("Invalid JSON: {}", e))?;
+
+    let mut out = String::with_capacity(text.len() * 2);
+    let mut depth = 0;
+    let mut chars = text.trim().chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                out.push(c);
+                while let Some(c) = chars.next() {
+                    out.push(c);
+                    match c {
+                        '\\' => out.extend(chars.next()),
+                        '"' => break,
+                        _ => {},
+                    }
+                }
+            },
+            '{' | '[' => {
+                out.push(c);
+                skip_whitespace(&mut chars);
+                // Object/array kosong tetap ditulis dalam satu baris
+                if matches!(chars.peek(), Some('}') | Some(']')) {
+                    out.extend(chars.next());
+                } else {
+                    depth += 1;
+                    newline(&mut out, depth);
+                }
+            },
+            '}' | ']' => {
+                depth -= 1;
+                newline(&mut out, depth);
+                out.push(c);
+            },
+            ',' => {
+                out.push(c);
+                newline(&mut out, depth);
+            },
+            ':' => out.push_str(": "),
+            c if c.is_whitespace() => {},
+            c => out.push(c),
+        }
+    }
+
+    Ok(out)
+}
+
+// Indentasi dokumen XML per elemen, elemen yang hanya berisi teks pendek tetap satu baris
+pub fn format_xml(text: &str) -> Result<String, String> {
+    indent_markup(text, false)
+}
+
+// Seperti format_xml, tapi mengenali elemen void (<br>, <img>), tag penutup yang tidak ada
+// dan isi <script>, <style>, <pre> yang tidak boleh diubah
+pub fn format_html(text: &str) -> Result<String, String> {
+    indent_markup(text, true)
+}
+
+// Normalisasi indentasi YAML (komentar tidak dipertahankan), mendukung beberapa dokumen
+pub fn format_yaml(text: &str) -> Result<String, String> {
+    let mut documents = Vec::new();
+    for document in serde_yaml::Deserializer::from_str(text) {
+        let value = serde_yaml::Value::deserialize(document)
+            .map_err(|e| format!("Invalid YAML: {}", e))?;
+        documents.push(serde_yaml::to_string(&value).map_err(|e| format!("Failed to format YAML: {}", e))?);
+    }
+
+    Ok(documents.join("---\n"))
+}
+
+// Hex dump gaya "hexdump -C": offset, 16 byte heksadesimal dan kolom ASCII.
+// Byte setelah limit tidak ditampilkan, hanya disebutkan jumlahnya.
+pub fn hex_dump(bytes: &[u8], limit: usize) -> String {
+    let shown = &bytes[..bytes.len().min(limit)];
+    let mut out = String::with_capacity(shown.len() / 16 * 80 + 80);
+
+    for (line, chunk) in shown.chunks(16).enumerate() {
+        let _ = write!(out, "{:08x}  ", line * 16);
+        for i in 0..16 {
+            match chunk.get(i) {
+                Some(byte) => { let _ = write!(out, "{:02x} ", byte); },
+                None => out.push_str("   "),
+            }
+            if i == 7 {
+                out.push(' ');
+            }
+        }
+        out.push_str(" |");
+        out.extend(chunk.iter().map(|&byte| if byte.is_ascii_graphic() || byte == b' ' { byte as char } else { '.' }));
+        out.push_str("|\n");
+    }
+
+    if bytes.len() > shown.len() {
+        let _ = writeln!(out, "... {} more bytes", bytes.len() - shown.len());
+    }
+    let _ = write!(out, "{:08x}", bytes.len());
+
+    out
+}
+
+fn newline(out: &mut String, depth: usize) {
+    out.push('\n');
+    for _ in 0..depth {
+        out.push_str(INDENT);
+    }
+}
+
+// Tulis satu baris markup dengan indentasi, tanpa baris kosong di awal output
+fn push_line(out: &mut String, depth: usize, text: &str) {
+    if out.is_empty() {
+        out.push_str(&INDENT.repeat(depth));
+    } else {
+        newline(out, depth);
+    }
+    out.push_str(text);
+}
+
+fn skip_whitespace(chars: &mut std::iter::Peekable<std::str::Chars>) {
+    while chars.peek().is_some_and(|c| c.is_whitespace()) {
+        chars.next();
+    }
+}
+
+// Token markup: tag pembuka, tag penutup, teks, dan sisanya (komentar, doctype, CDATA, PI)
+enum Token<'a> {
+    Open { name: String, raw: &'a str, closed: bool },
+    Close { name: String, raw: &'a str },
+    Text(&'a str),
+    // Isi elemen raw text HTML, ditulis tanpa perubahan
+    Raw(&'a str),
+    Other(&'a str),
+}
+
+fn tokenize(source: &str, html: bool) -> Result<Vec<Token<'_>>, String> {
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+
+    while pos < source.len() {
+        let rest = &source[pos..];
+        if !rest.starts_with('<') {
+            let end = rest.find('<').unwrap_or(rest.len());
+            tokens.push(Token::Text(&rest[..end]));
+            pos += end;
+            continue;
+        }
+
+        let terminator = if rest.starts_with("<!--") {
+            "-->"
+        } else if rest.starts_with("<![CDATA[") {
+            "]]>"
+        } else if rest.starts_with("<?") {
+            "?>"
+        } else {
+            ">"
+        };
+        let end = if terminator == ">" {
+            tag_end(rest)
+        } else {
+            rest.find(terminator).map(|i| i + terminator.len())
+        }
+        .ok_or_else(|| format!("Unterminated markup at byte {}", pos))?;
+
+        let raw = &rest[..end];
+        pos += end;
+
+        if raw.starts_with("<!") || raw.starts_with("<?") {
+            tokens.push(Token::Other(raw));
+        } else if let Some(close) = raw.strip_prefix("</") {
+            tokens.push(Token::Close { name: tag_name(close, html), raw });
+        } else {
+            let name = tag_name(&raw[1..], html);
+            let closed = raw.ends_with("/>") || (html && VOID_ELEMENTS.contains(&name.as_str()));
+
+            // Isi <script>, <style>, <pre> dan <textarea> disalin sampai tag penutupnya
+            if html && !closed && RAW_TEXT_ELEMENTS.contains(&name.as_str()) {
+                let content = &source[pos..];
+                let close_tag = format!("</{}", name);
+                let content_end = content.to_ascii_lowercase().find(&close_tag).unwrap_or(content.len());
+                tokens.push(Token::Open { name, raw, closed });
+                if !content[..content_end].trim().is_empty() {
+                    tokens.push(Token::Raw(&content[..content_end]));
+                }
+                pos += content_end;
+                continue;
+            }
+
+            tokens.push(Token::Open { name, raw, closed });
+        }
+    }
+
+    Ok(tokens)
+}
+
+// Posisi setelah '>' penutup tag, '>' di dalam nilai atribut yang dikutip dilewati
+fn tag_end(tag: &str) -> Option<usize> {
+    let mut quote = None;
+    for (i, c) in tag.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), c) if c == q => quote = None,
+            (None, '>') => return Some(i + 1),
+            _ => {},
+        }
+    }
+    None
+}
+
+fn tag_name(tag: &str, html: bool) -> String {
+    let name: String = tag.chars()
+        .take_while(|c| !c.is_whitespace() && *c != '/' && *c != '>')
+        .collect();
+    if html { name.to_ascii_lowercase() } else { name }
+}
+
+fn indent_markup(source: &str, html: bool) -> Result<String, String> {
+    let tokens = tokenize(source.trim(), html)?;
+    let mut out = String::with_capacity(source.len() * 2);
+    let mut open: Vec<&str> = Vec::new();
+    let mut i = 0;
+
+    while i < tokens.len() {
+        match &tokens[i] {
+            Token::Open { name, raw, closed } => {
+                if html {
+                    let implied = IMPLIED_END.iter().find(|(element, _)| element == name).map(|(_, closes)| *closes);
+                    while open.last().is_some_and(|last| implied.is_some_and(|closes| closes.contains(last))) {
+                        open.pop();
+                    }
+                }
+                if *closed {
+                    push_line(&mut out, open.len(), raw);
+                    i += 1;
+                    continue;
+                }
+
+                // <tag>teks pendek</tag> dan <tag></tag> tetap satu baris
+                let closes_at = |index: usize| matches!(tokens.get(index), Some(Token::Close { name: close, .. }) if close == name);
+                match tokens.get(i + 1) {
+                    Some(Token::Text(text)) if !text.trim().contains('\n') && closes_at(i + 2) => {
+                        if let Some(Token::Close { raw: close, .. }) = tokens.get(i + 2) {
+                            push_line(&mut out, open.len(), &format!("{}{}{}", raw, text.trim(), close));
+                        }
+                        i += 3;
+                    },
+                    _ if closes_at(i + 1) => {
+                        if let Some(Token::Close { raw: close, .. }) = tokens.get(i + 1) {
+                            push_line(&mut out, open.len(), &format!("{}{}", raw, close));
+                        }
+                        i += 2;
+                    },
+                    _ => {
+                        push_line(&mut out, open.len(), raw);
+                        open.push(name);
+                        i += 1;
+                    },
+                }
+            },
+            Token::Close { name, raw } => {
+                // HTML boleh tidak menutup elemen (misalnya <li>, <p>), tutup sampai elemen yang cocok
+                match open.iter().rposition(|open_name| open_name == name) {
+                    Some(index) if html || index == open.len() - 1 => open.truncate(index),
+                    None if html => {},
+                    _ => return Err(format!("Unexpected closing tag {}", raw)),
+                }
+                push_line(&mut out, open.len(), raw);
+                i += 1;
+            },
+            Token::Text(text) => {
+                for text_line in text.lines().map(str::trim).filter(|text_line| !text_line.is_empty()) {
+                    push_line(&mut out, open.len(), text_line);
+                }
+                i += 1;
+            },
+            Token::Raw(content) => {
+                out.push('\n');
+                out.push_str(content.trim_matches(|c| c == '\n' || c == '\r'));
+                i += 1;
+            },
+            Token::Other(raw) => {
+                push_line(&mut out, open.len(), raw);
+                i += 1;
+            },
+        }
+    }
+
+    if !html && !open.is_empty() {
+        return Err(format!("Unclosed element <{}>", open[open.len() - 1]));
+    }
+
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_keeps_key_order_and_number_text() {
+        let formatted = format_json(r#"{"z":1.50,"a":[],"s":"a,b:{\"c\"}","o":{"n":[1,{}]}}"#).unwrap();
+        assert_eq!(formatted, "{\n  \"z\": 1.50,\n  \"a\": [],\n  \"s\": \"a,b:{\\\"c\\\"}\",\n  \"o\": {\n    \"n\": [\n      1,\n      {}\n    ]\n  }\n}");
+
+        assert!(format_json("{\"a\": }").unwrap_err().starts_with("Invalid JSON"));
+    }
+
+    #[test]
+    fn xml_is_indented_per_element() {
+        let formatted = format_xml(r#"<?xml version="1.0"?><root><item id="a>b">One</item><empty></empty><br/></root>"#).unwrap();
+        assert_eq!(formatted, "<?xml version=\"1.0\"?>\n<root>\n  <item id=\"a>b\">One</item>\n  <empty></empty>\n  <br/>\n</root>");
+
+        assert_eq!(format_xml("<a><b></a>").unwrap_err(), "Unexpected closing tag </a>");
+        assert_eq!(format_xml("<a><b/>").unwrap_err(), "Unclosed element <a>");
+        assert!(format_xml("<a").unwrap_err().starts_with("Unterminated markup"));
+    }
+
+    #[test]
+    fn html_handles_void_implied_and_raw_elements() {
+        let formatted = format_html("<UL><li>One<li>Two</ul><br><script>if (a < b) {\n  go();\n}</script>").unwrap();
+        assert_eq!(formatted, "<UL>\n  <li>\n    One\n  <li>\n    Two\n</ul>\n<br>\n<script>\nif (a < b) {\n  go();\n}\n</script>");
+    }
+
+    #[test]
+    fn yaml_is_normalized_per_document() {
+        assert_eq!(format_yaml("a:   1\nb: [x, y]\n---\n- 2\n").unwrap(), "a: 1\nb:\n- x\n- y\n---\n- 2\n");
+        assert!(format_yaml("a: [").unwrap_err().starts_with("Invalid YAML"));
+    }
+
+    #[test]
+    fn pretty_formats_by_type() {
+        assert_eq!(pretty(&ResponseBodyType::Json, "[1,2]").unwrap(), "[\n  1,\n  2\n]");
+        assert_eq!(pretty(&ResponseBodyType::Html, "<p>hi</p>").unwrap(), "<p>hi</p>");
+        // Tipe tanpa formatter dikembalikan apa adanya
+        assert_eq!(pretty(&ResponseBodyType::Csv, "a,b\n1,2").unwrap(), "a,b\n1,2");
+        assert!(pretty(&ResponseBodyType::Json, "{").is_err());
+    }
+
+    #[test]
+    fn hex_dump_shows_offsets_ascii_and_limit() {
+        let dump = hex_dump(b"Hello, fmus!\x00\x01\x02\x03\xff", 16);
+        assert_eq!(dump, "00000000  48 65 6c 6c 6f 2c 20 66  6d 75 73 21 00 01 02 03  |Hello, fmus!....|\n... 1 more bytes\n00000011");
+    }
+}
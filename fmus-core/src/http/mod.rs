@@ -1,6 +1,17 @@
+pub mod charset;
 pub mod client;
+pub mod download;
+pub mod format;
+pub mod range;
+pub mod ratelimit;
 pub mod response;
+pub mod sniff;
 
-pub use client::{get, post, put, patch, delete, HttpClient, ClientConfig, RequestParams, AuthConfig, HttpResponse};
+pub use client::{get, post, put, patch, delete, HttpClient, ClientConfig, RequestParams, AuthConfig, HttpResponse, PreparedRequest};
+pub use charset::{DecodedText, EncodingSource};
+pub use download::{DownloadOptions, DownloadProgress, DownloadResult};
+pub use range::{ByteRange, ContentRange};
+pub use ratelimit::{RateLimit, RateLimitConfig};
+pub use response::ResponseBodyType;
 
 // Re-export HTTP-specific middlewares jika perlu
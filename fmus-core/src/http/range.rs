@@ -0,0 +1,147 @@
+use serde::{Deserialize, Serialize};
+
+// Nilai header Content-Range pada response 206 atau 416
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ContentRange {
+    // "bytes 0-499/1234", total None jika server menulis "*"
+    Bytes { start: u64, end: u64, total: Option<u64> },
+    // "bytes */1234" pada response 416 Range Not Satisfiable
+    Unsatisfied { total: u64 },
+}
+
+impl ContentRange {
+    pub fn parse(value: &str) -> Option<ContentRange> {
+        let spec = value.trim().strip_prefix("bytes")?.trim_start();
+        let (range, total) = spec.split_once('/')?;
+        let total = match total.trim() {
+            "*" => None,
+            total => Some(total.parse().ok()?),
+        };
+
+        if range.trim() == "*" {
+            return total.map(|total| ContentRange::Unsatisfied { total });
+        }
+
+        let (start, end) = range.split_once('-')?;
+        let start: u64 = start.trim().parse().ok()?;
+        let end: u64 = end.trim().parse().ok()?;
+        if end < start || total.is_some_and(|total| end >= total) {
+            return None;
+        }
+
+        Some(ContentRange::Bytes { start, end, total })
+    }
+
+    // Jumlah byte dalam range, 0 untuk Unsatisfied
+    pub fn len(&self) -> u64 {
+        match self {
+            ContentRange::Bytes { start, end, .. } => end - start + 1,
+            ContentRange::Unsatisfied { .. } => 0,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn total(&self) -> Option<u64> {
+        match self {
+            ContentRange::Bytes { total, .. } => *total,
+            ContentRange::Unsatisfied { total } => Some(*total),
+        }
+    }
+}
+
+// Range yang diminta lewat header Range, satu range saja
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ByteRange {
+    pub start: u64,
+    // Inklusif, None berarti sampai akhir file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end: Option<u64>,
+}
+
+impl ByteRange {
+    // Nilai header Range, misalnya "bytes=500-" atau "bytes=0-499"
+    pub fn header_value(&self) -> String {
+        match self.end {
+            Some(end) => format!("bytes={}-{}", self.start, end),
+            None => format!("bytes={}-", self.start),
+        }
+    }
+
+    // Cek Content-Range response terhadap range yang diminta dan jumlah byte body
+    pub fn check(&self, content_range: &ContentRange, body_len: u64) -> Result<(), String> {
+        let (start, end, total) = match *content_range {
+            ContentRange::Bytes { start, end, total } => (start, end, total),
+            ContentRange::Unsatisfied { total } => {
+                return Err(format!("Range {} not satisfiable (resource is {} bytes)", self.header_value(), total));
+            },
+        };
+
+        if start != self.start {
+            return Err(format!("Expected range to start at byte {} but Content-Range starts at {}", self.start, start));
+        }
+
+        // Server boleh memotong range yang melewati akhir file
+        let expected_end = match (self.end, total) {
+            (Some(end), Some(total)) => Some(end.min(total - 1)),
+            (Some(end), None) => Some(end),
+            (None, Some(total)) => Some(total - 1),
+            (None, None) => None,
+        };
+        if let Some(expected_end) = expected_end {
+            if end != expected_end {
+                return Err(format!("Expected range to end at byte {} but Content-Range ends at {}", expected_end, end));
+            }
+        }
+
+        if content_range.len() != body_len {
+            return Err(format!("Content-Range covers {} bytes but body has {} bytes", content_range.len(), body_len));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_content_range() {
+        assert_eq!(ContentRange::parse("bytes 0-499/1234"), Some(ContentRange::Bytes { start: 0, end: 499, total: Some(1234) }));
+        assert_eq!(ContentRange::parse("bytes 500-999/*"), Some(ContentRange::Bytes { start: 500, end: 999, total: None }));
+        assert_eq!(ContentRange::parse("bytes */1234"), Some(ContentRange::Unsatisfied { total: 1234 }));
+        assert_eq!(ContentRange::parse("bytes 0-499/1234").unwrap().len(), 500);
+
+        for invalid in ["bytes 5-4/10", "bytes 0-10/10", "bytes */*", "items 0-1/2", "bytes 0-1"] {
+            assert_eq!(ContentRange::parse(invalid), None, "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn checks_content_range_against_request() {
+        let open = ByteRange { start: 4, end: None };
+        assert_eq!(open.header_value(), "bytes=4-");
+        assert!(open.check(&ContentRange::parse("bytes 4-9/10").unwrap(), 6).is_ok());
+        assert_eq!(
+            open.check(&ContentRange::parse("bytes 0-9/10").unwrap(), 10).unwrap_err(),
+            "Expected range to start at byte 4 but Content-Range starts at 0",
+        );
+        assert_eq!(
+            open.check(&ContentRange::parse("bytes 4-9/10").unwrap(), 5).unwrap_err(),
+            "Content-Range covers 6 bytes but body has 5 bytes",
+        );
+
+        // Range yang melewati akhir file boleh dipotong server
+        let closed = ByteRange { start: 0, end: Some(99) };
+        assert!(closed.check(&ContentRange::parse("bytes 0-9/10").unwrap(), 10).is_ok());
+        assert_eq!(
+            closed.check(&ContentRange::parse("bytes 0-49/*").unwrap(), 50).unwrap_err(),
+            "Expected range to end at byte 99 but Content-Range ends at 49",
+        );
+        assert!(closed.check(&ContentRange::Unsatisfied { total: 10 }, 0).unwrap_err().contains("not satisfiable"));
+    }
+}
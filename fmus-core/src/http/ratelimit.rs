@@ -0,0 +1,424 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+// Jeda awal setelah 429 tanpa Retry-After, digandakan untuk setiap 429 berturut-turut
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+// Jeda dari header tidak dipercaya lebih dari ini, agar header yang salah tidak menahan run berjam-jam
+const MAX_HEADER_WAIT: Duration = Duration::from_secs(300);
+
+// Token bucket: rate request per detik, burst request boleh dikirim sekaligus
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RateLimit {
+    pub requests_per_second: f64,
+    // Default sama dengan requests_per_second (minimal 1)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub burst: Option<f64>,
+}
+
+// Pembatasan request di HttpClient, misalnya
+// {"per_host": {"requests_per_second": 10}, "max_connections_per_host": 4}
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RateLimitConfig {
+    // Batas untuk semua host sekaligus
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub global: Option<RateLimit>,
+    // Batas default untuk setiap host
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub per_host: Option<RateLimit>,
+    // Batas khusus per host, key berupa "api.example.com" atau "api.example.com:8443"
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub hosts: HashMap<String, RateLimit>,
+    // Batas request yang berjalan bersamaan ke satu host
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_connections_per_host: Option<usize>,
+    // Tahan request ke host setelah 429 dan ikuti header RateLimit-* / X-RateLimit-*
+    #[serde(default = "default_adaptive")]
+    pub adaptive: bool,
+    // Kirim ulang request yang mendapat 429 setelah jeda, 0 berarti response 429 dikembalikan apa adanya
+    #[serde(default)]
+    pub retry_on_429: u32,
+}
+
+fn default_adaptive() -> bool {
+    true
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        RateLimitConfig {
+            global: None,
+            per_host: None,
+            hosts: HashMap::new(),
+            max_connections_per_host: None,
+            adaptive: default_adaptive(),
+            retry_on_429: 0,
+        }
+    }
+}
+
+impl RateLimit {
+    fn validate(&self, name: &str) -> Result<(), String> {
+        if !(self.requests_per_second.is_finite() && self.requests_per_second > 0.0) {
+            return Err(format!("Rate limit {}: requests_per_second must be a positive number, got {}", name, self.requests_per_second));
+        }
+        match self.burst {
+            Some(burst) if !(burst.is_finite() && burst > 0.0) => {
+                Err(format!("Rate limit {}: burst must be a positive number, got {}", name, burst))
+            },
+            _ => Ok(()),
+        }
+    }
+}
+
+impl RateLimitConfig {
+    // Rate dan burst harus angka positif yang terhingga; 0, negatif atau NaN tidak punya arti sebagai batas
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(global) = &self.global {
+            global.validate("global")?;
+        }
+        if let Some(per_host) = &self.per_host {
+            per_host.validate("per_host")?;
+        }
+        for (host, limit) in &self.hosts {
+            limit.validate(host)?;
+        }
+        Ok(())
+    }
+
+    fn host_limit(&self, host: &str) -> Option<&RateLimit> {
+        let name = host.rsplit_once(':').map(|(name, _)| name).unwrap_or(host);
+        self.hosts.get(host).or_else(|| self.hosts.get(name)).or(self.per_host.as_ref())
+    }
+}
+
+struct Bucket {
+    rate: f64,
+    capacity: f64,
+    tokens: f64,
+    updated: Instant,
+}
+
+impl Bucket {
+    fn new(limit: &RateLimit, now: Instant) -> Self {
+        let rate = limit.requests_per_second;
+        let capacity = limit.burst.unwrap_or(rate).max(1.0);
+        Bucket { rate, capacity, tokens: capacity, updated: now }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.capacity);
+        self.updated = now;
+    }
+
+    // Waktu tunggu sampai satu token tersedia
+    fn wait(&mut self, now: Instant) -> Duration {
+        self.refill(now);
+        if self.tokens >= 1.0 {
+            Duration::ZERO
+        } else {
+            // Rate yang sangat kecil bisa menghasilkan jeda di luar jangkauan Duration
+            Duration::try_from_secs_f64((1.0 - self.tokens) / self.rate).unwrap_or(Duration::MAX)
+        }
+    }
+
+    fn take(&mut self) {
+        self.tokens -= 1.0;
+    }
+}
+
+#[derive(Default)]
+struct HostState {
+    bucket: Option<Bucket>,
+    // Bucket sementara dari header RateLimit-*: sisa kuota dibagi rata sampai reset
+    adaptive: Option<(Bucket, Instant)>,
+    blocked_until: Option<Instant>,
+    backoff: Option<Duration>,
+    connections: Option<Arc<Semaphore>>,
+}
+
+struct LimiterState {
+    global: Option<Bucket>,
+    hosts: HashMap<String, HostState>,
+}
+
+// Token bucket global dan per host, plus batas koneksi per host
+pub(crate) struct RateLimiter {
+    config: RateLimitConfig,
+    state: Mutex<LimiterState>,
+}
+
+// Izin mengirim request; slot koneksi host dilepas saat izin di-drop
+pub(crate) struct Permit {
+    _connection: Option<OwnedSemaphorePermit>,
+}
+
+impl RateLimiter {
+    pub(crate) fn new(config: RateLimitConfig) -> Self {
+        let global = config.global.as_ref().map(|limit| Bucket::new(limit, Instant::now()));
+        RateLimiter {
+            config,
+            state: Mutex::new(LimiterState { global, hosts: HashMap::new() }),
+        }
+    }
+
+    pub(crate) fn config(&self) -> &RateLimitConfig {
+        &self.config
+    }
+
+    // Tunggu slot koneksi lalu token global dan host. Token diambil dari semua bucket sekaligus
+    // agar request yang sedang menunggu satu bucket tidak menghabiskan bucket lain.
+    pub(crate) async fn acquire(&self, host: &str) -> Permit {
+        let semaphore = self.config.max_connections_per_host.map(|max| {
+            let mut state = self.state.lock().unwrap();
+            let host_state = self.host_state(&mut state, host);
+            host_state.connections.get_or_insert_with(|| Arc::new(Semaphore::new(max.max(1)))).clone()
+        });
+        let connection = match semaphore {
+            Some(semaphore) => semaphore.acquire_owned().await.ok(),
+            None => None,
+        };
+
+        loop {
+            let wait = {
+                let now = Instant::now();
+                let mut state = self.state.lock().unwrap();
+                let mut wait = state.global.as_mut().map(|bucket| bucket.wait(now)).unwrap_or_default();
+
+                let host_state = self.host_state(&mut state, host);
+                if host_state.adaptive.as_ref().is_some_and(|(_, until)| now >= *until) {
+                    host_state.adaptive = None;
+                }
+                wait = wait
+                    .max(host_state.blocked_until.map(|until| until.saturating_duration_since(now)).unwrap_or_default())
+                    .max(host_state.bucket.as_mut().map(|bucket| bucket.wait(now)).unwrap_or_default())
+                    .max(host_state.adaptive.as_mut().map(|(bucket, _)| bucket.wait(now)).unwrap_or_default());
+
+                if wait.is_zero() {
+                    if let Some(bucket) = host_state.bucket.as_mut() {
+                        bucket.take();
+                    }
+                    if let Some((bucket, _)) = host_state.adaptive.as_mut() {
+                        bucket.take();
+                    }
+                    if let Some(bucket) = state.global.as_mut() {
+                        bucket.take();
+                    }
+                }
+                wait
+            };
+
+            if wait.is_zero() {
+                return Permit { _connection: connection };
+            }
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    // Sesuaikan batas host dari response: 429 menahan host sampai Retry-After (atau backoff),
+    // header RateLimit-* membagi sisa kuota sampai waktu reset. Return jeda yang diminta server untuk 429.
+    pub(crate) fn observe(&self, host: &str, status: u16, headers: &HashMap<String, String>) -> Option<Duration> {
+        if !self.config.adaptive {
+            return (status == 429).then(|| retry_after(headers).unwrap_or(INITIAL_BACKOFF));
+        }
+
+        let now = Instant::now();
+        let mut state = self.state.lock().unwrap();
+        let host_state = self.host_state(&mut state, host);
+
+        if status == 429 {
+            let backoff = host_state.backoff.map(|backoff| (backoff * 2).min(MAX_BACKOFF)).unwrap_or(INITIAL_BACKOFF);
+            let wait = retry_after(headers).or_else(|| quota(headers).map(|(_, reset)| reset)).unwrap_or(backoff);
+            host_state.backoff = Some(backoff);
+            host_state.blocked_until = Some(now + wait);
+            host_state.adaptive = None;
+            return Some(wait);
+        }
+
+        host_state.backoff = None;
+        match quota(headers) {
+            Some((0, reset)) => {
+                host_state.blocked_until = Some(now + reset);
+                host_state.adaptive = None;
+            },
+            Some((remaining, reset)) if !reset.is_zero() => {
+                let limit = RateLimit { requests_per_second: remaining as f64 / reset.as_secs_f64(), burst: Some(1.0) };
+                host_state.adaptive = Some((Bucket::new(&limit, now), now + reset));
+            },
+            _ => {},
+        }
+        None
+    }
+
+    fn host_state<'s>(&self, state: &'s mut LimiterState, host: &str) -> &'s mut HostState {
+        state.hosts.entry(host.to_string()).or_insert_with(|| HostState {
+            bucket: self.config.host_limit(host).map(|limit| Bucket::new(limit, Instant::now())),
+            ..HostState::default()
+        })
+    }
+}
+
+fn header<'a>(headers: &'a HashMap<String, String>, name: &str) -> Option<&'a str> {
+    headers.iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.trim())
+}
+
+// Retry-After berupa detik atau HTTP-date
+fn retry_after(headers: &HashMap<String, String>) -> Option<Duration> {
+    let value = header(headers, "retry-after")?;
+    let wait = match value.parse::<f64>() {
+        Ok(seconds) if seconds >= 0.0 && seconds.is_finite() => Duration::from_secs_f64(seconds),
+        Ok(_) => return None,
+        Err(_) => httpdate::parse_http_date(value).ok()?
+            .duration_since(SystemTime::now())
+            .unwrap_or_default(),
+    };
+    Some(wait.min(MAX_HEADER_WAIT))
+}
+
+// Sisa kuota dan waktu sampai reset dari RateLimit-Remaining/Reset, X-RateLimit-Remaining/Reset,
+// atau header gabungan "RateLimit: limit=10, remaining=3, reset=5"
+fn quota(headers: &HashMap<String, String>) -> Option<(u64, Duration)> {
+    let combined = header(headers, "ratelimit").map(|value| {
+        value.split([',', ';'])
+            .filter_map(|part| part.split_once('='))
+            .map(|(key, value)| (key.trim().to_ascii_lowercase(), value.trim().to_string()))
+            .collect::<HashMap<_, _>>()
+    });
+    let field = |name: &str| {
+        header(headers, &format!("ratelimit-{}", name))
+            .or_else(|| header(headers, &format!("x-ratelimit-{}", name)))
+            .map(str::to_string)
+            .or_else(|| combined.as_ref().and_then(|fields| fields.get(name).cloned()))
+    };
+
+    let remaining = field("remaining")?.parse::<f64>().ok()?.max(0.0) as u64;
+    let reset = field("reset")?.parse::<f64>().ok().filter(|reset| reset.is_finite() && *reset >= 0.0)?;
+    Some((remaining, reset_duration(reset)))
+}
+
+// X-RateLimit-Reset kadang berupa epoch detik, bukan jumlah detik sampai reset
+fn reset_duration(reset: f64) -> Duration {
+    const EPOCH_THRESHOLD: f64 = 1_000_000_000.0;
+    let seconds = if reset >= EPOCH_THRESHOLD {
+        let now = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default().as_secs_f64();
+        (reset - now).max(0.0)
+    } else {
+        reset
+    };
+    Duration::from_secs_f64(seconds).min(MAX_HEADER_WAIT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs.iter().map(|(key, value)| (key.to_string(), value.to_string())).collect()
+    }
+
+    #[test]
+    fn bucket_refills_at_the_configured_rate() {
+        let start = Instant::now();
+        let mut bucket = Bucket::new(&RateLimit { requests_per_second: 2.0, burst: Some(2.0) }, start);
+        for _ in 0..2 {
+            assert_eq!(bucket.wait(start), Duration::ZERO);
+            bucket.take();
+        }
+        assert_eq!(bucket.wait(start), Duration::from_millis(500));
+        assert_eq!(bucket.wait(start + Duration::from_millis(500)), Duration::ZERO);
+        // Token tidak bertambah melebihi burst
+        bucket.refill(start + Duration::from_secs(60));
+        assert_eq!(bucket.tokens, 2.0);
+    }
+
+    #[test]
+    fn invalid_rates_are_rejected() {
+        let limit = |requests_per_second: f64, burst: Option<f64>| RateLimit { requests_per_second, burst };
+        for invalid in [limit(0.0, None), limit(-1.0, None), limit(f64::NAN, None), limit(f64::INFINITY, None), limit(1.0, Some(0.0))] {
+            let config = RateLimitConfig { per_host: Some(invalid), ..RateLimitConfig::default() };
+            assert!(config.validate().unwrap_err().starts_with("Rate limit per_host:"));
+        }
+        let config = RateLimitConfig {
+            global: Some(limit(10.0, Some(5.0))),
+            hosts: HashMap::from([("api.example.com".to_string(), limit(-2.0, None))]),
+            ..RateLimitConfig::default()
+        };
+        assert_eq!(
+            config.validate().unwrap_err(),
+            "Rate limit api.example.com: requests_per_second must be a positive number, got -2",
+        );
+        assert!(RateLimitConfig { global: Some(limit(0.5, None)), ..RateLimitConfig::default() }.validate().is_ok());
+
+        // Rate positif yang sangat kecil tidak membuat Duration overflow
+        let start = Instant::now();
+        let mut bucket = Bucket::new(&limit(1e-300, None), start);
+        bucket.take();
+        assert_eq!(bucket.wait(start), Duration::MAX);
+    }
+
+    #[test]
+    fn host_limits_prefer_exact_host_then_name() {
+        let config = RateLimitConfig {
+            per_host: Some(RateLimit { requests_per_second: 1.0, burst: None }),
+            hosts: HashMap::from([
+                ("api.example.com".to_string(), RateLimit { requests_per_second: 5.0, burst: None }),
+                ("api.example.com:8443".to_string(), RateLimit { requests_per_second: 9.0, burst: None }),
+            ]),
+            ..RateLimitConfig::default()
+        };
+        let rate = |host: &str| config.host_limit(host).map(|limit| limit.requests_per_second);
+        assert_eq!(rate("api.example.com:8443"), Some(9.0));
+        assert_eq!(rate("api.example.com:443"), Some(5.0));
+        assert_eq!(rate("other.example.com"), Some(1.0));
+    }
+
+    #[test]
+    fn reads_rate_limit_headers() {
+        assert_eq!(retry_after(&headers(&[("Retry-After", "2")])), Some(Duration::from_secs(2)));
+        assert_eq!(retry_after(&headers(&[("Retry-After", "99999")])), Some(MAX_HEADER_WAIT));
+        assert_eq!(retry_after(&headers(&[("Retry-After", "-1")])), None);
+        assert_eq!(
+            quota(&headers(&[("X-RateLimit-Remaining", "3"), ("X-RateLimit-Reset", "6")])),
+            Some((3, Duration::from_secs(6))),
+        );
+        assert_eq!(
+            quota(&headers(&[("RateLimit", "limit=10, remaining=0, reset=5")])),
+            Some((0, Duration::from_secs(5))),
+        );
+        assert_eq!(quota(&headers(&[("RateLimit-Remaining", "3")])), None);
+    }
+
+    #[test]
+    fn too_many_requests_backs_off() {
+        let limiter = RateLimiter::new(RateLimitConfig::default());
+        assert_eq!(limiter.observe("a", 429, &HashMap::new()), Some(INITIAL_BACKOFF));
+        assert_eq!(limiter.observe("a", 429, &HashMap::new()), Some(INITIAL_BACKOFF * 2));
+        assert_eq!(limiter.observe("a", 429, &headers(&[("Retry-After", "3")])), Some(Duration::from_secs(3)));
+        assert_eq!(limiter.observe("a", 200, &HashMap::new()), None);
+        assert_eq!(limiter.observe("a", 429, &HashMap::new()), Some(INITIAL_BACKOFF));
+
+        let state = limiter.state.lock().unwrap();
+        assert!(state.hosts["a"].blocked_until.is_some());
+        assert!(!state.hosts.contains_key("b"));
+    }
+
+    #[tokio::test]
+    async fn acquire_waits_for_tokens() {
+        let limiter = RateLimiter::new(RateLimitConfig {
+            global: Some(RateLimit { requests_per_second: 20.0, burst: Some(1.0) }),
+            ..RateLimitConfig::default()
+        });
+        let start = Instant::now();
+        for host in ["a", "b", "c"] {
+            limiter.acquire(host).await;
+        }
+        assert!(start.elapsed() >= Duration::from_millis(90), "{:?}", start.elapsed());
+    }
+}
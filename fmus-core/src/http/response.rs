@@ -2,10 +2,19 @@ use std::collections::HashMap;
 use serde::{Serialize, Deserialize};
 use serde_json::Value;
 
+use super::charset::{self, DecodedText};
+use super::{format, sniff};
+use super::range::{ByteRange, ContentRange};
 use super::client::HttpResponse;
+use crate::assertions::{self, Assertion, AssertionReport};
+use crate::codec::{self, BodyFormat};
+use crate::diff::{self, ResponseDiff};
+use crate::jsonpath::{self, JsonPathOp};
+use crate::markup::{self, MarkupValue};
+use crate::schema::{JsonSchema, SchemaViolation};
 
 // Enum untuk menyimpan tipe konten response
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ResponseBodyType {
     Json,
     Text,
@@ -14,18 +23,57 @@ pub enum ResponseBodyType {
     Xml,
     Image,
     Unknown,
+    Pdf,
+    Zip,
+    Protobuf,
+    MsgPack,
+    Cbor,
+    Csv,
+    Yaml,
+    JavaScript,
+    Audio,
+    Video,
+}
+
+impl ResponseBodyType {
+    // true jika body bisa ditampilkan sebagai teks
+    pub fn is_text(&self) -> bool {
+        matches!(
+            self,
+            ResponseBodyType::Json
+                | ResponseBodyType::Text
+                | ResponseBodyType::Html
+                | ResponseBodyType::Xml
+                | ResponseBodyType::Csv
+                | ResponseBodyType::Yaml
+                | ResponseBodyType::JavaScript
+        )
+    }
 }
 
 // Helper untuk pemrosesan response body
 impl HttpResponse {
-    // Konversi body ke string
+    // Konversi body ke string sesuai charset (Content-Type, BOM, lalu meta HTML/deklarasi XML)
     pub fn text(&self) -> Result<String, String> {
-        String::from_utf8(self.body.clone())
-            .map_err(|e| format!("Failed to convert response body to text: {}", e))
+        self.decode_text(false).map(|decoded| decoded.text)
     }
 
-    // Konversi body ke JSON
+    // Seperti text(), tapi byte yang tidak valid diganti U+FFFD
+    pub fn text_lossy(&self) -> String {
+        self.decode_text(true).map(|decoded| decoded.text).unwrap_or_default()
+    }
+
+    // Decode body beserta informasi encoding yang dipakai
+    pub fn decode_text(&self, lossy: bool) -> Result<DecodedText, String> {
+        charset::decode(&self.body, self.content_type(), lossy)
+    }
+
+    // Konversi body ke JSON. Body MessagePack dan CBOR (dari Content-Type) ikut di-decode ke JSON.
     pub fn json(&self) -> Result<Value, String> {
+        if let Some(format @ (BodyFormat::MsgPack | BodyFormat::Cbor)) = self.content_type().and_then(BodyFormat::from_content_type) {
+            return self.decode_body(&format);
+        }
+
         self.text()
             .and_then(|text| {
                 serde_json::from_str(&text)
@@ -33,36 +81,73 @@ impl HttpResponse {
             })
     }
 
-    // Mendeteksi tipe konten dari response
+    // Decode body dengan format tertentu ke JSON, misalnya protobuf dengan schema-nya
+    pub fn decode_body(&self, format: &BodyFormat) -> Result<Value, String> {
+        codec::decode(&self.body, format)
+    }
+
+    // Salinan response dengan body yang sudah di-decode menjadi JSON, sehingga assertion,
+    // capture dan script bisa dipakai untuk body MessagePack, CBOR maupun protobuf
+    pub fn to_json_response(&self, format: &BodyFormat) -> Result<HttpResponse, String> {
+        let json = self.decode_body(format)?;
+        let mut headers = self.headers.clone();
+        headers.retain(|key, _| !key.eq_ignore_ascii_case("content-type"));
+        headers.insert("content-type".to_string(), "application/json".to_string());
+
+        Ok(HttpResponse {
+            status: self.status,
+            headers,
+            body: serde_json::to_vec(&json).map_err(|e| format!("Failed to encode JSON: {}", e))?,
+            time: self.time,
+        })
+    }
+
+    // Nilai header Content-Type jika ada
+    pub fn content_type(&self) -> Option<&str> {
+        self.header("content-type")
+    }
+
+    // Mengambil nilai header tanpa membedakan huruf besar/kecil
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    // Mendeteksi tipe konten dari Content-Type. Jika tidak ada atau generik
+    // (application/octet-stream, text/plain), tipe ditentukan dari magic bytes lalu struktur body.
     pub fn detect_content_type(&self) -> ResponseBodyType {
-        let content_type = self.headers.get("content-type")
-            .or_else(|| self.headers.get("Content-Type"))
-            .map(|ct| ct.to_lowercase());
-
-        match content_type {
-            Some(ct) if ct.contains("application/json") => ResponseBodyType::Json,
-            Some(ct) if ct.contains("text/html") => ResponseBodyType::Html,
-            Some(ct) if ct.contains("text/xml") || ct.contains("application/xml") => ResponseBodyType::Xml,
-            Some(ct) if ct.contains("image/") => ResponseBodyType::Image,
-            Some(ct) if ct.contains("text/") => ResponseBodyType::Text,
-            Some(_) | None => {
-                // Coba deteksi JSON dari konten body
-                if let Ok(text) = self.text() {
-                    if text.trim().starts_with('{') && text.trim().ends_with('}') {
-                        if serde_json::from_str::<Value>(&text).is_ok() {
-                            return ResponseBodyType::Json;
-                        }
-                    }
-                }
-
-                // Jika tidak bisa mendeteksi, kembalikan tipe binary
-                if self.body.is_empty() {
-                    ResponseBodyType::Text
-                } else {
-                    ResponseBodyType::Binary
-                }
+        if let Some(body_type) = self.content_type().and_then(sniff::from_content_type) {
+            return body_type;
+        }
+        if self.body.is_empty() {
+            return ResponseBodyType::Text;
+        }
+        if let Some(body_type) = sniff::from_magic_bytes(&self.body) {
+            return body_type;
+        }
+
+        match self.text() {
+            Ok(text) => sniff::from_structure(&text),
+            Err(_) => ResponseBodyType::Binary,
+        }
+    }
+
+    // Body yang sudah diformat untuk ditampilkan: JSON, XML, HTML dan YAML diindentasi,
+    // body biner menjadi hex dump. Jika body tidak valid untuk tipenya, teks asli dikembalikan.
+    pub fn pretty(&self) -> String {
+        let body_type = self.detect_content_type();
+        if matches!(body_type, ResponseBodyType::MsgPack | ResponseBodyType::Cbor) {
+            if let Ok(json) = self.json() {
+                return serde_json::to_string_pretty(&json).unwrap_or_default();
             }
         }
+        if body_type.is_text() {
+            let text = self.text_lossy();
+            format::pretty(&body_type, &text).unwrap_or(text)
+        } else {
+            format::hex_dump(&self.body, format::HEX_DUMP_LIMIT)
+        }
     }
 
     // Assert helpers untuk testing
@@ -84,51 +169,197 @@ impl HttpResponse {
         }
     }
 
+    // Mengembalikan semua nilai yang cocok dengan ekspresi JSONPath (RFC 9535)
+    pub fn query_json_path(&self, path: &str) -> Result<Vec<Value>, String> {
+        let json_value = self.json()?;
+        let nodes = jsonpath::query(&json_value, path)?;
+        Ok(nodes.into_iter().cloned().collect())
+    }
+
     pub fn assert_json_path(&self, path: &str, expected: Value) -> Result<(), String> {
+        self.assert_json_path_op(path, &JsonPathOp::Equals(expected))
+    }
+
+    // Assertion JSONPath dengan operator (exists, contains, length, regex, perbandingan angka)
+    pub fn assert_json_path_op(&self, path: &str, op: &JsonPathOp) -> Result<(), String> {
         let json_value = self.json()?;
+        let nodes = jsonpath::query(&json_value, path)?;
+        op.check(path, &nodes)
+    }
 
-        // Implementasi dasar untuk jsonpath sederhana
-        // Untuk implementasi lengkap, bisa menggunakan library seperti jsonpath_lib
-        let parts: Vec<&str> = path.split('.').collect();
-        let mut current_value = &json_value;
-
-        for part in parts {
-            if let Some(index) = part.strip_prefix('[').and_then(|s| s.strip_suffix(']')) {
-                if let Ok(idx) = index.parse::<usize>() {
-                    if let Some(array_value) = current_value.as_array() {
-                        if idx < array_value.len() {
-                            current_value = &array_value[idx];
-                        } else {
-                            return Err(format!("Index {} out of bounds for array at path '{}'", idx, path));
-                        }
-                    } else {
-                        return Err(format!("Value at path '{}' is not an array", path));
-                    }
-                } else {
-                    return Err(format!("Invalid array index: {}", index));
-                }
-            } else {
-                if let Some(obj) = current_value.as_object() {
-                    if let Some(field_value) = obj.get(part) {
-                        current_value = field_value;
-                    } else {
-                        return Err(format!("Field '{}' not found in object at path '{}'", part, path));
-                    }
-                } else {
-                    return Err(format!("Value at path '{}' is not an object", path));
-                }
-            }
+    // Evaluasi XPath pada body XML, prefix namespace dari dokumen otomatis dikenali
+    pub fn xpath(&self, expr: &str) -> Result<Vec<MarkupValue>, String> {
+        markup::xpath(&self.text()?, expr)
+    }
+
+    // Evaluasi XPath dengan prefix namespace tambahan (misalnya untuk namespace default)
+    pub fn xpath_with_namespaces(&self, expr: &str, namespaces: &HashMap<String, String>) -> Result<Vec<MarkupValue>, String> {
+        markup::xpath_with_namespaces(&self.text()?, expr, namespaces)
+    }
+
+    // CSS selector pada body HTML, mendukung akhiran "::text" dan "::attr(nama)"
+    pub fn select(&self, css: &str) -> Result<Vec<MarkupValue>, String> {
+        markup::select(&self.text()?, css)
+    }
+
+    // Assertion XPath dengan operator yang sama seperti JSONPath, mengembalikan nilai yang ditemukan
+    pub fn assert_xpath(&self, expr: &str, op: &JsonPathOp) -> Result<Vec<MarkupValue>, String> {
+        let values = self.xpath(expr)?;
+        markup::check(expr, &values, op)?;
+        Ok(values)
+    }
+
+    // Assertion jumlah elemen yang cocok dengan CSS selector, mengembalikan elemen yang ditemukan
+    pub fn assert_css_count(&self, css: &str, expected: usize) -> Result<Vec<MarkupValue>, String> {
+        let values = self.select(css)?;
+        if values.len() == expected {
+            Ok(values)
+        } else {
+            Err(format!("Expected {} element(s) matching '{}' but found {}", expected, css, values.len()))
         }
+    }
 
-        if *current_value == expected {
+    // Memvalidasi body response terhadap JSON Schema (draft 7, 2019-09, 2020-12)
+    pub fn assert_json_schema(&self, schema: &JsonSchema) -> Result<(), Vec<SchemaViolation>> {
+        let json_value = self.json().map_err(|e| vec![SchemaViolation::general(e)])?;
+        let violations = schema
+            .validate(&json_value)
+            .map_err(|e| vec![SchemaViolation::general(e)])?;
+
+        if violations.is_empty() {
             Ok(())
         } else {
-            Err(format!(
-                "Expected value at path '{}' to be '{}' but got '{}'",
-                path,
-                expected.to_string(),
-                current_value.to_string()
-            ))
+            Err(violations)
         }
     }
+
+    // Header Content-Range yang sudah di-parse
+    pub fn content_range(&self) -> Option<ContentRange> {
+        self.header("content-range").and_then(ContentRange::parse)
+    }
+
+    // Memastikan response adalah 206 Partial Content dengan Content-Range yang sesuai range yang diminta
+    pub fn assert_range(&self, range: &ByteRange, total: Option<u64>) -> Result<ContentRange, String> {
+        if self.status != 206 {
+            return Err(format!("Expected status 206 for range {} but got {}", range.header_value(), self.status));
+        }
+
+        let content_range = match self.header("content-range") {
+            Some(value) => ContentRange::parse(value).ok_or_else(|| format!("Invalid Content-Range '{}'", value))?,
+            None => return Err("Expected header 'content-range' not found in response".to_string()),
+        };
+        range.check(&content_range, self.body.len() as u64)?;
+
+        if let Some(total) = total {
+            if content_range.total() != Some(total) {
+                return Err(format!("Expected Content-Range total of {} bytes but got {:?}", total, content_range.total()));
+            }
+        }
+
+        Ok(content_range)
+    }
+
+    // Perbedaan response ini (sebelum) dengan response lain (sesudah)
+    pub fn diff(&self, other: &HttpResponse) -> ResponseDiff {
+        diff::diff(self, other)
+    }
+
+    // Mengevaluasi assertion deklaratif menjadi laporan pass/fail
+    pub fn evaluate_assertions(&self, assertions: &[Assertion]) -> AssertionReport {
+        assertions::evaluate(assertions, self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(content_type: &str, body: &[u8]) -> HttpResponse {
+        let headers = HashMap::from([("content-type".to_string(), content_type.to_string())]);
+        HttpResponse { status: 200, headers, body: body.to_vec(), time: 1 }
+    }
+
+    const PAGE: &str = r#"<html><body>
+        <ul id="items"><li class="item" data-id="1">One</li><li class="item" data-id="2">Two</li></ul>
+        <a href="/next">Next</a>
+    </body></html>"#;
+
+    #[test]
+    fn select_returns_nodes_text_and_attributes() {
+        let page = response("text/html", PAGE.as_bytes());
+
+        let nodes = page.select("ul#items > li.item").unwrap();
+        assert_eq!(nodes.len(), 2);
+        assert!(matches!(&nodes[0], MarkupValue::Node { name, text, attributes, .. }
+            if name == "li" && text == "One" && attributes["data-id"] == "1"));
+
+        assert_eq!(page.select("li::text").unwrap()[1], MarkupValue::Text { value: "Two".to_string() });
+        assert_eq!(
+            page.select("a::attr(href)").unwrap(),
+            vec![MarkupValue::Attribute { name: "href".to_string(), value: "/next".to_string() }],
+        );
+        // Elemen tanpa atribut yang diminta dilewati
+        assert!(page.select("li::attr(href)").unwrap().is_empty());
+    }
+
+    #[test]
+    fn assert_css_count_compares_matches() {
+        let page = response("text/html", PAGE.as_bytes());
+
+        assert_eq!(page.assert_css_count("li", 2).unwrap().len(), 2);
+        assert_eq!(page.assert_css_count("table", 0).unwrap(), Vec::new());
+        assert_eq!(page.assert_css_count("li", 3).unwrap_err(), "Expected 3 element(s) matching 'li' but found 2");
+    }
+
+    #[test]
+    fn invalid_selectors_are_errors() {
+        let page = response("text/html", PAGE.as_bytes());
+
+        assert!(page.select("li[").unwrap_err().starts_with("Invalid CSS selector 'li['"));
+        assert!(page.assert_css_count("a::attr(href", 1).unwrap_err().contains("unclosed ::attr("));
+    }
+
+    #[test]
+    fn non_html_bodies() {
+        // Body non-HTML tetap di-parse sebagai dokumen, hanya elemen html/head/body yang ada
+        let json = response("application/json", br#"{"li": [1, 2]}"#);
+        assert!(json.select("li").unwrap().is_empty());
+        assert_eq!(json.assert_css_count("body", 1).unwrap().len(), 1);
+
+        let binary = response("text/html; charset=utf-8", &[0x3c, 0x62, 0xff, 0xfe]);
+        assert!(binary.select("b").is_err());
+    }
+
+    #[test]
+    fn assert_range_checks_status_and_content_range() {
+        let range = ByteRange { start: 2, end: Some(5) };
+        let partial = |content_range: &str, body: &[u8]| HttpResponse {
+            status: 206,
+            headers: HashMap::from([("content-range".to_string(), content_range.to_string())]),
+            body: body.to_vec(),
+            time: 1,
+        };
+
+        let content_range = partial("bytes 2-5/10", b"2345").assert_range(&range, Some(10)).unwrap();
+        assert_eq!(content_range, ContentRange::Bytes { start: 2, end: 5, total: Some(10) });
+
+        // Server yang mengabaikan Range mengirim 200 dengan body penuh
+        assert_eq!(
+            response("text/plain", b"0123456789").assert_range(&range, None).unwrap_err(),
+            "Expected status 206 for range bytes=2-5 but got 200",
+        );
+        assert_eq!(
+            partial("bytes 0-3/10", b"0123").assert_range(&range, None).unwrap_err(),
+            "Expected range to start at byte 2 but Content-Range starts at 0",
+        );
+        assert_eq!(
+            partial("bytes 2-5/12", b"2345").assert_range(&range, Some(10)).unwrap_err(),
+            "Expected Content-Range total of 10 bytes but got Some(12)",
+        );
+        assert_eq!(partial("2-5", b"2345").assert_range(&range, None).unwrap_err(), "Invalid Content-Range '2-5'");
+        let mut missing = partial("", b"2345");
+        missing.headers.clear();
+        assert_eq!(missing.assert_range(&range, None).unwrap_err(), "Expected header 'content-range' not found in response");
+    }
+
 }
@@ -0,0 +1,273 @@
+use serde::Deserialize;
+
+use super::response::ResponseBodyType;
+
+// Batas awal body yang diperiksa untuk deteksi struktur (HTML, CSV, YAML)
+const SNIFF_LIMIT: usize = 4096;
+
+// Tipe body dari header Content-Type. None jika tipenya generik
+// (misalnya application/octet-stream) sehingga isi body perlu diperiksa.
+pub fn from_content_type(content_type: &str) -> Option<ResponseBodyType> {
+    let essence = content_type.split(';').next().unwrap_or_default().trim().to_ascii_lowercase();
+    let (kind, subtype) = essence.split_once('/')?;
+
+    let body_type = match (kind, subtype) {
+        ("image", _) => ResponseBodyType::Image,
+        ("audio", _) => ResponseBodyType::Audio,
+        ("video", _) => ResponseBodyType::Video,
+        (_, "json") | (_, "x-ndjson") => ResponseBodyType::Json,
+        (_, sub) if sub.ends_with("+json") => ResponseBodyType::Json,
+        (_, "html") | (_, "xhtml+xml") => ResponseBodyType::Html,
+        (_, "xml") => ResponseBodyType::Xml,
+        (_, sub) if sub.ends_with("+xml") => ResponseBodyType::Xml,
+        (_, "yaml") | (_, "x-yaml") => ResponseBodyType::Yaml,
+        (_, sub) if sub.ends_with("+yaml") => ResponseBodyType::Yaml,
+        (_, "csv") | ("text", "tab-separated-values") => ResponseBodyType::Csv,
+        (_, "javascript") | (_, "x-javascript") | (_, "ecmascript") => ResponseBodyType::JavaScript,
+        ("application", "pdf") => ResponseBodyType::Pdf,
+        ("application", "zip") | ("application", "x-zip-compressed") => ResponseBodyType::Zip,
+        ("application", "protobuf")
+        | ("application", "x-protobuf")
+        | ("application", "x-google-protobuf")
+        | ("application", "vnd.google.protobuf")
+        | ("application", "grpc")
+        | ("application", "grpc+proto") => ResponseBodyType::Protobuf,
+        ("application", "msgpack") | ("application", "x-msgpack") | ("application", "vnd.msgpack") => ResponseBodyType::MsgPack,
+        ("application", "cbor") => ResponseBodyType::Cbor,
+        ("text", "plain") => return None,
+        ("text", _) => ResponseBodyType::Text,
+        _ => return None,
+    };
+
+    Some(body_type)
+}
+
+// Tipe body dari magic bytes di awal body (PDF, ZIP, gambar, audio, video)
+pub fn from_magic_bytes(body: &[u8]) -> Option<ResponseBodyType> {
+    let starts = |magic: &[u8]| body.starts_with(magic);
+    let at = |offset: usize, magic: &[u8]| body.get(offset..offset + magic.len()) == Some(magic);
+
+    if starts(b"%PDF-") {
+        return Some(ResponseBodyType::Pdf);
+    }
+    if starts(b"PK\x03\x04") || starts(b"PK\x05\x06") || starts(b"PK\x07\x08") {
+        return Some(ResponseBodyType::Zip);
+    }
+
+    // Gambar
+    if starts(b"\x89PNG\r\n\x1a\n")
+        || starts(b"\xff\xd8\xff")
+        || starts(b"GIF87a")
+        || starts(b"GIF89a")
+        || (starts(b"RIFF") && at(8, b"WEBP"))
+        || starts(b"II*\x00")
+        || starts(b"MM\x00*")
+        || starts(b"\x00\x00\x01\x00")
+    {
+        return Some(ResponseBodyType::Image);
+    }
+
+    // Container ISO (MP4, MOV, M4A, HEIC/AVIF), brand di offset 8 menentukan jenisnya
+    if at(4, b"ftyp") {
+        return Some(match body.get(8..12) {
+            Some(b"M4A ") | Some(b"M4B ") | Some(b"M4P ") => ResponseBodyType::Audio,
+            Some(b"heic") | Some(b"heix") | Some(b"mif1") | Some(b"avif") => ResponseBodyType::Image,
+            _ => ResponseBodyType::Video,
+        });
+    }
+
+    // Audio
+    if starts(b"ID3")
+        || starts(b"OggS")
+        || starts(b"fLaC")
+        || starts(b"MThd")
+        || starts(b"#!AMR")
+        || (starts(b"RIFF") && at(8, b"WAVE"))
+        || (starts(b"FORM") && at(8, b"AIFF"))
+        // Frame sync MP3 dan AAC (ADTS)
+        || (body.len() > 2 && body[0] == 0xff && matches!(body[1], 0xfb | 0xf3 | 0xf2 | 0xf1 | 0xf9))
+    {
+        return Some(ResponseBodyType::Audio);
+    }
+
+    // Video
+    if starts(b"\x1a\x45\xdf\xa3")
+        || starts(b"FLV\x01")
+        || starts(b"\x00\x00\x01\xba")
+        || (starts(b"RIFF") && at(8, b"AVI "))
+    {
+        return Some(ResponseBodyType::Video);
+    }
+
+    None
+}
+
+// Tipe body teks dari strukturnya: JSON, HTML, XML, CSV, YAML, selain itu teks biasa
+pub fn from_structure(text: &str) -> ResponseBodyType {
+    let trimmed = text.trim_start_matches('\u{feff}').trim();
+
+    if (trimmed.starts_with('{') || trimmed.starts_with('['))
+        && serde_json::from_str::<serde_json::Value>(trimmed).is_ok()
+    {
+        return ResponseBodyType::Json;
+    }
+
+    if trimmed.starts_with('<') {
+        let head = head(trimmed).to_ascii_lowercase();
+        if head.starts_with("<!doctype html")
+            || ["<html", "<head", "<body"].iter().any(|tag| head.contains(tag))
+        {
+            return ResponseBodyType::Html;
+        }
+        if head.starts_with("<?xml") || sxd_document::parser::parse(trimmed).is_ok() {
+            return ResponseBodyType::Xml;
+        }
+    }
+
+    if looks_like_csv(trimmed) {
+        return ResponseBodyType::Csv;
+    }
+    if looks_like_yaml(trimmed) {
+        return ResponseBodyType::Yaml;
+    }
+
+    ResponseBodyType::Text
+}
+
+fn head(text: &str) -> &str {
+    let mut end = text.len().min(SNIFF_LIMIT);
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    &text[..end]
+}
+
+// Minimal dua baris dengan jumlah kolom (> 1) yang sama untuk salah satu delimiter
+fn looks_like_csv(text: &str) -> bool {
+    let lines: Vec<&str> = head(text).lines().filter(|line| !line.trim().is_empty()).take(20).collect();
+    // Baris terakhir mungkin terpotong oleh batas sniffing
+    let lines = if text.len() > SNIFF_LIMIT && lines.len() > 2 { &lines[..lines.len() - 1] } else { &lines[..] };
+    if lines.len() < 2 {
+        return false;
+    }
+
+    [',', '\t', ';'].iter().any(|&delimiter| {
+        let columns = count_fields(lines[0], delimiter);
+        columns > 1 && lines.iter().all(|line| count_fields(line, delimiter) == columns)
+    })
+}
+
+// Jumlah field dalam satu baris CSV, delimiter di dalam tanda kutip tidak dihitung
+fn count_fields(line: &str, delimiter: char) -> usize {
+    let mut quoted = false;
+    let mut fields = 1;
+    for c in line.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            c if c == delimiter && !quoted => fields += 1,
+            _ => {},
+        }
+    }
+    fields
+}
+
+// Diawali "---" atau setiap baris berbentuk "key: value", item "- " atau lanjutan yang menjorok,
+// lalu dipastikan bisa di-parse sebagai mapping atau sequence YAML
+fn looks_like_yaml(text: &str) -> bool {
+    let lines: Vec<&str> = text.lines()
+        .filter(|line| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+        .collect();
+
+    let structured = text.starts_with("---")
+        || (lines.len() >= 2 && lines.iter().all(|line| {
+            let line = line.trim_end();
+            line.starts_with(' ')
+                || line.starts_with("- ")
+                || line.ends_with(':')
+                || line.split_once(": ").is_some_and(|(key, _)| {
+                    key.starts_with('"') || (!key.is_empty() && !key.contains(char::is_whitespace))
+                })
+        }));
+
+    // Hanya dokumen pertama yang diperiksa, body bisa berisi beberapa dokumen
+    structured && serde_yaml::Deserializer::from_str(text)
+        .next()
+        .and_then(|document| serde_yaml::Value::deserialize(document).ok())
+        .is_some_and(|value| value.is_mapping() || value.is_sequence())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn content_type_maps_to_body_type() {
+        assert_eq!(from_content_type("application/json; charset=utf-8"), Some(ResponseBodyType::Json));
+        assert_eq!(from_content_type("application/problem+json"), Some(ResponseBodyType::Json));
+        assert_eq!(from_content_type("Application/XHTML+XML"), Some(ResponseBodyType::Html));
+        assert_eq!(from_content_type("application/atom+xml"), Some(ResponseBodyType::Xml));
+        assert_eq!(from_content_type("text/csv"), Some(ResponseBodyType::Csv));
+        assert_eq!(from_content_type("image/svg+xml"), Some(ResponseBodyType::Image));
+        assert_eq!(from_content_type("text/markdown"), Some(ResponseBodyType::Text));
+        // Tipe generik diputuskan dari isi body
+        assert_eq!(from_content_type("text/plain"), None);
+        assert_eq!(from_content_type("application/octet-stream"), None);
+        assert_eq!(from_content_type("invalid"), None);
+    }
+
+    #[test]
+    fn magic_bytes_detect_binary_formats() {
+        let cases: &[(&[u8], ResponseBodyType)] = &[
+            (b"%PDF-1.7\n", ResponseBodyType::Pdf),
+            (b"PK\x03\x04rest", ResponseBodyType::Zip),
+            (b"\x89PNG\r\n\x1a\n\0\0", ResponseBodyType::Image),
+            (b"\xff\xd8\xff\xe0", ResponseBodyType::Image),
+            (b"RIFF\0\0\0\0WEBPVP8 ", ResponseBodyType::Image),
+            (b"RIFF\0\0\0\0WAVEfmt ", ResponseBodyType::Audio),
+            (b"RIFF\0\0\0\0AVI LIST", ResponseBodyType::Video),
+            (b"\0\0\0\x20ftypisom", ResponseBodyType::Video),
+            (b"\0\0\0\x20ftypM4A ", ResponseBodyType::Audio),
+            (b"\0\0\0\x20ftypavif", ResponseBodyType::Image),
+            (b"ID3\x04\0", ResponseBodyType::Audio),
+            (b"\xff\xfb\x90\x00", ResponseBodyType::Audio),
+            (b"\x1a\x45\xdf\xa3\x01", ResponseBodyType::Video),
+        ];
+        for (body, expected) in cases {
+            assert_eq!(from_magic_bytes(body), Some(*expected), "{:?}", body);
+        }
+
+        assert_eq!(from_magic_bytes(b"{\"a\": 1}"), None);
+        // Terlalu pendek untuk brand di offset 8
+        assert_eq!(from_magic_bytes(b"RIFF\0\0"), None);
+        assert_eq!(from_magic_bytes(b""), None);
+    }
+
+    #[test]
+    fn structure_detects_json_and_markup() {
+        assert_eq!(from_structure("\u{feff}  {\"a\": [1, 2]}\n"), ResponseBodyType::Json);
+        assert_eq!(from_structure("[1, 2, 3]"), ResponseBodyType::Json);
+        // Diawali kurung kurawal tapi bukan JSON valid
+        assert_eq!(from_structure("{not json"), ResponseBodyType::Text);
+
+        assert_eq!(from_structure("<!DOCTYPE html><p>hi</p>"), ResponseBodyType::Html);
+        assert_eq!(from_structure("<div><body>x</body></div>"), ResponseBodyType::Html);
+        assert_eq!(from_structure("<?xml version=\"1.0\"?><root/>"), ResponseBodyType::Xml);
+        assert_eq!(from_structure("<root><item id=\"1\"/></root>"), ResponseBodyType::Xml);
+        // Markup yang tidak well-formed dan bukan HTML tetap teks
+        assert_eq!(from_structure("<root><item></root>"), ResponseBodyType::Text);
+    }
+
+    #[test]
+    fn structure_detects_csv_and_yaml() {
+        assert_eq!(from_structure("id,name\n1,\"Doe, Jane\"\n2,Bob\n"), ResponseBodyType::Csv);
+        assert_eq!(from_structure("id\tname\n1\tAnn\n"), ResponseBodyType::Csv);
+        // Jumlah kolom berbeda
+        assert_eq!(from_structure("id,name\n1,Ann,extra\n"), ResponseBodyType::Text);
+
+        assert_eq!(from_structure("name: fmus\nversion: 1\ntags:\n  - http\n"), ResponseBodyType::Yaml);
+        assert_eq!(from_structure("---\n- a\n- b\n"), ResponseBodyType::Yaml);
+        // Satu baris "key: value" terlalu lemah untuk disebut YAML
+        assert_eq!(from_structure("Error: not found"), ResponseBodyType::Text);
+        assert_eq!(from_structure("hello world"), ResponseBodyType::Text);
+    }
+}
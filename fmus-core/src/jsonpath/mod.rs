@@ -0,0 +1,329 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use serde_json_path::{JsonPath, NormalizedPath, PathElement};
+
+// Parse ekspresi JSONPath (RFC 9535).
+// Path lama tanpa root seperti "data.items[0].id" tetap diterima dan diubah ke bracket notation.
+pub fn parse(path: &str) -> Result<JsonPath, String> {
+    JsonPath::parse(&normalize(path))
+        .map_err(|e| format!("Invalid JSONPath '{}': {}", path.trim(), e))
+}
+
+fn normalize(path: &str) -> String {
+    let path = path.trim();
+    if path.starts_with('$') {
+        path.to_string()
+    } else if path.starts_with('[') {
+        format!("${}", path)
+    } else {
+        legacy_path(path)
+    }
+}
+
+// Query singular (RFC 9535) hanya berisi selector nama dan index, tanpa wildcard, slice,
+// filter, union atau descendant. Parser hanya menerima singular query di sisi perbandingan filter.
+pub fn is_singular(path: &str) -> bool {
+    JsonPath::parse(&format!("$[?{} == null]", normalize(path))).is_ok()
+}
+
+// Path lama dipisah dengan titik dan setiap bagian adalah nama key apa adanya,
+// kecuali "*" dan index di akhir bagian seperti "items[0]", "items[*]" atau "[0]".
+fn legacy_path(path: &str) -> String {
+    let mut normalized = String::from("$");
+    for segment in path.split('.') {
+        let mut name = segment;
+        let mut indexes = Vec::new();
+        while let Some(rest) = name.strip_suffix(']') {
+            let Some((head, index)) = rest.rsplit_once('[') else { break };
+            if index != "*" && index.parse::<i64>().is_err() {
+                break;
+            }
+            indexes.push(index);
+            name = head;
+        }
+
+        match name {
+            "" if !indexes.is_empty() => {},
+            "*" => normalized.push_str("[*]"),
+            _ => normalized.push_str(&quote_name(name)),
+        }
+        for index in indexes.iter().rev() {
+            normalized.push_str(&format!("[{}]", index));
+        }
+    }
+    normalized
+}
+
+// Selector nama dalam bracket notation, misalnya ['a\'b'], dengan escape sesuai RFC 9535
+pub(crate) fn quote_name(name: &str) -> String {
+    let mut quoted = String::from("['");
+    for c in name.chars() {
+        match c {
+            '\\' => quoted.push_str("\\\\"),
+            '\'' => quoted.push_str("\\'"),
+            '\u{8}' => quoted.push_str("\\b"),
+            '\u{c}' => quoted.push_str("\\f"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if (c as u32) < 0x20 => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push_str("']");
+    quoted
+}
+
+// Mengembalikan semua node yang cocok dengan path
+pub fn query<'a>(value: &'a Value, path: &str) -> Result<Vec<&'a Value>, String> {
+    Ok(parse(path)?.query(value).all())
+}
+
+// Mengembalikan semua node beserta normalized path-nya, misalnya $['items'][0].
+// Nama key di-escape dengan quote_name, sama seperti path yang disusun diff.
+pub fn query_located<'a>(value: &'a Value, path: &str) -> Result<Vec<(String, &'a Value)>, String> {
+    Ok(parse(path)?
+        .query_located(value)
+        .into_iter()
+        .map(|node| (location(node.location()), node.node()))
+        .collect())
+}
+
+fn location(path: &NormalizedPath) -> String {
+    let mut location = String::from("$");
+    for element in path.iter() {
+        match element {
+            PathElement::Name(name) => location.push_str(&quote_name(name)),
+            PathElement::Index(index) => location.push_str(&format!("[{}]", index)),
+        }
+    }
+    location
+}
+
+// Operator assertion untuk hasil JSONPath
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", content = "value", rename_all = "snake_case")]
+pub enum JsonPathOp {
+    Equals(Value),
+    NotEquals(Value),
+    Exists,
+    NotExists,
+    // String berisi substring, array berisi elemen, atau object berisi key
+    Contains(Value),
+    // Panjang string, array atau object
+    Length(usize),
+    Regex(String),
+    GreaterThan(f64),
+    GreaterOrEqual(f64),
+    LessThan(f64),
+    LessOrEqual(f64),
+}
+
+impl JsonPathOp {
+    // Mengevaluasi operator terhadap node hasil query. Query singular dibandingkan
+    // sebagai satu nilai, query lain selalu sebagai array walaupun hanya satu node yang cocok.
+    pub fn check(&self, path: &str, nodes: &[&Value]) -> Result<(), String> {
+        self.check_nodes(path, nodes, is_singular(path))
+    }
+
+    // Mengevaluasi operator dengan bentuk hasil yang sudah ditentukan pemanggil
+    pub fn check_nodes(&self, path: &str, nodes: &[&Value], singular: bool) -> Result<(), String> {
+        match self {
+            JsonPathOp::Exists => {
+                if nodes.is_empty() {
+                    Err(format!("Expected path '{}' to exist", path))
+                } else {
+                    Ok(())
+                }
+            },
+            JsonPathOp::NotExists => {
+                if nodes.is_empty() {
+                    Ok(())
+                } else {
+                    Err(format!("Expected path '{}' not to exist but found {}", path, collect(nodes, singular)))
+                }
+            },
+            JsonPathOp::Equals(expected) => {
+                let actual = target(path, nodes, singular)?;
+                if actual == *expected {
+                    Ok(())
+                } else {
+                    Err(format!("Expected value at path '{}' to be '{}' but got '{}'", path, expected, actual))
+                }
+            },
+            JsonPathOp::NotEquals(expected) => {
+                let actual = target(path, nodes, singular)?;
+                if actual != *expected {
+                    Ok(())
+                } else {
+                    Err(format!("Expected value at path '{}' not to be '{}'", path, expected))
+                }
+            },
+            JsonPathOp::Contains(expected) => {
+                let actual = target(path, nodes, singular)?;
+                let found = match (&actual, expected) {
+                    (Value::String(text), Value::String(needle)) => text.contains(needle.as_str()),
+                    (Value::Array(items), needle) => items.contains(needle),
+                    (Value::Object(obj), Value::String(key)) => obj.contains_key(key),
+                    _ => false,
+                };
+
+                if found {
+                    Ok(())
+                } else {
+                    Err(format!("Expected value at path '{}' to contain '{}' but got '{}'", path, expected, actual))
+                }
+            },
+            JsonPathOp::Length(expected) => {
+                let actual = target(path, nodes, singular)?;
+                let length = match &actual {
+                    Value::String(text) => text.chars().count(),
+                    Value::Array(items) => items.len(),
+                    Value::Object(obj) => obj.len(),
+                    other => return Err(format!("Value at path '{}' has no length: '{}'", path, other)),
+                };
+
+                if length == *expected {
+                    Ok(())
+                } else {
+                    Err(format!("Expected length at path '{}' to be {} but got {}", path, expected, length))
+                }
+            },
+            JsonPathOp::Regex(pattern) => {
+                let regex = Regex::new(pattern)
+                    .map_err(|e| format!("Invalid regex '{}': {}", pattern, e))?;
+                let actual = target(path, nodes, singular)?;
+                let text = match &actual {
+                    Value::String(text) => text.clone(),
+                    other => other.to_string(),
+                };
+
+                if regex.is_match(&text) {
+                    Ok(())
+                } else {
+                    Err(format!("Expected value at path '{}' to match /{}/ but got '{}'", path, pattern, text))
+                }
+            },
+            JsonPathOp::GreaterThan(limit) => compare(target(path, nodes, singular)?, path, *limit, ">", |a, b| a > b),
+            JsonPathOp::GreaterOrEqual(limit) => compare(target(path, nodes, singular)?, path, *limit, ">=", |a, b| a >= b),
+            JsonPathOp::LessThan(limit) => compare(target(path, nodes, singular)?, path, *limit, "<", |a, b| a < b),
+            JsonPathOp::LessOrEqual(limit) => compare(target(path, nodes, singular)?, path, *limit, "<=", |a, b| a <= b),
+        }
+    }
+}
+
+// Hasil singular dibandingkan langsung, hasil lain dibandingkan sebagai array
+fn target(path: &str, nodes: &[&Value], singular: bool) -> Result<Value, String> {
+    if singular && nodes.is_empty() {
+        return Err(format!("No value found at path '{}'", path));
+    }
+    Ok(collect(nodes, singular))
+}
+
+// Null jika hasil singular kosong
+pub(crate) fn collect(nodes: &[&Value], singular: bool) -> Value {
+    match nodes {
+        [] if singular => Value::Null,
+        [node] if singular => (*node).clone(),
+        _ => Value::Array(nodes.iter().map(|node| (*node).clone()).collect()),
+    }
+}
+
+// Nilai aktual hasil query untuk laporan: null jika query singular tidak menemukan nilai
+pub fn actual_value(path: &str, nodes: &[&Value]) -> Value {
+    collect(nodes, is_singular(path))
+}
+
+fn compare(actual: Value, path: &str, limit: f64, symbol: &str, cmp: fn(f64, f64) -> bool) -> Result<(), String> {
+    let number = actual.as_f64()
+        .ok_or_else(|| format!("Value at path '{}' is not a number: '{}'", path, actual))?;
+
+    if cmp(number, limit) {
+        Ok(())
+    } else {
+        Err(format!("Expected value at path '{}' to be {} {} but got {}", path, symbol, limit, number))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn legacy_paths_become_bracket_notation() {
+        assert_eq!(legacy_path("data.items[0].id"), "$['data']['items'][0]['id']");
+        assert_eq!(legacy_path("items.[1]"), "$['items'][1]");
+        assert_eq!(legacy_path("rows[0][-1].*"), "$['rows'][0][-1][*]");
+        assert_eq!(legacy_path("first name.it's\\x"), "$['first name']['it\\'s\\\\x']");
+        assert_eq!(legacy_path("tags[a]"), "$['tags[a]']");
+    }
+
+    #[test]
+    fn legacy_keys_are_not_parsed_as_expressions() {
+        let value = json!({"a-b": {"it's": 1}, "first name": "Ann", "items": [{"id": 7}]});
+        assert_eq!(query(&value, "a-b.it's").unwrap(), vec![&json!(1)]);
+        assert_eq!(query(&value, "first name").unwrap(), vec![&json!("Ann")]);
+        assert_eq!(query(&value, "items[0].id").unwrap(), vec![&json!(7)]);
+        assert_eq!(query(&value, "$.items[*].id").unwrap(), vec![&json!(7)]);
+    }
+
+    #[test]
+    fn located_nodes_use_normalized_paths() {
+        let value = json!({"items": [{"id": 1}, {"id": 2}]});
+        let located = query_located(&value, "$.items[*].id").unwrap();
+        assert_eq!(located[1], ("$['items'][1]['id']".to_string(), &json!(2)));
+
+        let value = json!({"it's": {"a\\b": 1}});
+        let located = query_located(&value, "$.*.*").unwrap();
+        assert_eq!(located[0].0, "$['it\\'s']['a\\\\b']");
+        assert_eq!(query(&value, &located[0].0).unwrap(), vec![&json!(1)]);
+    }
+
+    #[test]
+    fn operators_check_query_results() {
+        let value = json!({"name": "fmus", "tags": ["a", "b"], "count": 3});
+        let check = |path: &str, op: JsonPathOp| op.check(path, &query(&value, path).unwrap());
+
+        assert!(check("name", JsonPathOp::Equals(json!("fmus"))).is_ok());
+        assert!(check("name", JsonPathOp::NotEquals(json!("fmus"))).is_err());
+        assert!(check("tags", JsonPathOp::Contains(json!("b"))).is_ok());
+        assert!(check("tags", JsonPathOp::Length(2)).is_ok());
+        assert!(check("name", JsonPathOp::Regex("^fm".to_string())).is_ok());
+        assert!(check("count", JsonPathOp::GreaterThan(2.0)).is_ok());
+        assert!(check("count", JsonPathOp::LessOrEqual(2.0)).is_err());
+        assert!(check("missing", JsonPathOp::NotExists).is_ok());
+        assert!(check("missing", JsonPathOp::Exists).is_err());
+        assert!(check("$.tags[*]", JsonPathOp::Equals(json!(["a", "b"]))).is_ok());
+    }
+
+    #[test]
+    fn singular_queries_only_use_names_and_indexes() {
+        for path in ["$", "$.a", "$['a'][0]", "$.a[-1].b", "data.items[0].id", "[0]"] {
+            assert!(is_singular(path), "{}", path);
+        }
+        for path in ["$.*", "$.items[*].id", "$..id", "$[0:2]", "$[0,1]", "$[?@.id == 1]", "items[*]"] {
+            assert!(!is_singular(path), "{}", path);
+        }
+    }
+
+    #[test]
+    fn non_singular_queries_compare_as_arrays() {
+        let value = json!({"items": [{"id": 7}], "empty": []});
+        let check = |path: &str, op: JsonPathOp| op.check(path, &query(&value, path).unwrap());
+
+        assert!(check("$.items[*].id", JsonPathOp::Equals(json!([7]))).is_ok());
+        assert!(check("$.items[*].id", JsonPathOp::Equals(json!(7))).is_err());
+        assert!(check("$..id", JsonPathOp::Length(1)).is_ok());
+        assert!(check("$.items[?@.id == 7].id", JsonPathOp::Contains(json!(7))).is_ok());
+        assert!(check("$.items[0:1]", JsonPathOp::Equals(json!([{"id": 7}]))).is_ok());
+        assert!(check("$.empty[*]", JsonPathOp::Equals(json!([]))).is_ok());
+        assert!(check("$.items[0].id", JsonPathOp::Equals(json!(7))).is_ok());
+
+        assert_eq!(actual_value("$.items[*].id", &query(&value, "$.items[*].id").unwrap()), json!([7]));
+        assert_eq!(actual_value("$.items[0].id", &query(&value, "$.items[0].id").unwrap()), json!(7));
+        assert_eq!(actual_value("$.missing", &[]), Value::Null);
+        assert_eq!(actual_value("$.missing[*]", &[]), json!([]));
+    }
+}
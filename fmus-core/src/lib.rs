@@ -1,15 +1,39 @@
+pub mod assertions;
 pub mod auth;
+pub mod capture;
+pub mod codec;
+pub mod collections;
+pub mod diff;
+pub mod environment;
+pub mod flow;
 pub mod http;
-pub mod graphql;
-pub mod grpc;
+pub mod jsonpath;
+pub mod load;
+pub mod markup;
+pub mod monitor;
 pub mod ws;
 pub mod middleware;
-pub mod plugins;
-pub mod utils;
+pub mod report;
+pub mod runner;
+pub mod schema;
+pub mod scripting;
+pub mod snapshot;
+pub mod vault;
+
+#[cfg(test)]
+mod test_server;
 
 // Re-export penting dari masing-masing modul
 pub use http::{get, post, put, patch, delete, HttpClient, ClientConfig, RequestParams, AuthConfig, HttpResponse};
 pub use auth::Auth;
+pub use capture::{Capture, CaptureResult};
+pub use runner::{RunOptions, RunPlan, RunResult, Runner};
+pub use vault::{Secret, SecretRef, Vault};
+
+// Test memakai allocator yang sama dengan binary agar batas memori script ikut diuji
+#[cfg(test)]
+#[global_allocator]
+static ALLOCATOR: scripting::ScriptAllocator = scripting::ScriptAllocator;
 
 // Tipe-tipe utama yang diekspose
 
@@ -45,6 +69,38 @@ impl FmusClient {
     pub fn set_env(&mut self, key: &str, value: &str) {
         self.environment.insert(key.to_string(), value.to_string());
     }
+
+    /// Mengirim request dengan {{variabel}} diganti dari environment
+    pub async fn request(&self, params: RequestParams) -> Result<HttpResponse, String> {
+        let scope = scripting::ScriptScope {
+            environment: self.environment.clone(),
+            ..scripting::ScriptScope::default()
+        };
+        self.http_client.request(scope.apply_to(params)).await
+    }
+
+    /// Menyimpan nilai capture dari response ke environment, apa pun scope-nya,
+    /// sehingga bisa dipakai sebagai {{variabel}} di request berikutnya
+    pub fn capture(&mut self, response: &HttpResponse, captures: &[Capture]) -> Vec<CaptureResult> {
+        let results = capture::capture_all(response, captures);
+        for result in &results {
+            if let Some(value) = &result.value {
+                self.environment.insert(result.variable.clone(), value.clone());
+            }
+        }
+        results
+    }
+
+    /// Mengirim request lalu menyimpan nilai capture dari response-nya
+    pub async fn request_with_captures(
+        &mut self,
+        params: RequestParams,
+        captures: &[Capture],
+    ) -> Result<(HttpResponse, Vec<CaptureResult>), String> {
+        let response = self.request(params).await?;
+        let results = self.capture(&response, captures);
+        Ok((response, results))
+    }
 }
 
 // Fungsi untuk membuat client
@@ -0,0 +1,510 @@
+mod stats;
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use futures_util::stream::{FuturesUnordered, StreamExt};
+use serde::{Deserialize, Serialize};
+use tokio::task::JoinHandle;
+
+use crate::http::HttpClient;
+use crate::runner::{RunOptions, RunPlan, Runner, VariableChanges};
+use crate::scripting::{ScriptInfo, ScriptLimits, ScriptScope};
+
+use stats::Metrics;
+pub use stats::{LatencyStats, LoadSummary, RequestStats};
+
+// Interval controller menambah/mengurangi VU dan menjadwalkan iterasi mode rate
+const TICK: Duration = Duration::from_millis(10);
+// Interval progress
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(1);
+// Batas iterasi bersamaan pada mode rate jika vus tidak diisi
+const DEFAULT_MAX_VUS: usize = 100;
+
+// Satu tahap ramp: target naik/turun secara linear dari target tahap sebelumnya (tahap pertama dari 0)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoadStage {
+    pub duration_ms: u64,
+    // Jumlah VU, atau iterasi per detik jika rate dipakai
+    pub target: f64,
+}
+
+// Metrik yang bisa diberi threshold; latency dalam milidetik, error_rate 0..1, throughput request per detik
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LoadMetric {
+    P50,
+    P90,
+    P95,
+    P99,
+    Max,
+    Mean,
+    ErrorRate,
+    Throughput,
+}
+
+// Syarat lulus load test, misalnya {"metric": "p95", "max": 500}
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Threshold {
+    pub metric: LoadMetric,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min: Option<f64>,
+    // Jika diisi, threshold hanya untuk request dengan ID ini
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+}
+
+impl Threshold {
+    fn evaluate(&self, summary: &LoadSummary) -> ThresholdResult {
+        let (latency, error_rate, throughput) = match &self.request_id {
+            Some(request_id) => match summary.requests_by_step.iter().find(|stats| &stats.request_id == request_id) {
+                Some(stats) => (&stats.latency, stats.error_rate, stats.throughput),
+                None => {
+                    return ThresholdResult {
+                        threshold: self.clone(),
+                        actual: None,
+                        passed: false,
+                        message: Some(format!("Request {} is not part of the load test", request_id)),
+                    };
+                },
+            },
+            None => (&summary.latency, summary.error_rate, summary.throughput),
+        };
+
+        let actual = match self.metric {
+            LoadMetric::P50 => latency.p50,
+            LoadMetric::P90 => latency.p90,
+            LoadMetric::P95 => latency.p95,
+            LoadMetric::P99 => latency.p99,
+            LoadMetric::Max => latency.max,
+            LoadMetric::Mean => latency.mean,
+            LoadMetric::ErrorRate => error_rate,
+            LoadMetric::Throughput => throughput,
+        };
+        let passed = self.max.is_none_or(|max| actual <= max) && self.min.is_none_or(|min| actual >= min);
+
+        ThresholdResult { threshold: self.clone(), actual: Some(actual), passed, message: None }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThresholdResult {
+    pub threshold: Threshold,
+    pub actual: Option<f64>,
+    pub passed: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+// Opsi load test. Mode VU: sejumlah virtual user menjalankan skenario berulang-ulang.
+// Mode rate (rate atau stages diisi bersama rate): iterasi baru dimulai dengan laju tetap,
+// dibatasi vus iterasi yang berjalan bersamaan.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LoadOptions {
+    // Jumlah VU (mode VU, default 1) atau batas iterasi bersamaan (mode rate, default 100)
+    #[serde(default)]
+    pub vus: Option<usize>,
+    // Target iterasi skenario per detik; untuk satu request sama dengan request per detik
+    #[serde(default)]
+    pub rate: Option<f64>,
+    // Ramp-up/ramp-down; target berupa VU, atau iterasi per detik jika rate diisi
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stages: Vec<LoadStage>,
+    // Durasi tanpa stages dalam milidetik
+    #[serde(default)]
+    pub duration_ms: Option<u64>,
+    // Batas total iterasi skenario dari semua VU
+    #[serde(default)]
+    pub iterations: Option<usize>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub thresholds: Vec<Threshold>,
+    // Timeout per request dalam detik
+    #[serde(default)]
+    pub timeout: Option<u64>,
+    #[serde(default)]
+    pub script_limits: ScriptLimits,
+}
+
+impl LoadOptions {
+    // Durasi total dari stages atau duration_ms
+    fn duration(&self) -> Option<Duration> {
+        if self.stages.is_empty() {
+            self.duration_ms.map(Duration::from_millis)
+        } else {
+            Some(Duration::from_millis(self.stages.iter().map(|stage| stage.duration_ms).sum()))
+        }
+    }
+
+    // Target (VU atau rate) pada waktu tertentu sesuai stages
+    fn target_at(&self, elapsed: Duration, constant: f64) -> f64 {
+        if self.stages.is_empty() {
+            return constant;
+        }
+
+        let elapsed = elapsed.as_millis() as f64;
+        let mut from = 0.0;
+        let mut stage_start = 0.0;
+        for stage in &self.stages {
+            let length = stage.duration_ms as f64;
+            if elapsed < stage_start + length {
+                return from + (stage.target - from) * (elapsed - stage_start) / length;
+            }
+            from = stage.target;
+            stage_start += length;
+        }
+        from
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.duration().is_none() && self.iterations.is_none() {
+            return Err("Load test needs a duration, stages or an iteration limit".to_string());
+        }
+        if self.rate.is_some_and(|rate| !(rate >= 0.0 && rate.is_finite())) {
+            return Err("Load test rate must be a positive number".to_string());
+        }
+        if self.stages.iter().any(|stage| !(stage.target >= 0.0 && stage.target.is_finite())) {
+            return Err("Load test stage targets must be positive numbers".to_string());
+        }
+        if self.vus == Some(0) {
+            return Err("Load test needs at least one virtual user".to_string());
+        }
+        Ok(())
+    }
+}
+
+// Progress load test, dikirim setiap detik
+#[derive(Debug, Clone, Serialize)]
+pub struct LoadProgress {
+    pub elapsed: u128,
+    // VU atau iterasi yang sedang berjalan
+    pub active: usize,
+    pub iterations: usize,
+    pub requests: usize,
+    pub failed: usize,
+    // Request per detik sejak progress sebelumnya
+    pub throughput: f64,
+}
+
+// Hasil load test
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoadResult {
+    pub collection_id: String,
+    pub collection_name: String,
+    pub summary: LoadSummary,
+    pub thresholds: Vec<ThresholdResult>,
+    // false jika ada threshold yang tidak terpenuhi
+    pub passed: bool,
+}
+
+// Status bersama antara controller dan VU
+struct LoadState {
+    metrics: Mutex<Metrics>,
+    // Jumlah VU yang diinginkan saat ini; VU dengan nomor di atasnya berhenti setelah iterasinya selesai
+    target: AtomicUsize,
+    stopping: AtomicBool,
+    started: AtomicUsize,
+    limit: Option<usize>,
+}
+
+impl LoadState {
+    // Nomor iterasi berikutnya, None jika batas iterasi sudah tercapai
+    fn next_iteration(&self) -> Option<usize> {
+        self.started
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |started| match self.limit {
+                Some(limit) if started >= limit => None,
+                _ => Some(started + 1),
+            })
+            .ok()
+    }
+
+    fn exhausted(&self) -> bool {
+        self.limit.is_some_and(|limit| self.started.load(Ordering::SeqCst) >= limit)
+    }
+
+    fn stopping(&self) -> bool {
+        self.stopping.load(Ordering::SeqCst)
+    }
+
+    fn metrics(&self) -> MutexGuard<'_, Metrics> {
+        self.metrics.lock().unwrap()
+    }
+}
+
+// Menghentikan VU saat LoadTest::run selesai atau dibatalkan, karena task VU tidak ikut berhenti
+// ketika future-nya di-drop
+struct StopOnDrop<'a>(&'a LoadState);
+
+impl Drop for StopOnDrop<'_> {
+    fn drop(&mut self) {
+        self.0.stopping.store(true, Ordering::SeqCst);
+    }
+}
+
+// Data yang dipakai bersama oleh controller dan task setiap VU
+struct LoadContext {
+    client: Arc<HttpClient>,
+    run_options: RunOptions,
+    plan: RunPlan,
+    state: LoadState,
+}
+
+impl LoadContext {
+    fn runner(&self) -> Runner<'_> {
+        Runner::new(&self.client, self.run_options.clone())
+    }
+
+    // Jalankan semua request dalam plan secara berurutan dan catat metriknya
+    async fn iteration(&self, runner: &Runner<'_>, iteration: usize, scope: &mut ScriptScope) {
+        scope.variables.clear();
+        // Perubahan variabel dari load test tidak ditulis balik ke environment atau collection
+        let mut changes = VariableChanges::default();
+        let state = &self.state;
+
+        for (index, step) in self.plan.steps.iter().enumerate() {
+            let info = ScriptInfo {
+                request_name: step.name.clone(),
+                request_id: step.request_id.clone(),
+                iteration,
+                // Tanpa batas iterasi jumlah akhirnya belum diketahui, jadi dipakai jumlah iterasi yang sudah dimulai
+                iteration_count: state.limit.unwrap_or_else(|| state.started.load(Ordering::SeqCst)),
+            };
+            let result = runner.run_step(step, &info, scope, &mut changes).await;
+            state.metrics().record(index, &result);
+        }
+
+        state.metrics().iterations += 1;
+    }
+}
+
+// Satu VU menjalankan skenario berulang-ulang dengan scope miliknya sendiri, sehingga capture dari
+// satu request berlaku untuk request berikutnya di VU yang sama. Return nomor VU saat berhenti.
+async fn virtual_user(context: Arc<LoadContext>, id: usize, mut scope: ScriptScope) -> Option<usize> {
+    let runner = context.runner();
+    let state = &context.state;
+    while !state.stopping() && id < state.target.load(Ordering::SeqCst) {
+        let Some(iteration) = state.next_iteration() else { break };
+        context.iteration(&runner, iteration, &mut scope).await;
+    }
+    Some(id)
+}
+
+// Satu iterasi pada mode rate
+async fn rate_iteration(context: Arc<LoadContext>, iteration: usize, mut scope: ScriptScope) -> Option<usize> {
+    let runner = context.runner();
+    context.iteration(&runner, iteration, &mut scope).await;
+    None
+}
+
+// Menjalankan RunPlan (satu request atau collection) sebagai load test lewat HttpClient yang dipakai bersama.
+// Setiap VU (atau iterasi pada mode rate) berjalan sebagai task sendiri agar bisa memakai semua thread runtime.
+pub struct LoadTest {
+    client: Arc<HttpClient>,
+    run_options: RunOptions,
+    options: LoadOptions,
+}
+
+impl LoadTest {
+    pub fn new(client: Arc<HttpClient>, options: LoadOptions) -> Self {
+        let run_options = RunOptions {
+            timeout: options.timeout,
+            script_limits: options.script_limits.clone(),
+            ..RunOptions::default()
+        };
+        LoadTest { client, run_options, options }
+    }
+
+    // Jalankan load test sampai durasi atau batas iterasi tercapai. Iterasi yang sedang berjalan saat
+    // waktu habis tetap ditunggu sampai selesai. on_progress dipanggil setiap detik.
+    pub async fn run(
+        &self,
+        plan: &RunPlan,
+        environment: HashMap<String, String>,
+        mut on_progress: impl FnMut(&LoadProgress),
+    ) -> Result<LoadResult, String> {
+        self.options.validate()?;
+        if plan.steps.is_empty() {
+            return Err("Nothing to run: the load test has no requests".to_string());
+        }
+
+        let options = &self.options;
+        let rate_mode = options.rate.is_some();
+        let max_vus = options.vus.unwrap_or(if rate_mode { DEFAULT_MAX_VUS } else { 1 });
+        let duration = options.duration();
+        let context = Arc::new(LoadContext {
+            client: Arc::clone(&self.client),
+            run_options: self.run_options.clone(),
+            plan: plan.clone(),
+            state: LoadState {
+                metrics: Mutex::new(Metrics::new(&plan.steps)),
+                target: AtomicUsize::new(0),
+                stopping: AtomicBool::new(false),
+                started: AtomicUsize::new(0),
+                limit: options.iterations,
+            },
+        });
+        let state = &context.state;
+        let _stop = StopOnDrop(state);
+        let scope = ScriptScope {
+            environment,
+            collection_variables: plan.variables.clone(),
+            ..ScriptScope::default()
+        };
+
+        let start = Instant::now();
+        let mut workers: FuturesUnordered<JoinHandle<Option<usize>>> = FuturesUnordered::new();
+        let mut alive: Vec<bool> = Vec::new();
+        let mut due = 0.0;
+        let mut last_tick = start;
+        let mut last_progress = (start, 0);
+
+        loop {
+            let now = Instant::now();
+            let elapsed = now - start;
+            if duration.is_some_and(|duration| elapsed >= duration) || state.exhausted() {
+                state.stopping.store(true, Ordering::SeqCst);
+            }
+
+            if !state.stopping() {
+                if rate_mode {
+                    // Jumlah iterasi yang seharusnya sudah dimulai = integral rate terhadap waktu
+                    let rate = options.target_at(elapsed, options.rate.unwrap_or_default());
+                    due += rate * (now - last_tick).as_secs_f64();
+                    while due >= 1.0 {
+                        due -= 1.0;
+                        if workers.len() >= max_vus {
+                            state.metrics().dropped_iterations += 1;
+                            continue;
+                        }
+                        let Some(iteration) = state.next_iteration() else { break };
+                        workers.push(tokio::spawn(rate_iteration(Arc::clone(&context), iteration, scope.clone())));
+                    }
+                } else {
+                    let target = options.target_at(elapsed, max_vus as f64).round() as usize;
+                    state.target.store(target, Ordering::SeqCst);
+                    alive.resize(alive.len().max(target), false);
+                    for (id, alive) in alive.iter_mut().enumerate().take(target) {
+                        if !*alive {
+                            *alive = true;
+                            workers.push(tokio::spawn(virtual_user(Arc::clone(&context), id, scope.clone())));
+                        }
+                    }
+                }
+            }
+            last_tick = now;
+
+            if state.stopping() && workers.is_empty() {
+                break;
+            }
+
+            if now - last_progress.0 >= PROGRESS_INTERVAL {
+                let metrics = state.metrics();
+                let requests = metrics.requests();
+                on_progress(&LoadProgress {
+                    elapsed: elapsed.as_millis(),
+                    active: workers.len(),
+                    iterations: metrics.iterations,
+                    requests,
+                    failed: metrics.failed(),
+                    throughput: (requests - last_progress.1) as f64 / (now - last_progress.0).as_secs_f64(),
+                });
+                last_progress = (now, requests);
+            }
+
+            tokio::select! {
+                Some(finished) = workers.next() => match finished {
+                    Ok(Some(id)) => alive[id] = false,
+                    Ok(None) => {},
+                    // Panic di VU diteruskan seperti saat VU masih berjalan di task ini
+                    Err(e) if e.is_panic() => std::panic::resume_unwind(e.into_panic()),
+                    Err(e) => return Err(format!("Virtual user stopped unexpectedly: {}", e)),
+                },
+                _ = tokio::time::sleep(TICK) => {},
+            }
+        }
+
+        let summary = state.metrics().summary(start.elapsed());
+        let thresholds: Vec<_> = options.thresholds.iter().map(|threshold| threshold.evaluate(&summary)).collect();
+        Ok(LoadResult {
+            collection_id: plan.collection_id.clone(),
+            collection_name: plan.collection_name.clone(),
+            passed: thresholds.iter().all(|threshold| threshold.passed),
+            summary,
+            thresholds,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::tests::{plan, step};
+    use crate::test_server;
+
+    #[test]
+    fn thresholds_check_bounds_per_request() {
+        let summary = LoadSummary {
+            latency: LatencyStats { p95: 120.0, ..LatencyStats::default() },
+            error_rate: 0.02,
+            requests_by_step: vec![RequestStats {
+                request_id: "login".to_string(),
+                name: "Login".to_string(),
+                requests: 10,
+                failed: 0,
+                error_rate: 0.0,
+                throughput: 5.0,
+                latency: LatencyStats { p95: 300.0, ..LatencyStats::default() },
+            }],
+            ..LoadSummary::default()
+        };
+        let threshold = |metric, max, min, request_id: Option<&str>| Threshold { metric, max, min, request_id: request_id.map(str::to_string) };
+
+        assert!(threshold(LoadMetric::P95, Some(200.0), None, None).evaluate(&summary).passed);
+        assert!(!threshold(LoadMetric::P95, Some(200.0), None, Some("login")).evaluate(&summary).passed);
+        assert!(!threshold(LoadMetric::ErrorRate, Some(0.01), None, None).evaluate(&summary).passed);
+        assert!(threshold(LoadMetric::Throughput, None, Some(5.0), Some("login")).evaluate(&summary).passed);
+
+        let missing = threshold(LoadMetric::P95, Some(200.0), None, Some("logout")).evaluate(&summary);
+        assert!(!missing.passed);
+        assert_eq!(missing.actual, None);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn duration_run_reports_started_iterations() {
+        let server = test_server::start().await;
+        let mut step = step("ping", &server.url, None);
+        step.test.push("pm.test('count', function () { \
+            if (pm.info.iterationCount < pm.info.iteration + 1) throw new Error('count ' + pm.info.iterationCount); \
+        });".to_string());
+        let options = LoadOptions { vus: Some(2), duration_ms: Some(300), ..LoadOptions::default() };
+
+        let result = LoadTest::new(Arc::new(HttpClient::new(None)), options)
+            .run(&plan(vec![step]), HashMap::new(), |_| {})
+            .await
+            .unwrap();
+
+        let summary = result.summary;
+        assert!(summary.requests > 0);
+        assert_eq!(summary.failed, 0, "{:?}", summary.errors);
+        assert_eq!(summary.iterations, summary.requests);
+        assert_eq!(server.requests(), summary.requests);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn virtual_users_share_the_iteration_limit() {
+        let server = test_server::start().await;
+        let options = LoadOptions { vus: Some(3), iterations: Some(10), ..LoadOptions::default() };
+
+        let result = LoadTest::new(Arc::new(HttpClient::new(None)), options)
+            .run(&plan(vec![step("ping", &format!("{}/slow/20", server.url), None)]), HashMap::new(), |_| {})
+            .await
+            .unwrap();
+
+        assert_eq!(result.summary.iterations, 10);
+        assert_eq!(result.summary.failed, 0);
+        assert_eq!(server.requests(), 10);
+    }
+}
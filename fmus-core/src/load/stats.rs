@@ -0,0 +1,261 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use hdrhistogram::Histogram;
+use serde::{Deserialize, Serialize};
+
+use crate::runner::{RequestResult, RunStep};
+
+// Latency tertinggi yang dicatat histogram (1 jam dalam mikrodetik), nilai di atasnya dipotong
+const MAX_LATENCY_US: u64 = 3_600_000_000;
+
+// Statistik latency dalam milidetik
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LatencyStats {
+    pub min: f64,
+    pub mean: f64,
+    pub p50: f64,
+    pub p90: f64,
+    pub p95: f64,
+    pub p99: f64,
+    pub max: f64,
+}
+
+impl LatencyStats {
+    fn from_histogram(histogram: &Histogram<u64>) -> Self {
+        if histogram.is_empty() {
+            return LatencyStats::default();
+        }
+        let ms = |us: u64| us as f64 / 1000.0;
+        LatencyStats {
+            min: ms(histogram.min()),
+            mean: histogram.mean() / 1000.0,
+            p50: ms(histogram.value_at_quantile(0.50)),
+            p90: ms(histogram.value_at_quantile(0.90)),
+            p95: ms(histogram.value_at_quantile(0.95)),
+            p99: ms(histogram.value_at_quantile(0.99)),
+            max: ms(histogram.max()),
+        }
+    }
+}
+
+// Statistik satu request dalam skenario
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RequestStats {
+    pub request_id: String,
+    pub name: String,
+    pub requests: usize,
+    pub failed: usize,
+    pub error_rate: f64,
+    // Request per detik
+    pub throughput: f64,
+    pub latency: LatencyStats,
+}
+
+// Jumlah request dan kegagalan untuk satu request atau seluruh load test
+struct Counter {
+    latency: Histogram<u64>,
+    requests: usize,
+    failed: usize,
+}
+
+impl Counter {
+    fn new() -> Self {
+        Counter {
+            latency: Histogram::new_with_max(MAX_LATENCY_US, 3).expect("valid histogram bounds"),
+            requests: 0,
+            failed: 0,
+        }
+    }
+
+    fn record(&mut self, latency: Option<Duration>, failed: bool) {
+        self.requests += 1;
+        if failed {
+            self.failed += 1;
+        }
+        // Request yang tidak terkirim sama sekali (misalnya error pre-request script) tidak punya latency
+        if let Some(latency) = latency {
+            self.latency.saturating_record((latency.as_micros() as u64).max(1));
+        }
+    }
+
+    fn error_rate(&self) -> f64 {
+        if self.requests == 0 { 0.0 } else { self.failed as f64 / self.requests as f64 }
+    }
+}
+
+// Pengumpul metrik selama load test berjalan
+pub(crate) struct Metrics {
+    total: Counter,
+    steps: Vec<(String, String, Counter)>,
+    statuses: BTreeMap<u16, usize>,
+    errors: BTreeMap<String, usize>,
+    pub(crate) iterations: usize,
+    pub(crate) dropped_iterations: usize,
+}
+
+impl Metrics {
+    pub(crate) fn new(steps: &[RunStep]) -> Self {
+        Metrics {
+            total: Counter::new(),
+            steps: steps.iter().map(|step| (step.request_id.clone(), step.name.clone(), Counter::new())).collect(),
+            statuses: BTreeMap::new(),
+            errors: BTreeMap::new(),
+            iterations: 0,
+            dropped_iterations: 0,
+        }
+    }
+
+    // Request dihitung gagal jika tidak mendapat response, status 4xx/5xx, atau ada test/assertion yang gagal
+    pub(crate) fn record(&mut self, index: usize, result: &RequestResult) {
+        let kind = error_kind(result);
+        if let Some(status) = result.status {
+            *self.statuses.entry(status).or_default() += 1;
+        }
+        if let Some(kind) = kind {
+            *self.errors.entry(kind.to_string()).or_default() += 1;
+        }
+
+        self.total.record(result.latency, kind.is_some());
+        if let Some((_, _, counter)) = self.steps.get_mut(index) {
+            counter.record(result.latency, kind.is_some());
+        }
+    }
+
+    pub(crate) fn requests(&self) -> usize {
+        self.total.requests
+    }
+
+    pub(crate) fn failed(&self) -> usize {
+        self.total.failed
+    }
+
+    pub(crate) fn summary(&self, elapsed: Duration) -> LoadSummary {
+        let seconds = elapsed.as_secs_f64().max(f64::EPSILON);
+        LoadSummary {
+            duration: elapsed.as_millis(),
+            iterations: self.iterations,
+            dropped_iterations: self.dropped_iterations,
+            requests: self.total.requests,
+            failed: self.total.failed,
+            error_rate: self.total.error_rate(),
+            throughput: self.total.requests as f64 / seconds,
+            latency: LatencyStats::from_histogram(&self.total.latency),
+            statuses: self.statuses.clone(),
+            errors: self.errors.clone(),
+            requests_by_step: self.steps.iter()
+                .map(|(request_id, name, counter)| RequestStats {
+                    request_id: request_id.clone(),
+                    name: name.clone(),
+                    requests: counter.requests,
+                    failed: counter.failed,
+                    error_rate: counter.error_rate(),
+                    throughput: counter.requests as f64 / seconds,
+                    latency: LatencyStats::from_histogram(&counter.latency),
+                })
+                .collect(),
+        }
+    }
+}
+
+// Jenis kegagalan untuk rincian error, None jika request berhasil
+fn error_kind(result: &RequestResult) -> Option<&'static str> {
+    match result.status {
+        None => {
+            let error = result.errors.first().map(|error| error.to_lowercase()).unwrap_or_default();
+            if error.contains("timed out") || error.contains("timeout") {
+                Some("timeout")
+            } else if error.contains("connect") || error.contains("dns") {
+                Some("connection")
+            } else if result.latency.is_none() {
+                // Gagal sebelum request dikirim, misalnya pre-request script error
+                Some("script")
+            } else {
+                Some("request")
+            }
+        },
+        Some(status) if status >= 500 => Some("http_5xx"),
+        Some(status) if status >= 400 => Some("http_4xx"),
+        Some(_) if !result.passed => Some("check"),
+        Some(_) => None,
+    }
+}
+
+// Ringkasan metrik load test
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LoadSummary {
+    // Durasi dalam milidetik
+    pub duration: u128,
+    // Iterasi skenario yang selesai
+    pub iterations: usize,
+    // Iterasi yang tidak dimulai pada mode rate karena semua VU sedang sibuk
+    pub dropped_iterations: usize,
+    pub requests: usize,
+    pub failed: usize,
+    pub error_rate: f64,
+    // Request per detik
+    pub throughput: f64,
+    pub latency: LatencyStats,
+    // Jumlah response per status code
+    pub statuses: BTreeMap<u16, usize>,
+    // Jumlah kegagalan per jenis: timeout, connection, request, script, http_4xx, http_5xx, check
+    pub errors: BTreeMap<String, usize>,
+    // Statistik setiap request dalam skenario, urut sesuai plan
+    pub requests_by_step: Vec<RequestStats>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::tests::step;
+
+    fn result(request_id: &str, status: Option<u16>, latency_ms: Option<u64>, passed: bool, error: Option<&str>) -> RequestResult {
+        RequestResult {
+            request_id: request_id.to_string(),
+            status,
+            latency: latency_ms.map(Duration::from_millis),
+            passed,
+            errors: error.map(str::to_string).into_iter().collect(),
+            ..RequestResult::default()
+        }
+    }
+
+    #[test]
+    fn summary_counts_latency_statuses_and_errors() {
+        let mut metrics = Metrics::new(&[step("a", "http://a", None), step("b", "http://b", None)]);
+        for ms in 1..=100 {
+            metrics.record(0, &result("a", Some(200), Some(ms), true, None));
+        }
+        metrics.record(1, &result("b", Some(503), Some(5), false, None));
+        metrics.record(1, &result("b", Some(200), Some(5), false, None));
+        metrics.record(1, &result("b", None, Some(30_000), false, Some("operation timed out")));
+        metrics.record(1, &result("b", None, None, false, Some("ReferenceError: x is not defined")));
+
+        let summary = metrics.summary(Duration::from_secs(2));
+        assert_eq!(summary.requests, 104);
+        assert_eq!(summary.failed, 4);
+        assert_eq!(summary.throughput, 52.0);
+        assert_eq!(summary.statuses, BTreeMap::from([(200, 101), (503, 1)]));
+        assert_eq!(summary.errors, BTreeMap::from([
+            ("check".to_string(), 1),
+            ("http_5xx".to_string(), 1),
+            ("script".to_string(), 1),
+            ("timeout".to_string(), 1),
+        ]));
+
+        let a = &summary.requests_by_step[0];
+        assert_eq!((a.requests, a.failed, a.error_rate), (100, 0, 0.0));
+        assert!((a.latency.p50 - 50.0).abs() < 0.1, "{:?}", a.latency);
+        assert!((a.latency.p99 - 99.0).abs() < 0.1, "{:?}", a.latency);
+        assert_eq!(a.latency.min, 1.0);
+        assert_eq!(summary.requests_by_step[1].error_rate, 1.0);
+    }
+
+    #[test]
+    fn empty_histogram_reports_zero_latency() {
+        let metrics = Metrics::new(&[]);
+        let summary = metrics.summary(Duration::ZERO);
+        assert_eq!(summary.latency.max, 0.0);
+        assert_eq!(summary.error_rate, 0.0);
+    }
+}
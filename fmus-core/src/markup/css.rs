@@ -0,0 +1,61 @@
+use scraper::{ElementRef, Html, Selector};
+
+use super::MarkupValue;
+
+// Target hasil selector: elemen, teksnya, atau salah satu atributnya
+enum SelectTarget {
+    Node,
+    Text,
+    Attribute(String),
+}
+
+// Memisahkan pseudo-element "::text" dan "::attr(nama)" dari selector CSS
+fn parse_target(css: &str) -> Result<(&str, SelectTarget), String> {
+    let css = css.trim();
+    if let Some(selector) = css.strip_suffix("::text") {
+        return Ok((selector, SelectTarget::Text));
+    }
+
+    if let Some(start) = css.rfind("::attr(") {
+        let name = css[start + "::attr(".len()..]
+            .strip_suffix(')')
+            .ok_or_else(|| format!("Invalid CSS selector '{}': unclosed ::attr(", css))?;
+        return Ok((&css[..start], SelectTarget::Attribute(name.trim().to_string())));
+    }
+
+    Ok((css, SelectTarget::Node))
+}
+
+// Menjalankan CSS selector pada dokumen HTML.
+// Akhiri selector dengan "::text" untuk mengambil teks atau "::attr(href)" untuk mengambil atribut.
+pub fn select(html: &str, css: &str) -> Result<Vec<MarkupValue>, String> {
+    let (selector, target) = parse_target(css)?;
+    let selector = Selector::parse(selector)
+        .map_err(|e| format!("Invalid CSS selector '{}': {}", css, e))?;
+    let document = Html::parse_document(html);
+
+    Ok(document
+        .select(&selector)
+        .filter_map(|element| match &target {
+            SelectTarget::Node => Some(node_value(element)),
+            SelectTarget::Text => Some(MarkupValue::Text { value: element.text().collect() }),
+            SelectTarget::Attribute(name) => element.value().attr(name).map(|value| MarkupValue::Attribute {
+                name: name.clone(),
+                value: value.to_string(),
+            }),
+        })
+        .collect())
+}
+
+fn node_value(element: ElementRef) -> MarkupValue {
+    MarkupValue::Node {
+        name: element.value().name().to_string(),
+        text: element.text().collect(),
+        attributes: element
+            .value()
+            .attrs()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect(),
+        markup: element.html(),
+    }
+}
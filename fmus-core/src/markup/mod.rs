@@ -0,0 +1,66 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::jsonpath::{self, JsonPathOp};
+
+pub mod css;
+pub mod xpath;
+
+pub use css::select;
+pub use xpath::{xpath, xpath_with_namespaces};
+
+// Nilai hasil query XPath atau CSS selector
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum MarkupValue {
+    Text { value: String },
+    Attribute { name: String, value: String },
+    // Elemen beserta teks, atribut dan markup-nya
+    Node {
+        name: String,
+        text: String,
+        #[serde(default)]
+        attributes: BTreeMap<String, String>,
+        markup: String,
+    },
+    // Hasil fungsi XPath seperti count() atau sum()
+    Number { value: f64 },
+    // Hasil ekspresi XPath boolean seperti boolean(//error)
+    Boolean { value: bool },
+}
+
+impl MarkupValue {
+    // Representasi teks: isi teks elemen, nilai atribut, atau angka/boolean sebagai string
+    pub fn text(&self) -> String {
+        match self {
+            MarkupValue::Text { value } | MarkupValue::Attribute { value, .. } => value.clone(),
+            MarkupValue::Node { text, .. } => text.clone(),
+            MarkupValue::Number { value } => value.to_string(),
+            MarkupValue::Boolean { value } => value.to_string(),
+        }
+    }
+
+    // Nilai JSON untuk dibandingkan dengan operator assertion (JsonPathOp)
+    pub fn to_json(&self) -> Value {
+        match self {
+            MarkupValue::Number { value } => json!(value),
+            MarkupValue::Boolean { value } => json!(value),
+            other => Value::String(other.text()),
+        }
+    }
+}
+
+// Mengevaluasi operator assertion terhadap nilai hasil XPath atau CSS selector.
+// Satu nilai dibandingkan langsung, beberapa nilai dibandingkan sebagai array.
+pub fn check(expr: &str, values: &[MarkupValue], op: &JsonPathOp) -> Result<(), String> {
+    let json_values: Vec<Value> = values.iter().map(|value| value.to_json()).collect();
+    op.check_nodes(expr, &json_values.iter().collect::<Vec<_>>(), values.len() <= 1)
+}
+
+// Nilai aktual untuk laporan: null jika tidak ada, array jika lebih dari satu
+pub fn actual_value(values: &[MarkupValue]) -> Value {
+    let json_values: Vec<Value> = values.iter().map(|value| value.to_json()).collect();
+    jsonpath::collect(&json_values.iter().collect::<Vec<_>>(), values.len() <= 1)
+}
@@ -0,0 +1,148 @@
+use std::collections::{BTreeMap, HashMap};
+
+use sxd_document::dom::Document;
+use sxd_document::parser;
+use sxd_xpath::nodeset::Node;
+use sxd_xpath::{Context, Factory, Value as XPathValue};
+
+use super::MarkupValue;
+
+// Evaluasi XPath 1.0 pada dokumen XML.
+// Prefix namespace yang dideklarasikan di dokumen otomatis bisa dipakai di ekspresi.
+pub fn xpath(xml: &str, expr: &str) -> Result<Vec<MarkupValue>, String> {
+    xpath_with_namespaces(xml, expr, &HashMap::new())
+}
+
+// Evaluasi XPath dengan prefix namespace tambahan, misalnya {"soap": "http://schemas.xmlsoap.org/soap/envelope/"}.
+// Namespace default (xmlns="...") harus diberi prefix di sini karena XPath 1.0 tidak mengenal namespace default.
+pub fn xpath_with_namespaces(xml: &str, expr: &str, namespaces: &HashMap<String, String>) -> Result<Vec<MarkupValue>, String> {
+    let package = parser::parse(xml)
+        .map_err(|e| format!("Failed to parse response body as XML: {:?}", e))?;
+    let document = package.as_document();
+
+    let compiled = Factory::new()
+        .build(expr)
+        .map_err(|e| format!("Invalid XPath '{}': {}", expr, e))?
+        .ok_or_else(|| format!("Invalid XPath '{}': empty expression", expr))?;
+
+    let mut context = Context::new();
+    for (prefix, uri) in document_namespaces(&document) {
+        context.set_namespace(&prefix, &uri);
+    }
+    for (prefix, uri) in namespaces {
+        context.set_namespace(prefix, uri);
+    }
+
+    let value = compiled
+        .evaluate(&context, document.root())
+        .map_err(|e| format!("Failed to evaluate XPath '{}': {}", expr, e))?;
+
+    Ok(match value {
+        XPathValue::Nodeset(nodes) => nodes.document_order().into_iter().map(node_value).collect(),
+        XPathValue::String(value) => vec![MarkupValue::Text { value }],
+        XPathValue::Number(value) => vec![MarkupValue::Number { value }],
+        XPathValue::Boolean(value) => vec![MarkupValue::Boolean { value }],
+    })
+}
+
+// Semua prefix namespace yang dideklarasikan di dokumen, deklarasi pertama yang dipakai
+fn document_namespaces(document: &Document) -> HashMap<String, String> {
+    let mut namespaces = HashMap::new();
+    // Stack berisi anak dalam urutan terbalik agar elemen dikunjungi sesuai urutan dokumen
+    let mut pending: Vec<_> = document.root().children().into_iter().rev().filter_map(|child| child.element()).collect();
+
+    while let Some(element) = pending.pop() {
+        for namespace in element.namespaces_in_scope() {
+            namespaces
+                .entry(namespace.prefix().to_string())
+                .or_insert_with(|| namespace.uri().to_string());
+        }
+        pending.extend(element.children().into_iter().rev().filter_map(|child| child.element()));
+    }
+
+    namespaces.remove("xml");
+    namespaces
+}
+
+fn node_value(node: Node) -> MarkupValue {
+    match node {
+        Node::Element(element) => MarkupValue::Node {
+            name: node.prefixed_name().unwrap_or_else(|| element.name().local_part().to_string()),
+            text: node.string_value(),
+            attributes: element
+                .attributes()
+                .into_iter()
+                .map(|attribute| {
+                    let name = Node::Attribute(attribute).prefixed_name()
+                        .unwrap_or_else(|| attribute.name().local_part().to_string());
+                    (name, attribute.value().to_string())
+                })
+                .collect::<BTreeMap<_, _>>(),
+            markup: outer_xml(node),
+        },
+        Node::Attribute(attribute) => MarkupValue::Attribute {
+            name: node.prefixed_name().unwrap_or_else(|| attribute.name().local_part().to_string()),
+            value: attribute.value().to_string(),
+        },
+        other => MarkupValue::Text { value: other.string_value() },
+    }
+}
+
+// Serialisasi sederhana sebuah node beserta anak-anaknya.
+// Deklarasi xmlns tidak disimpan sebagai atribut oleh sxd-document sehingga tidak ikut ditulis.
+fn outer_xml(node: Node) -> String {
+    match node {
+        Node::Element(element) => {
+            let name = node.prefixed_name().unwrap_or_else(|| element.name().local_part().to_string());
+            let mut markup = format!("<{}", name);
+            for attribute in element.attributes() {
+                let attribute_name = Node::Attribute(attribute).prefixed_name()
+                    .unwrap_or_else(|| attribute.name().local_part().to_string());
+                markup.push_str(&format!(" {}=\"{}\"", attribute_name, escape(attribute.value(), true)));
+            }
+
+            let children = node.children();
+            if children.is_empty() {
+                markup.push_str("/>");
+            } else {
+                markup.push('>');
+                for child in children {
+                    markup.push_str(&outer_xml(child));
+                }
+                markup.push_str(&format!("</{}>", name));
+            }
+            markup
+        },
+        Node::Text(text) => escape(text.text(), false),
+        Node::Comment(comment) => format!("<!--{}-->", comment.text()),
+        Node::ProcessingInstruction(pi) => match pi.value() {
+            Some(value) => format!("<?{} {}?>", pi.target(), value),
+            None => format!("<?{}?>", pi.target()),
+        },
+        other => other.string_value(),
+    }
+}
+
+fn escape(text: &str, attribute: bool) -> String {
+    let escaped = text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;");
+    if attribute {
+        escaped.replace('"', "&quot;")
+    } else {
+        escaped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_namespace_declaration_in_document_order_wins() {
+        let xml = r#"<root><first><a:item xmlns:a="urn:one">1</a:item></first><a:item xmlns:a="urn:two">2</a:item></root>"#;
+        let package = parser::parse(xml).unwrap();
+        assert_eq!(document_namespaces(&package.as_document())["a"], "urn:one");
+
+        let values = xpath(xml, "string(//a:item)").unwrap();
+        assert_eq!(values, vec![MarkupValue::Text { value: "1".to_string() }]);
+    }
+}
@@ -0,0 +1,230 @@
+use chrono::{DateTime, Datelike, Duration, LocalResult, NaiveDate, TimeZone, Timelike};
+
+// Batas pencarian jadwal berikutnya, cukup untuk 29 Februari yang jatuh di hari tertentu
+const SEARCH_YEARS: i32 = 28;
+
+// Ekspresi cron lima field: menit jam tanggal bulan hari, misalnya "*/15 9-17 * * MON-FRI".
+// Mendukung *, daftar (1,15), rentang (1-5), langkah (*/5, 10-50/10), nama bulan dan hari (JAN, MON),
+// 7 sebagai Minggu, serta @hourly, @daily, @midnight, @weekly, @monthly, @yearly dan @annually.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CronExpr {
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    // Seperti cron pada umumnya: jika tanggal dan hari sama-sama dibatasi, cukup salah satu yang cocok
+    days_restricted: bool,
+    weekdays_restricted: bool,
+}
+
+const MONTHS: [&str; 12] = ["JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC"];
+const WEEKDAYS: [&str; 7] = ["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+
+impl CronExpr {
+    pub fn parse(expression: &str) -> Result<CronExpr, String> {
+        let expression = expression.trim();
+        let expanded = match expression.to_ascii_lowercase().as_str() {
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            "@monthly" => "0 0 1 * *",
+            "@weekly" => "0 0 * * 0",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@hourly" => "0 * * * *",
+            other if other.starts_with('@') => return Err(format!("Unknown cron macro: {}", expression)),
+            _ => expression,
+        };
+
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(format!("Cron expression must have 5 fields (minute hour day month weekday), got {}: '{}'", fields.len(), expression));
+        }
+
+        // Hari 7 disimpan sebagai 0 (Minggu)
+        let weekdays = parse_field(fields[4], 0, 7, &WEEKDAYS, "weekday")?;
+        let weekdays = (weekdays | (weekdays >> 7)) & 0x7f;
+
+        Ok(CronExpr {
+            minutes: parse_field(fields[0], 0, 59, &[], "minute")?,
+            hours: parse_field(fields[1], 0, 23, &[], "hour")?,
+            days: parse_field(fields[2], 1, 31, &[], "day")?,
+            months: parse_field(fields[3], 1, 12, &MONTHS, "month")?,
+            weekdays,
+            days_restricted: !fields[2].starts_with('*'),
+            weekdays_restricted: !fields[4].starts_with('*'),
+        })
+    }
+
+    // Waktu pertama setelah `after` (tidak termasuk menit `after` itu sendiri) yang cocok dengan ekspresi,
+    // dalam zona waktu `after`. Menit yang tidak ada karena pergantian DST dilewati.
+    pub fn next_after<Tz: TimeZone>(&self, after: &DateTime<Tz>) -> Option<DateTime<Tz>> {
+        let timezone = after.timezone();
+        let local = after.naive_local();
+        let start = local.date();
+        let (start_hour, start_minute) = (local.hour(), local.minute());
+
+        let mut date = start;
+        while date.year() <= start.year() + SEARCH_YEARS {
+            if self.matches_date(date) {
+                for hour in (0..24).filter(|hour| bit(self.hours, *hour)) {
+                    for minute in (0..60).filter(|minute| bit(self.minutes, *minute)) {
+                        if date == start && (hour, minute) <= (start_hour, start_minute) {
+                            continue;
+                        }
+                        let naive = date.and_hms_opt(hour, minute, 0)?;
+                        match timezone.from_local_datetime(&naive) {
+                            LocalResult::Single(time) => return Some(time),
+                            LocalResult::Ambiguous(earliest, _) => return Some(earliest),
+                            LocalResult::None => continue,
+                        }
+                    }
+                }
+            }
+            date = next_date(date, self.months)?;
+        }
+        None
+    }
+
+    fn matches_date(&self, date: NaiveDate) -> bool {
+        if !bit(self.months, date.month()) {
+            return false;
+        }
+        let day = bit(self.days, date.day());
+        let weekday = bit(self.weekdays, date.weekday().num_days_from_sunday());
+        match (self.days_restricted, self.weekdays_restricted) {
+            (true, true) => day || weekday,
+            _ => day && weekday,
+        }
+    }
+}
+
+fn bit(set: u64, value: u32) -> bool {
+    set & (1 << value) != 0
+}
+
+// Hari berikutnya, langsung ke tanggal 1 bulan berikutnya yang cocok jika bulan ini tidak termasuk
+fn next_date(date: NaiveDate, months: u64) -> Option<NaiveDate> {
+    if bit(months, date.month()) {
+        return date.checked_add_signed(Duration::days(1));
+    }
+    let (mut year, mut month) = (date.year(), date.month());
+    loop {
+        (year, month) = if month == 12 { (year + 1, 1) } else { (year, month + 1) };
+        if bit(months, month) {
+            return NaiveDate::from_ymd_opt(year, month, 1);
+        }
+    }
+}
+
+fn parse_field(field: &str, min: u32, max: u32, names: &[&str], label: &str) -> Result<u64, String> {
+    let mut set = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step.parse().ok().filter(|step| *step > 0)
+                    .ok_or_else(|| format!("Invalid step in cron {} field: '{}'", label, part))?;
+                (range, step)
+            },
+            None => (part, 1),
+        };
+
+        let (start, end) = match range {
+            "*" => (min, max),
+            _ => match range.split_once('-') {
+                Some((start, end)) => (value(start, names, min, label)?, value(end, names, min, label)?),
+                // "10/5" berarti mulai dari 10 sampai batas atas dengan langkah 5
+                None if step > 1 => (value(range, names, min, label)?, max),
+                None => {
+                    let single = value(range, names, min, label)?;
+                    (single, single)
+                },
+            },
+        };
+
+        if start < min || end > max || start > end {
+            return Err(format!("Cron {} field out of range ({}-{}): '{}'", label, min, max, part));
+        }
+        for value in (start..=end).step_by(step as usize) {
+            set |= 1 << value;
+        }
+    }
+    Ok(set)
+}
+
+// Angka atau nama (JAN, MON), nama dihitung dari nilai minimum field
+fn value(text: &str, names: &[&str], min: u32, label: &str) -> Result<u32, String> {
+    if let Ok(number) = text.parse() {
+        return Ok(number);
+    }
+    names.iter()
+        .position(|name| name.eq_ignore_ascii_case(text))
+        .map(|index| index as u32 + min)
+        .ok_or_else(|| format!("Invalid value in cron {} field: '{}'", label, text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{FixedOffset, Utc};
+
+    fn at(text: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(text).unwrap().with_timezone(&Utc)
+    }
+
+    fn next(expression: &str, after: &str) -> String {
+        CronExpr::parse(expression).unwrap().next_after(&at(after)).unwrap().to_rfc3339()
+    }
+
+    #[test]
+    fn parses_macros_names_steps_and_sunday_as_seven() {
+        assert_eq!(CronExpr::parse("@hourly").unwrap(), CronExpr::parse("0 * * * *").unwrap());
+        assert_eq!(CronExpr::parse(" @Weekly ").unwrap(), CronExpr::parse("0 0 * * SUN").unwrap());
+        assert_eq!(CronExpr::parse("0 0 * * 7").unwrap(), CronExpr::parse("0 0 * * 0").unwrap());
+        assert_eq!(CronExpr::parse("0 0 * jan-mar MON-FRI").unwrap(), CronExpr::parse("0 0 * 1-3 1-5").unwrap());
+
+        let every_quarter = CronExpr::parse("*/15 * * * *").unwrap();
+        assert_eq!(every_quarter.minutes, (1 << 0) | (1 << 15) | (1 << 30) | (1 << 45));
+        let from_ten = CronExpr::parse("10/20 * * * *").unwrap();
+        assert_eq!(from_ten.minutes, (1 << 10) | (1 << 30) | (1 << 50));
+        assert_eq!(CronExpr::parse("1,5-6 * * * *").unwrap().minutes, (1 << 1) | (1 << 5) | (1 << 6));
+    }
+
+    #[test]
+    fn rejects_invalid_expressions() {
+        assert!(CronExpr::parse("@sometimes").unwrap_err().contains("Unknown cron macro"));
+        assert!(CronExpr::parse("* * * *").unwrap_err().contains("must have 5 fields"));
+        assert!(CronExpr::parse("60 * * * *").unwrap_err().contains("out of range"));
+        assert!(CronExpr::parse("* * 0 * *").unwrap_err().contains("out of range"));
+        assert!(CronExpr::parse("* 5-1 * * *").unwrap_err().contains("out of range"));
+        assert!(CronExpr::parse("*/0 * * * *").unwrap_err().contains("Invalid step"));
+        assert!(CronExpr::parse("* * * FOO *").unwrap_err().contains("Invalid value"));
+    }
+
+    #[test]
+    fn next_after_skips_the_current_minute_and_closed_days() {
+        // 2026-10-16 adalah hari Jumat
+        assert_eq!(next("*/15 9-17 * * MON-FRI", "2026-10-16T17:45:00Z"), "2026-10-19T09:00:00+00:00");
+        assert_eq!(next("*/15 9-17 * * MON-FRI", "2026-10-16T17:44:59Z"), "2026-10-16T17:45:00+00:00");
+        assert_eq!(next("@hourly", "2026-10-16T10:00:00Z"), "2026-10-16T11:00:00+00:00");
+        assert_eq!(next("@yearly", "2026-12-31T23:59:00Z"), "2027-01-01T00:00:00+00:00");
+    }
+
+    #[test]
+    fn day_and_weekday_match_either_when_both_are_restricted() {
+        assert_eq!(next("0 0 13 * FRI", "2026-10-01T00:00:00Z"), "2026-10-02T00:00:00+00:00");
+        assert_eq!(next("0 0 13 * *", "2026-10-01T00:00:00Z"), "2026-10-13T00:00:00+00:00");
+        // Field tanggal yang diawali "*" dianggap tidak dibatasi, jadi tanggal dan hari harus cocok semua
+        assert_eq!(next("0 0 */10 * FRI", "2026-10-01T00:00:00Z"), "2026-12-11T00:00:00+00:00");
+        assert_eq!(next("0 0 * * FRI", "2026-10-02T00:00:00Z"), "2026-10-09T00:00:00+00:00");
+    }
+
+    #[test]
+    fn finds_rare_dates_and_keeps_the_timezone() {
+        assert_eq!(next("0 0 29 2 *", "2026-03-01T00:00:00Z"), "2028-02-29T00:00:00+00:00");
+        assert_eq!(CronExpr::parse("0 0 31 4 *").unwrap().next_after(&at("2026-03-01T00:00:00Z")), None);
+
+        let jakarta = FixedOffset::east_opt(7 * 3600).unwrap();
+        let after = at("2026-10-16T20:30:00Z").with_timezone(&jakarta);
+        let next = CronExpr::parse("@daily").unwrap().next_after(&after).unwrap();
+        assert_eq!(next.to_rfc3339(), "2026-10-18T00:00:00+07:00");
+    }
+}
@@ -0,0 +1,151 @@
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::MonitorRun;
+
+// Berapa lama riwayat run disimpan, None berarti tanpa batas
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Retention {
+    #[serde(default)]
+    pub max_runs: Option<usize>,
+    #[serde(default)]
+    pub max_age_days: Option<u64>,
+}
+
+impl Default for Retention {
+    fn default() -> Self {
+        Retention { max_runs: Some(500), max_age_days: Some(30) }
+    }
+}
+
+// Riwayat run monitor di disk, satu file JSON Lines per monitor
+#[derive(Debug, Clone)]
+pub struct MonitorHistory {
+    dir: PathBuf,
+}
+
+impl MonitorHistory {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    // ID monitor dijadikan nama file yang aman dengan percent-encoding, sehingga ID berbeda selalu
+    // mendapat file berbeda. Huruf besar ikut di-encode agar tidak bentrok di filesystem yang
+    // tidak membedakan huruf besar dan kecil.
+    pub fn path_for(&self, monitor_id: &str) -> PathBuf {
+        let mut file_name = String::with_capacity(monitor_id.len());
+        for byte in monitor_id.bytes() {
+            match byte {
+                b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' => file_name.push(byte as char),
+                _ => file_name.push_str(&format!("%{:02X}", byte)),
+            }
+        }
+        self.dir.join(format!("{}.history.jsonl", file_name))
+    }
+
+    pub fn append(&self, run: &MonitorRun) -> Result<(), String> {
+        fs::create_dir_all(&self.dir)
+            .map_err(|e| format!("Failed to create monitor history directory {}: {}", self.dir.display(), e))?;
+
+        let path = self.path_for(&run.monitor_id);
+        let line = serde_json::to_string(run)
+            .map_err(|e| format!("Failed to serialize monitor run: {}", e))?;
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .and_then(|mut file| writeln!(file, "{}", line))
+            .map_err(|e| format!("Failed to write monitor history {}: {}", path.display(), e))
+    }
+
+    // Semua run yang tersimpan, dari yang paling lama. Baris yang rusak (misalnya tulisan terpotong) dilewati.
+    pub fn load(&self, monitor_id: &str) -> Result<Vec<MonitorRun>, String> {
+        let path = self.path_for(monitor_id);
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let content = fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read monitor history {}: {}", path.display(), e))?;
+        Ok(content.lines()
+            .filter(|line| !line.trim().is_empty())
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect())
+    }
+
+    // Paling banyak `limit` run terbaru, urut dari yang paling baru
+    pub fn recent(&self, monitor_id: &str, limit: usize) -> Result<Vec<MonitorRun>, String> {
+        Ok(self.load(monitor_id)?.into_iter().rev().take(limit).collect())
+    }
+
+    // Buang run yang melewati batas retensi, return jumlah run yang dibuang
+    pub fn prune(&self, monitor_id: &str, retention: &Retention, now: DateTime<Utc>) -> Result<usize, String> {
+        let runs = self.load(monitor_id)?;
+        let cutoff = retention.max_age_days.map(|days| now - Duration::days(days as i64));
+        let mut kept: Vec<&MonitorRun> = runs.iter()
+            .filter(|run| cutoff.is_none_or(|cutoff| run.started_at >= cutoff))
+            .collect();
+        if let Some(max_runs) = retention.max_runs {
+            let excess = kept.len().saturating_sub(max_runs);
+            kept.drain(..excess);
+        }
+
+        let removed = runs.len() - kept.len();
+        if removed == 0 {
+            return Ok(0);
+        }
+
+        let path = self.path_for(monitor_id);
+        let mut content = String::new();
+        for run in kept {
+            let line = serde_json::to_string(run)
+                .map_err(|e| format!("Failed to serialize monitor run: {}", e))?;
+            content.push_str(&line);
+            content.push('\n');
+        }
+        // Tulis ke file sementara lalu rename agar riwayat tidak hilang jika proses berhenti di tengah
+        let temp = path.with_extension("jsonl.tmp");
+        fs::write(&temp, content)
+            .and_then(|_| fs::rename(&temp, &path))
+            .map_err(|e| format!("Failed to write monitor history {}: {}", path.display(), e))?;
+        Ok(removed)
+    }
+
+    pub fn remove(&self, monitor_id: &str) -> Result<bool, String> {
+        let path = self.path_for(monitor_id);
+        if !path.exists() {
+            return Ok(false);
+        }
+
+        fs::remove_file(&path)
+            .map(|_| true)
+            .map_err(|e| format!("Failed to remove monitor history {}: {}", path.display(), e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn path_for_keeps_distinct_ids_apart() {
+        let history = MonitorHistory::new("history");
+        let file_name = |id: &str| history.path_for(id).file_name().unwrap().to_string_lossy().into_owned();
+
+        assert_eq!(file_name("api-health_2.v1"), "api-health_2.v1.history.jsonl");
+        assert_eq!(file_name("a/b"), "a%2Fb.history.jsonl");
+        assert_eq!(file_name("Api"), "%41pi.history.jsonl");
+        assert_eq!(file_name("é"), "%C3%A9.history.jsonl");
+        let ids = ["a/b", "a_b", "a%2Fb", "A", "a", "..", "a b"];
+        let names: std::collections::HashSet<String> = ids.iter().map(|id| file_name(id)).collect();
+        assert_eq!(names.len(), ids.len());
+    }
+}
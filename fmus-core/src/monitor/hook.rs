@@ -0,0 +1,132 @@
+use std::collections::HashMap;
+use std::process::Stdio;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+use crate::http::{HttpClient, RequestParams};
+
+use super::{Monitor, MonitorEvent, MonitorRun};
+
+// Batas waktu perintah atau webhook agar hook yang macet tidak menahan monitor
+const HOOK_TIMEOUT: Duration = Duration::from_secs(30);
+
+fn default_events() -> Vec<MonitorEvent> {
+    vec![MonitorEvent::Failure, MonitorEvent::Recovery]
+}
+
+// Hook lokal yang dipanggil saat monitor mulai gagal atau pulih, misalnya
+// {"type": "command", "command": "notify-send \"$FMUS_MONITOR_NAME\" $FMUS_MONITOR_EVENT"}
+// {"type": "webhook", "url": "http://localhost:9000/alerts", "on": ["failure"]}
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MonitorHook {
+    #[serde(default = "default_events")]
+    pub on: Vec<MonitorEvent>,
+    #[serde(flatten)]
+    pub target: HookTarget,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum HookTarget {
+    // Dijalankan lewat shell; payload JSON dikirim ke stdin, ringkasannya lewat env FMUS_MONITOR_*
+    Command { command: String },
+    // Payload JSON dikirim dengan POST
+    Webhook {
+        url: String,
+        #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+        headers: HashMap<String, String>,
+    },
+}
+
+// Isi yang dikirim ke hook
+#[derive(Debug, Clone, Serialize)]
+pub struct HookPayload<'a> {
+    pub event: MonitorEvent,
+    pub monitor_id: &'a str,
+    pub monitor_name: &'a str,
+    pub collection_id: &'a str,
+    pub run: &'a MonitorRun,
+}
+
+impl MonitorHook {
+    pub(crate) async fn fire(&self, client: &HttpClient, monitor: &Monitor, event: MonitorEvent, run: &MonitorRun) -> Result<(), String> {
+        let payload = HookPayload {
+            event,
+            monitor_id: &monitor.id,
+            monitor_name: &monitor.name,
+            collection_id: &monitor.collection_id,
+            run,
+        };
+
+        match &self.target {
+            HookTarget::Command { command } => run_command(command, &payload).await,
+            HookTarget::Webhook { url, headers } => {
+                let params = RequestParams {
+                    url: url.clone(),
+                    method: "POST".to_string(),
+                    headers: Some(headers.clone()),
+                    params: None,
+                    body: Some(serde_json::to_value(&payload).map_err(|e| format!("Failed to serialize hook payload: {}", e))?),
+                    timeout: Some(HOOK_TIMEOUT.as_secs()),
+                    auth: None,
+                    body_format: None,
+                };
+                let response = client.request(params).await
+                    .map_err(|e| format!("Webhook {} failed: {}", url, e))?;
+                if response.status >= 400 {
+                    return Err(format!("Webhook {} returned status {}", url, response.status));
+                }
+                Ok(())
+            },
+        }
+    }
+}
+
+async fn run_command(command: &str, payload: &HookPayload<'_>) -> Result<(), String> {
+    let input = serde_json::to_vec(payload).map_err(|e| format!("Failed to serialize hook payload: {}", e))?;
+
+    let mut process = shell(command);
+    process
+        .env("FMUS_MONITOR_EVENT", payload.event.as_str())
+        .env("FMUS_MONITOR_ID", payload.monitor_id)
+        .env("FMUS_MONITOR_NAME", payload.monitor_name)
+        .env("FMUS_MONITOR_STATUS", payload.run.status.as_str())
+        .env("FMUS_MONITOR_ERROR", payload.run.error.as_deref().unwrap_or(""))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .kill_on_drop(true);
+
+    let mut child = process.spawn().map_err(|e| format!("Failed to start hook '{}': {}", command, e))?;
+    let run = async {
+        // Perintah yang tidak membaca stdin tidak dianggap gagal
+        if let Some(mut stdin) = child.stdin.take() {
+            let _ = stdin.write_all(&input).await;
+        }
+        child.wait().await
+    };
+
+    match tokio::time::timeout(HOOK_TIMEOUT, run).await {
+        Ok(Ok(status)) if status.success() => Ok(()),
+        Ok(Ok(status)) => Err(format!("Hook '{}' exited with {}", command, status)),
+        Ok(Err(e)) => Err(format!("Hook '{}' failed: {}", command, e)),
+        Err(_) => Err(format!("Hook '{}' timed out after {} s", command, HOOK_TIMEOUT.as_secs())),
+    }
+}
+
+#[cfg(windows)]
+fn shell(command: &str) -> Command {
+    let mut process = Command::new("cmd");
+    process.arg("/C").arg(command);
+    process
+}
+
+#[cfg(not(windows))]
+fn shell(command: &str) -> Command {
+    let mut process = Command::new("sh");
+    process.arg("-c").arg(command);
+    process
+}
@@ -0,0 +1,524 @@
+mod cron;
+mod history;
+mod hook;
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Local, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::http::HttpClient;
+use crate::runner::{RunOptions, RunPlan, RunResult, RunSummary, Runner};
+
+pub use cron::CronExpr;
+pub use history::{MonitorHistory, Retention};
+pub use hook::{HookPayload, HookTarget, MonitorHook};
+
+// Seberapa sering scheduler mengecek monitor yang jatuh tempo
+const TICK: Duration = Duration::from_secs(1);
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_alert_after() -> usize {
+    1
+}
+
+// Kapan monitor dijalankan
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Schedule {
+    // Setiap N menit sejak run sebelumnya dimulai
+    Interval { minutes: u64 },
+    // Ekspresi cron lima field dalam waktu lokal, lihat CronExpr
+    Cron { expression: String },
+}
+
+impl Schedule {
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            Schedule::Interval { minutes: 0 } => Err("Monitor interval must be at least 1 minute".to_string()),
+            Schedule::Interval { .. } => Ok(()),
+            Schedule::Cron { expression } => CronExpr::parse(expression).map(|_| ()),
+        }
+    }
+
+    // Waktu run berikutnya. Interval dihitung dari run terakhir, jadi monitor yang terlewat
+    // (misalnya aplikasi ditutup) langsung dijalankan; cron selalu menunggu jadwal berikutnya setelah `now`.
+    pub fn next_run(&self, last_run: Option<DateTime<Utc>>, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match self {
+            Schedule::Interval { minutes } => Some(match last_run {
+                Some(last_run) => last_run + chrono::Duration::minutes(*minutes as i64),
+                None => now,
+            }),
+            Schedule::Cron { expression } => CronExpr::parse(expression).ok()?
+                .next_after(&now.with_timezone(&Local))
+                .map(|time| time.with_timezone(&Utc)),
+        }
+    }
+}
+
+// Collection (atau satu folder) yang dijalankan terjadwal terhadap sebuah environment
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Monitor {
+    pub id: String,
+    pub name: String,
+    pub collection_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub folder_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub environment_id: Option<String>,
+    pub schedule: Schedule,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub options: RunOptions,
+    // Jumlah run gagal berturut-turut sebelum event failure, untuk meredam endpoint yang kadang gagal
+    #[serde(default = "default_alert_after")]
+    pub alert_after: usize,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hooks: Vec<MonitorHook>,
+    #[serde(default)]
+    pub retention: Retention,
+}
+
+impl Monitor {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.id.trim().is_empty() {
+            return Err("Monitor id is required".to_string());
+        }
+        if self.collection_id.trim().is_empty() {
+            return Err(format!("Monitor '{}' has no collection", self.name));
+        }
+        self.schedule.validate().map_err(|e| format!("Monitor '{}': {}", self.name, e))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MonitorStatus {
+    Passed,
+    // Ada request, test atau assertion yang gagal
+    Failed,
+    // Run tidak bisa dimulai, misalnya collection tidak ditemukan
+    Error,
+    // Jadwal dilewati karena run sebelumnya belum selesai
+    Skipped,
+}
+
+impl MonitorStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MonitorStatus::Passed => "passed",
+            MonitorStatus::Failed => "failed",
+            MonitorStatus::Error => "error",
+            MonitorStatus::Skipped => "skipped",
+        }
+    }
+
+    fn failing(&self) -> bool {
+        matches!(self, MonitorStatus::Failed | MonitorStatus::Error)
+    }
+}
+
+// Perubahan keadaan monitor yang memanggil hook
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MonitorEvent {
+    // Monitor mulai gagal (setelah alert_after run gagal berturut-turut)
+    Failure,
+    // Monitor lolos lagi setelah event failure
+    Recovery,
+}
+
+impl MonitorEvent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MonitorEvent::Failure => "failure",
+            MonitorEvent::Recovery => "recovery",
+        }
+    }
+}
+
+// Request yang gagal dalam sebuah run monitor
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FailedRequest {
+    pub request_id: String,
+    pub name: String,
+    pub iteration: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<u16>,
+    pub failures: Vec<String>,
+}
+
+// Satu run monitor yang disimpan di riwayat
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MonitorRun {
+    pub id: String,
+    pub monitor_id: String,
+    pub started_at: DateTime<Utc>,
+    // Durasi dalam milidetik
+    pub duration: u128,
+    pub status: MonitorStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<RunSummary>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub failures: Vec<FailedRequest>,
+    // Nama request yang lolos di run sebelumnya tetapi gagal di run ini
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub regressions: Vec<String>,
+    // Run gagal berturut-turut sampai run ini, 0 jika run ini lolos
+    #[serde(default)]
+    pub consecutive_failures: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub event: Option<MonitorEvent>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hook_errors: Vec<String>,
+}
+
+impl MonitorRun {
+    fn new(monitor_id: &str, started_at: DateTime<Utc>, status: MonitorStatus) -> Self {
+        MonitorRun {
+            id: Uuid::new_v4().to_string(),
+            monitor_id: monitor_id.to_string(),
+            started_at,
+            duration: 0,
+            status,
+            summary: None,
+            error: None,
+            failures: Vec::new(),
+            regressions: Vec::new(),
+            consecutive_failures: 0,
+            event: None,
+            hook_errors: Vec::new(),
+        }
+    }
+
+    fn from_result(monitor_id: &str, started_at: DateTime<Utc>, result: &RunResult) -> Self {
+        let status = if result.passed() { MonitorStatus::Passed } else { MonitorStatus::Failed };
+        MonitorRun {
+            summary: Some(result.summary.clone()),
+            failures: result.results()
+                .filter(|result| !result.passed && !result.skipped)
+                .map(|result| FailedRequest {
+                    request_id: result.request_id.clone(),
+                    name: result.name.clone(),
+                    iteration: result.iteration,
+                    status: result.status,
+                    failures: result.failures.clone(),
+                })
+                .collect(),
+            ..MonitorRun::new(monitor_id, started_at, status)
+        }
+    }
+
+    // Bandingkan dengan riwayat (dari yang paling lama) untuk menentukan regresi dan event.
+    // Run yang dilewati tidak dihitung.
+    fn detect(&mut self, history: &[MonitorRun], alert_after: usize) {
+        let mut previous = history.iter().rev().filter(|run| run.status != MonitorStatus::Skipped);
+        let last = previous.clone().next();
+        let streak = previous.by_ref().take_while(|run| run.status.failing()).count();
+        let alert_after = alert_after.max(1);
+
+        if let Some(last) = last.filter(|last| last.status != MonitorStatus::Error) {
+            let failed_before: HashSet<&str> = last.failures.iter().map(|failure| failure.request_id.as_str()).collect();
+            let mut seen = HashSet::new();
+            self.regressions = self.failures.iter()
+                .filter(|failure| !failed_before.contains(failure.request_id.as_str()))
+                .filter(|failure| seen.insert(failure.request_id.as_str()))
+                .map(|failure| failure.name.clone())
+                .collect();
+        }
+
+        if self.status.failing() {
+            self.consecutive_failures = streak + 1;
+            if self.consecutive_failures == alert_after {
+                self.event = Some(MonitorEvent::Failure);
+            }
+        } else if streak >= alert_after {
+            self.event = Some(MonitorEvent::Recovery);
+        }
+    }
+}
+
+// Plan dan variabel environment untuk satu run monitor
+pub struct PreparedMonitor {
+    pub plan: RunPlan,
+    pub environment: HashMap<String, String>,
+}
+
+// Monitor beserta keadaan jadwalnya
+#[derive(Debug, Clone, Serialize)]
+pub struct MonitorState {
+    pub monitor: Monitor,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_run: Option<DateTime<Utc>>,
+    pub running: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_run: Option<MonitorRun>,
+}
+
+type PrepareFn = dyn Fn(&Monitor) -> Result<PreparedMonitor, String> + Send + Sync;
+
+#[derive(Default)]
+struct Schedules {
+    monitors: HashMap<String, Monitor>,
+    next_runs: HashMap<String, DateTime<Utc>>,
+    running: HashSet<String>,
+    // Run terakhir setiap monitor. Dibaca dari riwayat saat monitor ditambahkan lalu diperbarui setiap run,
+    // sehingga jadwal tidak perlu membaca file selagi mutex dipegang
+    last_runs: HashMap<String, MonitorRun>,
+}
+
+// Menjalankan monitor sesuai jadwal. Sebuah monitor tidak pernah berjalan dua kali bersamaan:
+// jadwal yang jatuh saat run sebelumnya belum selesai dicatat sebagai skipped.
+// Perubahan variabel selama run monitor tidak ditulis balik ke environment atau collection.
+pub struct Scheduler {
+    client: HttpClient,
+    history: MonitorHistory,
+    // Dipanggil setiap run agar perubahan collection dan environment ikut terpakai
+    prepare: Box<PrepareFn>,
+    schedules: Mutex<Schedules>,
+}
+
+impl Scheduler {
+    pub fn new(
+        client: HttpClient,
+        history: MonitorHistory,
+        prepare: impl Fn(&Monitor) -> Result<PreparedMonitor, String> + Send + Sync + 'static,
+    ) -> Self {
+        Scheduler {
+            client,
+            history,
+            prepare: Box::new(prepare),
+            schedules: Mutex::new(Schedules::default()),
+        }
+    }
+
+    pub fn history(&self) -> &MonitorHistory {
+        &self.history
+    }
+
+    // Tambah atau ganti monitor; jadwalnya dihitung ulang
+    pub fn save_monitor(&self, monitor: Monitor) -> Result<(), String> {
+        monitor.validate()?;
+        let last_run = self.read_last_run(&monitor.id);
+        let mut schedules = self.schedules.lock().unwrap();
+        schedules.next_runs.remove(&monitor.id);
+        match last_run {
+            Some(run) => schedules.last_runs.insert(monitor.id.clone(), run),
+            None => schedules.last_runs.remove(&monitor.id),
+        };
+        schedules.monitors.insert(monitor.id.clone(), monitor);
+        Ok(())
+    }
+
+    pub fn set_monitors(&self, monitors: Vec<Monitor>) -> Result<(), String> {
+        for monitor in &monitors {
+            monitor.validate()?;
+        }
+        let last_runs = monitors.iter()
+            .filter_map(|monitor| self.read_last_run(&monitor.id).map(|run| (monitor.id.clone(), run)))
+            .collect();
+        let mut schedules = self.schedules.lock().unwrap();
+        schedules.next_runs.clear();
+        schedules.last_runs = last_runs;
+        schedules.monitors = monitors.into_iter().map(|monitor| (monitor.id.clone(), monitor)).collect();
+        Ok(())
+    }
+
+    // Riwayat monitor tidak ikut dihapus, lihat MonitorHistory::remove
+    pub fn remove_monitor(&self, id: &str) -> bool {
+        let mut schedules = self.schedules.lock().unwrap();
+        schedules.next_runs.remove(id);
+        schedules.last_runs.remove(id);
+        schedules.monitors.remove(id).is_some()
+    }
+
+    fn read_last_run(&self, id: &str) -> Option<MonitorRun> {
+        match self.history.recent(id, 1) {
+            Ok(mut runs) => runs.pop(),
+            Err(e) => {
+                log::warn!("{}", e);
+                None
+            },
+        }
+    }
+
+    pub fn monitors(&self) -> Vec<Monitor> {
+        let mut monitors: Vec<Monitor> = self.schedules.lock().unwrap().monitors.values().cloned().collect();
+        monitors.sort_by(|a, b| a.name.cmp(&b.name));
+        monitors
+    }
+
+    pub fn states(&self) -> Vec<MonitorState> {
+        let schedules = self.schedules.lock().unwrap();
+        let mut states: Vec<MonitorState> = schedules.monitors.values()
+            .map(|monitor| MonitorState {
+                next_run: if monitor.enabled { schedules.next_runs.get(&monitor.id).copied() } else { None },
+                running: schedules.running.contains(&monitor.id),
+                last_run: schedules.last_runs.get(&monitor.id).cloned(),
+                monitor: monitor.clone(),
+            })
+            .collect();
+        drop(schedules);
+        states.sort_by(|a, b| a.monitor.name.cmp(&b.monitor.name));
+        states
+    }
+
+    // Jalankan monitor sekarang di luar jadwal, error jika monitor sedang berjalan
+    pub async fn run_now(&self, id: &str) -> Result<MonitorRun, String> {
+        let monitor = {
+            let mut schedules = self.schedules.lock().unwrap();
+            let monitor = schedules.monitors.get(id).cloned()
+                .ok_or_else(|| format!("Monitor not found: {}", id))?;
+            if !schedules.running.insert(id.to_string()) {
+                return Err(format!("Monitor '{}' is already running", monitor.name));
+            }
+            monitor
+        };
+        Ok(self.execute(&monitor).await)
+    }
+
+    // Loop scheduler, berjalan sampai task-nya dihentikan. on_run dipanggil untuk setiap run yang
+    // tercatat, termasuk run yang dilewati.
+    pub async fn run(self: Arc<Self>, on_run: impl Fn(&MonitorRun) + Send + Sync + 'static) {
+        let on_run = Arc::new(on_run);
+        loop {
+            let now = Utc::now();
+            for (monitor, overlapping) in self.due(now) {
+                if overlapping {
+                    let mut run = MonitorRun::new(&monitor.id, now, MonitorStatus::Skipped);
+                    run.error = Some("Previous run is still in progress".to_string());
+                    if let Err(e) = self.history.append(&run) {
+                        log::warn!("{}", e);
+                    }
+                    self.schedules.lock().unwrap().last_runs.insert(run.monitor_id.clone(), run.clone());
+                    on_run(&run);
+                    continue;
+                }
+
+                let scheduler = self.clone();
+                let on_run = on_run.clone();
+                tokio::spawn(async move {
+                    let run = scheduler.execute(&monitor).await;
+                    on_run(&run);
+                });
+            }
+            tokio::time::sleep(TICK).await;
+        }
+    }
+
+    // Monitor yang jatuh tempo, ditandai running di sini agar tidak bertabrakan dengan run_now.
+    // Nilai kedua true jika run sebelumnya masih berjalan.
+    fn due(&self, now: DateTime<Utc>) -> Vec<(Monitor, bool)> {
+        let mut schedules = self.schedules.lock().unwrap();
+        let Schedules { monitors, next_runs, running, last_runs } = &mut *schedules;
+        let mut due = Vec::new();
+
+        for monitor in monitors.values().filter(|monitor| monitor.enabled) {
+            let next_run = match next_runs.get(&monitor.id) {
+                Some(next_run) => *next_run,
+                None => {
+                    let last_run = last_runs.get(&monitor.id).map(|run| run.started_at);
+                    match monitor.schedule.next_run(last_run, now) {
+                        Some(next_run) => *next_runs.entry(monitor.id.clone()).or_insert(next_run),
+                        None => continue,
+                    }
+                },
+            };
+            if next_run > now {
+                continue;
+            }
+
+            match monitor.schedule.next_run(Some(now), now) {
+                Some(next_run) => next_runs.insert(monitor.id.clone(), next_run),
+                None => next_runs.remove(&monitor.id),
+            };
+            let overlapping = !running.insert(monitor.id.clone());
+            due.push((monitor.clone(), overlapping));
+        }
+
+        due
+    }
+
+    // Jalankan monitor yang sudah ditandai running, catat hasilnya lalu panggil hook
+    async fn execute(&self, monitor: &Monitor) -> MonitorRun {
+        let started_at = Utc::now();
+        let start = Instant::now();
+
+        let mut run = match (self.prepare)(monitor) {
+            Ok(prepared) => {
+                let result = Runner::new(&self.client, monitor.options.clone())
+                    .run(&prepared.plan, prepared.environment, |_| {})
+                    .await;
+                MonitorRun::from_result(&monitor.id, started_at, &result)
+            },
+            Err(e) => MonitorRun {
+                error: Some(e),
+                ..MonitorRun::new(&monitor.id, started_at, MonitorStatus::Error)
+            },
+        };
+        run.duration = start.elapsed().as_millis();
+
+        match self.history.load(&monitor.id) {
+            Ok(history) => run.detect(&history, monitor.alert_after),
+            Err(e) => log::warn!("{}", e),
+        }
+
+        if let Some(event) = run.event {
+            for hook in monitor.hooks.iter().filter(|hook| hook.on.contains(&event)) {
+                if let Err(e) = hook.fire(&self.client, monitor, event, &run).await {
+                    run.hook_errors.push(e);
+                }
+            }
+        }
+
+        if let Err(e) = self.history.append(&run)
+            .and_then(|_| self.history.prune(&monitor.id, &monitor.retention, Utc::now()))
+        {
+            log::warn!("{}", e);
+        }
+
+        let mut schedules = self.schedules.lock().unwrap();
+        schedules.running.remove(&monitor.id);
+        schedules.last_runs.insert(monitor.id.clone(), run.clone());
+        drop(schedules);
+        run
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn schedules_use_the_cached_last_run() {
+        let dir = std::env::temp_dir().join(format!("fmus-monitor-{}", Uuid::new_v4()));
+        let history = MonitorHistory::new(dir.clone());
+        let started_at = Utc::now() - chrono::Duration::minutes(2);
+        history.append(&MonitorRun::new("api", started_at, MonitorStatus::Passed)).unwrap();
+
+        let monitor: Monitor = serde_json::from_value(serde_json::json!({
+            "id": "api",
+            "name": "API",
+            "collection_id": "collection",
+            "schedule": {"type": "interval", "minutes": 5},
+        })).unwrap();
+        let scheduler = Scheduler::new(HttpClient::new(None), history, |_| Err("not used".to_string()));
+        scheduler.set_monitors(vec![monitor]).unwrap();
+
+        // Riwayat di disk dihapus: jadwal dan status tetap memakai run terakhir yang sudah dibaca
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(scheduler.due(Utc::now()).is_empty());
+        let states = scheduler.states();
+        assert_eq!(states[0].last_run.as_ref().map(|run| run.started_at), Some(started_at));
+        assert_eq!(states[0].next_run, Some(started_at + chrono::Duration::minutes(5)));
+    }
+}
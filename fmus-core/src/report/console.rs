@@ -0,0 +1,98 @@
+use std::io::{self, Write};
+
+use super::{display_name, Reporter};
+use crate::runner::RunResult;
+
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const DIM: &str = "\x1b[2m";
+const RED: &str = "\x1b[31m";
+const GREEN: &str = "\x1b[32m";
+const YELLOW: &str = "\x1b[33m";
+
+// Ringkasan run untuk terminal: satu baris per request, kegagalan, lalu tabel total
+pub struct ConsoleReporter {
+    pub color: bool,
+}
+
+impl ConsoleReporter {
+    fn paint(&self, code: &str, text: &str) -> String {
+        if self.color {
+            format!("{}{}{}", code, text, RESET)
+        } else {
+            text.to_string()
+        }
+    }
+}
+
+impl Reporter for ConsoleReporter {
+    fn name(&self) -> &'static str {
+        "console"
+    }
+
+    fn write(&self, result: &RunResult, out: &mut dyn Write) -> io::Result<()> {
+        let summary = &result.summary;
+        writeln!(out, "{}", self.paint(BOLD, &result.collection_name))?;
+
+        for iteration in &result.iterations {
+            if summary.iterations > 1 {
+                let title = format!("Iteration {}/{}", iteration.iteration + 1, summary.iterations);
+                writeln!(out, "\n{}", self.paint(BOLD, &title))?;
+            }
+
+            for request in &iteration.results {
+                let (mark, color) = match (request.skipped, request.passed) {
+                    (true, _) => ("○", DIM),
+                    (false, true) => ("✓", GREEN),
+                    (false, false) => ("✗", RED),
+                };
+                let status = match request.status {
+                    Some(status) => status.to_string(),
+                    None if request.skipped => "skip".to_string(),
+                    None => "---".to_string(),
+                };
+                let time = request.time.map(|time| format!(" {} ms", time)).unwrap_or_default();
+
+                writeln!(
+                    out,
+                    "  {} {} {} {}{}",
+                    self.paint(color, mark),
+                    self.paint(color, &status),
+                    request.method,
+                    display_name(request, result, false),
+                    self.paint(DIM, &time),
+                )?;
+                for (index, failure) in request.failures.iter().enumerate() {
+                    let line = format!("{}. {}", index + 1, failure.lines().next().unwrap_or(""));
+                    writeln!(out, "      {}", self.paint(RED, &line))?;
+                }
+            }
+        }
+
+        let row = |label: &str, passed: usize, failed: usize| {
+            let failed_text = format!("{:>8}", failed);
+            let failed_text = if failed > 0 { self.paint(RED, &failed_text) } else { failed_text };
+            format!("  {:<12}{:>10}{}", label, passed + failed, failed_text)
+        };
+        writeln!(out)?;
+        writeln!(out, "  {:<12}{:>10}{:>8}", "", "executed", "failed")?;
+        writeln!(out, "{}", row("requests", summary.passed, summary.failed))?;
+        writeln!(out, "{}", row("tests", summary.tests_passed, summary.tests_failed))?;
+        writeln!(out, "{}", row("assertions", summary.assertions_passed, summary.assertions_failed))?;
+        writeln!(out)?;
+
+        if summary.forced_sequential {
+            writeln!(out, "{}", self.paint(YELLOW, "Parallel folders ran sequentially because the collection uses flow control"))?;
+        }
+        if summary.stopped {
+            let not_run = summary.total.saturating_sub(summary.executed + summary.skipped);
+            writeln!(out, "{}", self.paint(YELLOW, &format!("Run stopped after a failure, {} request(s) not run", not_run)))?;
+        }
+        let verdict = if result.passed() {
+            self.paint(GREEN, "All requests passed")
+        } else {
+            self.paint(RED, &format!("{} of {} request(s) failed", summary.failed, summary.executed))
+        };
+        writeln!(out, "{} in {} ms", verdict, summary.time)
+    }
+}
@@ -0,0 +1,235 @@
+use std::io::{self, Write};
+
+use super::{display_name, escape, request_body, response_body, sorted_headers, Reporter};
+use crate::runner::{RequestResult, RunResult};
+
+// Laporan HTML mandiri (tanpa file atau script eksternal) yang bisa dibagikan:
+// ringkasan, grafik waktu response, lalu detail setiap request
+pub struct HtmlReporter;
+
+const STYLE: &str = r#"
+body { font-family: -apple-system, "Segoe UI", Roboto, sans-serif; margin: 0; padding: 24px 32px; color: #1f2328; background: #f6f8fa; }
+h1 { margin: 0 0 4px; font-size: 24px; }
+h2 { margin: 32px 0 12px; font-size: 18px; }
+h4 { margin: 16px 0 6px; font-size: 13px; text-transform: uppercase; color: #59636e; }
+.verdict { font-weight: 600; }
+.pass { color: #1a7f37; }
+.fail { color: #cf222e; }
+.muted { color: #59636e; }
+.cards { display: flex; gap: 12px; flex-wrap: wrap; margin-top: 16px; }
+.card { background: #fff; border: 1px solid #d1d9e0; border-radius: 6px; padding: 12px 16px; min-width: 140px; }
+.card .value { font-size: 22px; font-weight: 600; }
+.chart { background: #fff; border: 1px solid #d1d9e0; border-radius: 6px; padding: 12px 16px; }
+.bar-row { display: flex; align-items: center; gap: 8px; font-size: 12px; margin: 3px 0; }
+.bar-label { width: 30%; overflow: hidden; text-overflow: ellipsis; white-space: nowrap; }
+.bar-track { flex: 1; }
+.bar { height: 12px; border-radius: 3px; background: #2da44e; min-width: 2px; }
+.bar.fail { background: #cf222e; }
+.bar-time { width: 70px; text-align: right; }
+details { background: #fff; border: 1px solid #d1d9e0; border-radius: 6px; margin: 8px 0; }
+summary { padding: 10px 14px; cursor: pointer; font-weight: 500; }
+.body { padding: 0 14px 14px; }
+.method { font-family: monospace; font-weight: 600; margin-right: 6px; }
+table { border-collapse: collapse; width: 100%; font-size: 13px; }
+td, th { border-bottom: 1px solid #eaeef2; padding: 4px 8px; text-align: left; vertical-align: top; }
+pre { background: #f6f8fa; border: 1px solid #eaeef2; border-radius: 4px; padding: 8px; overflow: auto; max-height: 400px; font-size: 12px; white-space: pre-wrap; word-break: break-all; }
+ul.failures { margin: 8px 0; color: #cf222e; }
+"#;
+
+impl Reporter for HtmlReporter {
+    fn name(&self) -> &'static str {
+        "html"
+    }
+
+    fn write(&self, result: &RunResult, out: &mut dyn Write) -> io::Result<()> {
+        let summary = &result.summary;
+        let title = escape(&result.collection_name);
+
+        writeln!(out, "<!DOCTYPE html>")?;
+        writeln!(out, r#"<html lang="en"><head><meta charset="utf-8">"#)?;
+        writeln!(out, "<title>{} - run report</title>", title)?;
+        writeln!(out, "<style>{}</style></head><body>", STYLE)?;
+
+        writeln!(out, "<h1>{}</h1>", title)?;
+        let verdict = if result.passed() {
+            r#"<span class="verdict pass">All requests passed</span>"#.to_string()
+        } else {
+            format!(r#"<span class="verdict fail">{} of {} request(s) failed</span>"#, summary.failed, summary.executed)
+        };
+        writeln!(out, r#"<div>{} <span class="muted">in {} ms</span></div>"#, verdict, summary.time)?;
+        if summary.forced_sequential {
+            writeln!(out, r#"<div class="muted">Parallel folders ran sequentially because the collection uses flow control</div>"#)?;
+        }
+        if summary.stopped {
+            writeln!(out, r#"<div class="fail">Run stopped after a failure, {} request(s) not run</div>"#, summary.total.saturating_sub(summary.executed + summary.skipped))?;
+        }
+
+        writeln!(out, r#"<div class="cards">"#)?;
+        let cards = [
+            ("Iterations", summary.iterations.to_string(), 0),
+            ("Requests", summary.executed.to_string(), summary.failed),
+            ("Tests", (summary.tests_passed + summary.tests_failed).to_string(), summary.tests_failed),
+            ("Assertions", (summary.assertions_passed + summary.assertions_failed).to_string(), summary.assertions_failed),
+        ];
+        for (label, value, failed) in cards {
+            let failed = if failed > 0 { format!(r#" <span class="fail">({} failed)</span>"#, failed) } else { String::new() };
+            writeln!(out, r#"<div class="card"><div class="muted">{}</div><div class="value">{}{}</div></div>"#, label, value, failed)?;
+        }
+        writeln!(out, "</div>")?;
+
+        write_timing_chart(result, out)?;
+
+        for iteration in &result.iterations {
+            if summary.iterations > 1 {
+                writeln!(out, "<h2>Iteration {}/{}</h2>", iteration.iteration + 1, summary.iterations)?;
+                if !iteration.data.is_empty() {
+                    let data = serde_json::to_string(&iteration.data).unwrap_or_default();
+                    writeln!(out, r#"<div class="muted">Data: <code>{}</code></div>"#, escape(&data))?;
+                }
+            } else {
+                writeln!(out, "<h2>Requests</h2>")?;
+            }
+
+            for request in &iteration.results {
+                write_request(request, result, out)?;
+            }
+        }
+
+        writeln!(out, "</body></html>")
+    }
+}
+
+// Grafik batang waktu response per request, relatif terhadap request paling lambat
+fn write_timing_chart(result: &RunResult, out: &mut dyn Write) -> io::Result<()> {
+    let slowest = result.results().filter_map(|request| request.time).max().unwrap_or(0).max(1);
+
+    writeln!(out, "<h2>Response times</h2>")?;
+    writeln!(out, r#"<div class="chart">"#)?;
+    for request in result.results() {
+        let time = request.time.unwrap_or(0);
+        let width = time as f64 * 100.0 / slowest as f64;
+        let class = if request.passed { "bar" } else { "bar fail" };
+        let time_text = request.time.map(|time| format!("{} ms", time)).unwrap_or_else(|| "no response".to_string());
+        writeln!(
+            out,
+            r#"<div class="bar-row"><div class="bar-label">{}</div><div class="bar-track"><div class="{}" style="width: {:.1}%"></div></div><div class="bar-time">{}</div></div>"#,
+            escape(&display_name(request, result, true)),
+            class,
+            width,
+            time_text,
+        )?;
+    }
+    writeln!(out, "</div>")
+}
+
+fn write_request(request: &RequestResult, run: &RunResult, out: &mut dyn Write) -> io::Result<()> {
+    let (class, mark) = match (request.skipped, request.passed) {
+        (true, _) => ("muted", "○"),
+        (false, true) => ("pass", "✓"),
+        (false, false) => ("fail", "✗"),
+    };
+    let status = match request.status {
+        Some(status) => status.to_string(),
+        None if request.skipped => "skipped".to_string(),
+        None => "no response".to_string(),
+    };
+    let time = request.time.map(|time| format!(", {} ms", time)).unwrap_or_default();
+
+    writeln!(out, "<details{}>", if request.passed { "" } else { " open" })?;
+    writeln!(
+        out,
+        r#"<summary><span class="{}">{}</span> <span class="method">{}</span>{} <span class="muted">{}{}</span></summary>"#,
+        class,
+        mark,
+        escape(&request.method),
+        escape(&display_name(request, run, false)),
+        status,
+        time,
+    )?;
+    writeln!(out, r#"<div class="body">"#)?;
+    writeln!(out, r#"<div class="muted">{}</div>"#, escape(&request.url))?;
+
+    if !request.failures.is_empty() {
+        writeln!(out, r#"<ul class="failures">"#)?;
+        for failure in &request.failures {
+            writeln!(out, "<li>{}</li>", escape(failure))?;
+        }
+        writeln!(out, "</ul>")?;
+    }
+
+    if !request.tests.is_empty() {
+        writeln!(out, "<h4>Tests</h4><table>")?;
+        for test in &request.tests {
+            let (class, label) = match (test.skipped, test.passed) {
+                (true, _) => ("muted", "skipped"),
+                (false, true) => ("pass", "passed"),
+                (false, false) => ("fail", "failed"),
+            };
+            let error = test.error.as_deref().map(escape).unwrap_or_default();
+            writeln!(out, r#"<tr><td>{}</td><td class="{}">{}</td><td>{}</td></tr>"#, escape(&test.name), class, label, error)?;
+        }
+        writeln!(out, "</table>")?;
+    }
+
+    if !request.assertions.results.is_empty() {
+        writeln!(out, "<h4>Assertions</h4><table>")?;
+        writeln!(out, "<tr><th>Assertion</th><th>Result</th><th>Expected</th><th>Actual</th></tr>")?;
+        for assertion in &request.assertions.results {
+            let (class, label) = if assertion.passed { ("pass", "passed") } else { ("fail", "failed") };
+            writeln!(
+                out,
+                r#"<tr><td>{}</td><td class="{}">{}</td><td><code>{}</code></td><td><code>{}</code></td></tr>"#,
+                escape(&assertion.assertion.describe()),
+                class,
+                label,
+                escape(&assertion.expected.to_string()),
+                escape(&assertion.actual.to_string()),
+            )?;
+        }
+        writeln!(out, "</table>")?;
+    }
+
+    if let Some(snapshot) = request.snapshot.as_ref().filter(|snapshot| !snapshot.diff.is_empty()) {
+        writeln!(out, "<h4>Snapshot diff</h4><pre>{}</pre>", escape(&snapshot.diff))?;
+    }
+
+    if let Some(params) = &request.request {
+        writeln!(out, "<h4>Request</h4>")?;
+        if let Some(headers) = &params.headers {
+            write_headers(sorted_headers(headers), out)?;
+        }
+        if let Some(body) = request_body(params) {
+            writeln!(out, "<pre>{}</pre>", escape(&body))?;
+        }
+    }
+
+    if let Some(response) = &request.response {
+        writeln!(out, "<h4>Response</h4>")?;
+        writeln!(out, r#"<div class="muted">{} bytes</div>"#, response.body.len())?;
+        write_headers(sorted_headers(&response.headers), out)?;
+        if !response.body.is_empty() {
+            writeln!(out, "<pre>{}</pre>", escape(&response_body(response)))?;
+        }
+    }
+
+    if !request.logs.is_empty() {
+        writeln!(out, "<h4>Console</h4><pre>")?;
+        for log in &request.logs {
+            writeln!(out, "[{}] {}", escape(&log.level), escape(&log.message))?;
+        }
+        writeln!(out, "</pre>")?;
+    }
+
+    writeln!(out, "</div></details>")
+}
+
+fn write_headers(headers: Vec<(&String, &String)>, out: &mut dyn Write) -> io::Result<()> {
+    if headers.is_empty() {
+        return Ok(());
+    }
+    writeln!(out, "<table>")?;
+    for (name, value) in headers {
+        writeln!(out, "<tr><th>{}</th><td>{}</td></tr>", escape(name), escape(value))?;
+    }
+    writeln!(out, "</table>")
+}
@@ -0,0 +1,69 @@
+use std::io::{self, Write};
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde_json::{json, Value};
+
+use super::Reporter;
+use crate::runner::{RequestResult, RunResult};
+
+// Hasil run lengkap sebagai JSON, termasuk request yang dikirim dan response-nya
+pub struct JsonReporter;
+
+impl Reporter for JsonReporter {
+    fn name(&self) -> &'static str {
+        "json"
+    }
+
+    fn write(&self, result: &RunResult, out: &mut dyn Write) -> io::Result<()> {
+        let mut report = serde_json::to_value(result)?;
+        let iterations = report["iterations"].as_array_mut().into_iter().flatten();
+        for (iteration, run_iteration) in iterations.zip(&result.iterations) {
+            let requests = iteration["results"].as_array_mut().into_iter().flatten();
+            for (value, request) in requests.zip(&run_iteration.results) {
+                value["request"] = request_detail(request);
+                value["response"] = response_detail(request);
+            }
+        }
+
+        serde_json::to_writer_pretty(&mut *out, &report)?;
+        writeln!(out)
+    }
+}
+
+// Auth sudah dibuang dan secret sudah disensor oleh write_reports
+fn request_detail(result: &RequestResult) -> Value {
+    match &result.request {
+        Some(params) => json!({
+            "method": params.method,
+            "url": params.url,
+            "headers": params.headers,
+            "params": params.params,
+            "body": params.body,
+        }),
+        None => Value::Null,
+    }
+}
+
+// Body teks ditulis apa adanya, body biner sebagai base64
+fn response_detail(result: &RequestResult) -> Value {
+    match &result.response {
+        Some(response) => {
+            let body_type = response.detect_content_type();
+            let (body, encoding) = if body_type.is_text() {
+                (response.text_lossy(), "text")
+            } else {
+                (BASE64.encode(&response.body), "base64")
+            };
+            json!({
+                "status": response.status,
+                "headers": response.headers,
+                "body_type": body_type,
+                "body": body,
+                "body_encoding": encoding,
+                "time": response.time,
+            })
+        },
+        None => Value::Null,
+    }
+}
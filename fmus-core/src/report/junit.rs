@@ -0,0 +1,129 @@
+use std::io::{self, Write};
+
+use super::{display_name, escape, Reporter};
+use crate::runner::{RequestResult, RunResult};
+
+// Laporan JUnit XML untuk tab test di CI: satu testsuite per request,
+// satu testcase per pm.test, assertion, snapshot dan capture
+pub struct JunitReporter;
+
+struct TestCase {
+    name: String,
+    failure: Option<String>,
+    error: Option<String>,
+    skipped: bool,
+}
+
+impl TestCase {
+    fn new(name: impl Into<String>) -> Self {
+        TestCase { name: name.into(), failure: None, error: None, skipped: false }
+    }
+}
+
+fn test_cases(result: &RequestResult) -> Vec<TestCase> {
+    let mut cases = Vec::new();
+
+    for error in &result.errors {
+        cases.push(TestCase { error: Some(error.clone()), ..TestCase::new(&result.name) });
+    }
+    for test in &result.tests {
+        let failure = (!test.passed && !test.skipped)
+            .then(|| test.error.clone().unwrap_or_else(|| "Test failed".to_string()));
+        cases.push(TestCase { failure, skipped: test.skipped, ..TestCase::new(&test.name) });
+    }
+    for assertion in &result.assertions.results {
+        let failure = (!assertion.passed)
+            .then(|| assertion.message.clone().unwrap_or_else(|| "Assertion failed".to_string()));
+        cases.push(TestCase { failure, ..TestCase::new(assertion.assertion.describe()) });
+    }
+    if let Some(snapshot) = &result.snapshot {
+        let failure = (!snapshot.passed()).then(|| snapshot.diff.clone());
+        cases.push(TestCase { failure, ..TestCase::new(format!("matches snapshot {}", snapshot.name)) });
+    }
+    for capture in &result.captures {
+        cases.push(TestCase { failure: capture.error.clone(), ..TestCase::new(format!("captures {}", capture.variable)) });
+    }
+
+    if result.skipped {
+        cases.push(TestCase { skipped: true, ..TestCase::new(&result.name) });
+    }
+
+    // Request tanpa test tetap muncul sebagai satu testcase
+    if cases.is_empty() {
+        let failure = (!result.passed).then(|| result.failures.join("\n"));
+        cases.push(TestCase { failure, ..TestCase::new(&result.name) });
+    }
+    cases
+}
+
+fn seconds(ms: u128) -> String {
+    format!("{:.3}", ms as f64 / 1000.0)
+}
+
+impl Reporter for JunitReporter {
+    fn name(&self) -> &'static str {
+        "junit"
+    }
+
+    fn write(&self, result: &RunResult, out: &mut dyn Write) -> io::Result<()> {
+        let suites: Vec<_> = result.results()
+            .map(|request| (request, test_cases(request)))
+            .collect();
+        let count = |filter: fn(&TestCase) -> bool| -> usize {
+            suites.iter().map(|(_, cases)| cases.iter().filter(|case| filter(case)).count()).sum()
+        };
+
+        writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+        writeln!(
+            out,
+            r#"<testsuites name="{}" tests="{}" failures="{}" errors="{}" time="{}">"#,
+            escape(&result.collection_name),
+            count(|_| true),
+            count(|case| case.failure.is_some()),
+            count(|case| case.error.is_some()),
+            seconds(result.summary.time),
+        )?;
+
+        for (index, (request, cases)) in suites.iter().enumerate() {
+            let name = escape(&display_name(request, result, true));
+            let time = seconds(request.time.unwrap_or(0));
+            writeln!(
+                out,
+                r#"  <testsuite name="{}" id="{}" tests="{}" failures="{}" errors="{}" skipped="{}" time="{}">"#,
+                name,
+                index,
+                cases.len(),
+                cases.iter().filter(|case| case.failure.is_some()).count(),
+                cases.iter().filter(|case| case.error.is_some()).count(),
+                cases.iter().filter(|case| case.skipped).count(),
+                time,
+            )?;
+
+            for case in cases {
+                write!(out, r#"    <testcase name="{}" classname="{}" time="{}""#, escape(&case.name), name, time)?;
+                if let Some(failure) = &case.failure {
+                    let message = failure.lines().next().unwrap_or("");
+                    writeln!(out, ">")?;
+                    writeln!(out, r#"      <failure type="AssertionFailure" message="{}">{}</failure>"#, escape(message), escape(failure))?;
+                    writeln!(out, "    </testcase>")?;
+                } else if let Some(error) = &case.error {
+                    writeln!(out, ">")?;
+                    writeln!(out, r#"      <error type="Error" message="{}">{}</error>"#, escape(error), escape(error))?;
+                    writeln!(out, "    </testcase>")?;
+                } else if case.skipped {
+                    writeln!(out, "><skipped/></testcase>")?;
+                } else {
+                    writeln!(out, "/>")?;
+                }
+            }
+
+            if !request.logs.is_empty() {
+                let logs: Vec<_> = request.logs.iter().map(|log| format!("[{}] {}", log.level, log.message)).collect();
+                writeln!(out, "    <system-out>{}</system-out>", escape(&logs.join("\n")))?;
+            }
+            writeln!(out, "  </testsuite>")?;
+        }
+
+        writeln!(out, "</testsuites>")
+    }
+}
@@ -0,0 +1,297 @@
+mod console;
+mod html;
+mod json;
+mod junit;
+mod redact;
+
+use std::fs::{self, File};
+use std::io::{self, BufWriter, IsTerminal, Write};
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::http::{HttpResponse, RequestParams};
+use crate::runner::{RequestResult, RunResult};
+
+pub use console::ConsoleReporter;
+pub use html::HtmlReporter;
+pub use json::JsonReporter;
+pub use junit::JunitReporter;
+
+// Batas panjang body request/response yang ditulis ke laporan
+const BODY_LIMIT: usize = 64 * 1024;
+
+// Menulis hasil run dalam satu format laporan
+pub trait Reporter {
+    // Nama format, dipakai di pesan error
+    fn name(&self) -> &'static str;
+
+    fn write(&self, result: &RunResult, out: &mut dyn Write) -> io::Result<()>;
+}
+
+// Format laporan yang tersedia
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReporterKind {
+    Console,
+    Json,
+    Junit,
+    Html,
+}
+
+// Satu reporter dalam run beserta file tujuannya
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReporterConfig {
+    #[serde(rename = "type")]
+    pub kind: ReporterKind,
+    // None berarti ditulis ke stdout
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<PathBuf>,
+}
+
+impl ReporterConfig {
+    // Warna console hanya dipakai saat menulis ke terminal dan NO_COLOR tidak diset
+    pub fn reporter(&self) -> Box<dyn Reporter> {
+        match self.kind {
+            ReporterKind::Console => {
+                let color = self.output.is_none() && io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none();
+                Box::new(ConsoleReporter { color })
+            },
+            ReporterKind::Json => Box::new(JsonReporter),
+            ReporterKind::Junit => Box::new(JunitReporter),
+            ReporterKind::Html => Box::new(HtmlReporter),
+        }
+    }
+}
+
+// Menulis semua laporan. Reporter yang gagal tidak menghentikan reporter lain.
+// Header Authorization, Cookie dan sejenisnya serta nilai dari secret selalu disensor.
+pub fn write_reports(result: &RunResult, reporters: &[ReporterConfig]) -> Result<(), String> {
+    if reporters.is_empty() {
+        return Ok(());
+    }
+    let result = redact::Redactor::new(&result.secrets).run(result);
+    let errors: Vec<String> = reporters.iter()
+        .filter_map(|config| write_report(&result, config).err())
+        .collect();
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors.join("; "))
+    }
+}
+
+fn write_report(result: &RunResult, config: &ReporterConfig) -> Result<(), String> {
+    let reporter = config.reporter();
+    let written = match &config.output {
+        Some(path) => {
+            if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+                fs::create_dir_all(dir)
+                    .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+            }
+            let file = File::create(path)
+                .map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+            let mut out = BufWriter::new(file);
+            reporter.write(result, &mut out).and_then(|_| out.flush())
+        },
+        None => {
+            let mut out = io::stdout().lock();
+            reporter.write(result, &mut out).and_then(|_| out.flush())
+        },
+    };
+
+    written.map_err(|e| format!("Failed to write {} report: {}", reporter.name(), e))
+}
+
+// Nama request untuk laporan: "Folder / Sub / Request". with_iteration menambah nomor iterasi
+// jika run punya lebih dari satu iterasi, untuk laporan yang tidak dikelompokkan per iterasi.
+fn display_name(result: &RequestResult, run: &RunResult, with_iteration: bool) -> String {
+    let mut name = result.folder_path.iter()
+        .chain(std::iter::once(&result.name))
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .join(" / ");
+    if with_iteration && run.summary.iterations > 1 {
+        name.push_str(&format!(" (iteration {})", result.iteration + 1));
+    }
+    name
+}
+
+// Body request untuk ditampilkan, string JSON ditulis apa adanya
+fn request_body(params: &RequestParams) -> Option<String> {
+    params.body.as_ref().map(|body| match body {
+        Value::String(text) => truncate(text.clone()),
+        other => truncate(serde_json::to_string_pretty(other).unwrap_or_default()),
+    })
+}
+
+// Body response yang sudah diformat, atau hex dump untuk body biner
+fn response_body(response: &HttpResponse) -> String {
+    truncate(response.pretty())
+}
+
+fn truncate(mut text: String) -> String {
+    if text.len() > BODY_LIMIT {
+        let mut end = BODY_LIMIT;
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        text.truncate(end);
+        text.push_str("\n... (truncated)");
+    }
+    text
+}
+
+// Escape teks untuk XML dan HTML; karakter kontrol yang tidak valid di XML dibuang
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            '\t' | '\n' | '\r' => escaped.push(c),
+            c if c < ' ' => {},
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// Header diurutkan agar laporan stabil
+fn sorted_headers<'a>(headers: impl IntoIterator<Item = (&'a String, &'a String)>) -> Vec<(&'a String, &'a String)> {
+    let mut headers: Vec<_> = headers.into_iter().collect();
+    headers.sort_by_key(|(name, _)| name.to_ascii_lowercase());
+    headers
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use serde_json::json;
+
+    use super::*;
+    use crate::assertions::Assertion;
+    use crate::http::HttpClient;
+    use crate::runner::tests::{plan, step};
+    use crate::runner::{RunOptions, Runner};
+    use crate::test_server;
+
+    const NAME: &str = r#"<img src=x onerror="alert('name')"> & co"#;
+    const BODY: &str = "<html><body><script>alert('body')</script></body></html>";
+
+    // Run dengan nama, body response, pesan test dan log yang berisi markup
+    async fn run_result() -> RunResult {
+        let server = test_server::start_with(|_| test_server::response(200, &[("Content-Type", "text/html")], BODY.as_bytes())).await;
+        let mut failing = step(NAME, &server.url, None);
+        failing.test = vec![r#"
+            console.log('<b>log</b>\u0001');
+            pm.test('passes', function () {});
+            pm.test('<fails>', function () { throw new Error('expected "<a>" ]]> got </testcase>'); });
+        "#.to_string()];
+        failing.assertions = vec![Assertion::StatusEquals { status: 201 }];
+        let mut passing = step("passing", &server.url, None);
+        passing.folder_path = vec!["Folder".to_string()];
+        let client = HttpClient::new(None);
+
+        let mut result = Runner::new(&client, RunOptions::default())
+            .run(&plan(vec![failing, passing]), HashMap::new(), |_| {})
+            .await;
+        result.collection_name = "Collection <&>".to_string();
+        result
+    }
+
+    fn render(reporter: &dyn Reporter, result: &RunResult) -> String {
+        let mut out = Vec::new();
+        reporter.write(result, &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn escape_handles_markup_and_control_characters() {
+        assert_eq!(escape("<a href=\"x\">'&'</a>\u{1}\t"), "&lt;a href=&quot;x&quot;&gt;&#39;&amp;&#39;&lt;/a&gt;\t");
+    }
+
+    #[tokio::test]
+    async fn junit_report_is_well_formed_and_counts_cases() {
+        let result = run_result().await;
+        let xml = render(&JunitReporter, &result);
+
+        let package = sxd_document::parser::parse(&xml).expect("JUnit report must be valid XML");
+        let document = package.as_document();
+        let query = |xpath: &str| sxd_xpath::evaluate_xpath(&document, xpath).unwrap().string();
+
+        assert_eq!(query("/testsuites/@name"), "Collection <&>");
+        assert_eq!(query("count(/testsuites/testsuite)"), "2");
+        // pm.test x2 dan satu assertion, lalu satu testcase untuk request tanpa test
+        assert_eq!(query("/testsuites/@tests"), "4");
+        assert_eq!(query("/testsuites/@failures"), "2");
+        assert_eq!(query("/testsuites/testsuite[1]/@name"), NAME);
+        assert_eq!(query("/testsuites/testsuite[1]/@failures"), "2");
+        assert_eq!(query("/testsuites/testsuite[1]/testcase[2]/@name"), "<fails>");
+        assert_eq!(query("/testsuites/testsuite[1]/testcase[2]/failure"), "expected \"<a>\" ]]> got </testcase>");
+        assert_eq!(query("/testsuites/testsuite[1]/system-out"), "[log] <b>log</b>");
+        assert_eq!(query("/testsuites/testsuite[2]/@name"), "Folder / passing");
+        assert_eq!(query("count(/testsuites/testsuite[2]/testcase/failure)"), "0");
+    }
+
+    #[tokio::test]
+    async fn json_report_includes_summary_and_exchange() {
+        let result = run_result().await;
+        let report: Value = serde_json::from_str(&render(&JsonReporter, &result)).unwrap();
+
+        assert_eq!(report["collection_name"], json!("Collection <&>"));
+        assert_eq!(report["summary"]["executed"], json!(2));
+        assert_eq!(report["summary"]["failed"], json!(1));
+        let request = &report["iterations"][0]["results"][0];
+        assert_eq!(request["name"], json!(NAME));
+        assert_eq!(request["passed"], json!(false));
+        assert_eq!(request["tests"][1]["name"], json!("<fails>"));
+        assert_eq!(request["assertions"]["failed"], json!(1));
+        assert_eq!(request["request"]["method"], json!("GET"));
+        assert_eq!(request["response"]["status"], json!(200));
+        assert_eq!(request["response"]["body_type"], json!("Html"));
+        assert_eq!(request["response"]["body"], json!(BODY));
+        assert_eq!(request["response"]["body_encoding"], json!("text"));
+    }
+
+    #[tokio::test]
+    async fn html_report_escapes_names_and_bodies() {
+        let result = run_result().await;
+        let html = render(&HtmlReporter, &result);
+
+        // Laporan sendiri tidak memakai script maupun gambar, jadi tag ini hanya bisa berasal dari data run
+        assert!(!html.contains("<script"), "{}", html);
+        assert!(!html.contains("<img"), "{}", html);
+        assert!(!html.contains("<b>log"), "{}", html);
+        // Body response diformat dulu, jadi isi script berada di baris sendiri
+        assert!(html.contains("&lt;script&gt;\nalert(&#39;body&#39;)\n    &lt;/script&gt;"), "{}", html);
+        assert!(html.contains("&lt;img src=x onerror=&quot;alert(&#39;name&#39;)&quot;&gt; &amp; co"));
+        assert!(html.contains("<title>Collection &lt;&amp;&gt; - run report</title>"));
+        assert!(html.contains("1 of 2 request(s) failed"));
+    }
+
+    #[tokio::test]
+    async fn write_reports_redacts_secrets_in_files() {
+        let mut result = run_result().await;
+        result.secrets = vec!["alert('body')".to_string()];
+        let dir = std::env::temp_dir().join(format!("fmus-report-{}", uuid::Uuid::new_v4()));
+        let reporters = [
+            ReporterConfig { kind: ReporterKind::Json, output: Some(dir.join("nested/report.json")) },
+            ReporterConfig { kind: ReporterKind::Html, output: Some(dir.join("report.html")) },
+        ];
+
+        write_reports(&result, &reporters).unwrap();
+        let json = fs::read_to_string(dir.join("nested/report.json")).unwrap();
+        let html = fs::read_to_string(dir.join("report.html")).unwrap();
+        assert!(!json.contains("alert('body')") && !html.contains("alert(&#39;body&#39;)"));
+        assert!(json.contains("<script>[REDACTED]</script>"), "{}", json);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
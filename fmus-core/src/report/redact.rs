@@ -0,0 +1,155 @@
+use std::collections::HashMap;
+
+use serde_json::{Map, Value};
+
+use crate::http::{HttpResponse, RequestParams};
+use crate::runner::{RequestResult, RunResult};
+
+// Header yang nilainya selalu disensor, apa pun isinya
+const SENSITIVE_HEADERS: &[&str] = &["authorization", "proxy-authorization", "cookie", "set-cookie"];
+
+const REDACTED: &str = "[REDACTED]";
+
+// Menyensor header sensitif dan semua nilai secret dari hasil run sebelum ditulis ke laporan
+pub(super) struct Redactor {
+    // Nilai secret, yang paling panjang lebih dulu agar secret yang memuat secret lain tersensor utuh
+    secrets: Vec<String>,
+}
+
+impl Redactor {
+    pub(super) fn new(secrets: &[String]) -> Self {
+        let mut secrets: Vec<String> = secrets.iter().filter(|secret| !secret.is_empty()).cloned().collect();
+        secrets.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+        secrets.dedup();
+        Redactor { secrets }
+    }
+
+    pub(super) fn run(&self, result: &RunResult) -> RunResult {
+        let mut result = result.clone();
+        for iteration in &mut result.iterations {
+            for value in iteration.data.values_mut() {
+                *value = self.value(value);
+            }
+            for request in &mut iteration.results {
+                self.request_result(request);
+            }
+        }
+        for value in result.changes.environment.values_mut().chain(result.changes.collection.values_mut()).flatten() {
+            *value = self.text(value);
+        }
+        result
+    }
+
+    fn request_result(&self, result: &mut RequestResult) {
+        result.url = self.text(&result.url);
+        for test in &mut result.tests {
+            test.error = test.error.as_deref().map(|error| self.text(error));
+        }
+        for assertion in &mut result.assertions.results {
+            assertion.expected = self.value(&assertion.expected);
+            assertion.actual = self.value(&assertion.actual);
+            assertion.message = assertion.message.as_deref().map(|message| self.text(message));
+        }
+        if let Some(snapshot) = &mut result.snapshot {
+            snapshot.diff = self.text(&snapshot.diff);
+            for difference in &mut snapshot.differences {
+                difference.expected = difference.expected.as_ref().map(|value| self.value(value));
+                difference.actual = difference.actual.as_ref().map(|value| self.value(value));
+                difference.message = difference.message.as_deref().map(|message| self.text(message));
+            }
+        }
+        for capture in &mut result.captures {
+            capture.value = capture.value.as_deref().map(|value| self.text(value));
+            capture.error = capture.error.as_deref().map(|error| self.text(error));
+        }
+        for log in &mut result.logs {
+            log.message = self.text(&log.message);
+        }
+        for message in result.errors.iter_mut().chain(&mut result.failures) {
+            *message = self.text(message);
+        }
+        if let Some(request) = &mut result.request {
+            self.request(request);
+        }
+        if let Some(response) = &mut result.response {
+            self.response(response);
+        }
+    }
+
+    // Auth tidak pernah ditulis ke laporan, jadi ikut dibuang
+    fn request(&self, request: &mut RequestParams) {
+        request.url = self.text(&request.url);
+        if let Some(headers) = &mut request.headers {
+            self.headers(headers);
+        }
+        for value in request.params.iter_mut().flat_map(|params| params.values_mut()) {
+            *value = self.text(value);
+        }
+        request.body = request.body.as_ref().map(|body| self.value(body));
+        request.auth = None;
+    }
+
+    // Body biner ditulis sebagai hex atau base64, jadi hanya body UTF-8 yang disensor
+    fn response(&self, response: &mut HttpResponse) {
+        self.headers(&mut response.headers);
+        if let Ok(text) = std::str::from_utf8(&response.body) {
+            response.body = self.text(text).into_bytes();
+        }
+    }
+
+    fn headers(&self, headers: &mut HashMap<String, String>) {
+        for (name, value) in headers.iter_mut() {
+            *value = if SENSITIVE_HEADERS.contains(&name.to_ascii_lowercase().as_str()) {
+                REDACTED.to_string()
+            } else {
+                self.text(value)
+            };
+        }
+    }
+
+    fn text(&self, text: &str) -> String {
+        let mut text = text.to_string();
+        for secret in &self.secrets {
+            if text.contains(secret.as_str()) {
+                text = text.replace(secret.as_str(), REDACTED);
+            }
+        }
+        text
+    }
+
+    fn value(&self, value: &Value) -> Value {
+        match value {
+            Value::String(text) => Value::String(self.text(text)),
+            Value::Array(items) => Value::Array(items.iter().map(|item| self.value(item)).collect()),
+            Value::Object(map) => Value::Object(map.iter()
+                .map(|(key, value)| (self.text(key), self.value(value)))
+                .collect::<Map<String, Value>>()),
+            other => other.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn redacts_secret_values_and_sensitive_headers() {
+        let redactor = Redactor::new(&["s3cret".to_string(), "s3cret-long".to_string(), String::new()]);
+        assert_eq!(redactor.text("token=s3cret-long&b=s3cret"), "token=[REDACTED]&b=[REDACTED]");
+        assert_eq!(redactor.value(&json!({"s3cret": ["x s3cret", 1]})), json!({"[REDACTED]": ["x [REDACTED]", 1]}));
+
+        let mut headers = HashMap::from([
+            ("Set-Cookie".to_string(), "id=1".to_string()),
+            ("Proxy-Authorization".to_string(), "Basic abc".to_string()),
+            ("X-Trace".to_string(), "s3cret".to_string()),
+            ("Accept".to_string(), "*/*".to_string()),
+        ]);
+        redactor.headers(&mut headers);
+        assert_eq!(headers["Set-Cookie"], REDACTED);
+        assert_eq!(headers["Proxy-Authorization"], REDACTED);
+        assert_eq!(headers["X-Trace"], REDACTED);
+        assert_eq!(headers["Accept"], "*/*");
+    }
+}
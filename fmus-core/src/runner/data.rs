@@ -0,0 +1,153 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use serde_json::Value;
+
+// Satu baris data file, tersedia sebagai pm.iterationData dan {{variabel}}
+pub type IterationData = HashMap<String, Value>;
+
+// Membaca data file: CSV dengan baris header (.csv), atau JSON array berisi object
+pub fn load_data_file(path: impl AsRef<Path>) -> Result<Vec<IterationData>, String> {
+    let path = path.as_ref();
+    let text = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read data file {}: {}", path.display(), e))?;
+
+    let is_csv = path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("csv"));
+    let rows = if is_csv { parse_csv(&text) } else { parse_json(&text) };
+    rows.map_err(|e| format!("Invalid data file {}: {}", path.display(), e))
+}
+
+// CSV dengan baris header. Semua nilai dibaca sebagai string, sama seperti Postman.
+pub fn parse_csv(text: &str) -> Result<Vec<IterationData>, String> {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::Headers)
+        .from_reader(text.as_bytes());
+
+    let headers = reader.headers()
+        .map_err(|e| format!("Invalid CSV header: {}", e))?
+        .clone();
+    if headers.iter().any(|header| header.is_empty()) {
+        return Err("CSV header contains an empty column name".to_string());
+    }
+
+    reader.records()
+        .map(|record| {
+            let record = record.map_err(|e| format!("Invalid CSV row: {}", e))?;
+            Ok(headers.iter()
+                .zip(record.iter())
+                .map(|(header, value)| (header.to_string(), Value::String(value.to_string())))
+                .collect())
+        })
+        .collect()
+}
+
+// JSON array berisi object, satu object per iterasi
+pub fn parse_json(text: &str) -> Result<Vec<IterationData>, String> {
+    let value: Value = serde_json::from_str(text).map_err(|e| format!("Invalid JSON: {}", e))?;
+    let Value::Array(rows) = value else {
+        return Err("Expected a JSON array of objects".to_string());
+    };
+
+    rows.into_iter()
+        .enumerate()
+        .map(|(index, row)| match row {
+            Value::Object(fields) => Ok(fields.into_iter().collect()),
+            other => Err(format!("Expected an object at index {} but got {}", index, other)),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::http::HttpClient;
+    use crate::runner::tests::{plan, step};
+    use crate::runner::{RunOptions, Runner};
+    use crate::test_server;
+
+    #[test]
+    fn csv_rows_are_keyed_by_header() {
+        let rows = parse_csv("\u{feff} id , name\n1,Ann\n2,\"Doe, Jane\"\n").unwrap();
+        assert_eq!(rows.len(), 2);
+        // Header di-trim, nilai tetap apa adanya dan selalu string
+        assert_eq!(rows[0], HashMap::from([("id".to_string(), json!("1")), ("name".to_string(), json!("Ann"))]));
+        assert_eq!(rows[1]["name"], json!("Doe, Jane"));
+
+        assert_eq!(parse_csv("id,,name\n1,2,3\n").unwrap_err(), "CSV header contains an empty column name");
+    }
+
+    #[test]
+    fn json_rows_keep_their_types() {
+        let rows = parse_json(r#"[{"id": 1, "active": true, "tags": ["a"], "note": null}, {}]"#).unwrap();
+        assert_eq!(rows[0]["id"], json!(1));
+        assert_eq!(rows[0]["active"], json!(true));
+        assert_eq!(rows[0]["tags"], json!(["a"]));
+        assert_eq!(rows[0]["note"], Value::Null);
+        assert!(rows[1].is_empty());
+
+        assert_eq!(parse_json(r#"{"id": 1}"#).unwrap_err(), "Expected a JSON array of objects");
+        assert_eq!(parse_json(r#"[{"id": 1}, 2]"#).unwrap_err(), "Expected an object at index 1 but got 2");
+    }
+
+    #[test]
+    fn empty_and_ragged_files() {
+        assert!(parse_csv("").unwrap().is_empty());
+        assert!(parse_csv("id,name\n").unwrap().is_empty());
+        assert!(parse_json("[]").unwrap().is_empty());
+        assert!(parse_json("").unwrap_err().starts_with("Invalid JSON"));
+
+        // Baris dengan jumlah kolom berbeda dari header ditolak
+        for ragged in ["id,name\n1\n", "id,name\n1,Ann,extra\n"] {
+            let error = parse_csv(ragged).unwrap_err();
+            assert!(error.starts_with("Invalid CSV row"), "{}", error);
+        }
+    }
+
+    #[test]
+    fn load_data_file_picks_format_by_extension() {
+        let dir = std::env::temp_dir().join(format!("fmus-data-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("rows.CSV"), "id\n1\n").unwrap();
+        fs::write(dir.join("rows.json"), r#"[{"id": 1}]"#).unwrap();
+        fs::write(dir.join("broken.json"), "{").unwrap();
+
+        assert_eq!(load_data_file(dir.join("rows.CSV")).unwrap()[0]["id"], json!("1"));
+        assert_eq!(load_data_file(dir.join("rows.json")).unwrap()[0]["id"], json!(1));
+        let error = load_data_file(dir.join("broken.json")).unwrap_err();
+        assert!(error.starts_with(&format!("Invalid data file {}: Invalid JSON", dir.join("broken.json").display())), "{}", error);
+        assert!(load_data_file(dir.join("missing.csv")).unwrap_err().starts_with("Failed to read data file"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn iterations_follow_data_rows() {
+        let server = test_server::start().await;
+        let client = HttpClient::new(None);
+        let plan = plan(vec![step("user", &format!("{}/users/{{{{id}}}}", server.url), None)]);
+        let data = parse_csv("id\n7\n8\n").unwrap();
+
+        let result = Runner::new(&client, RunOptions { data: data.clone(), ..RunOptions::default() })
+            .run(&plan, HashMap::new(), |_| {})
+            .await;
+        let urls: Vec<&str> = result.results().map(|result| result.url.as_str()).collect();
+        assert_eq!(urls, [format!("{}/users/7", server.url), format!("{}/users/8", server.url)]);
+        assert_eq!(result.iterations[1].data["id"], json!("8"));
+
+        // Iterasi lebih banyak dari baris data memakai baris terakhir, data kosong tetap satu iterasi
+        let result = Runner::new(&client, RunOptions { data, iterations: Some(3), ..RunOptions::default() })
+            .run(&plan, HashMap::new(), |_| {})
+            .await;
+        assert!(result.results().last().unwrap().url.ends_with("/users/8"));
+        assert_eq!(result.summary.iterations, 3);
+        let result = Runner::new(&client, RunOptions { data: parse_csv("id\n").unwrap(), ..RunOptions::default() })
+            .run(&plan, HashMap::from([("id".to_string(), "env".to_string())]), |_| {})
+            .await;
+        assert_eq!(result.summary.iterations, 1);
+        assert!(result.results().next().unwrap().url.ends_with("/users/env"));
+    }
+}
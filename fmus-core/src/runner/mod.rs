@@ -0,0 +1,1390 @@
+pub mod data;
+mod schedule;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use futures_util::stream::{FuturesUnordered, StreamExt};
+use serde::{Deserialize, Serialize};
+
+use crate::assertions::{Assertion, AssertionReport};
+use crate::capture::{self, Capture, CaptureResult, CaptureScope};
+use crate::codec::BodyFormat;
+use crate::collections::{Collection, CollectionStore, ExecutionMode, Folder, Request};
+use crate::diff::{self, DiffOptions, ResponseDiff};
+use crate::flow::{self, FlowAction, FlowDecision, FlowRule};
+use crate::http::{AuthConfig, HttpClient, HttpResponse, RequestParams};
+use crate::scripting::{self, NextRequest, ScriptInfo, ScriptLimits, ScriptLog, ScriptOutcome, ScriptScope, ScriptTestResult};
+use crate::snapshot::{SnapshotConfig, SnapshotMode, SnapshotResult, SnapshotStore};
+use crate::vault::{self, Vault};
+
+pub use data::{load_data_file, IterationData};
+pub use schedule::PlanNode;
+
+// Batas request bersamaan jika max_concurrency tidak diisi
+const DEFAULT_MAX_CONCURRENCY: usize = 10;
+// Batas request per iterasi jika flow aktif dan max_flow_steps tidak diisi, agar goto/repeat tidak berputar selamanya
+const DEFAULT_MAX_FLOW_STEPS: usize = 1000;
+
+// Satu request yang sudah disiapkan untuk dijalankan, lepas dari CollectionStore
+#[derive(Debug, Clone)]
+pub struct RunStep {
+    pub request_id: String,
+    pub name: String,
+    // Nama folder dari luar ke dalam
+    pub folder_path: Vec<String>,
+    pub params: RequestParams,
+    // Script dari collection, folder lalu request
+    pub pre_request: Vec<String>,
+    pub test: Vec<String>,
+    pub assertions: Vec<Assertion>,
+    pub snapshot: Option<SnapshotConfig>,
+    pub captures: Vec<Capture>,
+    pub response_format: Option<BodyFormat>,
+    pub flow: Vec<FlowRule>,
+}
+
+impl RunStep {
+    // Step memakai control flow jika punya aturan flow atau script yang memanggil setNextRequest.
+    // Panggilan yang tidak terdeteksi di sini (nama dibentuk dinamis) dilaporkan saat run, lihat run_iteration.
+    fn uses_flow(&self) -> bool {
+        !self.flow.is_empty() || self.pre_request.iter().chain(&self.test).any(|script| scripting::uses_set_next_request(script))
+    }
+}
+
+impl RunStep {
+    // Kumpulkan request, auth dan script dari collection dan folder di atasnya
+    // Nilai rahasia dari auth ditambahkan ke secrets
+    fn prepare(
+        request: &Request,
+        collection: &Collection,
+        folders: &[&Folder],
+        vault: Option<&Vault>,
+        secrets: &mut Vec<String>,
+    ) -> Result<RunStep, String> {
+        let auth = request.resolved_auth(vault)?;
+        if let Some(auth) = &auth {
+            vault::secret_values(auth, secrets);
+        }
+        let auth = auth
+            .map(serde_json::from_value::<AuthConfig>)
+            .transpose()
+            .map_err(|e| format!("Invalid auth for request {}: {}", request.name, e))?;
+
+        let chain: Vec<_> = std::iter::once(&collection.scripts)
+            .chain(folders.iter().map(|folder| &folder.scripts))
+            .chain(std::iter::once(&request.scripts))
+            .collect();
+
+        let mut assertions = request.assertions.clone();
+        if let Some(schema) = &request.schema {
+            assertions.push(Assertion::JsonSchema { schema: schema.clone() });
+        }
+
+        Ok(RunStep {
+            request_id: request.id.clone(),
+            name: request.name.clone(),
+            folder_path: folders.iter().map(|folder| folder.name.clone()).collect(),
+            params: RequestParams {
+                url: request.url.clone(),
+                method: request.method.clone(),
+                headers: if request.headers.is_empty() { None } else { Some(request.headers.clone()) },
+                params: if request.params.is_empty() { None } else { Some(request.params.clone()) },
+                body: request.body.clone(),
+                timeout: None,
+                auth,
+                body_format: request.body_format.clone(),
+            },
+            pre_request: chain.iter().filter_map(|scripts| scripts.pre_request.clone()).collect(),
+            test: chain.iter().filter_map(|scripts| scripts.test.clone()).collect(),
+            assertions,
+            snapshot: request.snapshot.clone(),
+            captures: request.captures.clone(),
+            response_format: request.response_format.clone(),
+            flow: request.flow.clone(),
+        })
+    }
+}
+
+// Urutan request yang akan dijalankan beserta variabel collection-nya
+#[derive(Debug, Clone)]
+pub struct RunPlan {
+    pub collection_id: String,
+    pub collection_name: String,
+    pub variables: HashMap<String, String>,
+    pub steps: Vec<RunStep>,
+    // Susunan folder untuk menentukan step mana yang boleh berjalan bersamaan
+    pub layout: PlanNode,
+    // Nilai secret yang dibuka dari vault, disensor dari laporan. Pemanggil menambahkan
+    // nilai variable secret dari environment.
+    pub secrets: Vec<String>,
+}
+
+impl RunPlan {
+    fn new(collection: &Collection) -> Self {
+        RunPlan {
+            collection_id: collection.id.clone(),
+            collection_name: collection.name.clone(),
+            variables: collection.variables.clone(),
+            steps: Vec::new(),
+            layout: PlanNode::Group { execution: None, items: Vec::new() },
+            secrets: Vec::new(),
+        }
+    }
+
+    // Semua request dalam collection. Request di sebuah collection atau folder dijalankan
+    // lebih dulu, lalu isi sub-folder sesuai urutannya.
+    pub fn for_collection(store: &CollectionStore, collection_id: &str, vault: Option<&Vault>) -> Result<RunPlan, String> {
+        let collection = store.get_collection(collection_id)
+            .ok_or_else(|| format!("Collection not found: {}", collection_id))?;
+
+        let mut plan = RunPlan::new(collection);
+        let items = plan.push_items(store, collection, &[], &collection.requests, &collection.folders, vault)?;
+        plan.layout = PlanNode::Group { execution: collection.execution, items };
+        Ok(plan)
+    }
+
+    // Semua request dalam satu folder beserta sub-folder-nya
+    pub fn for_folder(store: &CollectionStore, folder_id: &str, vault: Option<&Vault>) -> Result<RunPlan, String> {
+        let (collection, path) = store.folder_context(folder_id)
+            .ok_or_else(|| format!("Folder {} does not belong to any collection", folder_id))?;
+        let folder = path[path.len() - 1];
+        // Mode folder yang tidak diatur diwarisi dari folder terdekat di atasnya
+        let execution = path.iter().rev().find_map(|folder| folder.execution).or(collection.execution);
+
+        let mut plan = RunPlan::new(collection);
+        let items = plan.push_items(store, collection, &path, &folder.requests, &folder.folders, vault)?;
+        plan.layout = PlanNode::Group { execution, items };
+        Ok(plan)
+    }
+
+    // Satu request tersimpan
+    pub fn for_request(store: &CollectionStore, request_id: &str, vault: Option<&Vault>) -> Result<RunPlan, String> {
+        let request = store.get_request(request_id)
+            .ok_or_else(|| format!("Request not found: {}", request_id))?;
+        let (collection, folders) = store.request_context(request_id)
+            .ok_or_else(|| format!("Request {} does not belong to any collection", request_id))?;
+
+        let mut plan = RunPlan::new(collection);
+        plan.steps.push(RunStep::prepare(request, collection, &folders, vault, &mut plan.secrets)?);
+        plan.layout = PlanNode::Group { execution: None, items: vec![PlanNode::Step(0)] };
+        Ok(plan)
+    }
+
+    fn push_items<'s>(
+        &mut self,
+        store: &'s CollectionStore,
+        collection: &Collection,
+        folders: &[&'s Folder],
+        request_ids: &[String],
+        folder_ids: &[String],
+        vault: Option<&Vault>,
+    ) -> Result<Vec<PlanNode>, String> {
+        let mut items = Vec::new();
+        for request_id in request_ids {
+            let request = store.get_request(request_id)
+                .ok_or_else(|| format!("Request not found: {}", request_id))?;
+            items.push(PlanNode::Step(self.steps.len()));
+            self.steps.push(RunStep::prepare(request, collection, folders, vault, &mut self.secrets)?);
+        }
+
+        for folder_id in folder_ids {
+            let folder = store.get_folder(folder_id)
+                .ok_or_else(|| format!("Folder not found: {}", folder_id))?;
+            let mut path = folders.to_vec();
+            path.push(folder);
+            let folder_items = self.push_items(store, collection, &path, &folder.requests, &folder.folders, vault)?;
+            items.push(PlanNode::Group { execution: folder.execution, items: folder_items });
+        }
+
+        Ok(items)
+    }
+}
+
+// Opsi menjalankan collection
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RunOptions {
+    // Hentikan run setelah request pertama yang gagal
+    #[serde(default)]
+    pub bail: bool,
+    // Jeda dalam milidetik sejak request terakhir dimulai atau selesai sebelum request berikutnya dimulai
+    #[serde(default)]
+    pub delay_ms: u64,
+    // Collection dan folder tanpa mode eksekusi dijalankan parallel
+    #[serde(default)]
+    pub parallel: bool,
+    // Batas request yang berjalan bersamaan, default 10
+    #[serde(default)]
+    pub max_concurrency: Option<usize>,
+    // Batas request per iterasi saat flow (goto, repeat, setNextRequest) dipakai, default 1000
+    #[serde(default)]
+    pub max_flow_steps: Option<usize>,
+    // Jumlah iterasi, default jumlah baris data atau 1 jika tanpa data
+    #[serde(default)]
+    pub iterations: Option<usize>,
+    // Jeda antar iterasi dalam milidetik
+    #[serde(default)]
+    pub iteration_delay_ms: u64,
+    // Satu baris per iterasi; jika iterasi lebih banyak dari baris, baris terakhir dipakai ulang
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub data: Vec<IterationData>,
+    // Timeout per request dalam detik, untuk request yang tidak mengatur timeout sendiri
+    #[serde(default)]
+    pub timeout: Option<u64>,
+    // Snapshot hanya dicek jika mode diisi
+    #[serde(default)]
+    pub snapshot_mode: Option<SnapshotMode>,
+    #[serde(default)]
+    pub script_limits: ScriptLimits,
+}
+
+// Perubahan variabel dari script dan capture: Some(nilai) untuk set, None untuk unset
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VariableChanges {
+    pub environment: HashMap<String, Option<String>>,
+    pub collection: HashMap<String, Option<String>>,
+}
+
+impl VariableChanges {
+    pub fn is_empty(&self) -> bool {
+        self.environment.is_empty() && self.collection.is_empty()
+    }
+
+    fn record_captures(&mut self, captures: &[CaptureResult]) {
+        for result in captures {
+            match (&result.value, result.scope) {
+                (Some(value), CaptureScope::Environment) => {
+                    self.environment.insert(result.variable.clone(), Some(value.clone()));
+                },
+                (Some(value), CaptureScope::Collection) => {
+                    self.collection.insert(result.variable.clone(), Some(value.clone()));
+                },
+                (None, _) | (_, CaptureScope::Run) => {},
+            }
+        }
+    }
+}
+
+// Hasil menjalankan satu request
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RequestResult {
+    pub request_id: String,
+    pub name: String,
+    pub folder_path: Vec<String>,
+    // Nomor iterasi, mulai dari 0
+    pub iteration: usize,
+    // Method dan URL setelah script dan variabel diterapkan
+    pub method: String,
+    pub url: String,
+    // None jika request tidak mendapat response
+    pub status: Option<u16>,
+    pub time: Option<u128>,
+    pub size: Option<usize>,
+    pub tests: Vec<ScriptTestResult>,
+    pub assertions: AssertionReport,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snapshot: Option<SnapshotResult>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub captures: Vec<CaptureResult>,
+    pub logs: Vec<ScriptLog>,
+    // Error script, error jaringan dan error snapshot
+    pub errors: Vec<String>,
+    // Request dilewati oleh aturan flow skip
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub skipped: bool,
+    // Keputusan flow setelah request ini, None berarti lanjut ke request berikutnya
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub flow: Option<FlowDecision>,
+    pub passed: bool,
+    // Ringkasan semua kegagalan untuk ditampilkan
+    pub failures: Vec<String>,
+    // Request yang dikirim dan response mentahnya, tidak ikut diserialisasi karena body bisa besar
+    #[serde(skip)]
+    pub request: Option<RequestParams>,
+    #[serde(skip)]
+    pub response: Option<HttpResponse>,
+    // Durasi request HTTP dengan presisi penuh, untuk statistik load test
+    #[serde(skip)]
+    pub latency: Option<Duration>,
+    // Permintaan pm.execution.setNextRequest dari script
+    #[serde(skip)]
+    pub next_request: Option<NextRequest>,
+}
+
+impl RequestResult {
+    fn new(step: &RunStep, info: &ScriptInfo) -> Self {
+        RequestResult {
+            request_id: step.request_id.clone(),
+            name: step.name.clone(),
+            folder_path: step.folder_path.clone(),
+            iteration: info.iteration,
+            method: step.params.method.clone(),
+            url: step.params.url.clone(),
+            ..RequestResult::default()
+        }
+    }
+
+    // Catat hasil script, return false jika script gagal dijalankan
+    fn record(&mut self, outcome: &ScriptOutcome, changes: &mut VariableChanges) -> bool {
+        self.tests.extend(outcome.tests.iter().cloned());
+        self.logs.extend(outcome.logs.iter().cloned());
+        changes.environment.extend(outcome.environment_changes.clone());
+        changes.collection.extend(outcome.collection_changes.clone());
+        if outcome.next_request.is_some() {
+            self.next_request = outcome.next_request.clone();
+        }
+
+        match &outcome.error {
+            Some(error) => {
+                self.errors.push(error.clone());
+                false
+            },
+            None => true,
+        }
+    }
+
+    fn finish(mut self) -> Self {
+        let mut failures = self.errors.clone();
+        failures.extend(self.tests.iter().filter(|test| !test.passed && !test.skipped).map(|test| match &test.error {
+            Some(error) => format!("{}: {}", test.name, error),
+            None => test.name.clone(),
+        }));
+        failures.extend(self.assertions.failures().map(|result| match &result.message {
+            Some(message) => message.clone(),
+            None => result.assertion.describe(),
+        }));
+        if let Some(snapshot) = self.snapshot.as_ref().filter(|snapshot| !snapshot.passed()) {
+            failures.push(format!("Response does not match snapshot {}", snapshot.name));
+        }
+        failures.extend(self.captures.iter().filter_map(|capture| {
+            capture.error.as_ref().map(|error| format!("Capture {}: {}", capture.variable, error))
+        }));
+
+        self.passed = failures.is_empty() && (self.status.is_some() || self.skipped);
+        self.failures = failures;
+        self
+    }
+
+    // Tandai gagal karena alasan di luar request itu sendiri, misalnya batas flow
+    fn fail(&mut self, error: String) {
+        self.failures.push(error.clone());
+        self.errors.push(error);
+        self.passed = false;
+    }
+}
+
+// Ringkasan satu run
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RunSummary {
+    // Jumlah iterasi yang dijalankan
+    pub iterations: usize,
+    // Jumlah request dalam plan dikali iterasi, bisa lebih banyak dari yang dijalankan jika run dihentikan
+    pub total: usize,
+    // Request yang dikirim, termasuk pengulangan oleh flow
+    pub executed: usize,
+    // Request yang dilewati oleh aturan flow skip
+    #[serde(default)]
+    pub skipped: usize,
+    pub passed: usize,
+    pub failed: usize,
+    pub tests_passed: usize,
+    pub tests_failed: usize,
+    pub assertions_passed: usize,
+    pub assertions_failed: usize,
+    // Durasi seluruh run dalam milidetik
+    pub time: u128,
+    // Run dihentikan karena bail
+    pub stopped: bool,
+    // Plan punya collection/folder parallel tapi dijalankan berurutan karena memakai flow
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub forced_sequential: bool,
+}
+
+impl RunSummary {
+    fn new(total: usize, iterations: &[IterationResult], time: Duration, stopped: bool, forced_sequential: bool) -> Self {
+        let all = || iterations.iter().flat_map(|iteration| &iteration.results);
+        let results = || all().filter(|result| !result.skipped);
+        let executed = results().count();
+        let passed = results().filter(|result| result.passed).count();
+        let tests = results().flat_map(|result| &result.tests).filter(|test| !test.skipped);
+        let tests_passed = tests.clone().filter(|test| test.passed).count();
+
+        RunSummary {
+            iterations: iterations.len(),
+            total,
+            executed,
+            skipped: all().count() - executed,
+            passed,
+            failed: executed - passed,
+            tests_passed,
+            tests_failed: tests.count() - tests_passed,
+            assertions_passed: results().map(|result| result.assertions.passed).sum(),
+            assertions_failed: results().map(|result| result.assertions.failed).sum(),
+            time: time.as_millis(),
+            stopped,
+            forced_sequential,
+        }
+    }
+}
+
+// Hasil satu iterasi beserta baris data yang dipakai
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IterationResult {
+    pub iteration: usize,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub data: IterationData,
+    pub results: Vec<RequestResult>,
+    pub passed: bool,
+}
+
+// Hasil menjalankan collection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunResult {
+    pub collection_id: String,
+    pub collection_name: String,
+    pub iterations: Vec<IterationResult>,
+    pub summary: RunSummary,
+    // Perubahan variabel selama run, untuk ditulis balik ke environment dan collection
+    pub changes: VariableChanges,
+    // Nilai secret dari plan, disensor oleh write_reports
+    #[serde(skip)]
+    pub secrets: Vec<String>,
+}
+
+impl RunResult {
+    pub fn passed(&self) -> bool {
+        self.summary.failed == 0 && !self.summary.stopped
+    }
+
+    // Semua hasil request dari semua iterasi, urut sesuai eksekusi
+    pub fn results(&self) -> impl Iterator<Item = &RequestResult> {
+        self.iterations.iter().flat_map(|iteration| &iteration.results)
+    }
+}
+
+// Progress run untuk ditampilkan selagi request berjalan
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RunEvent<'a> {
+    // forced_sequential: lihat RunSummary::forced_sequential
+    RunStarted { collection_id: &'a str, collection_name: &'a str, iterations: usize, total: usize, forced_sequential: bool },
+    IterationStarted { iteration: usize, data: &'a IterationData },
+    // index adalah posisi request dalam plan
+    RequestStarted { iteration: usize, index: usize, request_id: &'a str, name: &'a str },
+    RequestFinished { iteration: usize, index: usize, result: &'a RequestResult },
+    IterationFinished { iteration: usize, passed: bool },
+    RunFinished { summary: &'a RunSummary },
+}
+
+// Menjalankan RunPlan lewat HttpClient yang dipakai bersama
+pub struct Runner<'a> {
+    client: &'a HttpClient,
+    options: RunOptions,
+    snapshots: Option<SnapshotStore>,
+}
+
+impl<'a> Runner<'a> {
+    pub fn new(client: &'a HttpClient, options: RunOptions) -> Self {
+        Runner { client, options, snapshots: None }
+    }
+
+    // Lokasi snapshot untuk collection yang dijalankan
+    pub fn with_snapshots(mut self, store: SnapshotStore) -> Self {
+        self.snapshots = Some(store);
+        self
+    }
+
+    // Jalankan semua request dalam plan untuk setiap iterasi. Request di folder sequential berjalan
+    // sesuai urutan; request di folder parallel berjalan bersamaan kecuali menunggu variabel dari
+    // request sebelumnya (lihat RunPlan::dependencies). Hasil selalu diurutkan sesuai plan.
+    // Plan yang memakai flow (aturan flow atau setNextRequest) selalu berjalan berurutan mengikuti flow-nya;
+    // jika plan punya folder parallel, hal ini ditandai dengan forced_sequential di RunStarted dan summary.
+    // Variabel dari script dan capture berlaku untuk request yang dimulai setelahnya dan terbawa ke
+    // iterasi berikutnya, kecuali variabel lokal (pm.variables) yang dikosongkan setiap iterasi.
+    // on_event dipanggil untuk setiap progress.
+    pub async fn run(
+        &self,
+        plan: &RunPlan,
+        environment: HashMap<String, String>,
+        mut on_event: impl FnMut(&RunEvent),
+    ) -> RunResult {
+        let start = Instant::now();
+        let options = &self.options;
+        let iteration_count = options.iterations.unwrap_or(options.data.len().max(1));
+        let default_mode = if options.parallel { ExecutionMode::Parallel } else { ExecutionMode::Sequential };
+        let dependencies = plan.dependencies(default_mode);
+        // Flow bergantung pada urutan, jadi plan dengan flow selalu dijalankan berurutan
+        let uses_flow = plan.steps.iter().any(RunStep::uses_flow);
+        let forced_sequential = uses_flow && plan.layout.has_parallel(default_mode);
+        if forced_sequential {
+            log::warn!("Collection {} uses flow control, running parallel folders sequentially", plan.collection_name);
+        }
+        let mut scope = ScriptScope {
+            environment,
+            collection_variables: plan.variables.clone(),
+            ..ScriptScope::default()
+        };
+        let mut changes = VariableChanges::default();
+        let mut iterations = Vec::new();
+        let mut stopped = false;
+
+        on_event(&RunEvent::RunStarted {
+            collection_id: &plan.collection_id,
+            collection_name: &plan.collection_name,
+            iterations: iteration_count,
+            total: plan.steps.len() * iteration_count,
+            forced_sequential,
+        });
+
+        for iteration in 0..iteration_count {
+            if iteration > 0 && options.iteration_delay_ms > 0 {
+                tokio::time::sleep(Duration::from_millis(options.iteration_delay_ms)).await;
+            }
+
+            let data = options.data.get(iteration).or(options.data.last()).cloned().unwrap_or_default();
+            scope.iteration_data = data.clone();
+            scope.variables.clear();
+            on_event(&RunEvent::IterationStarted { iteration, data: &data });
+
+            let info = ScriptInfo { iteration, iteration_count, ..ScriptInfo::default() };
+            let (results, end) = if uses_flow {
+                self.run_flow_iteration(plan, &info, &mut scope, &mut changes, &mut on_event).await
+            } else {
+                self.run_iteration(plan, &dependencies, &info, &mut scope, &mut changes, &mut on_event).await
+            };
+            // Run dianggap dihentikan jika bail melewatkan request di iterasi ini atau iterasi berikutnya
+            stopped = match end {
+                IterationEnd::Completed => false,
+                IterationEnd::Bailed { remaining } => remaining || iteration + 1 < iteration_count,
+            };
+
+            let passed = results.iter().all(|result| result.passed);
+            on_event(&RunEvent::IterationFinished { iteration, passed });
+            iterations.push(IterationResult { iteration, data, results, passed });
+            if stopped {
+                break;
+            }
+        }
+
+        let summary = RunSummary::new(plan.steps.len() * iteration_count, &iterations, start.elapsed(), stopped, forced_sequential);
+        on_event(&RunEvent::RunFinished { summary: &summary });
+
+        RunResult {
+            collection_id: plan.collection_id.clone(),
+            collection_name: plan.collection_name.clone(),
+            iterations,
+            summary,
+            changes,
+            secrets: plan.secrets.clone(),
+        }
+    }
+
+    // Jalankan satu iterasi. Step dimulai sesuai urutan plan begitu semua ketergantungannya selesai,
+    // paling banyak max_concurrency sekaligus. Setiap step bekerja dengan salinan scope saat ia dimulai;
+    // perubahan variabelnya digabung ke scope bersama saat selesai.
+    // Return hasil yang urut sesuai plan dan cara iterasi berakhir.
+    async fn run_iteration(
+        &self,
+        plan: &RunPlan,
+        dependencies: &[Vec<usize>],
+        info: &ScriptInfo,
+        scope: &mut ScriptScope,
+        changes: &mut VariableChanges,
+        on_event: &mut impl FnMut(&RunEvent),
+    ) -> (Vec<RequestResult>, IterationEnd) {
+        let options = &self.options;
+        let max_concurrency = options.max_concurrency.unwrap_or(DEFAULT_MAX_CONCURRENCY).max(1);
+        let delay = Duration::from_millis(options.delay_ms);
+        let iteration = info.iteration;
+
+        let mut started = vec![false; plan.steps.len()];
+        let mut finished = vec![false; plan.steps.len()];
+        let mut results: Vec<Option<RequestResult>> = vec![None; plan.steps.len()];
+        let mut running = FuturesUnordered::new();
+        let mut not_before: Option<Instant> = None;
+        let mut bailed = false;
+
+        loop {
+            let ready = |started: &[bool], finished: &[bool]| {
+                (0..plan.steps.len()).find(|&index| !started[index] && dependencies[index].iter().all(|&dependency| finished[dependency]))
+            };
+
+            while !bailed && running.len() < max_concurrency {
+                let Some(index) = ready(&started, &finished) else { break };
+                if not_before.is_some_and(|at| Instant::now() < at) {
+                    break;
+                }
+
+                let step = &plan.steps[index];
+                started[index] = true;
+                on_event(&RunEvent::RequestStarted { iteration, index, request_id: &step.request_id, name: &step.name });
+
+                let info = ScriptInfo { request_name: step.name.clone(), request_id: step.request_id.clone(), ..info.clone() };
+                let before = scope.clone();
+                running.push(async move {
+                    let mut after = before.clone();
+                    let mut step_changes = VariableChanges::default();
+                    let result = self.run_step(step, &info, &mut after, &mut step_changes).await;
+                    (index, before, after, step_changes, result)
+                });
+                if !delay.is_zero() {
+                    not_before = Some(Instant::now() + delay);
+                }
+            }
+
+            let waiting = !bailed && ready(&started, &finished).is_some();
+            let next = match (running.is_empty(), not_before.filter(|_| waiting)) {
+                (true, None) => break,
+                (true, Some(at)) => {
+                    tokio::time::sleep_until(at.into()).await;
+                    continue;
+                },
+                // Request berikutnya siap setelah jeda selesai, tanpa menunggu request yang sedang berjalan
+                (false, Some(at)) if running.len() < max_concurrency => tokio::select! {
+                    next = running.next() => next,
+                    _ = tokio::time::sleep_until(at.into()) => continue,
+                },
+                (false, _) => running.next().await,
+            };
+            let Some((index, before, after, step_changes, mut result)) = next else { break };
+
+            merge_scope(scope, &before, &after);
+            changes.environment.extend(step_changes.environment);
+            changes.collection.extend(step_changes.collection);
+            finished[index] = true;
+            if !delay.is_zero() {
+                not_before = Some(Instant::now() + delay);
+            }
+
+            if result.next_request.is_some() {
+                result.logs.push(ScriptLog {
+                    level: "warn".to_string(),
+                    message: "pm.execution.setNextRequest was ignored: the call was not detected before the run, so requests ran in plan order".to_string(),
+                });
+            }
+
+            on_event(&RunEvent::RequestFinished { iteration, index, result: &result });
+            if !result.passed && options.bail {
+                bailed = true;
+            }
+            results[index] = Some(result);
+        }
+
+        let end = if bailed {
+            IterationEnd::Bailed { remaining: started.contains(&false) }
+        } else {
+            IterationEnd::Completed
+        };
+        (results.into_iter().flatten().collect(), end)
+    }
+
+    // Jalankan satu iterasi berurutan mengikuti flow: aturan skip dicek sebelum request dikirim,
+    // lalu aturan goto/repeat/stop, atau setNextRequest dari script jika tidak ada aturan yang cocok.
+    // Hasil urut sesuai eksekusi, jadi request yang diulang muncul beberapa kali.
+    // Kondisi skip yang error dicatat sebagai request gagal lalu run lanjut seperti kegagalan lain.
+    async fn run_flow_iteration(
+        &self,
+        plan: &RunPlan,
+        info: &ScriptInfo,
+        scope: &mut ScriptScope,
+        changes: &mut VariableChanges,
+        on_event: &mut impl FnMut(&RunEvent),
+    ) -> (Vec<RequestResult>, IterationEnd) {
+        let options = &self.options;
+        let max_steps = options.max_flow_steps.unwrap_or(DEFAULT_MAX_FLOW_STEPS);
+        let iteration = info.iteration;
+        let mut results = Vec::new();
+        let mut cursor = 0;
+        let mut attempt = 1;
+        let mut sent = 0;
+
+        while let Some(step) = plan.steps.get(cursor) {
+            on_event(&RunEvent::RequestStarted { iteration, index: cursor, request_id: &step.request_id, name: &step.name });
+            let info = ScriptInfo { request_name: step.name.clone(), request_id: step.request_id.clone(), ..info.clone() };
+
+            match flow::should_skip(&step.flow, scope) {
+                Ok(true) => {
+                    let result = RequestResult { skipped: true, flow: Some(FlowDecision::Skip), ..RequestResult::new(step, &info) }.finish();
+                    on_event(&RunEvent::RequestFinished { iteration, index: cursor, result: &result });
+                    results.push(result);
+                    cursor += 1;
+                    attempt = 1;
+                    continue;
+                },
+                Ok(false) => {},
+                Err(e) => {
+                    let mut result = RequestResult::new(step, &info).finish();
+                    result.fail(e);
+                    on_event(&RunEvent::RequestFinished { iteration, index: cursor, result: &result });
+                    results.push(result);
+                    cursor += 1;
+                    attempt = 1;
+                    if options.bail {
+                        return (results, IterationEnd::Bailed { remaining: cursor < plan.steps.len() });
+                    }
+                    continue;
+                },
+            }
+
+            if sent > 0 && options.delay_ms > 0 {
+                tokio::time::sleep(Duration::from_millis(options.delay_ms)).await;
+            }
+            let mut result = self.run_step(step, &info, scope, changes).await;
+            sent += 1;
+
+            let action = flow::next_action(&step.flow, scope, result.response.as_ref());
+            let mut next = Some(cursor + 1);
+            let mut next_attempt = 1;
+            let mut repeat_delay = 0;
+            match action {
+                Ok(Some(FlowAction::Repeat { max_attempts, delay_ms })) => {
+                    result.flow = Some(FlowDecision::Repeat { attempt, max_attempts: *max_attempts });
+                    if attempt < *max_attempts {
+                        next = Some(cursor);
+                        next_attempt = attempt + 1;
+                        repeat_delay = *delay_ms;
+                    } else {
+                        result.fail(format!("Repeat condition still true after {} attempts", max_attempts));
+                    }
+                },
+                Ok(Some(FlowAction::Goto { request })) => {
+                    result.flow = Some(FlowDecision::Goto { request: request.clone() });
+                    next = find_step(plan, request);
+                    if next.is_none() {
+                        result.fail(format!("Flow target request not found in this run: {}", request));
+                    }
+                },
+                Ok(Some(FlowAction::Stop)) => {
+                    result.flow = Some(FlowDecision::Stop);
+                    next = None;
+                },
+                // Skip hanya berlaku sebelum request dikirim, tidak dikembalikan oleh next_action
+                Ok(Some(FlowAction::Skip)) | Ok(None) => match result.next_request.clone() {
+                    Some(NextRequest::Name(request)) => {
+                        result.flow = Some(FlowDecision::Goto { request: request.clone() });
+                        next = find_step(plan, &request);
+                        if next.is_none() {
+                            result.fail(format!("setNextRequest target not found in this run: {}", request));
+                        }
+                    },
+                    Some(NextRequest::Stop) => {
+                        result.flow = Some(FlowDecision::Stop);
+                        next = None;
+                    },
+                    None => {},
+                },
+                Err(e) => {
+                    result.fail(e);
+                    next = None;
+                },
+            }
+            if next.is_some() && sent >= max_steps {
+                result.fail(format!("Flow stopped after {} requests in one iteration (max_flow_steps)", max_steps));
+                next = None;
+            }
+
+            on_event(&RunEvent::RequestFinished { iteration, index: cursor, result: &result });
+            let failed = !result.passed;
+            results.push(result);
+            if failed && options.bail {
+                return (results, IterationEnd::Bailed { remaining: matches!(next, Some(next) if next < plan.steps.len()) });
+            }
+
+            match next {
+                Some(index) => cursor = index,
+                None => break,
+            }
+            attempt = next_attempt;
+            if repeat_delay > 0 {
+                tokio::time::sleep(Duration::from_millis(repeat_delay)).await;
+            }
+        }
+
+        (results, IterationEnd::Completed)
+    }
+
+    // Jalankan satu request: pre-request script, kirim, capture, test script, assertion lalu snapshot.
+    // Scope diperbarui agar request berikutnya melihat variabel baru.
+    pub async fn run_step(
+        &self,
+        step: &RunStep,
+        info: &ScriptInfo,
+        scope: &mut ScriptScope,
+        changes: &mut VariableChanges,
+    ) -> RequestResult {
+        let limits = &self.options.script_limits;
+        let mut result = RequestResult::new(step, info);
+
+        let mut params = step.params.clone();
+        for script in &step.pre_request {
+            let outcome = run_script(script, &params, None, scope, info, limits).await;
+            let ok = result.record(&outcome, changes);
+            params = outcome.request;
+            *scope = outcome.scope;
+
+            // Sama seperti Postman, request tidak dikirim jika pre-request script error
+            if !ok {
+                return result.finish();
+            }
+        }
+
+        let mut params = scope.apply_to(params);
+        if params.timeout.is_none() {
+            params.timeout = self.options.timeout;
+        }
+        result.method = params.method.clone();
+        result.url = params.url.clone();
+        result.request = Some(params.clone());
+
+        // Body protobuf/MessagePack/CBOR di-decode dulu agar assertion, capture dan script melihat JSON
+        let sent = Instant::now();
+        let response = self.client.request(params.clone()).await;
+        result.latency = Some(sent.elapsed());
+        let response = response.and_then(|response| match &step.response_format {
+            Some(format) => response.to_json_response(format),
+            None => Ok(response),
+        });
+        let response = match response {
+            Ok(response) => response,
+            Err(e) => {
+                result.errors.push(e);
+                return result.finish();
+            },
+        };
+        result.status = Some(response.status);
+        result.time = Some(response.time);
+        result.size = Some(response.body.len());
+
+        // Capture dijalankan sebelum test script agar nilainya bisa dicek lewat pm.*
+        let captures = capture::capture_all(&response, &step.captures);
+        capture::apply(&captures, scope);
+        changes.record_captures(&captures);
+        result.captures = captures;
+
+        if !step.test.is_empty() {
+            let shared = Arc::new(response.clone());
+            for script in &step.test {
+                let outcome = run_script(script, &params, Some(&shared), scope, info, limits).await;
+                result.record(&outcome, changes);
+                *scope = outcome.scope;
+            }
+        }
+
+        result.assertions = response.evaluate_assertions(&step.assertions);
+        if let (Some(config), Some(mode)) = (&step.snapshot, self.options.snapshot_mode) {
+            let snapshot = match &self.snapshots {
+                Some(store) => store.check(&step.request_id, &response, config, mode),
+                None => Err("Snapshot directory is not available".to_string()),
+            };
+            match snapshot {
+                Ok(snapshot) => result.snapshot = Some(snapshot),
+                Err(e) => result.errors.push(e),
+            }
+        }
+
+        result.response = Some(response);
+        result.finish()
+    }
+
+    // Jalankan request pertama dalam plan dengan variabel environment dan variabel collection plan.
+    // Perubahan variabel dikembalikan tanpa disimpan; pemanggil yang menentukan apakah ditulis balik.
+    pub async fn run_request(&self, plan: &RunPlan, environment: HashMap<String, String>) -> Result<(RequestResult, VariableChanges), String> {
+        let step = plan.steps.first().ok_or_else(|| "Nothing to run".to_string())?;
+        let info = ScriptInfo {
+            request_name: step.name.clone(),
+            request_id: step.request_id.clone(),
+            ..ScriptInfo::default()
+        };
+        let mut scope = ScriptScope {
+            environment,
+            collection_variables: plan.variables.clone(),
+            ..ScriptScope::default()
+        };
+        let mut changes = VariableChanges::default();
+
+        let result = self.run_step(step, &info, &mut scope, &mut changes).await;
+        Ok((result, changes))
+    }
+
+    // Jalankan request yang sama di dua environment (misalnya staging dan production) lalu bandingkan
+    // response-nya. Setiap sisi memakai scope sendiri dan perubahan variabel dari script dan capture
+    // dibuang, jadi perbandingan tidak mengubah environment mana pun.
+    pub async fn compare_environments(
+        &self,
+        left: (&RunPlan, HashMap<String, String>),
+        right: (&RunPlan, HashMap<String, String>),
+        options: &DiffOptions,
+    ) -> Result<EnvironmentComparison, String> {
+        let (left, _) = self.run_request(left.0, left.1).await?;
+        let (right, _) = self.run_request(right.0, right.1).await?;
+
+        let diff = match (&left.response, &right.response) {
+            (Some(before), Some(after)) => Some(diff::diff_with_options(before, after, options)?),
+            _ => None,
+        };
+        Ok(EnvironmentComparison { left, right, diff })
+    }
+}
+
+// Hasil menjalankan satu request di dua environment
+#[derive(Debug, Clone)]
+pub struct EnvironmentComparison {
+    pub left: RequestResult,
+    pub right: RequestResult,
+    // None jika salah satu request tidak mendapat response
+    pub diff: Option<ResponseDiff>,
+}
+
+// Cara sebuah iterasi berakhir
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum IterationEnd {
+    // Iterasi berjalan sampai selesai
+    Completed,
+    // Dihentikan karena bail; remaining true jika masih ada request yang belum dijalankan
+    Bailed { remaining: bool },
+}
+
+// Script berjalan sinkron sampai selesai atau timeout, jadi dijalankan lewat spawn_blocking agar
+// request lain yang berjalan bersamaan di runtime yang sama tidak ikut tertahan.
+// Tanpa response script dijalankan sebagai pre-request script.
+async fn run_script(
+    script: &str,
+    params: &RequestParams,
+    response: Option<&Arc<HttpResponse>>,
+    scope: &ScriptScope,
+    info: &ScriptInfo,
+    limits: &ScriptLimits,
+) -> ScriptOutcome {
+    let script = script.to_string();
+    let request = params.clone();
+    let response = response.cloned();
+    let task_scope = scope.clone();
+    let info = info.clone();
+    let limits = limits.clone();
+    let task = tokio::task::spawn_blocking(move || match &response {
+        Some(response) => scripting::run_test(&script, &request, response, &task_scope, &info, &limits),
+        None => scripting::run_pre_request(&script, &request, &task_scope, &info, &limits),
+    });
+
+    match task.await {
+        Ok(outcome) => outcome,
+        Err(e) => ScriptOutcome::unchanged(params, scope, Some(format!("Script runtime failed: {}", e))),
+    }
+}
+
+// Terapkan perubahan variabel yang dibuat satu step (selisih scope sebelum dan sesudahnya) ke scope bersama
+fn merge_scope(scope: &mut ScriptScope, before: &ScriptScope, after: &ScriptScope) {
+    let maps = [
+        (&mut scope.environment, &before.environment, &after.environment),
+        (&mut scope.collection_variables, &before.collection_variables, &after.collection_variables),
+        (&mut scope.variables, &before.variables, &after.variables),
+        (&mut scope.globals, &before.globals, &after.globals),
+    ];
+    for (shared, before, after) in maps {
+        for (key, value) in after {
+            if before.get(key) != Some(value) {
+                shared.insert(key.clone(), value.clone());
+            }
+        }
+        for key in before.keys().filter(|key| !after.contains_key(*key)) {
+            shared.remove(key);
+        }
+    }
+}
+
+// Index step berdasarkan ID request, lalu nama (seperti setNextRequest di Postman)
+fn find_step(plan: &RunPlan, request: &str) -> Option<usize> {
+    plan.steps.iter().position(|step| step.request_id == request)
+        .or_else(|| plan.steps.iter().position(|step| step.name == request))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::test_server;
+
+    pub(crate) fn step(name: &str, url: &str, pre_request: Option<&str>) -> RunStep {
+        RunStep {
+            request_id: name.to_string(),
+            name: name.to_string(),
+            folder_path: Vec::new(),
+            params: RequestParams {
+                url: url.to_string(),
+                method: "GET".to_string(),
+                headers: None,
+                params: None,
+                body: None,
+                timeout: None,
+                auth: None,
+                body_format: None,
+            },
+            pre_request: pre_request.map(str::to_string).into_iter().collect(),
+            test: Vec::new(),
+            assertions: Vec::new(),
+            snapshot: None,
+            captures: Vec::new(),
+            response_format: None,
+            flow: Vec::new(),
+        }
+    }
+
+    pub(crate) fn plan(steps: Vec<RunStep>) -> RunPlan {
+        RunPlan {
+            collection_id: "collection".to_string(),
+            collection_name: "Collection".to_string(),
+            variables: HashMap::new(),
+            layout: PlanNode::Group { execution: None, items: (0..steps.len()).map(PlanNode::Step).collect() },
+            steps,
+            secrets: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn parallel_steps_are_not_blocked_by_scripts() {
+        let server = test_server::start().await;
+        let busy = "var start = Date.now(); while (Date.now() - start < 500) {}";
+        let plan = plan(vec![
+            step("busy", &server.url, Some(busy)),
+            step("fast", &server.url, None),
+        ]);
+        let client = HttpClient::new(None);
+        let runner = Runner::new(&client, RunOptions { parallel: true, ..RunOptions::default() });
+
+        let start = Instant::now();
+        let mut finished = Vec::new();
+        let result = runner.run(&plan, HashMap::new(), |event| {
+            if let RunEvent::RequestFinished { result, .. } = event {
+                finished.push((result.name.clone(), start.elapsed()));
+            }
+        }).await;
+
+        assert!(result.passed());
+        assert_eq!(finished[0].0, "fast");
+        assert!(finished[0].1 < Duration::from_millis(400), "fast step waited {:?}", finished[0].1);
+        assert_eq!(server.requests(), 2);
+    }
+
+    fn rule(rule: serde_json::Value) -> FlowRule {
+        serde_json::from_value(rule).unwrap()
+    }
+
+    #[tokio::test]
+    async fn bail_on_the_last_flow_step_does_not_stop_the_run() {
+        let server = test_server::start().await;
+        let mut first = step("first", &server.url, None);
+        first.flow = vec![rule(serde_json::json!({"when": "vars.missing", "action": "skip"}))];
+        let mut last = step("last", &server.url, None);
+        last.assertions = vec![Assertion::StatusEquals { status: 500 }];
+        let client = HttpClient::new(None);
+        let runner = Runner::new(&client, RunOptions { bail: true, ..RunOptions::default() });
+
+        let result = runner.run(&plan(vec![first, last]), HashMap::new(), |_| {}).await;
+
+        assert!(!result.passed());
+        assert!(!result.summary.stopped);
+        assert_eq!(result.iterations[0].results.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn broken_skip_condition_fails_the_step_and_continues() {
+        let server = test_server::start().await;
+        let mut broken = step("broken", &server.url, None);
+        broken.flow = vec![rule(serde_json::json!({"when": "vars.x ==", "action": "skip"}))];
+        let client = HttpClient::new(None);
+        let runner = Runner::new(&client, RunOptions::default());
+
+        let result = runner.run(&plan(vec![broken, step("next", &server.url, None)]), HashMap::new(), |_| {}).await;
+
+        let results = &result.iterations[0].results;
+        assert_eq!(results.len(), 2);
+        assert!(!results[0].passed);
+        assert!(results[1].passed);
+        assert!(!result.summary.stopped);
+        assert_eq!(server.requests(), 1);
+    }
+
+    #[tokio::test]
+    async fn repeat_and_goto_follow_flow_rules() {
+        let server = test_server::start().await;
+        let count = "pm.variables.set('n', String(Number(pm.variables.get('n') || 0) + 1))";
+        let mut poll = step("poll", &server.url, Some(count));
+        poll.flow = vec![rule(serde_json::json!({"when": "n < 3", "action": "repeat", "max_attempts": 5}))];
+        let mut jump = step("jump", &server.url, None);
+        jump.flow = vec![rule(serde_json::json!({"action": "goto", "request": "last"}))];
+        let client = HttpClient::new(None);
+        let runner = Runner::new(&client, RunOptions::default());
+
+        let plan = plan(vec![poll, jump, step("skipped", &server.url, None), step("last", &server.url, None)]);
+        let result = runner.run(&plan, HashMap::new(), |_| {}).await;
+
+        let names: Vec<&str> = result.results().map(|result| result.name.as_str()).collect();
+        assert_eq!(names, ["poll", "poll", "poll", "jump", "last"]);
+        assert_eq!(result.iterations[0].results[1].flow, Some(FlowDecision::Repeat { attempt: 2, max_attempts: 5 }));
+        assert_eq!(result.iterations[0].results[2].flow, None);
+        assert!(result.passed());
+    }
+
+    #[tokio::test]
+    async fn environment_comparison_keeps_variable_changes_out() {
+        let server = test_server::start_with(|request| {
+            let body = serde_json::json!({"path": request.path}).to_string();
+            test_server::response(200, &[("Content-Type", "application/json")], body.as_bytes())
+        }).await;
+        // Jika perubahan dari sisi kiri terbawa ke sisi kanan, marker menjadi "leaked"
+        let marker = "pm.environment.set('marker', pm.environment.get('marker') ? 'leaked' : 'users')";
+        let plan = plan(vec![step("users", "{{base}}/{{marker}}", Some(marker))]);
+        let environment = |version: &str| HashMap::from([("base".to_string(), format!("{}/{}", server.url, version))]);
+        let client = HttpClient::new(None);
+        let runner = Runner::new(&client, RunOptions::default());
+
+        let comparison = runner
+            .compare_environments((&plan, environment("v1")), (&plan, environment("v2")), &DiffOptions::default())
+            .await
+            .unwrap();
+
+        assert!(comparison.left.url.ends_with("/v1/users"), "{}", comparison.left.url);
+        assert!(comparison.right.url.ends_with("/v2/users"), "{}", comparison.right.url);
+        let diff = comparison.diff.unwrap();
+        assert_eq!(diff.body, vec![crate::diff::Difference::changed(
+            "$['path']".to_string(),
+            serde_json::json!("/v1/users"),
+            serde_json::json!("/v2/users"),
+        )]);
+
+        let (_, changes) = runner.run_request(&plan, environment("v1")).await.unwrap();
+        assert_eq!(changes.environment.get("marker"), Some(&Some("users".to_string())));
+    }
+
+    // Request collection dengan URL {{base}}/<nama>
+    fn stored_request(id: &str, pre_request: Option<&str>) -> serde_json::Value {
+        serde_json::json!({
+            "id": id, "name": id, "description": null, "method": "GET", "url": format!("{{{{base}}}}/{}", id),
+            "headers": {}, "params": {}, "body": null, "auth": null,
+            "scripts": {"pre_request": pre_request},
+        })
+    }
+
+    fn folder(id: &str, requests: &[&str], folders: &[&str]) -> serde_json::Value {
+        serde_json::json!({
+            "id": id, "name": id.to_uppercase(), "description": null, "requests": requests, "folders": folders,
+            "scripts": {"pre_request": format!("// {}", id)},
+        })
+    }
+
+    // Server yang mencatat path setiap request
+    async fn recording_server() -> (test_server::TestServer, Arc<Mutex<Vec<String>>>) {
+        let paths = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&paths);
+        let server = test_server::start_with(move |request| {
+            seen.lock().unwrap().push(request.path.clone());
+            test_server::response(200, &[("Content-Type", "text/plain")], b"ok")
+        }).await;
+        (server, paths)
+    }
+
+    #[tokio::test]
+    async fn collection_plan_walks_folders_in_order() {
+        // c: r1, folder a (r2, sub-folder b (r3)), folder d (r4)
+        let bundle: crate::collections::CollectionBundle = serde_json::from_value(serde_json::json!({
+            "collection": {
+                "id": "c", "name": "C", "description": null, "requests": ["r1"], "folders": ["a", "d"],
+                "variables": {}, "scripts": {"pre_request": "// c"},
+            },
+            "folders": [folder("a", &["r2"], &["b"]), folder("b", &["r3"], &[]), folder("d", &["r4"], &[])],
+            "requests": [
+                stored_request("r1", None), stored_request("r2", None),
+                stored_request("r3", Some("// r3")), stored_request("r4", None),
+            ],
+        })).unwrap();
+        let mut store = CollectionStore::new();
+        store.import_bundle(bundle);
+
+        let plan = RunPlan::for_collection(&store, "c", None).unwrap();
+        let names: Vec<&str> = plan.steps.iter().map(|step| step.name.as_str()).collect();
+        assert_eq!(names, ["r1", "r2", "r3", "r4"]);
+        assert_eq!(plan.steps[2].folder_path, ["A", "B"]);
+        // Script collection, folder lalu request
+        assert_eq!(plan.steps[2].pre_request, ["// c", "// a", "// b", "// r3"]);
+
+        let folder_plan = RunPlan::for_folder(&store, "a", None).unwrap();
+        let names: Vec<&str> = folder_plan.steps.iter().map(|step| step.name.as_str()).collect();
+        assert_eq!(names, ["r2", "r3"]);
+        assert!(RunPlan::for_folder(&store, "missing", None).is_err());
+
+        let (server, paths) = recording_server().await;
+        let client = HttpClient::new(None);
+        let result = Runner::new(&client, RunOptions::default())
+            .run(&plan, HashMap::from([("base".to_string(), server.url.clone())]), |_| {})
+            .await;
+
+        assert!(result.passed());
+        assert_eq!(*paths.lock().unwrap(), ["/r1", "/r2", "/r3", "/r4"]);
+        assert_eq!(result.results().last().unwrap().url, format!("{}/r4", server.url));
+    }
+
+    #[tokio::test]
+    async fn iterations_repeat_the_plan() {
+        let server = test_server::start().await;
+        let client = HttpClient::new(None);
+        let runner = Runner::new(&client, RunOptions { iterations: Some(3), ..RunOptions::default() });
+        let mut started = Vec::new();
+
+        let result = runner.run(&plan(vec![step("a", &server.url, None), step("b", &server.url, None)]), HashMap::new(), |event| {
+            if let RunEvent::IterationStarted { iteration, .. } = event {
+                started.push(*iteration);
+            }
+        }).await;
+
+        assert_eq!(started, [0, 1, 2]);
+        let iterations: Vec<usize> = result.results().map(|result| result.iteration).collect();
+        assert_eq!(iterations, [0, 0, 1, 1, 2, 2]);
+        assert_eq!((result.summary.iterations, result.summary.total, result.summary.executed), (3, 6, 6));
+        assert_eq!(server.requests(), 6);
+    }
+
+    #[tokio::test]
+    async fn parallel_steps_wait_for_captured_variables() {
+        let server = test_server::start().await;
+        let mut login = step("login", &format!("{}/slow/300", server.url), None);
+        login.captures = vec![serde_json::from_value(serde_json::json!({"variable": "token", "from": "status"})).unwrap()];
+        let profile = step("profile", &format!("{}/profile/{{{{token}}}}", server.url), None);
+        let other = step("other", &server.url, None);
+        let plan = plan(vec![login, profile, other]);
+        assert_eq!(plan.dependencies(ExecutionMode::Parallel), vec![vec![], vec![0], vec![]]);
+
+        let client = HttpClient::new(None);
+        let runner = Runner::new(&client, RunOptions { parallel: true, ..RunOptions::default() });
+        let mut events = Vec::new();
+        let result = runner.run(&plan, HashMap::new(), |event| match event {
+            RunEvent::RequestStarted { name, .. } => events.push(format!("start {}", name)),
+            RunEvent::RequestFinished { result, .. } => events.push(format!("finish {}", result.name)),
+            _ => {},
+        }).await;
+
+        assert!(result.passed());
+        let position = |event: &str| events.iter().position(|e| e == event).unwrap();
+        assert!(position("start profile") > position("finish login"), "{:?}", events);
+        assert!(position("finish other") < position("finish login"), "{:?}", events);
+        assert!(result.iterations[0].results[1].url.ends_with("/profile/200"));
+        // Hasil tetap urut sesuai plan
+        let names: Vec<&str> = result.results().map(|result| result.name.as_str()).collect();
+        assert_eq!(names, ["login", "profile", "other"]);
+    }
+
+    #[tokio::test]
+    async fn delay_is_applied_between_requests() {
+        let server = test_server::start().await;
+        let client = HttpClient::new(None);
+        let runner = Runner::new(&client, RunOptions { delay_ms: 150, ..RunOptions::default() });
+        let steps = vec![step("a", &server.url, None), step("b", &server.url, None), step("c", &server.url, None)];
+
+        let start = Instant::now();
+        let result = runner.run(&plan(steps), HashMap::new(), |_| {}).await;
+
+        assert!(result.passed());
+        assert!(start.elapsed() >= Duration::from_millis(300), "{:?}", start.elapsed());
+        assert_eq!(server.requests(), 3);
+    }
+
+    #[tokio::test]
+    async fn summary_counts_requests_tests_and_assertions() {
+        let server = test_server::start().await;
+        let mut passing = step("passing", &server.url, None);
+        passing.test = vec!["pm.test('ok', function () { pm.response.to.have.status(200); });".to_string()];
+        passing.assertions = vec![Assertion::StatusEquals { status: 200 }];
+        let mut failing_assertion = step("failing assertion", &server.url, None);
+        failing_assertion.assertions = vec![Assertion::StatusEquals { status: 200 }, Assertion::StatusEquals { status: 404 }];
+        let mut failing_test = step("failing test", &server.url, None);
+        failing_test.test = vec!["pm.test('a', function () {}); pm.test('b', function () { throw new Error('no'); });".to_string()];
+        let mut skipped = step("skipped", &server.url, None);
+        skipped.flow = vec![rule(serde_json::json!({"when": "true", "action": "skip"}))];
+        let client = HttpClient::new(None);
+        let runner = Runner::new(&client, RunOptions::default());
+
+        let result = runner.run(&plan(vec![passing, failing_assertion, failing_test, skipped]), HashMap::new(), |_| {}).await;
+
+        let summary = &result.summary;
+        assert_eq!((summary.total, summary.executed, summary.skipped), (4, 3, 1));
+        assert_eq!((summary.passed, summary.failed), (1, 2));
+        assert_eq!((summary.tests_passed, summary.tests_failed), (2, 1));
+        assert_eq!((summary.assertions_passed, summary.assertions_failed), (2, 1));
+        assert!(!summary.stopped && !result.passed());
+        assert_eq!(result.iterations[0].results[2].failures, ["b: no"]);
+        assert_eq!(server.requests(), 3);
+
+        // Dengan bail run berhenti setelah request gagal pertama
+        let runner = Runner::new(&client, RunOptions { bail: true, ..RunOptions::default() });
+        let steps = vec![step("fail", "http://127.0.0.1:1", None), step("never", &server.url, None)];
+        let result = runner.run(&plan(steps), HashMap::new(), |_| {}).await;
+        assert!(result.summary.stopped);
+        assert_eq!((result.summary.total, result.summary.executed, result.summary.failed), (2, 1, 1));
+    }
+
+
+    #[tokio::test]
+    async fn flow_in_parallel_plan_is_reported_as_sequential() {
+        let server = test_server::start().await;
+        let slow = format!("{}/slow/200", server.url);
+        let mut first = step("first", &slow, None);
+        first.flow = vec![rule(serde_json::json!({"when": "false", "action": "skip"}))];
+        let steps = vec![first, step("second", &slow, None)];
+        let client = HttpClient::new(None);
+        let runner = Runner::new(&client, RunOptions { parallel: true, ..RunOptions::default() });
+
+        let mut flagged = None;
+        let start = Instant::now();
+        let result = runner.run(&plan(steps.clone()), HashMap::new(), |event| {
+            if let RunEvent::RunStarted { forced_sequential, .. } = event {
+                flagged = Some(*forced_sequential);
+            }
+        }).await;
+
+        assert_eq!(flagged, Some(true));
+        assert!(result.summary.forced_sequential);
+        assert!(start.elapsed() >= Duration::from_millis(400), "{:?}", start.elapsed());
+
+        // Tanpa folder parallel tidak ada yang diturunkan
+        let result = Runner::new(&client, RunOptions::default()).run(&plan(steps), HashMap::new(), |_| {}).await;
+        assert!(!result.summary.forced_sequential);
+        assert!(serde_json::to_value(&result.summary).unwrap().get("forced_sequential").is_none());
+    }
+
+
+    #[tokio::test]
+    async fn set_next_request_in_comments_keeps_parallel_run() {
+        let server = test_server::start().await;
+        let slow = format!("{}/slow/300", server.url);
+        let commented = "// pm.execution.setNextRequest('b') is not needed here\nconsole.log('setNextRequest')";
+        let steps = vec![step("a", &slow, Some(commented)), step("b", &slow, None)];
+        let client = HttpClient::new(None);
+        let runner = Runner::new(&client, RunOptions { parallel: true, ..RunOptions::default() });
+
+        let start = Instant::now();
+        let result = runner.run(&plan(steps), HashMap::new(), |_| {}).await;
+
+        assert!(result.passed());
+        assert!(!result.summary.forced_sequential);
+        assert!(start.elapsed() < Duration::from_millis(550), "{:?}", start.elapsed());
+    }
+
+    #[tokio::test]
+    async fn undetected_set_next_request_is_reported() {
+        let server = test_server::start().await;
+        let dynamic = "pm.execution['setNext' + 'Request']('c')";
+        let steps = vec![step("a", &server.url, Some(dynamic)), step("b", &server.url, None), step("c", &server.url, None)];
+        let client = HttpClient::new(None);
+        let runner = Runner::new(&client, RunOptions { parallel: true, ..RunOptions::default() });
+
+        let result = runner.run(&plan(steps), HashMap::new(), |_| {}).await;
+
+        assert_eq!(result.summary.executed, 3);
+        let logs = &result.iterations[0].results[0].logs;
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0].level, "warn");
+        assert!(logs[0].message.starts_with("pm.execution.setNextRequest was ignored"));
+    }
+
+}
@@ -0,0 +1,118 @@
+use std::collections::HashSet;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use super::{RunPlan, RunStep};
+use crate::collections::ExecutionMode;
+
+static TEMPLATE_PATTERN: Lazy<Regex> = Lazy::new(|| Regex::new(r"\{\{([^{}]+)\}\}").unwrap());
+// pm.environment.get("x"), pm.variables.has('x'), postman.getEnvironmentVariable("x")
+static SCRIPT_READ_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"\b(?:get|has|getEnvironmentVariable)\(\s*["'`]([^"'`]+)["'`]"#).unwrap()
+});
+// pm.environment.set("x", ...), pm.collectionVariables.unset('x'), postman.setEnvironmentVariable("x", ...)
+static SCRIPT_WRITE_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"\b(?:set|unset|setEnvironmentVariable|clearEnvironmentVariable)\(\s*["'`]([^"'`]+)["'`]"#).unwrap()
+});
+
+// Susunan request dalam plan sesuai collection dan folder asalnya
+#[derive(Debug, Clone)]
+pub enum PlanNode {
+    // Index request di RunPlan::steps
+    Step(usize),
+    // Isi collection atau folder: request-nya lebih dulu, lalu sub-folder.
+    // execution None berarti mengikuti mode induknya.
+    Group { execution: Option<ExecutionMode>, items: Vec<PlanNode> },
+}
+
+impl PlanNode {
+    // true jika ada collection/folder parallel dengan lebih dari satu item
+    pub(crate) fn has_parallel(&self, inherited: ExecutionMode) -> bool {
+        match self {
+            PlanNode::Step(_) => false,
+            PlanNode::Group { execution, items } => {
+                let mode = execution.unwrap_or(inherited);
+                (mode == ExecutionMode::Parallel && items.len() > 1) || items.iter().any(|item| item.has_parallel(mode))
+            },
+        }
+    }
+}
+
+impl RunPlan {
+    // Untuk setiap step, index step lain yang harus selesai sebelum step itu dimulai.
+    // Ketergantungan berasal dari dua sumber:
+    // - urutan item di collection/folder sequential
+    // - variabel: step yang membaca variabel menunggu semua step sebelumnya yang mengisi variabel itu
+    //   (lewat capture atau script), begitu juga dua step yang mengisi variabel yang sama.
+    // Variabel dideteksi dari {{template}} dan pemanggilan get/set dengan nama literal, jadi nama
+    // variabel yang dibentuk secara dinamis tidak terdeteksi; folder seperti itu sebaiknya sequential.
+    pub fn dependencies(&self, default: ExecutionMode) -> Vec<Vec<usize>> {
+        let mut dependencies = vec![Vec::new(); self.steps.len()];
+        link(&self.layout, default, &[], &mut dependencies);
+
+        let reads: Vec<_> = self.steps.iter().map(reads).collect();
+        let writes: Vec<_> = self.steps.iter().map(writes).collect();
+        for (index, step_dependencies) in dependencies.iter_mut().enumerate() {
+            for earlier in 0..index {
+                let conflict = !writes[earlier].is_disjoint(&reads[index]) || !writes[earlier].is_disjoint(&writes[index]);
+                if conflict {
+                    step_dependencies.push(earlier);
+                }
+            }
+            step_dependencies.sort_unstable();
+            step_dependencies.dedup();
+        }
+
+        dependencies
+    }
+}
+
+// Catat ketergantungan urutan dan return step yang menandai node selesai
+fn link(node: &PlanNode, inherited: ExecutionMode, before: &[usize], dependencies: &mut [Vec<usize>]) -> Vec<usize> {
+    match node {
+        PlanNode::Step(index) => {
+            dependencies[*index].extend_from_slice(before);
+            vec![*index]
+        },
+        PlanNode::Group { execution, items } => {
+            let mode = execution.unwrap_or(inherited);
+            match mode {
+                ExecutionMode::Sequential => items.iter()
+                    .fold(before.to_vec(), |before, item| link(item, mode, &before, dependencies)),
+                ExecutionMode::Parallel if items.is_empty() => before.to_vec(),
+                ExecutionMode::Parallel => items.iter()
+                    .flat_map(|item| link(item, mode, before, dependencies))
+                    .collect(),
+            }
+        },
+    }
+}
+
+fn scripts(step: &RunStep) -> impl Iterator<Item = &String> {
+    step.pre_request.iter().chain(&step.test)
+}
+
+// Variabel yang dipakai request: template di URL, header, query, body dan auth, serta bacaan di script
+fn reads(step: &RunStep) -> HashSet<String> {
+    let params = serde_json::to_string(&step.params).unwrap_or_default();
+    let mut names: HashSet<String> = TEMPLATE_PATTERN.captures_iter(&params)
+        .map(|caps| caps[1].trim().to_string())
+        .collect();
+
+    for script in scripts(step) {
+        for pattern in [&*TEMPLATE_PATTERN, &*SCRIPT_READ_PATTERN] {
+            names.extend(pattern.captures_iter(script).map(|caps| caps[1].trim().to_string()));
+        }
+    }
+    names
+}
+
+// Variabel yang diisi request lewat capture atau script
+fn writes(step: &RunStep) -> HashSet<String> {
+    let mut names: HashSet<String> = step.captures.iter().map(|capture| capture.variable.clone()).collect();
+    for script in scripts(step) {
+        names.extend(SCRIPT_WRITE_PATTERN.captures_iter(script).map(|caps| caps[1].to_string()));
+    }
+    names
+}
@@ -0,0 +1,247 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use jsonschema::Draft;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use url::Url;
+
+// Versi JSON Schema yang didukung
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SchemaDraft {
+    #[serde(rename = "draft7")]
+    Draft7,
+    #[serde(rename = "2019-09")]
+    Draft201909,
+    #[serde(rename = "2020-12")]
+    Draft202012,
+}
+
+impl SchemaDraft {
+    fn to_draft(self) -> Draft {
+        match self {
+            SchemaDraft::Draft7 => Draft::Draft7,
+            SchemaDraft::Draft201909 => Draft::Draft201909,
+            SchemaDraft::Draft202012 => Draft::Draft202012,
+        }
+    }
+}
+
+// Sumber schema: inline atau file lokal
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SchemaSource {
+    Inline(Value),
+    File(PathBuf),
+}
+
+// JSON Schema untuk memvalidasi response.
+// Jika draft kosong, versi dideteksi dari "$schema" (default 2020-12).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonSchema {
+    pub source: SchemaSource,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub draft: Option<SchemaDraft>,
+}
+
+// Satu pelanggaran schema
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SchemaViolation {
+    // JSON Pointer ke nilai yang tidak valid, misalnya /items/0/id
+    pub instance_path: String,
+    // JSON Pointer ke keyword schema yang gagal, misalnya /properties/items/items/required
+    pub schema_path: String,
+    pub message: String,
+}
+
+impl SchemaViolation {
+    // Pelanggaran yang tidak terkait lokasi tertentu (schema tidak valid, body bukan JSON)
+    pub fn general(message: String) -> Self {
+        Self {
+            instance_path: String::new(),
+            schema_path: String::new(),
+            message,
+        }
+    }
+}
+
+impl From<Value> for JsonSchema {
+    fn from(schema: Value) -> Self {
+        JsonSchema::inline(schema)
+    }
+}
+
+impl JsonSchema {
+    pub fn inline(schema: Value) -> Self {
+        Self {
+            source: SchemaSource::Inline(schema),
+            draft: None,
+        }
+    }
+
+    pub fn file(path: &Path) -> Self {
+        Self {
+            source: SchemaSource::File(path.to_path_buf()),
+            draft: None,
+        }
+    }
+
+    pub fn with_draft(mut self, draft: SchemaDraft) -> Self {
+        self.draft = Some(draft);
+        self
+    }
+
+    // Memuat schema dan memberi "$id" file:// agar $ref relatif bisa dibaca dari disk.
+    // Schema inline memakai working directory sebagai basis $ref relatif.
+    fn load(&self) -> Result<Value, String> {
+        let (mut schema, location) = match &self.source {
+            SchemaSource::Inline(schema) => {
+                let cwd = std::env::current_dir()
+                    .map_err(|e| format!("Failed to resolve working directory: {}", e))?;
+                (schema.clone(), cwd.join("inline-schema.json"))
+            },
+            SchemaSource::File(path) => {
+                let content = fs::read_to_string(path)
+                    .map_err(|e| format!("Failed to read schema file {}: {}", path.display(), e))?;
+                let schema = serde_json::from_str(&content)
+                    .map_err(|e| format!("Failed to parse schema file {}: {}", path.display(), e))?;
+                let path = fs::canonicalize(path)
+                    .map_err(|e| format!("Failed to resolve schema file {}: {}", path.display(), e))?;
+                (schema, path)
+            },
+        };
+
+        if let Value::Object(obj) = &mut schema {
+            if !obj.contains_key("$id") && !obj.contains_key("id") {
+                if let Ok(uri) = Url::from_file_path(&location) {
+                    obj.insert("$id".to_string(), Value::String(uri.to_string()));
+                }
+            }
+        }
+
+        Ok(schema)
+    }
+
+    // Memvalidasi nilai JSON, mengembalikan semua pelanggaran (kosong jika valid)
+    pub fn validate(&self, instance: &Value) -> Result<Vec<SchemaViolation>, String> {
+        let schema = self.load()?;
+
+        let mut options = jsonschema::options();
+        if let Some(draft) = self.draft {
+            options.with_draft(draft.to_draft());
+        }
+
+        let validator = options
+            .build(&schema)
+            .map_err(|e| format!("Invalid JSON schema: {}", e))?;
+
+        Ok(validator
+            .iter_errors(instance)
+            .map(|error| SchemaViolation {
+                instance_path: error.instance_path.to_string(),
+                schema_path: error.schema_path.to_string(),
+                message: error.to_string(),
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("fmus-schema-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn inline_schema_reports_every_violation() {
+        let schema = JsonSchema::inline(json!({
+            "type": "object",
+            "required": ["items"],
+            "properties": {
+                "items": {"type": "array", "items": {"type": "object", "required": ["id"]}}
+            }
+        }));
+
+        assert!(schema.validate(&json!({"items": [{"id": 1}]})).unwrap().is_empty());
+
+        let violations = schema.validate(&json!({"items": [{"id": 1}, {}, {"name": "x"}]})).unwrap();
+        assert_eq!(violations.len(), 2);
+        assert_eq!(violations[0].instance_path, "/items/1");
+        assert_eq!(violations[0].schema_path, "/properties/items/items/required");
+        assert!(violations[0].message.contains("\"id\" is a required property"), "{}", violations[0].message);
+
+        let violations = schema.validate(&json!([])).unwrap();
+        assert_eq!(violations[0].instance_path, "");
+        assert!(violations[0].message.contains("is not of type \"object\""), "{}", violations[0].message);
+    }
+
+    #[test]
+    fn file_schema_gets_file_id_unless_it_has_one() {
+        let dir = temp_dir();
+        let path = dir.join("user.json");
+        fs::write(&path, r#"{"type": "object"}"#).unwrap();
+
+        let schema = JsonSchema::file(&path).load().unwrap();
+        let expected = Url::from_file_path(fs::canonicalize(&path).unwrap()).unwrap();
+        assert_eq!(schema["$id"], json!(expected.to_string()));
+
+        fs::write(&path, r#"{"$id": "https://example.com/user.json", "type": "object"}"#).unwrap();
+        let schema = JsonSchema::file(&path).load().unwrap();
+        assert_eq!(schema["$id"], json!("https://example.com/user.json"));
+
+        let inline = JsonSchema::inline(json!({"type": "object"})).load().unwrap();
+        assert!(inline["$id"].as_str().unwrap().ends_with("/inline-schema.json"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn relative_refs_resolve_between_files() {
+        let dir = temp_dir();
+        fs::create_dir_all(dir.join("defs")).unwrap();
+        fs::write(dir.join("defs").join("id.json"), r#"{"type": "integer", "minimum": 1}"#).unwrap();
+        fs::write(dir.join("order.json"), r#"{
+            "type": "object",
+            "properties": {"id": {"$ref": "defs/id.json"}}
+        }"#).unwrap();
+
+        let schema = JsonSchema::file(&dir.join("order.json"));
+        assert!(schema.validate(&json!({"id": 3})).unwrap().is_empty());
+
+        let violations = schema.validate(&json!({"id": 0})).unwrap();
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].instance_path, "/id");
+        assert!(violations[0].message.contains("less than the minimum"), "{}", violations[0].message);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn draft_can_be_forced() {
+        // Di draft 7 "dependentRequired" belum ada sehingga diabaikan
+        let schema = JsonSchema::inline(json!({"dependentRequired": {"a": ["b"]}}));
+        assert_eq!(schema.validate(&json!({"a": 1})).unwrap().len(), 1);
+        assert!(schema.with_draft(SchemaDraft::Draft7).validate(&json!({"a": 1})).unwrap().is_empty());
+    }
+
+    #[test]
+    fn loading_errors_name_the_problem() {
+        let dir = temp_dir();
+        let missing = JsonSchema::file(&dir.join("missing.json")).validate(&json!({})).unwrap_err();
+        assert!(missing.starts_with("Failed to read schema file"), "{}", missing);
+
+        fs::write(dir.join("broken.json"), "{not json").unwrap();
+        let broken = JsonSchema::file(&dir.join("broken.json")).validate(&json!({})).unwrap_err();
+        assert!(broken.starts_with("Failed to parse schema file"), "{}", broken);
+
+        let invalid = JsonSchema::inline(json!({"type": "banana"})).validate(&json!({})).unwrap_err();
+        assert!(invalid.starts_with("Invalid JSON schema"), "{}", invalid);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,575 @@
+// Boa 0.18 tidak punya interrupt, jadi script yang timeout tidak bisa dihentikan dari luar.
+// Sebagai gantinya panggilan tick disisipkan ke source script: di awal body function dan
+// arrow function, di awal block setelah `)` (if, loop, catch) dan di kondisi while/for.
+// Tick memeriksa deadline dan batas memori lalu melempar error yang tidak bisa di-catch.
+// Loop di luar itu (misalnya for-of tanpa kurung kurawal) tetap dibatasi
+// loop_iteration_limit per call frame.
+//
+// Sisipan ditentukan dari token, lalu diverifikasi: AST script yang sudah disisipi, setelah
+// semua tick dibuang, harus sama persis dengan AST script asli. Jika tokenizer salah membaca
+// script sehingga verifikasi gagal, script dijalankan tanpa tick.
+
+use std::convert::Infallible;
+use std::ops::ControlFlow;
+
+use boa_ast::expression::operator::binary::BinaryOp;
+use boa_ast::statement::iteration::ForLoop;
+use boa_ast::visitor::{VisitWith, VisitorMut};
+use boa_ast::{Expression, Script, Statement, StatementList, StatementListItem};
+use boa_interner::{Interner, Sym};
+use boa_parser::{Parser, Source};
+
+// Menyisipkan panggilan `tick()` ke script. None jika script tidak bisa di-parse (syntax error
+// dilaporkan oleh engine seperti biasa) atau jika sisipan tidak lolos verifikasi.
+pub(crate) fn instrument(script: &str, tick: &str) -> Option<String> {
+    let mut interner = Interner::default();
+    let original = parse(script, &mut interner).ok()?;
+
+    let instrumented = Tokens::new(script).insert_ticks(tick);
+    let mut stripped = parse(&instrumented, &mut interner).ok()?;
+    let mut stripper = TickStripper { tick: interner.get_or_intern(tick) };
+    let _ = stripper.visit_script_mut(&mut stripped);
+
+    (stripped == original).then_some(instrumented)
+}
+
+// true jika script mengakses property `name` (`a.name`, `a?.name`, `a["name"]`) atau memanggil
+// fungsi bernama `name`. Komentar dan string lain dilewati oleh tokenizer, jadi tidak ikut terhitung.
+pub(crate) fn uses_property(script: &str, name: &str) -> bool {
+    let tokens = Tokens::new(script);
+    (0..tokens.tokens.len()).any(|i| match tokens.tokens[i].kind {
+        Kind::Ident => {
+            tokens.text(i) == name
+                && ((i > 0 && (tokens.is_punct(i - 1, ".") || tokens.is_punct(i - 1, "?."))) || tokens.is_punct(i + 1, "("))
+        },
+        Kind::Str => {
+            let text = tokens.text(i);
+            text.len() >= 2
+                && &text[1..text.len() - 1] == name
+                && i > 0
+                && tokens.is_punct(i - 1, "[")
+                && tokens.is_punct(i + 1, "]")
+        },
+        _ => false,
+    })
+}
+
+fn parse(script: &str, interner: &mut Interner) -> Result<Script, boa_parser::Error> {
+    Parser::new(Source::from_bytes(script)).parse_script(interner)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Kind {
+    Ident,
+    Punct,
+    Str,
+    Num,
+    Regex,
+    // Bagian template literal; `open` jika diakhiri `${`
+    Template { open: bool },
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Token {
+    kind: Kind,
+    start: usize,
+    end: usize,
+    newline_before: bool,
+}
+
+// Kata kunci yang boleh diikuti regex literal, bukan operator bagi
+const REGEX_AFTER: &[&str] = &[
+    "return", "typeof", "instanceof", "in", "of", "new", "delete", "void", "throw", "case", "do", "else", "yield", "await",
+];
+
+struct Tokens<'a> {
+    source: &'a str,
+    tokens: Vec<Token>,
+}
+
+impl<'a> Tokens<'a> {
+    // Tokenizer sederhana yang cukup untuk menemukan posisi sisipan. Regex dan pembagian dibedakan
+    // dari token sebelumnya; salah tebak di kasus langka tertangkap oleh verifikasi AST.
+    fn new(source: &'a str) -> Self {
+        let bytes = source.as_bytes();
+        let mut tokens = Tokens { source, tokens: Vec::new() };
+        // true untuk `${` template, false untuk `{` biasa
+        let mut braces: Vec<bool> = Vec::new();
+        let mut newline = false;
+        let mut i = 0;
+
+        while let Some(c) = source[i..].chars().next() {
+            let start = i;
+            if c.is_whitespace() {
+                newline |= matches!(c, '\n' | '\r' | '\u{2028}' | '\u{2029}');
+                i += c.len_utf8();
+                continue;
+            }
+            if source[i..].starts_with("//") {
+                i = source[i..].find(['\n', '\r', '\u{2028}', '\u{2029}']).map_or(bytes.len(), |n| i + n);
+                continue;
+            }
+            if source[i..].starts_with("/*") {
+                let end = source[i + 2..].find("*/").map_or(bytes.len(), |n| i + 2 + n + 2);
+                newline |= source[i..end].contains(['\n', '\r', '\u{2028}', '\u{2029}']);
+                i = end;
+                continue;
+            }
+
+            let kind = match c {
+                '\'' | '"' => {
+                    i = skip_string(bytes, i + 1, c as u8);
+                    Kind::Str
+                },
+                '`' => {
+                    let (end, open) = skip_template(bytes, i + 1);
+                    if open {
+                        braces.push(true);
+                    }
+                    i = end;
+                    Kind::Template { open }
+                },
+                '}' if braces.last() == Some(&true) => {
+                    braces.pop();
+                    let (end, open) = skip_template(bytes, i + 1);
+                    if open {
+                        braces.push(true);
+                    }
+                    i = end;
+                    Kind::Template { open }
+                },
+                '0'..='9' => {
+                    i = skip_number(bytes, i);
+                    Kind::Num
+                },
+                '.' if bytes.get(i + 1).is_some_and(u8::is_ascii_digit) => {
+                    i = skip_number(bytes, i);
+                    Kind::Num
+                },
+                '/' if tokens.regex_allowed() => {
+                    i = skip_regex(bytes, i + 1);
+                    Kind::Regex
+                },
+                '$' | '_' | '#' | '\\' => {
+                    i = skip_ident(source, i + 1);
+                    Kind::Ident
+                },
+                _ if c.is_alphabetic() => {
+                    i = skip_ident(source, i + c.len_utf8());
+                    Kind::Ident
+                },
+                _ => {
+                    i += punct_len(&source[i..]);
+                    match c {
+                        '{' => braces.push(false),
+                        '}' => {
+                            braces.pop();
+                        },
+                        _ => {},
+                    }
+                    Kind::Punct
+                },
+            };
+
+            // Posisi selalu di batas karakter, juga untuk escape yang tidak lengkap
+            while !source.is_char_boundary(i) {
+                i += 1;
+            }
+            tokens.tokens.push(Token { kind, start, end: i, newline_before: newline });
+            newline = false;
+        }
+
+        tokens
+    }
+
+    fn text(&self, i: usize) -> &'a str {
+        self.tokens.get(i).map_or("", |t| &self.source[t.start..t.end])
+    }
+
+    fn is_punct(&self, i: usize, punct: &str) -> bool {
+        self.tokens.get(i).is_some_and(|t| t.kind == Kind::Punct) && self.text(i) == punct
+    }
+
+    // Kata kunci, bukan nama property setelah `.` atau `?.`
+    fn is_keyword(&self, i: usize, keyword: &str) -> bool {
+        self.tokens.get(i).is_some_and(|t| t.kind == Kind::Ident)
+            && self.text(i) == keyword
+            && !(i > 0 && (self.is_punct(i - 1, ".") || self.is_punct(i - 1, "?.")))
+    }
+
+    fn opens(&self, i: usize) -> bool {
+        match self.tokens[i].kind {
+            Kind::Punct => matches!(self.text(i), "(" | "[" | "{"),
+            Kind::Template { open } => open,
+            _ => false,
+        }
+    }
+
+    fn closes(&self, i: usize) -> bool {
+        match self.tokens[i].kind {
+            Kind::Punct => matches!(self.text(i), ")" | "]" | "}"),
+            Kind::Template { .. } => !self.text(i).starts_with('`'),
+            _ => false,
+        }
+    }
+
+    fn regex_allowed(&self) -> bool {
+        let Some(previous) = self.tokens.last() else {
+            return true;
+        };
+        let text = self.text(self.tokens.len() - 1);
+        match previous.kind {
+            Kind::Punct => !matches!(text, ")" | "]" | "}" | "++" | "--"),
+            Kind::Ident => REGEX_AFTER.contains(&text),
+            Kind::Template { open } => open,
+            Kind::Str | Kind::Num | Kind::Regex => false,
+        }
+    }
+
+    // Menentukan posisi sisipan dan menyusun source baru
+    fn insert_ticks(&self, tick: &str) -> String {
+        let call = format!("{}()", tick);
+        let mut inserts: Vec<(usize, String)> = Vec::new();
+        let mut depth = 0usize;
+        // Kedalaman kurung saat `class` muncul; `{` pertama di kedalaman itu adalah body class
+        let mut classes: Vec<usize> = Vec::new();
+
+        for i in 0..self.tokens.len() {
+            if self.closes(i) {
+                depth = depth.saturating_sub(1);
+            }
+
+            if self.is_punct(i, "{") {
+                let after_head = i > 0 && (self.is_punct(i - 1, ")") || self.is_punct(i - 1, "=>"));
+                // Body switch dan body kosong tidak disisipi
+                let skip = self.is_keyword(i + 1, "case") || self.is_keyword(i + 1, "default") || self.is_punct(i + 1, "}");
+                if classes.last() == Some(&depth) {
+                    classes.pop();
+                } else if after_head && !skip {
+                    inserts.push(self.body_insert(i + 1, &call));
+                }
+            } else if self.is_punct(i, "=>") && i + 1 < self.tokens.len() && !self.is_punct(i + 1, "{") {
+                // Body ekspresi: `x => expr` menjadi `x => tick() ? 0 : expr`
+                inserts.push((self.tokens[i + 1].start, format!("{} ? 0 : ", call)));
+            } else if self.is_keyword(i, "class") {
+                if !(self.is_punct(i + 1, ":") || self.is_punct(i + 1, "(") || self.is_punct(i + 1, ",")) {
+                    classes.push(depth);
+                }
+            } else if self.is_keyword(i, "while") && self.is_punct(i + 1, "(") {
+                inserts.push((self.tokens[i + 1].end, format!("{}, ", call)));
+            } else if self.is_keyword(i, "for") && self.is_punct(i + 1, "(") {
+                inserts.extend(self.for_insert(i + 1, &call));
+            }
+
+            if self.opens(i) {
+                depth += 1;
+            }
+        }
+
+        let mut output = String::with_capacity(self.source.len() + inserts.len() * (call.len() + 8));
+        let mut last = 0;
+        inserts.sort_by_key(|(position, _)| *position);
+        for (position, insert) in inserts {
+            output.push_str(&self.source[last..position]);
+            output.push_str(&insert);
+            last = position;
+        }
+        output.push_str(&self.source[last..]);
+        output
+    }
+
+    // Sisipan di awal body, setelah directive prologue seperti "use strict"
+    fn body_insert(&self, mut i: usize, call: &str) -> (usize, String) {
+        let mut position = self.tokens[i - 1].end;
+        while let Some(token) = self.tokens.get(i).filter(|t| t.kind == Kind::Str) {
+            let next = self.tokens.get(i + 1);
+            if self.is_punct(i + 1, ";") {
+                position = self.tokens[i + 1].end;
+                i += 2;
+            } else if self.is_punct(i + 1, "}")
+                || next.is_some_and(|next| next.newline_before && matches!(next.kind, Kind::Ident | Kind::Str | Kind::Num))
+            {
+                return (token.end, format!(";{};", call));
+            } else {
+                break;
+            }
+        }
+        (position, format!("{};", call))
+    }
+
+    // Tick di kondisi for klasik, atau di bagian update jika tanpa kondisi.
+    // for-in dan for-of tidak disisipi.
+    fn for_insert(&self, open: usize, call: &str) -> Option<(usize, String)> {
+        let mut depth = 0usize;
+        let mut semicolons = Vec::new();
+        let mut close = None;
+        for i in open..self.tokens.len() {
+            if self.closes(i) {
+                depth -= 1;
+                if depth == 0 {
+                    close = Some(i);
+                    break;
+                }
+            } else if depth == 1 && self.is_punct(i, ";") {
+                semicolons.push(i);
+            }
+            if self.opens(i) {
+                depth += 1;
+            }
+        }
+
+        let close = close?;
+        let [first, second] = semicolons[..] else {
+            return None;
+        };
+        if second > first + 1 {
+            Some((self.tokens[first + 1].start, format!("{}, ", call)))
+        } else if close > second + 1 {
+            Some((self.tokens[second + 1].start, format!("{}, ", call)))
+        } else {
+            Some((self.tokens[close].start, call.to_string()))
+        }
+    }
+}
+
+fn skip_string(bytes: &[u8], mut i: usize, quote: u8) -> usize {
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            c if c == quote => return i + 1,
+            _ => i += 1,
+        }
+    }
+    bytes.len()
+}
+
+// Membaca template sampai backtick penutup atau `${`
+fn skip_template(bytes: &[u8], mut i: usize) -> (usize, bool) {
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b'`' => return (i + 1, false),
+            b'$' if bytes.get(i + 1) == Some(&b'{') => return (i + 2, true),
+            _ => i += 1,
+        }
+    }
+    (bytes.len(), false)
+}
+
+fn skip_regex(bytes: &[u8], mut i: usize) -> usize {
+    let mut in_class = false;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 1,
+            b'[' => in_class = true,
+            b']' => in_class = false,
+            b'/' if !in_class => {
+                i += 1;
+                break;
+            },
+            b'\n' | b'\r' => break,
+            _ => {},
+        }
+        i += 1;
+    }
+    while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_' || bytes[i] == b'$') {
+        i += 1;
+    }
+    i.min(bytes.len())
+}
+
+fn skip_number(bytes: &[u8], mut i: usize) -> usize {
+    let hex = bytes[i] == b'0' && matches!(bytes.get(i + 1), Some(b'x' | b'X'));
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' | b'-' if !hex && matches!(bytes[i - 1], b'e' | b'E') => i += 1,
+            c if c.is_ascii_alphanumeric() || c == b'_' || c == b'.' => i += 1,
+            _ => break,
+        }
+    }
+    i
+}
+
+// Sisa identifier setelah karakter pertama, termasuk escape seperti a dan \u{61}
+fn skip_ident(source: &str, mut i: usize) -> usize {
+    if source[..i].ends_with('\\') {
+        i = skip_unicode_escape(source, i);
+    }
+    while let Some(c) = source.get(i..).and_then(|rest| rest.chars().next()) {
+        if c == '\\' {
+            i = skip_unicode_escape(source, i + 1);
+        } else if c == '$' || c == '_' || c.is_alphanumeric() || c == '\u{200c}' || c == '\u{200d}' {
+            i += c.len_utf8();
+        } else {
+            break;
+        }
+    }
+    i
+}
+
+fn skip_unicode_escape(source: &str, i: usize) -> usize {
+    match source.get(i..) {
+        Some(rest) if rest.starts_with("u{") => rest.find('}').map_or(source.len(), |n| i + n + 1),
+        _ => (i + 5).min(source.len()),
+    }
+}
+
+fn punct_len(rest: &str) -> usize {
+    for punct in ["...", "=>", "++", "--"] {
+        if rest.starts_with(punct) {
+            return punct.len();
+        }
+    }
+    if rest.starts_with("?.") && !rest.as_bytes().get(2).is_some_and(u8::is_ascii_digit) {
+        return 2;
+    }
+    rest.chars().next().map_or(1, char::len_utf8)
+}
+
+// Membuang semua tick dari AST, kebalikan dari insert_ticks
+struct TickStripper {
+    tick: Sym,
+}
+
+impl TickStripper {
+    fn is_tick(&self, expression: &Expression) -> bool {
+        match expression {
+            Expression::Call(call) => call.args().is_empty()
+                && matches!(call.function(), Expression::Identifier(ident) if ident.sym() == self.tick),
+            _ => false,
+        }
+    }
+
+    fn strip_list(&self, list: &mut StatementList) {
+        let is_tick = |item: &StatementListItem| {
+            matches!(item, StatementListItem::Statement(Statement::Expression(expression)) if self.is_tick(expression))
+        };
+        if list.statements().iter().any(is_tick) {
+            let items: Vec<_> = list.statements().iter().filter(|item| !is_tick(item)).cloned().collect();
+            *list = StatementList::new(items, list.strict());
+        }
+    }
+}
+
+impl<'ast> VisitorMut<'ast> for TickStripper {
+    type BreakTy = Infallible;
+
+    fn visit_script_mut(&mut self, node: &'ast mut Script) -> ControlFlow<Infallible> {
+        self.strip_list(node.statements_mut());
+        node.visit_with_mut(self)
+    }
+
+    fn visit_statement_list_mut(&mut self, node: &'ast mut StatementList) -> ControlFlow<Infallible> {
+        self.strip_list(node);
+        node.visit_with_mut(self)
+    }
+
+    fn visit_expression_mut(&mut self, node: &'ast mut Expression) -> ControlFlow<Infallible> {
+        loop {
+            let replacement = match &*node {
+                Expression::Conditional(conditional) if self.is_tick(conditional.condition()) => {
+                    conditional.if_false().clone()
+                },
+                Expression::Binary(binary) if binary.op() == BinaryOp::Comma && self.is_tick(binary.lhs()) => {
+                    binary.rhs().clone()
+                },
+                _ => break,
+            };
+            *node = replacement;
+        }
+        node.visit_with_mut(self)
+    }
+
+    fn visit_for_loop_mut(&mut self, node: &'ast mut ForLoop) -> ControlFlow<Infallible> {
+        if node.final_expr().is_some_and(|expression| self.is_tick(expression)) {
+            *node = ForLoop::new(node.init().cloned(), node.condition().cloned(), None, node.body().clone());
+        }
+        node.visit_with_mut(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TICK: &str = "__tick";
+
+    fn instrumented(script: &str) -> String {
+        instrument(script, TICK).unwrap()
+    }
+
+    #[test]
+    fn property_use_ignores_comments_and_strings() {
+        let uses = |script: &str| uses_property(script, "setNextRequest");
+        assert!(uses("pm.execution.setNextRequest('next');"));
+        assert!(uses("postman.setNextRequest(null)"));
+        assert!(uses("pm.execution['setNextRequest']('next')"));
+        assert!(uses("pm?.execution?.setNextRequest"));
+        // Fungsi hasil destructuring dipanggil langsung
+        assert!(uses("const { setNextRequest } = pm.execution; setNextRequest('next');"));
+
+        assert!(!uses("// pm.execution.setNextRequest('next')"));
+        assert!(!uses("/* setNextRequest(null) */ pm.test('ok', () => {});"));
+        assert!(!uses("console.log('call pm.execution.setNextRequest(name) to jump');"));
+        assert!(!uses("var help = `setNextRequest(${name})`;"));
+        assert!(!uses("var setNextRequestCount = 1; obj.setNextRequests = 2;"));
+    }
+
+    #[test]
+    fn ticks_function_bodies_and_loop_conditions() {
+        let script = "function f(a) { return a; }\nwhile (x) y();\nfor (let i = 0; i < 3; i++) {}\nconst g = (a) => a + 1;";
+        assert_eq!(
+            instrumented(script),
+            "function f(a) {__tick(); return a; }\nwhile (__tick(), x) y();\nfor (let i = 0; __tick(), i < 3; i++) {}\nconst g = (a) => __tick() ? 0 : a + 1;",
+        );
+    }
+
+    #[test]
+    fn keeps_directives_first() {
+        assert_eq!(
+            instrumented("function f() { 'use strict'; return 1; }"),
+            "function f() { 'use strict';__tick(); return 1; }",
+        );
+        assert_eq!(
+            instrumented("function f() { \"use strict\"\n return 1; }"),
+            "function f() { \"use strict\";__tick();\n return 1; }",
+        );
+    }
+
+    #[test]
+    fn handles_empty_for_and_switch() {
+        assert_eq!(instrumented("for (;;) break;"), "for (;;__tick()) break;");
+        assert_eq!(instrumented("for (;; i++) break;"), "for (;; __tick(), i++) break;");
+        assert_eq!(instrumented("switch (x) { case 1: break; }"), "switch (x) { case 1: break; }");
+        assert_eq!(instrumented("for (const k in o) {}"), "for (const k in o) {}");
+    }
+
+    #[test]
+    fn leaves_strings_templates_and_regexes_alone() {
+        let script = "const s = 'a) { b', t = `x${ (() => 1)() }) {`, r = /\\) {/g; function f() { return `}`; }";
+        let result = instrumented(script);
+        assert!(result.contains("'a) { b'"));
+        assert!(result.contains("/\\) {/g"));
+        assert!(result.contains("(() => __tick() ? 0 : 1)()"));
+        assert!(result.contains("function f() {__tick(); return `}`; }"));
+    }
+
+    #[test]
+    fn skips_class_bodies_with_call_heritage() {
+        let result = instrumented("class A extends mixin(B) { m() { return 1; } }");
+        assert_eq!(result, "class A extends mixin(B) { m() {__tick(); return 1; } }");
+    }
+
+    #[test]
+    fn verifies_larger_scripts() {
+        let instrumented = instrument(crate::scripting::PRELUDE, TICK).unwrap();
+        assert!(instrumented.matches("__tick()").count() > 50);
+    }
+
+    #[test]
+    fn rejects_invalid_or_misread_scripts() {
+        assert_eq!(instrument("function (", TICK), None);
+        // `/` setelah `)` dibaca sebagai pembagian, sehingga tick masuk ke dalam regex
+        assert_eq!(instrument("if (a) /(x) {/.test(s);", TICK), None);
+    }
+}
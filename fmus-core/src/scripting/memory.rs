@@ -0,0 +1,56 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+thread_local! {
+    // Selisih byte yang dialokasikan dan dibebaskan oleh thread ini
+    static ALLOCATED: Cell<isize> = const { Cell::new(0) };
+}
+
+// Allocator global yang mencatat memori per thread, dipakai untuk membatasi memori script.
+// Binary yang menjalankan script memasangnya sendiri:
+//
+//     #[global_allocator]
+//     static ALLOCATOR: fmus_core::scripting::ScriptAllocator = fmus_core::scripting::ScriptAllocator;
+//
+// Tanpa allocator ini batas memori script tidak berlaku, batas lain tetap berlaku.
+pub struct ScriptAllocator;
+
+fn track(delta: isize) {
+    let _ = ALLOCATED.try_with(|allocated| allocated.set(allocated.get().wrapping_add(delta)));
+}
+
+// Jumlah byte yang sedang dialokasikan oleh thread saat ini
+pub(crate) fn allocated() -> isize {
+    ALLOCATED.try_with(Cell::get).unwrap_or(0)
+}
+
+unsafe impl GlobalAlloc for ScriptAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            track(layout.size() as isize);
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            track(layout.size() as isize);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        track(-(layout.size() as isize));
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            track(new_size as isize - layout.size() as isize);
+        }
+        new_ptr
+    }
+}
@@ -0,0 +1,661 @@
+mod instrument;
+mod memory;
+
+pub use memory::ScriptAllocator;
+
+use std::collections::HashMap;
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use boa_engine::context::{ContextBuilder, HostHooks};
+use boa_engine::object::FunctionObjectBuilder;
+use boa_engine::property::Attribute;
+use boa_engine::realm::Realm;
+use boa_engine::{js_string, Context, JsNativeError, JsResult, JsString, JsValue, NativeFunction, Source};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::http::{HttpResponse, RequestParams};
+
+// API `pm` yang dijalankan sebelum script pengguna
+const PRELUDE: &str = include_str!("prelude.js");
+
+// Waktu tunggu tambahan setelah timeout agar error dari tick sempat diterima
+const TIMEOUT_GRACE: Duration = Duration::from_millis(100);
+
+static VARIABLE_PATTERN: Lazy<Regex> = Lazy::new(|| Regex::new(r"\{\{([^{}]+)\}\}").unwrap());
+
+// Script pre-request dan test milik request, folder atau collection
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Scripts {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pre_request: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub test: Option<String>,
+}
+
+impl Scripts {
+    pub fn is_empty(&self) -> bool {
+        self.pre_request.as_deref().unwrap_or("").trim().is_empty()
+            && self.test.as_deref().unwrap_or("").trim().is_empty()
+    }
+}
+
+// Batas eksekusi script.
+// Timeout dan max_memory diperiksa oleh tick yang disisipkan ke script (lihat instrument.rs),
+// max_memory hanya berlaku jika binary memasang ScriptAllocator.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScriptLimits {
+    pub timeout_ms: u64,
+    pub loop_iteration_limit: u64,
+    pub recursion_limit: usize,
+    pub stack_size_limit: usize,
+    pub max_script_size: usize,
+    // Batas memori yang dialokasikan selama script berjalan, dalam byte
+    pub max_memory: usize,
+}
+
+impl Default for ScriptLimits {
+    fn default() -> Self {
+        Self {
+            timeout_ms: 5_000,
+            loop_iteration_limit: 1_000_000,
+            recursion_limit: 512,
+            stack_size_limit: 64 * 1024,
+            max_script_size: 256 * 1024,
+            max_memory: 256 * 1024 * 1024,
+        }
+    }
+}
+
+// Variabel yang bisa dibaca dan diubah oleh script
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScriptScope {
+    #[serde(default)]
+    pub environment: HashMap<String, String>,
+    #[serde(default)]
+    pub collection_variables: HashMap<String, String>,
+    // Variabel lokal yang hanya berlaku selama satu run
+    #[serde(default)]
+    pub variables: HashMap<String, String>,
+    // Baris data file untuk iterasi saat ini
+    #[serde(default)]
+    pub iteration_data: HashMap<String, Value>,
+    // pm.globals, prioritas paling rendah
+    #[serde(default)]
+    pub globals: HashMap<String, String>,
+}
+
+impl ScriptScope {
+    // Nilai variabel dengan prioritas sama seperti Postman: local > data > environment > collection > global
+    pub fn resolve(&self, key: &str) -> Option<String> {
+        self.variables.get(key)
+            .cloned()
+            .or_else(|| self.iteration_data.get(key).map(|value| match value {
+                Value::String(text) => text.clone(),
+                other => other.to_string(),
+            }))
+            .or_else(|| self.environment.get(key).cloned())
+            .or_else(|| self.collection_variables.get(key).cloned())
+            .or_else(|| self.globals.get(key).cloned())
+    }
+
+    // Mengganti {{variabel}} di template, variabel yang tidak dikenal dibiarkan apa adanya
+    pub fn replace_in(&self, template: &str) -> String {
+        VARIABLE_PATTERN
+            .replace_all(template, |caps: &regex::Captures| {
+                self.resolve(caps[1].trim()).unwrap_or_else(|| caps[0].to_string())
+            })
+            .into_owned()
+    }
+
+    // Mengganti {{variabel}} di URL, header, query dan body request
+    pub fn apply_to(&self, mut params: RequestParams) -> RequestParams {
+        params.url = self.replace_in(&params.url);
+        for values in [&mut params.headers, &mut params.params].into_iter().flatten() {
+            for value in values.values_mut() {
+                *value = self.replace_in(value);
+            }
+        }
+        params.body = params.body.map(|body| self.replace_in_value(body));
+        params
+    }
+
+    // Mengganti {{variabel}} hanya di string dan key object, sehingga nilai berisi `"` atau `\`
+    // tidak merusak struktur JSON body
+    fn replace_in_value(&self, value: Value) -> Value {
+        match value {
+            Value::String(text) => Value::String(self.replace_in(&text)),
+            Value::Array(items) => Value::Array(items.into_iter().map(|item| self.replace_in_value(item)).collect()),
+            Value::Object(map) => Value::Object(map.into_iter()
+                .map(|(key, value)| (self.replace_in(&key), self.replace_in_value(value)))
+                .collect()),
+            other => other,
+        }
+    }
+}
+
+// Informasi request yang sedang dijalankan, tersedia sebagai pm.info
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScriptInfo {
+    pub request_name: String,
+    #[serde(default)]
+    pub request_id: String,
+    #[serde(default)]
+    pub iteration: usize,
+    #[serde(default = "default_iteration_count")]
+    pub iteration_count: usize,
+}
+
+fn default_iteration_count() -> usize {
+    1
+}
+
+impl Default for ScriptInfo {
+    fn default() -> Self {
+        Self {
+            request_name: String::new(),
+            request_id: String::new(),
+            iteration: 0,
+            iteration_count: default_iteration_count(),
+        }
+    }
+}
+
+// Hasil satu pm.test
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScriptTestResult {
+    pub name: String,
+    pub passed: bool,
+    #[serde(default)]
+    pub error: Option<String>,
+    #[serde(default)]
+    pub skipped: bool,
+}
+
+// Satu baris output console.*
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScriptLog {
+    pub level: String,
+    pub message: String,
+}
+
+// Request berikutnya yang diminta lewat pm.execution.setNextRequest
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NextRequest {
+    Name(String),
+    // setNextRequest(null) menghentikan run
+    Stop,
+}
+
+// Hasil eksekusi script
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScriptOutcome {
+    // Request setelah diubah oleh pre-request script
+    pub request: RequestParams,
+    // Variabel setelah script selesai
+    pub scope: ScriptScope,
+    // Perubahan yang perlu ditulis balik: Some(nilai) untuk set, None untuk unset
+    pub environment_changes: HashMap<String, Option<String>>,
+    pub collection_changes: HashMap<String, Option<String>>,
+    pub tests: Vec<ScriptTestResult>,
+    pub logs: Vec<ScriptLog>,
+    pub next_request: Option<NextRequest>,
+    // Error di luar pm.test: syntax error, exception, timeout atau batas eksekusi
+    pub error: Option<String>,
+}
+
+impl ScriptOutcome {
+    pub(crate) fn unchanged(request: &RequestParams, scope: &ScriptScope, error: Option<String>) -> Self {
+        Self {
+            request: request.clone(),
+            scope: scope.clone(),
+            environment_changes: HashMap::new(),
+            collection_changes: HashMap::new(),
+            tests: Vec::new(),
+            logs: Vec::new(),
+            next_request: None,
+            error,
+        }
+    }
+
+    // Script sukses jika tidak ada error dan semua pm.test lulus
+    pub fn passed(&self) -> bool {
+        self.error.is_none() && self.tests.iter().all(|test| test.passed)
+    }
+}
+
+// Output mentah dari prelude.js
+#[derive(Deserialize)]
+struct RawOutput {
+    request: RawRequest,
+    environment: HashMap<String, String>,
+    collection_variables: HashMap<String, String>,
+    variables: HashMap<String, String>,
+    globals: HashMap<String, String>,
+    environment_changes: HashMap<String, Option<String>>,
+    collection_changes: HashMap<String, Option<String>>,
+    tests: Vec<ScriptTestResult>,
+    logs: Vec<ScriptLog>,
+    next_request: Option<RawNextRequest>,
+    error: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RawRequest {
+    url: String,
+    method: String,
+    headers: HashMap<String, String>,
+    params: HashMap<String, String>,
+    body: String,
+}
+
+#[derive(Deserialize)]
+struct RawNextRequest {
+    name: Option<String>,
+    #[serde(default)]
+    stop: bool,
+}
+
+// true jika script memakai pm.execution.setNextRequest (atau postman.setNextRequest) di luar komentar dan string
+pub fn uses_set_next_request(script: &str) -> bool {
+    instrument::uses_property(script, "setNextRequest")
+}
+
+// Menjalankan pre-request script. Perubahan pada pm.request dikembalikan di outcome.request.
+pub fn run_pre_request(
+    script: &str,
+    request: &RequestParams,
+    scope: &ScriptScope,
+    info: &ScriptInfo,
+    limits: &ScriptLimits,
+) -> ScriptOutcome {
+    run(script, "prerequest", request, None, scope, info, limits)
+}
+
+// Menjalankan test script terhadap response
+pub fn run_test(
+    script: &str,
+    request: &RequestParams,
+    response: &HttpResponse,
+    scope: &ScriptScope,
+    info: &ScriptInfo,
+    limits: &ScriptLimits,
+) -> ScriptOutcome {
+    run(script, "test", request, Some(response), scope, info, limits)
+}
+
+fn run(
+    script: &str,
+    event_name: &str,
+    request: &RequestParams,
+    response: Option<&HttpResponse>,
+    scope: &ScriptScope,
+    info: &ScriptInfo,
+    limits: &ScriptLimits,
+) -> ScriptOutcome {
+    if script.trim().is_empty() {
+        return ScriptOutcome::unchanged(request, scope, None);
+    }
+
+    if script.len() > limits.max_script_size {
+        let error = format!("Script is too large: {} bytes (limit {} bytes)", script.len(), limits.max_script_size);
+        return ScriptOutcome::unchanged(request, scope, Some(error));
+    }
+
+    let input = json!({
+        "request": {
+            "url": request.url,
+            "method": request.method,
+            "headers": request.headers,
+            "params": request.params,
+            "body": request.body,
+        },
+        "response": response.map(response_input),
+        "scope": scope,
+        "info": {
+            "event_name": event_name,
+            "request_name": info.request_name,
+            "request_id": info.request_id,
+            "iteration": info.iteration,
+            "iteration_count": info.iteration_count,
+        },
+    });
+
+    // Context Boa tidak Send, jadi dibuat di thread sendiri. Thread berhenti sendiri lewat tick
+    // saat deadline lewat; waktu tunggu di sini sedikit lebih lama agar error dari tick yang sampai.
+    // Boa tidak bisa dihentikan dari luar: jika tick tidak tercapai (panggilan native yang lama atau
+    // script yang berjalan tanpa tick), thread dibiarkan berjalan sampai selesai atau terkena
+    // loop_iteration_limit, dan hasilnya dibuang.
+    let (sender, receiver) = mpsc::channel();
+    let script = script.to_string();
+    let thread_limits = limits.clone();
+    let timeout = Duration::from_millis(limits.timeout_ms);
+    let deadline = Instant::now() + timeout;
+    let spawned = thread::Builder::new()
+        .name("fmus-script".to_string())
+        .spawn(move || {
+            let _ = sender.send(execute(&script, &input, &thread_limits, deadline));
+        });
+
+    if let Err(e) = spawned {
+        return ScriptOutcome::unchanged(request, scope, Some(format!("Failed to start script runtime: {}", e)));
+    }
+
+    let raw = match receiver.recv_timeout(timeout + TIMEOUT_GRACE) {
+        Ok(Ok(raw)) => raw,
+        Ok(Err(e)) => return ScriptOutcome::unchanged(request, scope, Some(e)),
+        Err(_) => {
+            let error = format!("Script timed out after {} ms", limits.timeout_ms);
+            return ScriptOutcome::unchanged(request, scope, Some(error));
+        },
+    };
+
+    build_outcome(raw, request, scope)
+}
+
+// Host hooks yang menolak eval dan new Function, karena source dari sana tidak disisipi tick
+struct ScriptHooks;
+
+impl HostHooks for ScriptHooks {
+    fn ensure_can_compile_strings(
+        &self,
+        _realm: Realm,
+        _parameters: &[JsString],
+        _body: &JsString,
+        _direct: bool,
+        _context: &mut Context,
+    ) -> JsResult<()> {
+        Err(JsNativeError::eval().with_message("eval and new Function are not available in scripts").into())
+    }
+}
+
+static SCRIPT_HOOKS: ScriptHooks = ScriptHooks;
+
+// Menjalankan prelude dan script pengguna, lalu membaca output dari function finish
+fn execute(script: &str, input: &Value, limits: &ScriptLimits, deadline: Instant) -> Result<RawOutput, String> {
+    // Nama tick acak agar tidak bentrok dengan identifier di script
+    let tick = format!("__fmus_tick_{}", uuid::Uuid::new_v4().simple());
+    // Tanpa tick, timeout hanya ditegakkan oleh recv_timeout di run() dan batas loop/rekursi Boa
+    let script = instrument::instrument(script, &tick).unwrap_or_else(|| script.to_string());
+
+    let mut context = ContextBuilder::new()
+        .host_hooks(&SCRIPT_HOOKS)
+        .build()
+        .map_err(|e| format!("Failed to initialize script runtime: {}", e))?;
+    context.runtime_limits_mut().set_loop_iteration_limit(limits.loop_iteration_limit);
+    context.runtime_limits_mut().set_recursion_limit(limits.recursion_limit);
+    context.runtime_limits_mut().set_stack_size_limit(limits.stack_size_limit);
+
+    // Memori dihitung relatif terhadap kondisi setelah context dibuat
+    let memory_start = memory::allocated();
+    let timeout_ms = limits.timeout_ms;
+    let max_memory = limits.max_memory;
+    let check = move |_: &JsValue, _: &[JsValue], _: &mut Context| -> JsResult<JsValue> {
+        // Error runtime_limit tidak bisa di-catch oleh try/catch di script
+        if Instant::now() >= deadline {
+            return Err(JsNativeError::runtime_limit()
+                .with_message(format!("Script timed out after {} ms", timeout_ms))
+                .into());
+        }
+        if memory::allocated().saturating_sub(memory_start) > max_memory as isize {
+            return Err(JsNativeError::runtime_limit()
+                .with_message(format!("Script exceeded the memory limit of {} bytes", max_memory))
+                .into());
+        }
+        Ok(JsValue::undefined())
+    };
+    let check = FunctionObjectBuilder::new(context.realm(), NativeFunction::from_copy_closure(check)).build();
+    // Tidak writable dan tidak configurable agar tidak bisa ditimpa script
+    context
+        .register_global_property(js_string!(tick.as_str()), check, Attribute::empty())
+        .map_err(|e| format!("Failed to initialize script runtime: {}", e))?;
+
+    let finish = context
+        .eval(Source::from_bytes(PRELUDE))
+        .and_then(|prelude| {
+            let prelude = prelude
+                .as_callable()
+                .cloned()
+                .ok_or_else(|| JsNativeError::typ().with_message("prelude is not a function"))?;
+            let input = JsValue::from_json(input, &mut context)?;
+            prelude.call(&JsValue::undefined(), &[input], &mut context)
+        })
+        .map_err(|e| format!("Failed to initialize script runtime: {}", e))?;
+    let finish = finish
+        .as_callable()
+        .cloned()
+        .ok_or_else(|| "Failed to initialize script runtime: prelude did not return a function".to_string())?;
+
+    // Error dari script dicatat, tapi hasil pm.test dan perubahan variabel sebelumnya tetap dipakai
+    let script_error = context
+        .eval(Source::from_bytes(&script))
+        .err()
+        .map(|e| format!("Script error: {}", e));
+
+    let output = finish
+        .call(&JsValue::undefined(), &[], &mut context)
+        .map_err(|e| format!("Failed to read script result: {}", e))?;
+    let output = output
+        .as_string()
+        .ok_or_else(|| "Failed to read script result: not a string".to_string())?
+        .to_std_string_escaped();
+
+    let mut raw: RawOutput = serde_json::from_str(&output)
+        .map_err(|e| format!("Failed to read script result: {}", e))?;
+    if raw.error.is_none() {
+        raw.error = script_error;
+    }
+
+    Ok(raw)
+}
+
+fn response_input(response: &HttpResponse) -> Value {
+    let reason = reqwest::StatusCode::from_u16(response.status)
+        .ok()
+        .and_then(|status| status.canonical_reason())
+        .unwrap_or("");
+
+    json!({
+        "code": response.status,
+        "status": reason,
+        "headers": response.headers,
+        "body": response.text_lossy(),
+        "response_time": response.time as u64,
+        "response_size": response.body.len(),
+    })
+}
+
+fn build_outcome(raw: RawOutput, request: &RequestParams, scope: &ScriptScope) -> ScriptOutcome {
+    // Body JSON tetap dikirim sebagai JSON, selain itu sebagai string
+    let body = if raw.request.body.is_empty() {
+        None
+    } else {
+        match &request.body {
+            Some(Value::String(_)) | None => Some(Value::String(raw.request.body)),
+            Some(_) => Some(serde_json::from_str(&raw.request.body).unwrap_or(Value::String(raw.request.body))),
+        }
+    };
+
+    let request = RequestParams {
+        url: raw.request.url,
+        method: raw.request.method,
+        headers: if raw.request.headers.is_empty() { None } else { Some(raw.request.headers) },
+        params: if raw.request.params.is_empty() { None } else { Some(raw.request.params) },
+        body,
+        timeout: request.timeout,
+        auth: request.auth.clone(),
+        body_format: request.body_format.clone(),
+    };
+
+    let next_request = raw.next_request.map(|next| match next.name {
+        Some(name) if !next.stop => NextRequest::Name(name),
+        _ => NextRequest::Stop,
+    });
+
+    ScriptOutcome {
+        request,
+        scope: ScriptScope {
+            environment: raw.environment,
+            collection_variables: raw.collection_variables,
+            variables: raw.variables,
+            iteration_data: scope.iteration_data.clone(),
+            globals: raw.globals,
+        },
+        environment_changes: raw.environment_changes,
+        collection_changes: raw.collection_changes,
+        tests: raw.tests,
+        logs: raw.logs,
+        next_request,
+        error: raw.error,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scope(pairs: &[(&str, &str)]) -> ScriptScope {
+        ScriptScope {
+            environment: pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+            ..ScriptScope::default()
+        }
+    }
+
+    fn params(body: Value) -> RequestParams {
+        RequestParams {
+            url: "https://example.com/{{path}}".to_string(),
+            method: "POST".to_string(),
+            headers: None,
+            params: None,
+            body: Some(body),
+            timeout: None,
+            auth: None,
+            body_format: None,
+        }
+    }
+
+    #[test]
+    fn apply_to_keeps_json_structure_for_special_characters() {
+        let scope = scope(&[("name", "say \"hi\"\\\nbye"), ("key", "k\"1"), ("path", "users")]);
+        let applied = scope.apply_to(params(json!({
+            "name": "{{name}}",
+            "{{key}}": ["{{name}}", 1, true, null],
+            "count": 2,
+        })));
+
+        assert_eq!(applied.url, "https://example.com/users");
+        assert_eq!(applied.body, Some(json!({
+            "name": "say \"hi\"\\\nbye",
+            "k\"1": ["say \"hi\"\\\nbye", 1, true, null],
+            "count": 2,
+        })));
+    }
+
+    #[test]
+    fn apply_to_leaves_unknown_variables() {
+        let applied = scope(&[]).apply_to(params(json!({"id": "{{missing}}"})));
+        assert_eq!(applied.body, Some(json!({"id": "{{missing}}"})));
+        assert_eq!(applied.url, "https://example.com/{{path}}");
+    }
+
+    #[test]
+    fn resolve_uses_postman_priority() {
+        let mut scope = scope(&[("a", "env")]);
+        scope.collection_variables.insert("a".to_string(), "collection".to_string());
+        assert_eq!(scope.resolve("a").as_deref(), Some("env"));
+        scope.iteration_data.insert("a".to_string(), json!(5));
+        assert_eq!(scope.resolve("a").as_deref(), Some("5"));
+        scope.variables.insert("a".to_string(), "local".to_string());
+        assert_eq!(scope.resolve("a").as_deref(), Some("local"));
+    }
+
+    fn run_script(script: &str, scope: &ScriptScope, limits: &ScriptLimits) -> ScriptOutcome {
+        run_pre_request(script, &params(json!({})), scope, &ScriptInfo::default(), limits)
+    }
+
+    #[test]
+    fn timed_out_script_stops_itself() {
+        let limits = ScriptLimits {
+            timeout_ms: 200,
+            loop_iteration_limit: u64::MAX,
+            ..ScriptLimits::default()
+        };
+        for script in [
+            "while (true) {}",
+            "try { for (;;) {} } catch (e) {}",
+            "function spin() { try { while (true) {} } finally { spin(); } } spin();",
+        ] {
+            let outcome = run_script(script, &ScriptScope::default(), &limits);
+            // Error datang dari tick di thread script, bukan dari recv_timeout
+            let error = outcome.error.unwrap();
+            assert!(error.starts_with("Script error:"), "{}: {}", script, error);
+            assert!(error.contains("timed out after 200 ms"), "{}: {}", script, error);
+        }
+    }
+
+    #[test]
+    fn memory_limit_stops_script() {
+        let limits = ScriptLimits {
+            max_memory: 8 * 1024 * 1024,
+            loop_iteration_limit: u64::MAX,
+            ..ScriptLimits::default()
+        };
+        let outcome = run_script(
+            "pm.test('before', function () {}); var items = []; while (true) items.push('x'.repeat(1024) + items.length);",
+            &ScriptScope::default(),
+            &limits,
+        );
+        assert!(outcome.error.unwrap().contains("memory limit of 8388608 bytes"));
+        assert_eq!(outcome.tests.len(), 1);
+    }
+
+    #[test]
+    fn globals_have_their_own_scope() {
+        let mut scope = ScriptScope::default();
+        scope.globals.insert("base".to_string(), "https://example.com".to_string());
+        let outcome = run_script(
+            "pm.globals.set('token', 'abc'); pm.variables.set('local', pm.variables.get('base') + '/x');",
+            &scope,
+            &ScriptLimits::default(),
+        );
+        assert_eq!(outcome.error, None);
+        assert_eq!(outcome.scope.globals.get("token").map(String::as_str), Some("abc"));
+        assert_eq!(outcome.scope.variables.get("local").map(String::as_str), Some("https://example.com/x"));
+        assert!(!outcome.scope.variables.contains_key("token"));
+    }
+
+    #[test]
+    fn scripts_cannot_replace_prelude_output() {
+        let outcome = run_script(
+            "globalThis.__finish = function () { return '{}'; }; var output = null; JSON.stringify = null; \
+             pm.environment.set('a', '1');",
+            &ScriptScope::default(),
+            &ScriptLimits::default(),
+        );
+        assert_eq!(outcome.error, None);
+        assert_eq!(outcome.environment_changes.get("a"), Some(&Some("1".to_string())));
+    }
+
+    #[test]
+    fn scripts_that_cannot_be_instrumented_still_run() {
+        let outcome = run_script(
+            "if (true) /(x) {/.test('x {') && pm.environment.set('a', '1');",
+            &ScriptScope::default(),
+            &ScriptLimits::default(),
+        );
+        assert_eq!(outcome.error, None);
+        assert_eq!(outcome.environment_changes.get("a"), Some(&Some("1".to_string())));
+    }
+
+    #[test]
+    fn eval_is_rejected() {
+        let outcome = run_script("eval('1 + 1')", &ScriptScope::default(), &ScriptLimits::default());
+        assert!(outcome.error.unwrap().contains("eval and new Function are not available"));
+    }
+}
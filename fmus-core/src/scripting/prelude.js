@@ -0,0 +1,410 @@
+// API `pm` yang kompatibel dengan Postman.
+// Prelude ini dievaluasi menjadi function yang dipanggil dari Rust dengan input, hasilnya function
+// finish yang dipanggil setelah script selesai. Output disimpan di closure agar tidak bisa
+// ditimpa oleh script pengguna.
+(function (input) {
+  var stringify = JSON.stringify;
+  var output = {
+    request: null,
+    environment: {},
+    collection_variables: {},
+    variables: {},
+    globals: {},
+    environment_changes: {},
+    collection_changes: {},
+    tests: [],
+    logs: [],
+    next_request: null,
+    error: null
+  };
+  var MAX_LOGS = 1000;
+
+  function str(value) {
+    if (value === undefined || value === null) return '';
+    if (typeof value === 'object') return JSON.stringify(value);
+    return String(value);
+  }
+
+  // Kumpulan variabel dengan get/set/unset, perubahan dicatat agar bisa ditulis balik oleh Rust
+  function variableScope(values, changes) {
+    return {
+      get: function (key) { return Object.prototype.hasOwnProperty.call(values, key) ? values[key] : undefined; },
+      has: function (key) { return Object.prototype.hasOwnProperty.call(values, key); },
+      set: function (key, value) {
+        values[key] = str(value);
+        if (changes) changes[key] = values[key];
+      },
+      unset: function (key) {
+        delete values[key];
+        if (changes) changes[key] = null;
+      },
+      clear: function () {
+        Object.keys(values).forEach(function (key) {
+          delete values[key];
+          if (changes) changes[key] = null;
+        });
+      },
+      toObject: function () { return Object.assign({}, values); },
+      replaceIn: function (template) { return replaceIn(template); }
+    };
+  }
+
+  var environment = Object.assign({}, input.scope.environment);
+  var collectionVariables = Object.assign({}, input.scope.collection_variables);
+  var localVariables = Object.assign({}, input.scope.variables);
+  var globals = Object.assign({}, input.scope.globals);
+  var iterationData = Object.assign({}, input.scope.iteration_data);
+
+  // Urutan prioritas sama dengan Postman: local > data > environment > collection > global
+  function resolve(key) {
+    var scopes = [localVariables, iterationData, environment, collectionVariables, globals];
+    for (var i = 0; i < scopes.length; i++) {
+      if (Object.prototype.hasOwnProperty.call(scopes[i], key)) return scopes[i][key];
+    }
+    return undefined;
+  }
+
+  function replaceIn(template) {
+    return String(template).replace(/\{\{([^{}]+)\}\}/g, function (match, key) {
+      var value = resolve(key.trim());
+      return value === undefined ? match : str(value);
+    });
+  }
+
+  // Header sebagai daftar key/value dengan nama yang tidak case-sensitive
+  function headerList(headers) {
+    var list = Object.keys(headers || {}).map(function (key) { return { key: key, value: headers[key] }; });
+    function find(name) {
+      var lower = String(name).toLowerCase();
+      for (var i = 0; i < list.length; i++) {
+        if (list[i].key.toLowerCase() === lower) return i;
+      }
+      return -1;
+    }
+    return {
+      get: function (name) { var i = find(name); return i < 0 ? undefined : list[i].value; },
+      has: function (name) { return find(name) >= 0; },
+      add: function (header) { list.push({ key: header.key, value: str(header.value) }); },
+      upsert: function (header) {
+        var i = find(header.key);
+        if (i < 0) list.push({ key: header.key, value: str(header.value) });
+        else list[i].value = str(header.value);
+      },
+      remove: function (name) { var i = find(name); if (i >= 0) list.splice(i, 1); },
+      each: function (fn) { list.forEach(fn); },
+      toObject: function () {
+        var obj = {};
+        list.forEach(function (header) { obj[header.key] = header.value; });
+        return obj;
+      }
+    };
+  }
+
+  var requestInput = input.request;
+  var body = requestInput.body;
+  var request = {
+    url: {
+      raw: requestInput.url,
+      toString: function () { return this.raw; },
+      getQueryString: function () { var i = this.raw.indexOf('?'); return i < 0 ? '' : this.raw.slice(i + 1); }
+    },
+    method: requestInput.method,
+    headers: headerList(requestInput.headers),
+    body: {
+      mode: 'raw',
+      raw: body === null || body === undefined ? '' : (typeof body === 'string' ? body : JSON.stringify(body, null, 2))
+    },
+    params: Object.assign({}, requestInput.params || {})
+  };
+
+  var response = null;
+  if (input.response) {
+    var res = input.response;
+    var resHeaders = headerList(res.headers);
+    response = {
+      code: res.code,
+      status: res.status,
+      responseTime: res.response_time,
+      responseSize: res.response_size,
+      headers: resHeaders,
+      text: function () { return res.body; },
+      json: function () { return JSON.parse(res.body); }
+    };
+  }
+
+  function fail(message) {
+    throw new Error(message);
+  }
+
+  function show(value) {
+    if (typeof value === 'string') return "'" + value + "'";
+    try { return JSON.stringify(value); } catch (e) { return String(value); }
+  }
+
+  function deepEqual(a, b) {
+    if (a === b) return true;
+    if (typeof a !== 'object' || typeof b !== 'object' || a === null || b === null) return false;
+    if (Array.isArray(a) !== Array.isArray(b)) return false;
+    var keysA = Object.keys(a);
+    var keysB = Object.keys(b);
+    if (keysA.length !== keysB.length) return false;
+    for (var i = 0; i < keysA.length; i++) {
+      if (!deepEqual(a[keysA[i]], b[keysA[i]])) return false;
+    }
+    return true;
+  }
+
+  function typeOf(value) {
+    if (value === null) return 'null';
+    if (Array.isArray(value)) return 'array';
+    return typeof value;
+  }
+
+  // Subset assertion chai yang umum dipakai di script Postman
+  function expect(actual, label) {
+    var negate = false;
+    var deep = false;
+    var prefix = label ? label + ': ' : '';
+
+    function assert(passed, message, negatedMessage) {
+      if (negate ? passed : !passed) fail(prefix + (negate ? negatedMessage : message));
+      return chain;
+    }
+
+    var chain = {
+      equal: function (expected) {
+        if (deep) return chain.eql(expected);
+        return assert(actual === expected,
+          'expected ' + show(actual) + ' to equal ' + show(expected),
+          'expected ' + show(actual) + ' to not equal ' + show(expected));
+      },
+      eql: function (expected) {
+        return assert(deepEqual(actual, expected),
+          'expected ' + show(actual) + ' to deeply equal ' + show(expected),
+          'expected ' + show(actual) + ' to not deeply equal ' + show(expected));
+      },
+      a: function (type) {
+        var expected = String(type).toLowerCase();
+        return assert(typeOf(actual) === expected,
+          'expected ' + show(actual) + ' to be a ' + expected,
+          'expected ' + show(actual) + ' not to be a ' + expected);
+      },
+      include: function (needle) {
+        var found;
+        if (typeof actual === 'string') found = actual.indexOf(needle) >= 0;
+        else if (Array.isArray(actual)) found = actual.some(function (item) { return deepEqual(item, needle); });
+        else if (actual && typeof actual === 'object') {
+          found = Object.keys(needle || {}).every(function (key) { return deepEqual(actual[key], needle[key]); });
+        } else found = false;
+        return assert(found,
+          'expected ' + show(actual) + ' to include ' + show(needle),
+          'expected ' + show(actual) + ' to not include ' + show(needle));
+      },
+      property: function (name, value) {
+        var has = actual !== null && actual !== undefined && Object.prototype.hasOwnProperty.call(Object(actual), name);
+        if (arguments.length > 1) {
+          return assert(has && deepEqual(actual[name], value),
+            'expected ' + show(actual) + ' to have property ' + show(name) + ' of ' + show(value),
+            'expected ' + show(actual) + ' to not have property ' + show(name) + ' of ' + show(value));
+        }
+        return assert(has,
+          'expected ' + show(actual) + ' to have property ' + show(name),
+          'expected ' + show(actual) + ' to not have property ' + show(name));
+      },
+      above: function (limit) {
+        return assert(actual > limit,
+          'expected ' + show(actual) + ' to be above ' + limit,
+          'expected ' + show(actual) + ' to be at most ' + limit);
+      },
+      least: function (limit) {
+        return assert(actual >= limit,
+          'expected ' + show(actual) + ' to be at least ' + limit,
+          'expected ' + show(actual) + ' to be below ' + limit);
+      },
+      below: function (limit) {
+        return assert(actual < limit,
+          'expected ' + show(actual) + ' to be below ' + limit,
+          'expected ' + show(actual) + ' to be at least ' + limit);
+      },
+      most: function (limit) {
+        return assert(actual <= limit,
+          'expected ' + show(actual) + ' to be at most ' + limit,
+          'expected ' + show(actual) + ' to be above ' + limit);
+      },
+      lengthOf: function (length) {
+        var actualLength = actual === null || actual === undefined ? undefined : actual.length;
+        return assert(actualLength === length,
+          'expected ' + show(actual) + ' to have length ' + length + ' but got ' + actualLength,
+          'expected ' + show(actual) + ' to not have length ' + length);
+      },
+      match: function (pattern) {
+        return assert(pattern.test(String(actual)),
+          'expected ' + show(actual) + ' to match ' + pattern,
+          'expected ' + show(actual) + ' not to match ' + pattern);
+      },
+      oneOf: function (list) {
+        return assert(list.some(function (item) { return deepEqual(item, actual); }),
+          'expected ' + show(actual) + ' to be one of ' + show(list),
+          'expected ' + show(actual) + ' to not be one of ' + show(list));
+      },
+      status: function (expected) {
+        var code = actual && actual.code;
+        if (typeof expected === 'string') {
+          return assert(actual && actual.status === expected,
+            'expected response to have status reason ' + show(expected) + ' but got ' + show(actual && actual.status),
+            'expected response to not have status reason ' + show(expected));
+        }
+        return assert(code === expected,
+          'expected response to have status code ' + expected + ' but got ' + code,
+          'expected response to not have status code ' + expected);
+      },
+      header: function (name, value) {
+        var headers = actual && actual.headers;
+        var found = headers ? headers.get(name) : undefined;
+        if (arguments.length > 1) {
+          return assert(found === value,
+            'expected response to have header ' + name + ' with value ' + show(value) + ' but got ' + show(found),
+            'expected response to not have header ' + name + ' with value ' + show(value));
+        }
+        return assert(found !== undefined,
+          'expected response to have header ' + name,
+          'expected response to not have header ' + name);
+      },
+      jsonBody: function (path) {
+        var json;
+        try { json = actual.json(); } catch (e) { json = undefined; }
+        if (arguments.length === 0) {
+          return assert(json !== undefined, 'expected response body to be valid JSON', 'expected response body to not be valid JSON');
+        }
+        var value = json;
+        String(path).split('.').forEach(function (key) { value = value === undefined || value === null ? undefined : value[key]; });
+        return assert(value !== undefined,
+          'expected response body to have path ' + show(path),
+          'expected response body to not have path ' + show(path));
+      }
+    };
+
+    chain.equals = chain.equal;
+    chain.eq = chain.equal;
+    chain.eqls = chain.eql;
+    chain.an = chain.a;
+    chain.includes = chain.include;
+    chain.contain = chain.include;
+    chain.contains = chain.include;
+    chain.greaterThan = chain.above;
+    chain.gt = chain.above;
+    chain.gte = chain.least;
+    chain.lessThan = chain.below;
+    chain.lt = chain.below;
+    chain.lte = chain.most;
+    chain.length = chain.lengthOf;
+    chain.matches = chain.match;
+
+    // Kata penghubung dan properti assertion seperti `.to.be.ok` atau `.not.empty`
+    ['to', 'be', 'been', 'is', 'that', 'which', 'and', 'has', 'have', 'with', 'at', 'of', 'same', 'does', 'still', 'also'].forEach(function (word) {
+      Object.defineProperty(chain, word, { get: function () { return chain; } });
+    });
+    Object.defineProperty(chain, 'not', { get: function () { negate = !negate; return chain; } });
+    Object.defineProperty(chain, 'deep', { get: function () { deep = true; return chain; } });
+    var flags = {
+      ok: function () { return assert(!!actual, 'expected ' + show(actual) + ' to be truthy', 'expected ' + show(actual) + ' to be falsy'); },
+      true: function () { return assert(actual === true, 'expected ' + show(actual) + ' to be true', 'expected ' + show(actual) + ' to not be true'); },
+      false: function () { return assert(actual === false, 'expected ' + show(actual) + ' to be false', 'expected ' + show(actual) + ' to not be false'); },
+      null: function () { return assert(actual === null, 'expected ' + show(actual) + ' to be null', 'expected ' + show(actual) + ' to not be null'); },
+      undefined: function () { return assert(actual === undefined, 'expected ' + show(actual) + ' to be undefined', 'expected ' + show(actual) + ' to not be undefined'); },
+      exist: function () { return assert(actual !== null && actual !== undefined, 'expected value to exist', 'expected ' + show(actual) + ' to not exist'); },
+      empty: function () {
+        var size = typeof actual === 'string' || Array.isArray(actual) ? actual.length : Object.keys(actual || {}).length;
+        return assert(size === 0, 'expected ' + show(actual) + ' to be empty', 'expected ' + show(actual) + ' not to be empty');
+      },
+      json: function () { return chain.jsonBody(); },
+      success: function () { var code = actual && actual.code; return assert(code >= 200 && code < 300, 'expected response to have status code 2XX but got ' + code, 'expected response to not have status code 2XX'); }
+    };
+    Object.keys(flags).forEach(function (name) {
+      Object.defineProperty(chain, name, { get: flags[name] });
+    });
+
+    return chain;
+  }
+
+  function test(name, fn) {
+    var result = { name: String(name), passed: true, error: null };
+    try {
+      if (typeof fn === 'function') fn();
+    } catch (e) {
+      result.passed = false;
+      result.error = e && e.message ? e.message : String(e);
+    }
+    output.tests.push(result);
+    return result.passed;
+  }
+  test.skip = function (name) {
+    output.tests.push({ name: String(name), passed: true, error: null, skipped: true });
+  };
+
+  function setNextRequest(name) {
+    output.next_request = name === null ? { stop: true } : { name: String(name) };
+  }
+
+  function log(level) {
+    return function () {
+      if (output.logs.length >= MAX_LOGS) return;
+      var parts = Array.prototype.slice.call(arguments).map(function (value) {
+        return typeof value === 'string' ? value : show(value);
+      });
+      output.logs.push({ level: level, message: parts.join(' ') });
+    };
+  }
+
+  if (response) {
+    Object.defineProperty(response, 'to', { get: function () { return expect(response).to; } });
+  }
+
+  var pm = {
+    environment: variableScope(environment, output.environment_changes),
+    collectionVariables: variableScope(collectionVariables, output.collection_changes),
+    variables: variableScope(localVariables, null),
+    iterationData: variableScope(iterationData, null),
+    globals: variableScope(globals, null),
+    request: request,
+    response: response,
+    info: {
+      eventName: input.info.event_name,
+      requestName: input.info.request_name,
+      requestId: input.info.request_id,
+      iteration: input.info.iteration,
+      iterationCount: input.info.iteration_count
+    },
+    test: test,
+    expect: expect,
+    execution: { setNextRequest: setNextRequest }
+  };
+  pm.variables.get = function (key) { return resolve(key); };
+  pm.variables.has = function (key) { return resolve(key) !== undefined; };
+  pm.variables.replaceIn = replaceIn;
+
+  globalThis.pm = pm;
+  globalThis.postman = {
+    setNextRequest: setNextRequest,
+    getEnvironmentVariable: pm.environment.get,
+    setEnvironmentVariable: pm.environment.set,
+    clearEnvironmentVariable: pm.environment.unset
+  };
+  globalThis.console = { log: log('log'), info: log('info'), warn: log('warn'), error: log('error'), debug: log('debug') };
+
+  // Dipanggil setelah script selesai untuk menyalin state akhir ke output
+  return function () {
+    output.request = {
+      url: request.url.toString(),
+      method: request.method,
+      headers: request.headers.toObject(),
+      params: request.params,
+      body: request.body.raw
+    };
+    output.environment = environment;
+    output.collection_variables = collectionVariables;
+    output.variables = localVariables;
+    output.globals = globals;
+    return stringify(output);
+  };
+})
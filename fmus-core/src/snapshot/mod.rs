@@ -0,0 +1,386 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::diff::{self, CompareRules, Difference};
+use crate::http::HttpResponse;
+
+// Body yang disimpan di snapshot
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
+pub enum SnapshotBody {
+    Json(Value),
+    Text(String),
+    // Body biner disimpan sebagai base64
+    Binary(String),
+}
+
+// Snapshot "golden" sebuah response
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub status: u16,
+    // Hanya header yang dipilih di SnapshotConfig, nama dalam huruf kecil
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    pub body: SnapshotBody,
+}
+
+// Konfigurasi snapshot per request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotConfig {
+    // Header yang ikut disimpan dan dibandingkan
+    #[serde(default = "default_headers")]
+    pub headers: Vec<String>,
+    // Path yang diabaikan dan matcher untuk field yang berubah-ubah (timestamp, UUID, regex)
+    #[serde(default)]
+    pub rules: CompareRules,
+}
+
+fn default_headers() -> Vec<String> {
+    vec!["content-type".to_string()]
+}
+
+impl Default for SnapshotConfig {
+    fn default() -> Self {
+        Self {
+            headers: default_headers(),
+            rules: CompareRules::default(),
+        }
+    }
+}
+
+// Mode snapshot saat request dijalankan
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SnapshotMode {
+    // Bandingkan dengan snapshot, rekam jika belum ada
+    #[default]
+    Compare,
+    // Timpa snapshot dengan response saat ini
+    Update,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SnapshotStatus {
+    Matched,
+    // Snapshot belum ada dan baru direkam
+    Recorded,
+    Updated,
+    Mismatched,
+}
+
+// Hasil pemeriksaan snapshot
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotResult {
+    pub name: String,
+    pub status: SnapshotStatus,
+    #[serde(default)]
+    pub differences: Vec<Difference>,
+    // Diff yang mudah dibaca, kosong jika cocok
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub diff: String,
+}
+
+impl SnapshotResult {
+    pub fn passed(&self) -> bool {
+        self.status != SnapshotStatus::Mismatched
+    }
+}
+
+impl Snapshot {
+    // Membuat snapshot dari response, body JSON disimpan terstruktur
+    pub fn from_response(response: &HttpResponse, headers: &[String]) -> Self {
+        let headers = headers
+            .iter()
+            .filter_map(|name| response.header(name).map(|value| (name.to_lowercase(), value.to_string())))
+            .collect();
+
+        let body = match (response.json(), response.text()) {
+            (Ok(json), _) => SnapshotBody::Json(json),
+            (Err(_), Ok(text)) => SnapshotBody::Text(text),
+            _ => SnapshotBody::Binary(BASE64.encode(&response.body)),
+        };
+
+        Self { status: response.status, headers, body }
+    }
+
+    // Membandingkan snapshot ini (yang diharapkan) dengan snapshot aktual
+    pub fn compare(&self, actual: &Snapshot, rules: &CompareRules) -> Result<Vec<Difference>, String> {
+        let mut differences = Vec::new();
+
+        if self.status != actual.status {
+            differences.push(Difference::changed("status".to_string(), self.status.into(), actual.status.into()));
+        }
+
+        differences.extend(diff::diff_headers(&self.headers, &actual.headers));
+
+        match (&self.body, &actual.body) {
+            (SnapshotBody::Json(expected), SnapshotBody::Json(actual)) => {
+                differences.extend(diff::diff_json(expected, actual, rules)?);
+            },
+            (expected, actual) if expected != actual => {
+                differences.push(Difference::changed("$".to_string(), body_value(expected), body_value(actual)));
+            },
+            _ => {},
+        }
+
+        Ok(differences)
+    }
+}
+
+fn body_value(body: &SnapshotBody) -> Value {
+    match body {
+        SnapshotBody::Json(value) => value.clone(),
+        SnapshotBody::Text(text) | SnapshotBody::Binary(text) => Value::String(text.clone()),
+    }
+}
+
+// Diff yang mudah dibaca: daftar perbedaan, ditambah unified diff untuk body teks
+fn render_diff(expected: &Snapshot, actual: &Snapshot, differences: &[Difference]) -> String {
+    match (&expected.body, &actual.body) {
+        (SnapshotBody::Text(expected_text), SnapshotBody::Text(actual_text)) if expected_text != actual_text => {
+            // Body teks tidak perlu diulang utuh, cukup unified diff-nya
+            let others: Vec<_> = differences.iter().filter(|d| d.path != "$").cloned().collect();
+            let mut output = diff::render(&others);
+            if !output.is_empty() {
+                output.push_str("\n\n");
+            }
+            output.push_str(&diff::text_diff(expected_text, actual_text));
+            output
+        },
+        _ => diff::render(differences),
+    }
+}
+
+// Penyimpanan snapshot di disk, satu file JSON per snapshot
+#[derive(Debug, Clone)]
+pub struct SnapshotStore {
+    dir: PathBuf,
+}
+
+impl SnapshotStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    // Nama snapshot dijadikan nama file yang aman
+    pub fn path_for(&self, name: &str) -> PathBuf {
+        let file_name: String = name
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.' { c } else { '_' })
+            .collect();
+        self.dir.join(format!("{}.snap.json", file_name))
+    }
+
+    pub fn load(&self, name: &str) -> Result<Option<Snapshot>, String> {
+        let path = self.path_for(name);
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let content = fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read snapshot {}: {}", path.display(), e))?;
+        serde_json::from_str(&content)
+            .map(Some)
+            .map_err(|e| format!("Failed to parse snapshot {}: {}", path.display(), e))
+    }
+
+    pub fn save(&self, name: &str, snapshot: &Snapshot) -> Result<(), String> {
+        fs::create_dir_all(&self.dir)
+            .map_err(|e| format!("Failed to create snapshot directory {}: {}", self.dir.display(), e))?;
+
+        let path = self.path_for(name);
+        let content = serde_json::to_string_pretty(snapshot)
+            .map_err(|e| format!("Failed to serialize snapshot: {}", e))?;
+        fs::write(&path, content)
+            .map_err(|e| format!("Failed to write snapshot {}: {}", path.display(), e))
+    }
+
+    pub fn remove(&self, name: &str) -> Result<bool, String> {
+        let path = self.path_for(name);
+        if !path.exists() {
+            return Ok(false);
+        }
+
+        fs::remove_file(&path)
+            .map(|_| true)
+            .map_err(|e| format!("Failed to remove snapshot {}: {}", path.display(), e))
+    }
+
+    // Membandingkan response dengan snapshot tersimpan, merekam jika belum ada atau mode update
+    pub fn check(&self, name: &str, response: &HttpResponse, config: &SnapshotConfig, mode: SnapshotMode) -> Result<SnapshotResult, String> {
+        let actual = Snapshot::from_response(response, &config.headers);
+        let result = |status, differences, diff| SnapshotResult { name: name.to_string(), status, differences, diff };
+
+        let expected = match (mode, self.load(name)?) {
+            (SnapshotMode::Update, existing) => {
+                self.save(name, &actual)?;
+                let status = if existing.is_some() { SnapshotStatus::Updated } else { SnapshotStatus::Recorded };
+                return Ok(result(status, Vec::new(), String::new()));
+            },
+            (SnapshotMode::Compare, None) => {
+                self.save(name, &actual)?;
+                return Ok(result(SnapshotStatus::Recorded, Vec::new(), String::new()));
+            },
+            (SnapshotMode::Compare, Some(expected)) => expected,
+        };
+
+        let differences = expected.compare(&actual, &config.rules)?;
+        if differences.is_empty() {
+            Ok(result(SnapshotStatus::Matched, differences, String::new()))
+        } else {
+            let diff = render_diff(&expected, &actual, &differences);
+            Ok(result(SnapshotStatus::Mismatched, differences, diff))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use serde_json::json;
+
+    use crate::diff::{DiffKind, FieldMatcher, ValueMatcher};
+
+    fn response(status: u16, content_type: &str, body: &[u8]) -> HttpResponse {
+        let headers = HashMap::from([
+            ("Content-Type".to_string(), content_type.to_string()),
+            ("Date".to_string(), "Mon, 01 Jan 2024 00:00:00 GMT".to_string()),
+        ]);
+        HttpResponse { status, headers, body: body.to_vec(), time: 1 }
+    }
+
+    fn json_response(body: Value) -> HttpResponse {
+        response(200, "application/json", body.to_string().as_bytes())
+    }
+
+    fn store() -> SnapshotStore {
+        SnapshotStore::new(std::env::temp_dir().join(format!("fmus-snapshot-{}", uuid::Uuid::new_v4())))
+    }
+
+    #[test]
+    fn snapshots_keep_selected_headers_and_body_type() {
+        let snapshot = Snapshot::from_response(&json_response(json!({"id": 1})), &default_headers());
+        assert_eq!(snapshot.headers, BTreeMap::from([("content-type".to_string(), "application/json".to_string())]));
+        assert_eq!(snapshot.body, SnapshotBody::Json(json!({"id": 1})));
+
+        let text = Snapshot::from_response(&response(200, "text/plain", b"hello"), &[]);
+        assert!(text.headers.is_empty());
+        assert_eq!(text.body, SnapshotBody::Text("hello".to_string()));
+
+        let binary = Snapshot::from_response(&response(200, "text/plain; charset=utf-8", &[0xff, 0x00]), &[]);
+        assert_eq!(binary.body, SnapshotBody::Binary("/wA=".to_string()));
+    }
+
+    #[test]
+    fn first_check_records_then_matches() {
+        let store = store();
+        let config = SnapshotConfig::default();
+        let response = json_response(json!({"id": 1, "name": "fmus"}));
+
+        let recorded = store.check("users/get one", &response, &config, SnapshotMode::Compare).unwrap();
+        assert_eq!(recorded.status, SnapshotStatus::Recorded);
+        assert!(store.path_for("users/get one").ends_with("users_get_one.snap.json"));
+        assert!(store.load("users/get one").unwrap().is_some());
+
+        let matched = store.check("users/get one", &response, &config, SnapshotMode::Compare).unwrap();
+        assert_eq!(matched.status, SnapshotStatus::Matched);
+        assert!(matched.passed() && matched.diff.is_empty());
+
+        fs::remove_dir_all(store.dir()).unwrap();
+    }
+
+    #[test]
+    fn mismatches_list_differences() {
+        let store = store();
+        let config = SnapshotConfig::default();
+        store.check("user", &json_response(json!({"id": 1, "name": "fmus"})), &config, SnapshotMode::Compare).unwrap();
+
+        let mut changed = json_response(json!({"id": 2, "name": "fmus"}));
+        changed.status = 201;
+        let result = store.check("user", &changed, &config, SnapshotMode::Compare).unwrap();
+        assert_eq!(result.status, SnapshotStatus::Mismatched);
+        assert!(!result.passed());
+        let paths: Vec<_> = result.differences.iter().map(|difference| difference.path.as_str()).collect();
+        assert_eq!(paths, ["status", "$['id']"]);
+        assert!(result.diff.contains("$['id']"), "{}", result.diff);
+
+        fs::remove_dir_all(store.dir()).unwrap();
+    }
+
+    #[test]
+    fn ignore_paths_and_matchers_allow_volatile_fields() {
+        let store = store();
+        let config = SnapshotConfig {
+            headers: default_headers(),
+            rules: CompareRules {
+                ignore: vec!["$.meta".to_string()],
+                matchers: vec![
+                    FieldMatcher { path: "$.id".to_string(), matcher: ValueMatcher::Uuid },
+                    FieldMatcher { path: "$.created".to_string(), matcher: ValueMatcher::Timestamp },
+                ],
+            },
+        };
+        let body = |id: &str, created: &str, request: u32| {
+            json_response(json!({"id": id, "created": created, "meta": {"request": request}, "name": "fmus"}))
+        };
+
+        store.check("item", &body("6f1c2a1e-0000-4000-8000-000000000001", "2024-01-31T10:00:00Z", 1), &config, SnapshotMode::Compare).unwrap();
+        let result = store
+            .check("item", &body("6f1c2a1e-0000-4000-8000-000000000002", "2024-02-01T11:30:00Z", 2), &config, SnapshotMode::Compare)
+            .unwrap();
+        assert_eq!(result.status, SnapshotStatus::Matched, "{}", result.diff);
+
+        let result = store.check("item", &body("not-a-uuid", "2024-02-01T11:30:00Z", 3), &config, SnapshotMode::Compare).unwrap();
+        assert_eq!(result.status, SnapshotStatus::Mismatched);
+        assert_eq!(result.differences.len(), 1);
+        assert_eq!(result.differences[0].kind, DiffKind::Mismatch);
+
+        fs::remove_dir_all(store.dir()).unwrap();
+    }
+
+    #[test]
+    fn update_mode_overwrites_the_snapshot() {
+        let store = store();
+        let config = SnapshotConfig::default();
+
+        let recorded = store.check("user", &json_response(json!({"id": 1})), &config, SnapshotMode::Update).unwrap();
+        assert_eq!(recorded.status, SnapshotStatus::Recorded);
+
+        let updated = store.check("user", &json_response(json!({"id": 2})), &config, SnapshotMode::Update).unwrap();
+        assert_eq!(updated.status, SnapshotStatus::Updated);
+        assert_eq!(store.load("user").unwrap().unwrap().body, SnapshotBody::Json(json!({"id": 2})));
+
+        let matched = store.check("user", &json_response(json!({"id": 2})), &config, SnapshotMode::Compare).unwrap();
+        assert_eq!(matched.status, SnapshotStatus::Matched);
+
+        assert!(store.remove("user").unwrap());
+        assert!(!store.remove("user").unwrap());
+        fs::remove_dir_all(store.dir()).unwrap();
+    }
+
+    #[test]
+    fn text_bodies_render_a_unified_diff() {
+        let store = store();
+        let config = SnapshotConfig::default();
+        store.check("page", &response(200, "text/plain", b"one\ntwo\n"), &config, SnapshotMode::Compare).unwrap();
+
+        let result = store.check("page", &response(200, "text/plain", b"one\nthree\n"), &config, SnapshotMode::Compare).unwrap();
+        assert_eq!(result.status, SnapshotStatus::Mismatched);
+        assert!(result.diff.contains("-two"), "{}", result.diff);
+        assert!(result.diff.contains("+three"), "{}", result.diff);
+
+        fs::remove_dir_all(store.dir()).unwrap();
+    }
+}
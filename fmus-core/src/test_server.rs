@@ -0,0 +1,96 @@
+// Server HTTP kecil untuk test yang perlu mengirim request sungguhan.
+// start() menjawab setiap request dengan 200 dan body "ok"; path /slow/<ms> menunggu dulu sekian milidetik.
+// start_with() memakai handler sendiri yang menerima request.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+pub(crate) struct TestServer {
+    pub(crate) url: String,
+    requests: Arc<AtomicUsize>,
+}
+
+impl TestServer {
+    // Jumlah request yang sudah dijawab
+    pub(crate) fn requests(&self) -> usize {
+        self.requests.load(Ordering::SeqCst)
+    }
+}
+
+// Head request yang diterima server
+pub(crate) struct TestRequest {
+    pub(crate) path: String,
+    // Nama header huruf kecil
+    pub(crate) headers: HashMap<String, String>,
+}
+
+impl TestRequest {
+    pub(crate) fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(&name.to_ascii_lowercase()).map(String::as_str)
+    }
+}
+
+// Response mentah dengan Content-Length dan Connection: close
+pub(crate) fn response(status: u16, headers: &[(&str, &str)], body: &[u8]) -> Vec<u8> {
+    let mut head = format!("HTTP/1.1 {} Test\r\n", status);
+    for (name, value) in headers {
+        head.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    head.push_str(&format!("Content-Length: {}\r\nConnection: close\r\n\r\n", body.len()));
+
+    let mut bytes = head.into_bytes();
+    bytes.extend_from_slice(body);
+    bytes
+}
+
+pub(crate) async fn start() -> TestServer {
+    start_with(|_| response(200, &[("Content-Type", "text/plain")], b"ok")).await
+}
+
+pub(crate) async fn start_with(handler: impl Fn(&TestRequest) -> Vec<u8> + Send + Sync + 'static) -> TestServer {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let requests = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&requests);
+    let handler = Arc::new(handler);
+
+    tokio::spawn(async move {
+        while let Ok((mut socket, _)) = listener.accept().await {
+            let counter = Arc::clone(&counter);
+            let handler = Arc::clone(&handler);
+            tokio::spawn(async move {
+                // Cukup baca sampai akhir header, request test tidak punya body besar
+                let mut buffer = Vec::new();
+                let mut chunk = [0u8; 4096];
+                while !buffer.windows(4).any(|window| window == b"\r\n\r\n") {
+                    match socket.read(&mut chunk).await {
+                        Ok(0) | Err(_) => return,
+                        Ok(n) => buffer.extend_from_slice(&chunk[..n]),
+                    }
+                }
+
+                let head = String::from_utf8_lossy(&buffer);
+                let mut lines = head.split("\r\n");
+                let path = lines.next().and_then(|line| line.split_whitespace().nth(1)).unwrap_or("/").to_string();
+                let headers = lines
+                    .filter_map(|line| line.split_once(':'))
+                    .map(|(name, value)| (name.trim().to_ascii_lowercase(), value.trim().to_string()))
+                    .collect();
+                let request = TestRequest { path, headers };
+                if let Some(ms) = request.path.strip_prefix("/slow/").and_then(|ms| ms.parse().ok()) {
+                    tokio::time::sleep(Duration::from_millis(ms)).await;
+                }
+
+                counter.fetch_add(1, Ordering::SeqCst);
+                let _ = socket.write_all(&handler(&request)).await;
+            });
+        }
+    });
+
+    TestServer { url, requests }
+}
@@ -0,0 +1,5 @@
+pub mod secret;
+pub mod store;
+
+pub use secret::{collect_refs, redact_json, reveal_json, seal_json, secret_values, Secret, SecretRef, SECRET_FIELDS};
+pub use store::{EncryptedBlob, KdfParams, Vault, VaultData, VaultEntry, VaultEntryInfo};
@@ -0,0 +1,275 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+use super::store::Vault;
+
+// Nama field JSON yang dianggap rahasia pada konfigurasi auth
+pub const SECRET_FIELDS: &[&str] = &[
+    "password",
+    "token",
+    "client_secret",
+    "access_token",
+    "refresh_token",
+    "api_key",
+];
+
+// Referensi ke entry di dalam vault
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SecretRef {
+    pub vault_ref: String,
+}
+
+impl SecretRef {
+    pub fn new(id: &str) -> Self {
+        Self {
+            vault_ref: id.to_string(),
+        }
+    }
+
+    pub fn id(&self) -> &str {
+        &self.vault_ref
+    }
+}
+
+// Nilai rahasia: bisa berupa referensi vault atau nilai polos yang belum disegel.
+// Bentuk JSON tetap kompatibel dengan file lama yang menyimpan string biasa.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Secret {
+    Ref(SecretRef),
+    Plain(String),
+}
+
+impl Secret {
+    pub fn plain(value: &str) -> Self {
+        Secret::Plain(value.to_string())
+    }
+
+    pub fn reference(id: &str) -> Self {
+        Secret::Ref(SecretRef::new(id))
+    }
+
+    // Mengembalikan nilai polos jika secret belum disegel ke vault
+    pub fn expose(&self) -> Option<&str> {
+        match self {
+            Secret::Plain(value) => Some(value),
+            Secret::Ref(_) => None,
+        }
+    }
+
+    pub fn secret_ref(&self) -> Option<&SecretRef> {
+        match self {
+            Secret::Ref(secret_ref) => Some(secret_ref),
+            Secret::Plain(_) => None,
+        }
+    }
+
+    pub fn is_sealed(&self) -> bool {
+        matches!(self, Secret::Ref(_))
+    }
+
+    // Memindahkan nilai polos ke vault dan menggantinya dengan referensi
+    pub fn seal(&mut self, vault: &mut Vault, name: &str) -> Result<(), String> {
+        if let Secret::Plain(value) = self {
+            let secret_ref = vault.put_secret(name, value)?;
+            *self = Secret::Ref(secret_ref);
+        }
+        Ok(())
+    }
+
+    // Mengambil nilai sebenarnya, membuka referensi lewat vault bila perlu
+    pub fn reveal(&self, vault: Option<&Vault>) -> Result<String, String> {
+        match self {
+            Secret::Plain(value) => Ok(value.clone()),
+            Secret::Ref(secret_ref) => match vault {
+                Some(vault) => vault.get_secret(secret_ref),
+                None => Err(format!(
+                    "Secret '{}' is stored in the vault but no vault is available",
+                    secret_ref.vault_ref
+                )),
+            },
+        }
+    }
+
+    // Versi yang aman untuk diekspor: nilai polos dikosongkan, referensi dipertahankan
+    pub fn redacted(&self) -> Self {
+        match self {
+            Secret::Plain(_) => Secret::Plain(String::new()),
+            Secret::Ref(secret_ref) => Secret::Ref(secret_ref.clone()),
+        }
+    }
+}
+
+impl Default for Secret {
+    fn default() -> Self {
+        Secret::Plain(String::new())
+    }
+}
+
+impl From<&str> for Secret {
+    fn from(value: &str) -> Self {
+        Secret::plain(value)
+    }
+}
+
+impl From<String> for Secret {
+    fn from(value: String) -> Self {
+        Secret::Plain(value)
+    }
+}
+
+// Debug tidak pernah menampilkan nilai rahasia
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Secret::Plain(_) => f.write_str("Secret(***)"),
+            Secret::Ref(secret_ref) => write!(f, "Secret(vault_ref={})", secret_ref.vault_ref),
+        }
+    }
+}
+
+fn is_secret_field(key: &str) -> bool {
+    SECRET_FIELDS.contains(&key)
+}
+
+fn ref_from_value(value: &Value) -> Option<SecretRef> {
+    let obj = value.as_object()?;
+    if obj.len() != 1 {
+        return None;
+    }
+    obj.get("vault_ref")
+        .and_then(|v| v.as_str())
+        .map(SecretRef::new)
+}
+
+// Menyegel semua field rahasia di dalam JSON (misalnya konfigurasi auth dari UI)
+pub fn seal_json(value: &mut Value, vault: &mut Vault, name_prefix: &str) -> Result<(), String> {
+    if let Value::Object(obj) = value {
+        seal_object(obj, vault, name_prefix)?;
+    } else if let Value::Array(items) = value {
+        for item in items {
+            seal_json(item, vault, name_prefix)?;
+        }
+    }
+    Ok(())
+}
+
+fn seal_object(obj: &mut Map<String, Value>, vault: &mut Vault, name_prefix: &str) -> Result<(), String> {
+    for (key, field) in obj.iter_mut() {
+        match field {
+            Value::String(plain) if is_secret_field(key) && !plain.is_empty() => {
+                let secret_ref = vault.put_secret(&format!("{}.{}", name_prefix, key), plain)?;
+                *field = json!(secret_ref);
+            }
+            Value::Object(_) | Value::Array(_) => seal_json(field, vault, name_prefix)?,
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+// Membuka semua referensi vault di dalam JSON menjadi nilai polos
+pub fn reveal_json(value: &Value, vault: Option<&Vault>) -> Result<Value, String> {
+    if let Some(secret_ref) = ref_from_value(value) {
+        return Secret::Ref(secret_ref).reveal(vault).map(Value::String);
+    }
+
+    match value {
+        Value::Object(obj) => {
+            let mut revealed = Map::new();
+            for (key, field) in obj {
+                revealed.insert(key.clone(), reveal_json(field, vault)?);
+            }
+            Ok(Value::Object(revealed))
+        }
+        Value::Array(items) => items
+            .iter()
+            .map(|item| reveal_json(item, vault))
+            .collect::<Result<Vec<_>, _>>()
+            .map(Value::Array),
+        other => Ok(other.clone()),
+    }
+}
+
+// Mengumpulkan nilai field rahasia yang polos, misalnya dari hasil reveal_json
+pub fn secret_values(value: &Value, values: &mut Vec<String>) {
+    match value {
+        Value::Object(obj) => {
+            for (key, field) in obj {
+                match field {
+                    Value::String(plain) if is_secret_field(key) && !plain.is_empty() => values.push(plain.clone()),
+                    other => secret_values(other, values),
+                }
+            }
+        }
+        Value::Array(items) => items.iter().for_each(|item| secret_values(item, values)),
+        _ => {}
+    }
+}
+
+// Mengosongkan semua field rahasia yang masih polos, referensi vault dibiarkan
+pub fn redact_json(value: &Value) -> Value {
+    match value {
+        Value::Object(obj) => {
+            let mut redacted = Map::new();
+            for (key, field) in obj {
+                let field = match field {
+                    Value::String(_) if is_secret_field(key) => Value::String(String::new()),
+                    other => redact_json(other),
+                };
+                redacted.insert(key.clone(), field);
+            }
+            Value::Object(redacted)
+        }
+        Value::Array(items) => Value::Array(items.iter().map(redact_json).collect()),
+        other => other.clone(),
+    }
+}
+
+// Mengumpulkan semua referensi vault yang ada di dalam JSON
+pub fn collect_refs(value: &Value, refs: &mut Vec<SecretRef>) {
+    if let Some(secret_ref) = ref_from_value(value) {
+        refs.push(secret_ref);
+        return;
+    }
+
+    match value {
+        Value::Object(obj) => obj.values().for_each(|field| collect_refs(field, refs)),
+        Value::Array(items) => items.iter().for_each(|item| collect_refs(item, refs)),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sealed_json_keeps_only_references() {
+        let mut vault = Vault::create("passphrase").unwrap();
+        let mut auth = json!({"username": "ann", "password": "s3cret", "nested": [{"token": "t0k"}], "token": ""});
+        seal_json(&mut auth, &mut vault, "login").unwrap();
+
+        assert_eq!(auth["username"], "ann");
+        assert_eq!(auth["token"], "");
+        assert!(!auth.to_string().contains("s3cret"));
+        let mut refs = Vec::new();
+        collect_refs(&auth, &mut refs);
+        assert_eq!(refs.len(), 2);
+
+        let revealed = reveal_json(&auth, Some(&vault)).unwrap();
+        assert_eq!(revealed, json!({"username": "ann", "password": "s3cret", "nested": [{"token": "t0k"}], "token": ""}));
+        let mut values = Vec::new();
+        secret_values(&revealed, &mut values);
+        assert_eq!(values, ["t0k", "s3cret"]);
+        assert!(reveal_json(&auth, None).is_err());
+    }
+
+    #[test]
+    fn secret_debug_hides_plain_values() {
+        assert_eq!(format!("{:?}", Secret::plain("s3cret")), "Secret(***)");
+        assert_eq!(redact_json(&json!({"password": "s3cret", "user": "ann"})), json!({"password": "", "user": "ann"}));
+    }
+}
@@ -0,0 +1,376 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use zeroize::Zeroizing;
+
+use super::secret::SecretRef;
+
+const VAULT_VERSION: u32 = 1;
+const KEY_LEN: usize = 32;
+const SALT_LEN: usize = 16;
+// Plaintext yang dienkripsi untuk memverifikasi passphrase saat unlock
+const CHECK_PLAINTEXT: &[u8] = b"fmus-post-vault";
+const CHECK_AAD: &[u8] = b"fmus-post-vault-check";
+
+// Parameter derivasi kunci Argon2id yang disimpan bersama vault
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KdfParams {
+    pub algorithm: String,
+    pub salt: String,
+    pub memory_kib: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+}
+
+impl KdfParams {
+    fn generate() -> Self {
+        let mut salt = [0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+
+        Self {
+            algorithm: "argon2id".to_string(),
+            salt: BASE64.encode(salt),
+            memory_kib: 19 * 1024,
+            iterations: 2,
+            parallelism: 1,
+        }
+    }
+
+    // Menurunkan kunci enkripsi dari passphrase
+    fn derive_key(&self, passphrase: &str) -> Result<Zeroizing<[u8; KEY_LEN]>, String> {
+        if self.algorithm != "argon2id" {
+            return Err(format!("Unsupported vault KDF: {}", self.algorithm));
+        }
+
+        let salt = BASE64
+            .decode(&self.salt)
+            .map_err(|e| format!("Invalid vault salt: {}", e))?;
+        let params = Params::new(self.memory_kib, self.iterations, self.parallelism, Some(KEY_LEN))
+            .map_err(|e| format!("Invalid vault KDF parameters: {}", e))?;
+
+        let mut key = Zeroizing::new([0u8; KEY_LEN]);
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+            .hash_password_into(passphrase.as_bytes(), &salt, key.as_mut())
+            .map_err(|e| format!("Failed to derive vault key: {}", e))?;
+
+        Ok(key)
+    }
+}
+
+// Data terenkripsi (XChaCha20-Poly1305) dalam bentuk base64
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EncryptedBlob {
+    pub nonce: String,
+    pub ciphertext: String,
+}
+
+// Entry rahasia yang tersimpan di vault
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VaultEntry {
+    pub name: String,
+    #[serde(flatten)]
+    pub secret: EncryptedBlob,
+}
+
+// Informasi entry tanpa nilai rahasia, aman untuk ditampilkan di UI
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VaultEntryInfo {
+    pub id: String,
+    pub name: String,
+}
+
+// Format vault di disk. Tidak pernah berisi nilai yang sudah didekripsi.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VaultData {
+    pub version: u32,
+    pub kdf: KdfParams,
+    pub check: EncryptedBlob,
+    #[serde(default)]
+    pub entries: BTreeMap<String, VaultEntry>,
+}
+
+// Vault untuk menyimpan secret terenkripsi dengan master passphrase
+pub struct Vault {
+    data: VaultData,
+    key: Option<Zeroizing<[u8; KEY_LEN]>>,
+}
+
+impl Vault {
+    // Membuat vault baru yang langsung dalam keadaan unlocked
+    pub fn create(passphrase: &str) -> Result<Self, String> {
+        if passphrase.is_empty() {
+            return Err("Vault passphrase must not be empty".to_string());
+        }
+
+        let kdf = KdfParams::generate();
+        let key = kdf.derive_key(passphrase)?;
+        let check = encrypt(&key, CHECK_PLAINTEXT, CHECK_AAD)?;
+
+        Ok(Self {
+            data: VaultData {
+                version: VAULT_VERSION,
+                kdf,
+                check,
+                entries: BTreeMap::new(),
+            },
+            key: Some(key),
+        })
+    }
+
+    // Memuat vault dari data tersimpan, dalam keadaan locked
+    pub fn from_data(data: VaultData) -> Result<Self, String> {
+        if data.version != VAULT_VERSION {
+            return Err(format!("Unsupported vault version: {}", data.version));
+        }
+
+        Ok(Self { data, key: None })
+    }
+
+    pub fn from_json(content: &str) -> Result<Self, String> {
+        let data: VaultData = serde_json::from_str(content)
+            .map_err(|e| format!("Failed to parse vault: {}", e))?;
+        Self::from_data(data)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read vault file {}: {}", path.display(), e))?;
+        Self::from_json(&content)
+    }
+
+    // Memuat vault lalu membukanya dengan passphrase dari FMUS_VAULT_PASSPHRASE,
+    // untuk CLI dan fmus-monitor yang tidak bisa meminta passphrase secara interaktif
+    pub fn load_unlocked(path: &Path) -> Result<Self, String> {
+        let mut vault = Self::load(path)?;
+        let passphrase = std::env::var("FMUS_VAULT_PASSPHRASE")
+            .map_err(|_| "FMUS_VAULT_PASSPHRASE must be set to unlock the vault".to_string())?;
+        vault.unlock(&passphrase)?;
+        Ok(vault)
+    }
+
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(&self.data)
+            .map_err(|e| format!("Failed to serialize vault: {}", e))
+    }
+
+    // Menyimpan vault ke disk (hanya data terenkripsi)
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create vault directory {}: {}", parent.display(), e))?;
+        }
+
+        let tmp_path = path.with_extension("tmp");
+        fs::write(&tmp_path, self.to_json()?)
+            .map_err(|e| format!("Failed to write vault file {}: {}", tmp_path.display(), e))?;
+        fs::rename(&tmp_path, path)
+            .map_err(|e| format!("Failed to write vault file {}: {}", path.display(), e))
+    }
+
+    pub fn data(&self) -> &VaultData {
+        &self.data
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.key.is_none()
+    }
+
+    // Membuka vault dengan passphrase
+    pub fn unlock(&mut self, passphrase: &str) -> Result<(), String> {
+        let key = self.data.kdf.derive_key(passphrase)?;
+        decrypt(&key, &self.data.check, CHECK_AAD)
+            .map_err(|_| "Invalid vault passphrase".to_string())?;
+
+        self.key = Some(key);
+        Ok(())
+    }
+
+    // Mengunci vault dan menghapus kunci dari memori
+    pub fn lock(&mut self) {
+        self.key = None;
+    }
+
+    // Mengganti passphrase dan mengenkripsi ulang semua entry
+    pub fn change_passphrase(&mut self, current: &str, new_passphrase: &str) -> Result<(), String> {
+        if new_passphrase.is_empty() {
+            return Err("Vault passphrase must not be empty".to_string());
+        }
+
+        let old_key = self.data.kdf.derive_key(current)?;
+        decrypt(&old_key, &self.data.check, CHECK_AAD)
+            .map_err(|_| "Invalid vault passphrase".to_string())?;
+
+        let kdf = KdfParams::generate();
+        let new_key = kdf.derive_key(new_passphrase)?;
+
+        let mut entries = BTreeMap::new();
+        for (id, entry) in &self.data.entries {
+            let plaintext = decrypt(&old_key, &entry.secret, id.as_bytes())?;
+            entries.insert(
+                id.clone(),
+                VaultEntry {
+                    name: entry.name.clone(),
+                    secret: encrypt(&new_key, &plaintext, id.as_bytes())?,
+                },
+            );
+        }
+
+        self.data.kdf = kdf;
+        self.data.check = encrypt(&new_key, CHECK_PLAINTEXT, CHECK_AAD)?;
+        self.data.entries = entries;
+        self.key = Some(new_key);
+        Ok(())
+    }
+
+    fn key(&self) -> Result<&[u8; KEY_LEN], String> {
+        self.key
+            .as_deref()
+            .ok_or_else(|| "Vault is locked".to_string())
+    }
+
+    // Menyimpan secret baru dan mengembalikan referensinya
+    pub fn put_secret(&mut self, name: &str, value: &str) -> Result<SecretRef, String> {
+        let id = Uuid::new_v4().to_string();
+        let secret = encrypt(self.key()?, value.as_bytes(), id.as_bytes())?;
+
+        self.data.entries.insert(
+            id.clone(),
+            VaultEntry {
+                name: name.to_string(),
+                secret,
+            },
+        );
+
+        Ok(SecretRef::new(&id))
+    }
+
+    // Mengganti nilai secret yang sudah ada
+    pub fn update_secret(&mut self, secret_ref: &SecretRef, value: &str) -> Result<(), String> {
+        let secret = encrypt(self.key()?, value.as_bytes(), secret_ref.id().as_bytes())?;
+
+        match self.data.entries.get_mut(secret_ref.id()) {
+            Some(entry) => {
+                entry.secret = secret;
+                Ok(())
+            }
+            None => Err(format!("Vault entry not found: {}", secret_ref.id())),
+        }
+    }
+
+    // Mendekripsi secret berdasarkan referensi
+    pub fn get_secret(&self, secret_ref: &SecretRef) -> Result<String, String> {
+        let key = self.key()?;
+        let entry = self.data.entries.get(secret_ref.id())
+            .ok_or_else(|| format!("Vault entry not found: {}", secret_ref.id()))?;
+
+        let plaintext = decrypt(key, &entry.secret, secret_ref.id().as_bytes())?;
+        String::from_utf8(plaintext.to_vec())
+            .map_err(|e| format!("Vault entry is not valid UTF-8: {}", e))
+    }
+
+    pub fn remove_secret(&mut self, secret_ref: &SecretRef) -> bool {
+        self.data.entries.remove(secret_ref.id()).is_some()
+    }
+
+    pub fn contains(&self, secret_ref: &SecretRef) -> bool {
+        self.data.entries.contains_key(secret_ref.id())
+    }
+
+    pub fn list_entries(&self) -> Vec<VaultEntryInfo> {
+        self.data.entries
+            .iter()
+            .map(|(id, entry)| VaultEntryInfo {
+                id: id.clone(),
+                name: entry.name.clone(),
+            })
+            .collect()
+    }
+}
+
+// Enkripsi dengan id entry sebagai associated data agar ciphertext tidak bisa ditukar
+fn encrypt(key: &[u8; KEY_LEN], plaintext: &[u8], aad: &[u8]) -> Result<EncryptedBlob, String> {
+    let cipher = XChaCha20Poly1305::new(key.into());
+    let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+
+    let ciphertext = cipher
+        .encrypt(&nonce, Payload { msg: plaintext, aad })
+        .map_err(|_| "Failed to encrypt vault entry".to_string())?;
+
+    Ok(EncryptedBlob {
+        nonce: BASE64.encode(nonce),
+        ciphertext: BASE64.encode(ciphertext),
+    })
+}
+
+fn decrypt(key: &[u8; KEY_LEN], blob: &EncryptedBlob, aad: &[u8]) -> Result<Zeroizing<Vec<u8>>, String> {
+    let nonce = BASE64
+        .decode(&blob.nonce)
+        .map_err(|e| format!("Invalid vault nonce: {}", e))?;
+    if nonce.len() != 24 {
+        return Err("Invalid vault nonce length".to_string());
+    }
+    let ciphertext = BASE64
+        .decode(&blob.ciphertext)
+        .map_err(|e| format!("Invalid vault ciphertext: {}", e))?;
+
+    let cipher = XChaCha20Poly1305::new(key.into());
+    cipher
+        .decrypt(XNonce::from_slice(&nonce), Payload { msg: &ciphertext, aad })
+        .map(Zeroizing::new)
+        .map_err(|_| "Failed to decrypt vault entry".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn secrets_survive_save_and_unlock() {
+        let mut vault = Vault::create("correct horse").unwrap();
+        let secret_ref = vault.put_secret("api.token", "s3cret").unwrap();
+        let json = vault.to_json().unwrap();
+        assert!(!json.contains("s3cret"));
+
+        let mut loaded = Vault::from_json(&json).unwrap();
+        assert!(loaded.is_locked());
+        assert_eq!(loaded.get_secret(&secret_ref).unwrap_err(), "Vault is locked");
+        assert_eq!(loaded.unlock("wrong").unwrap_err(), "Invalid vault passphrase");
+        loaded.unlock("correct horse").unwrap();
+        assert_eq!(loaded.get_secret(&secret_ref).unwrap(), "s3cret");
+    }
+
+    #[test]
+    fn entries_are_bound_to_their_id() {
+        let mut vault = Vault::create("passphrase").unwrap();
+        let first = vault.put_secret("first", "one").unwrap();
+        let second = vault.put_secret("second", "two").unwrap();
+
+        // Ciphertext yang dipindah ke entry lain tidak bisa didekripsi
+        let moved = vault.data.entries[first.id()].secret.clone();
+        vault.data.entries.get_mut(second.id()).unwrap().secret = moved;
+        assert!(vault.get_secret(&second).is_err());
+        assert_eq!(vault.get_secret(&first).unwrap(), "one");
+    }
+
+    #[test]
+    fn change_passphrase_reencrypts_entries() {
+        let mut vault = Vault::create("old").unwrap();
+        let secret_ref = vault.put_secret("token", "value").unwrap();
+        assert!(vault.change_passphrase("wrong", "new").is_err());
+        vault.change_passphrase("old", "new").unwrap();
+
+        let mut loaded = Vault::from_json(&vault.to_json().unwrap()).unwrap();
+        assert!(loaded.unlock("old").is_err());
+        loaded.unlock("new").unwrap();
+        assert_eq!(loaded.get_secret(&secret_ref).unwrap(), "value");
+    }
+}
@@ -4,7 +4,6 @@ use std::time::{Duration, Instant};
 
 use futures_util::{SinkExt, StreamExt};
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
 use tokio::net::TcpStream;
 use tokio::sync::mpsc::{self, Receiver, Sender};
 use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream};
@@ -61,7 +60,7 @@ impl WebSocketClient {
     pub fn new(url: &str, config: Option<WsConfig>) -> Self {
         Self {
             url: url.to_string(),
-            config: config.unwrap_or_else(|| WsConfig {
+            config: config.unwrap_or(WsConfig {
                 headers: None,
                 timeout: Some(30),
                 auto_reconnect: Some(true),
@@ -166,6 +165,7 @@ impl WebSocketClient {
     }
 
     // Loop koneksi untuk websocket
+    #[allow(clippy::too_many_arguments)]
     async fn connection_loop(
         url: Url,
         mut command_rx: Receiver<WsCommand>,
@@ -250,7 +250,7 @@ impl WebSocketClient {
 
     // Memproses pesan websocket
     async fn process_websocket(
-        mut ws_stream: WsStream,
+        ws_stream: WsStream,
         command_rx: &mut Receiver<WsCommand>,
         status: &Arc<Mutex<WsStatus>>,
         on_message: &Option<MessageHandler>,
@@ -295,6 +295,8 @@ impl WebSocketClient {
                             // Update last ping time
                             last_ping = Instant::now();
                         },
+                        // Frame mentah hanya muncul saat menulis, tidak pernah saat membaca
+                        Some(Ok(Message::Frame(_))) => {},
                         Some(Ok(Message::Close(frame))) => {
                             if let Some(handler) = on_close {
                                 handler(
@@ -1,11 +1,27 @@
-use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use fmus_core::assertions::{Assertion, AssertionReport};
+use fmus_core::capture::CaptureResult;
+use fmus_core::codec::BodyFormat;
+use fmus_core::collections::CollectionStore;
+use fmus_core::diff::{DiffOptions, ResponseDiff};
+use fmus_core::http::charset::{self, Encoding, EncodingSource};
+use fmus_core::http::{AuthConfig, HttpResponse as CoreResponse, RateLimitConfig, RequestParams, ResponseBodyType};
+use fmus_core::load::{LoadOptions, LoadResult, LoadTest};
+use fmus_core::report::{self, ReporterConfig};
+use fmus_core::runner::{load_data_file, RequestResult, RunOptions, RunPlan, RunResult, Runner, VariableChanges};
+use fmus_core::snapshot::{SnapshotMode, SnapshotResult, SnapshotStore};
+use fmus_core::scripting::{ScriptLog, ScriptTestResult};
+use fmus_core::vault::Vault;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
-use std::collections::HashMap;
-use std::time::{Duration, Instant};
-use tauri::command;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use tauri::{command, State, Window};
 
-// Komentar: Struktur untuk request options
+use crate::AppState;
+
+// Komentar: Struktur untuk request options, mengikuti RequestParams dari fmus-core
 #[derive(Debug, Deserialize)]
 pub struct RequestOptions {
     #[serde(default)]
@@ -14,158 +30,444 @@ pub struct RequestOptions {
     params: HashMap<String, String>,
     #[serde(default)]
     body: Option<Value>,
+    // Komentar: Timeout dalam detik, sama dengan fmus-core
     #[serde(default)]
     timeout: Option<u64>,
+    // Komentar: Format auth fmus-core, misalnya {"type": "api_key", "credentials": {...}}
     #[serde(default)]
-    auth: Option<Auth>,
-}
-
-// Komentar: Struktur untuk authentication
-#[derive(Debug, Deserialize)]
-pub struct Auth {
-    #[serde(rename = "type")]
-    auth_type: String,
-    #[serde(default)]
-    username: Option<String>,
+    auth: Option<AuthConfig>,
+    // Komentar: Format body request (msgpack, cbor, protobuf), default JSON
     #[serde(default)]
-    password: Option<String>,
+    body_format: Option<BodyFormat>,
+    // Komentar: Format body response yang di-decode ke JSON untuk ditampilkan
     #[serde(default)]
-    token: Option<String>,
-    #[serde(default)]
-    api_key: Option<String>,
-    #[serde(default)]
-    prefix: Option<String>,
+    response_format: Option<BodyFormat>,
+}
+
+impl RequestOptions {
+    // Komentar: Konversi ke RequestParams milik fmus-core
+    fn into_params(self, method: &str, url: String) -> RequestParams {
+        RequestParams {
+            url,
+            method: method.to_string(),
+            headers: if self.headers.is_empty() { None } else { Some(self.headers) },
+            params: if self.params.is_empty() { None } else { Some(self.params) },
+            body: self.body,
+            timeout: self.timeout,
+            auth: self.auth,
+            body_format: self.body_format,
+        }
+    }
+}
+
+// Komentar: Batas ukuran body yang langsung dikirim ke UI, sisanya diambil lewat get_response_body
+const MAX_INLINE_BODY: usize = 1024 * 1024;
+// Komentar: Jumlah response terakhir yang disimpan untuk diambil body lengkapnya
+const MAX_CACHED_RESPONSES: usize = 20;
+
+// Komentar: Encoding body yang dikirim ke UI
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum BodyEncoding {
+    Text,
+    Base64,
+}
+
+// Komentar: Potongan body response, teks jika valid untuk charset response dan base64 jika tidak
+#[derive(Debug, Serialize)]
+pub struct ResponseBody {
+    body: String,
+    encoding: BodyEncoding,
+    // Komentar: Charset yang dipakai untuk decode teks, misalnya "Shift_JIS"
+    charset: String,
+    offset: usize,
+    // Komentar: Offset potongan berikutnya; bisa sebelum offset + panjang potongan jika karakter
+    // Komentar: multibyte terpotong di akhir, byte-nya ikut di potongan berikutnya
+    next_offset: usize,
+    size: usize,
+    truncated: bool,
+}
+
+impl ResponseBody {
+    fn encode(bytes: &[u8], offset: usize, limit: usize, charset: &'static Encoding) -> Self {
+        let start = offset.min(bytes.len());
+        let end = start.saturating_add(limit).min(bytes.len());
+        let slice = &bytes[start..end];
+        let truncated = end < bytes.len();
+
+        // Komentar: Jika potongan berhenti di tengah karakter multibyte, potongan berikutnya dimulai dari karakter itu
+        let (body, encoding, next_offset) = match charset::decode_chunk(slice, charset, !truncated) {
+            Some((text, consumed)) => (text, BodyEncoding::Text, start + consumed),
+            None => (BASE64.encode(slice), BodyEncoding::Base64, end),
+        };
+
+        ResponseBody {
+            body,
+            encoding,
+            charset: charset.name().to_string(),
+            offset: start,
+            next_offset,
+            size: bytes.len(),
+            truncated,
+        }
+    }
 }
 
 // Komentar: Struktur untuk HTTP response
 #[derive(Debug, Serialize)]
 pub struct HttpResponse {
+    id: String,
     status: u16,
     headers: HashMap<String, String>,
-    body: Value,
+    body: String,
+    body_encoding: BodyEncoding,
+    body_type: ResponseBodyType,
+    content_type: Option<String>,
+    // Komentar: Charset body dan asalnya (header Content-Type, BOM, meta HTML atau default UTF-8)
+    charset: String,
+    charset_source: EncodingSource,
+    size: usize,
+    truncated: bool,
+    // Komentar: Offset untuk mengambil sisa body lewat get_response_body jika truncated
+    next_offset: usize,
+    // Komentar: Hasil parse JSON terpisah, None jika body bukan JSON atau terpotong
+    json: Option<Value>,
     time: u64,
 }
 
-// Komentar: Konversi HeaderMap ke HashMap untuk serialisasi
-fn headers_to_map(headers: &HeaderMap) -> HashMap<String, String> {
-    let mut map = HashMap::new();
-    for (key, value) in headers.iter() {
-        if let Ok(v) = value.to_str() {
-            map.insert(key.as_str().to_string(), v.to_string());
+// Komentar: Cache response terakhir agar body lengkap bisa diambil kemudian
+#[derive(Default)]
+pub struct ResponseCache {
+    responses: VecDeque<(String, CoreResponse)>,
+}
+
+impl ResponseCache {
+    fn insert(&mut self, id: String, response: CoreResponse) {
+        if self.responses.len() >= MAX_CACHED_RESPONSES {
+            self.responses.pop_front();
         }
+        self.responses.push_back((id, response));
+    }
+
+    fn get(&self, id: &str) -> Option<&CoreResponse> {
+        self.responses.iter().find(|(cached_id, _)| cached_id == id).map(|(_, response)| response)
     }
-    map
 }
 
-// Komentar: Fungsi untuk menambahkan auth headers
-fn apply_auth(headers: &mut HeaderMap, auth: &Auth) -> Result<(), String> {
-    match auth.auth_type.as_str() {
-        "basic" => {
-            let username = auth.username.as_ref().ok_or("Username is required for basic auth")?;
-            let password = auth.password.as_ref().ok_or("Password is required for basic auth")?;
+// Komentar: Konversi response fmus-core ke format UI tanpa kehilangan isi body
+fn build_response(response: CoreResponse, state: &AppState) -> HttpResponse {
+    let id = uuid::Uuid::new_v4().to_string();
+    let (encoding, charset_source) = charset::detect_encoding(&response.body, response.content_type());
+    let body = ResponseBody::encode(&response.body, 0, MAX_INLINE_BODY, encoding);
+    let json = if body.truncated { None } else { response.json().ok() };
+
+    let http_response = HttpResponse {
+        id: id.clone(),
+        status: response.status,
+        headers: response.headers.clone(),
+        body_type: response.detect_content_type(),
+        content_type: response.content_type().map(|ct| ct.to_string()),
+        charset: body.charset,
+        charset_source,
+        body: body.body,
+        body_encoding: body.encoding,
+        size: body.size,
+        truncated: body.truncated,
+        next_offset: body.next_offset,
+        json,
+        time: response.time as u64,
+    };
 
-            let auth_str = format!("{}:{}", username, password);
-            let auth_header = format!("Basic {}", base64::encode(auth_str));
+    state.responses.lock().unwrap().insert(id, response);
+    http_response
+}
 
-            headers.insert(
-                reqwest::header::AUTHORIZATION,
-                HeaderValue::from_str(&auth_header).map_err(|e| e.to_string())?,
-            );
-        },
-        "bearer" => {
-            let token = auth.token.as_ref().ok_or("Token is required for bearer auth")?;
-            let auth_header = format!("Bearer {}", token);
-
-            headers.insert(
-                reqwest::header::AUTHORIZATION,
-                HeaderValue::from_str(&auth_header).map_err(|e| e.to_string())?,
-            );
-        },
-        "apikey" => {
-            let key = auth.api_key.as_ref().ok_or("API key is required")?;
-            let prefix = auth.prefix.as_deref().unwrap_or("X-API-Key");
-
-            headers.insert(
-                HeaderName::from_bytes(prefix.as_bytes()).map_err(|e| e.to_string())?,
-                HeaderValue::from_str(key).map_err(|e| e.to_string())?,
-            );
-        },
-        _ => return Err(format!("Unsupported auth type: {}", auth.auth_type)),
-    }
+// Komentar: Fungsi untuk mengirim HTTP request lewat client fmus-core yang dipakai bersama
+#[command]
+pub async fn http_request(method: String, url: String, options: RequestOptions, state: State<'_, AppState>) -> Result<HttpResponse, String> {
+    let response_format = options.response_format.clone();
+    let params = options.into_params(&method, url);
+    let response = state.http_client.request(params).await?;
+    let response = match &response_format {
+        Some(format) => response.to_json_response(format)?,
+        None => response,
+    };
 
-    Ok(())
+    Ok(build_response(response, &state))
+}
+
+// Komentar: Mengambil body lengkap (atau sebagian) dari response yang terpotong
+#[command]
+pub fn get_response_body(
+    response_id: String,
+    offset: Option<usize>,
+    length: Option<usize>,
+    state: State<AppState>
+) -> Result<ResponseBody, String> {
+    let cache = state.responses.lock().unwrap();
+    let response = cache.get(&response_id)
+        .ok_or_else(|| format!("Response not found or expired: {}", response_id))?;
+
+    let (encoding, _) = charset::detect_encoding(&response.body, response.content_type());
+    Ok(ResponseBody::encode(&response.body, offset.unwrap_or(0), length.unwrap_or(usize::MAX), encoding))
+}
+
+// Komentar: Body response yang sudah diformat untuk ditampilkan
+#[derive(Debug, Serialize)]
+pub struct PrettyBody {
+    body_type: ResponseBodyType,
+    // Komentar: JSON, XML, HTML dan YAML diindentasi, body biner menjadi hex dump
+    body: String,
+}
+
+// Komentar: Mengambil body response dalam bentuk yang sudah diformat sesuai tipenya
+#[command]
+pub fn get_pretty_body(response_id: String, state: State<AppState>) -> Result<PrettyBody, String> {
+    let cache = state.responses.lock().unwrap();
+    let response = cache.get(&response_id)
+        .ok_or_else(|| format!("Response not found or expired: {}", response_id))?;
+
+    Ok(PrettyBody {
+        body_type: response.detect_content_type(),
+        body: response.pretty(),
+    })
 }
 
-// Komentar: Fungsi untuk mengirim HTTP request
+// Komentar: Mengevaluasi assertion terhadap response yang masih ada di cache
 #[command]
-pub async fn http_request(method: String, url: String, options: RequestOptions) -> Result<HttpResponse, String> {
-    let start_time = Instant::now();
-
-    // Komentar: Setup HTTP client
-    let client = reqwest::Client::new();
-
-    // Komentar: Setup request builder
-    let mut req_builder = match method.to_uppercase().as_str() {
-        "GET" => client.get(url),
-        "POST" => client.post(url),
-        "PUT" => client.put(url),
-        "DELETE" => client.delete(url),
-        "PATCH" => client.patch(url),
-        "HEAD" => client.head(url),
-        "OPTIONS" => client.request(reqwest::Method::OPTIONS, url),
-        _ => return Err(format!("Unsupported HTTP method: {}", method)),
+pub fn evaluate_assertions(response_id: String, assertions: Vec<Assertion>, state: State<AppState>) -> Result<AssertionReport, String> {
+    let cache = state.responses.lock().unwrap();
+    let response = cache.get(&response_id)
+        .ok_or_else(|| format!("Response not found or expired: {}", response_id))?;
+
+    Ok(response.evaluate_assertions(&assertions))
+}
+
+// Komentar: Laporan menjalankan request tersimpan: response, hasil pm.test dan assertion
+#[derive(Debug, Default, Serialize)]
+pub struct RequestRunReport {
+    response: Option<HttpResponse>,
+    tests: Vec<ScriptTestResult>,
+    assertions: AssertionReport,
+    snapshot: Option<SnapshotResult>,
+    captures: Vec<CaptureResult>,
+    logs: Vec<ScriptLog>,
+    errors: Vec<String>,
+}
+
+// Komentar: Plan yang sudah disiapkan untuk dijalankan, tanpa memegang lock
+pub(crate) struct PreparedRun {
+    pub(crate) plan: RunPlan,
+    pub(crate) environment: HashMap<String, String>,
+    environment_id: Option<String>,
+}
+
+// Komentar: Susun plan dari collection store dan resolve variabel environment (default environment aktif)
+pub(crate) fn prepare_run(
+    environment_id: Option<String>,
+    state: &AppState,
+    plan: impl FnOnce(&CollectionStore, Option<&Vault>) -> Result<RunPlan, String>
+) -> Result<PreparedRun, String> {
+    let vault_state = state.vault.lock().unwrap();
+    let environments = state.environments.lock().unwrap();
+    let collections = state.collections.lock().unwrap();
+
+    let mut plan = plan(&collections, vault_state.unlocked())?;
+    let environment_id = environment_id
+        .or_else(|| environments.get_active_environment().map(|env| env.id.clone()));
+    let environment = match &environment_id {
+        Some(id) => {
+            plan.secrets.extend(environments.secret_values(id, vault_state.unlocked())?);
+            environments.resolve_variables(id, vault_state.unlocked())?
+        },
+        None => HashMap::new(),
     };
 
-    // Komentar: Set query parameters
-    if !options.params.is_empty() {
-        req_builder = req_builder.query(&options.params);
-    }
+    Ok(PreparedRun { plan, environment, environment_id })
+}
 
-    // Komentar: Set headers
-    let mut headers = HeaderMap::new();
-    for (key, value) in options.headers {
-        if let Ok(header_name) = HeaderName::from_bytes(key.as_bytes()) {
-            if let Ok(header_value) = HeaderValue::from_str(&value) {
-                headers.insert(header_name, header_value);
+// Komentar: Tulis balik perubahan pm.environment, pm.collectionVariables dan capture ke store
+fn apply_variable_changes(changes: &VariableChanges, run: &PreparedRun, state: &AppState) -> Result<(), String> {
+    let mut vault_state = state.vault.lock().unwrap();
+    let mut environments = state.environments.lock().unwrap();
+    let mut collections = state.collections.lock().unwrap();
+
+    collections.apply_variable_changes(&run.plan.collection_id, &changes.collection);
+
+    if let Some(environment_id) = &run.environment_id {
+        let stale_refs = environments.apply_script_changes(environment_id, &changes.environment);
+        // Komentar: Entry lama dihapus walau vault terkunci (tidak perlu kunci), nilai baru disegel saat unlock
+        let mut changed = false;
+        if let Some(vault) = vault_state.vault.as_mut() {
+            for secret_ref in &stale_refs {
+                changed |= vault.remove_secret(secret_ref);
             }
         }
+        if let Some(vault) = vault_state.unlocked_mut() {
+            changed |= environments.seal_secrets(vault)? > 0;
+        }
+        if changed {
+            vault_state.persist()?;
+        }
+    }
+
+    Ok(())
+}
+
+// Komentar: Runner dengan HTTP client bersama; snapshot disimpan per collection
+fn runner<'a>(run: &PreparedRun, options: RunOptions, state: &'a AppState) -> Runner<'a> {
+    let runner = Runner::new(&state.http_client, options);
+    match state.snapshots.lock().unwrap().as_ref() {
+        Some(store) => runner.with_snapshots(SnapshotStore::new(store.dir().join(&run.plan.collection_id))),
+        None => runner,
     }
+}
 
-    // Komentar: Apply authentication if provided
-    if let Some(auth) = &options.auth {
-        apply_auth(&mut headers, auth)?;
+// Komentar: Laporan satu request untuk UI
+fn request_report(result: RequestResult, state: &AppState) -> RequestRunReport {
+    RequestRunReport {
+        response: result.response.map(|response| build_response(response, state)),
+        tests: result.tests,
+        assertions: result.assertions,
+        snapshot: result.snapshot,
+        captures: result.captures,
+        logs: result.logs,
+        errors: result.errors,
     }
+}
+
+// Komentar: Menjalankan request tersimpan beserta pre-request script, test script, assertion dan snapshot-nya.
+// Komentar: update_snapshots menimpa snapshot dengan response saat ini.
+#[command]
+pub async fn run_request(
+    request_id: String,
+    environment_id: Option<String>,
+    update_snapshots: Option<bool>,
+    state: State<'_, AppState>
+) -> Result<RequestRunReport, String> {
+    let run = prepare_run(environment_id, &state, |store, vault| RunPlan::for_request(store, &request_id, vault))?;
+    let mode = if update_snapshots.unwrap_or(false) { SnapshotMode::Update } else { SnapshotMode::Compare };
+    let options = RunOptions { snapshot_mode: Some(mode), ..RunOptions::default() };
+    let (result, changes) = runner(&run, options, &state).run_request(&run.plan, run.environment.clone()).await?;
+    apply_variable_changes(&changes, &run, &state)?;
+
+    Ok(request_report(result, &state))
+}
 
-    req_builder = req_builder.headers(headers);
+// Komentar: Hasil menjalankan satu request di dua environment
+#[derive(Debug, Serialize)]
+pub struct EnvironmentComparison {
+    left: RequestRunReport,
+    right: RequestRunReport,
+    // Komentar: None jika salah satu request gagal mendapat response
+    diff: Option<ResponseDiff>,
+}
+
+// Komentar: Menjalankan request tersimpan di dua environment (misalnya staging dan production) lalu membandingkan response-nya.
+// Komentar: Perubahan variabel dari script dibuang oleh Runner::compare_environments, jadi tidak ada yang ditulis balik.
+#[command]
+pub async fn compare_environments(
+    request_id: String,
+    left_environment_id: String,
+    right_environment_id: String,
+    options: Option<DiffOptions>,
+    state: State<'_, AppState>
+) -> Result<EnvironmentComparison, String> {
+    let left_run = prepare_run(Some(left_environment_id), &state, |store, vault| RunPlan::for_request(store, &request_id, vault))?;
+    let right_run = prepare_run(Some(right_environment_id), &state, |store, vault| RunPlan::for_request(store, &request_id, vault))?;
+
+    let comparison = runner(&left_run, RunOptions::default(), &state)
+        .compare_environments(
+            (&left_run.plan, left_run.environment.clone()),
+            (&right_run.plan, right_run.environment.clone()),
+            &options.unwrap_or_default(),
+        )
+        .await?;
+
+    Ok(EnvironmentComparison {
+        left: request_report(comparison.left, &state),
+        right: request_report(comparison.right, &state),
+        diff: comparison.diff,
+    })
+}
 
-    // Komentar: Set request body
-    if let Some(body) = options.body {
-        req_builder = req_builder.json(&body);
+// Komentar: Menjalankan semua request dalam collection (atau satu folder), berurutan atau parallel sesuai options dan mode folder.
+// Komentar: data_file (CSV atau JSON) membuat satu iterasi per baris, menimpa data di options.
+// Komentar: Progress dikirim ke window sebagai event "collection-run-progress"; laporan ditulis setelah run selesai.
+#[command]
+pub async fn run_collection(
+    collection_id: String,
+    folder_id: Option<String>,
+    environment_id: Option<String>,
+    data_file: Option<String>,
+    options: Option<RunOptions>,
+    reporters: Option<Vec<ReporterConfig>>,
+    window: Window,
+    state: State<'_, AppState>
+) -> Result<RunResult, String> {
+    let mut options = options.unwrap_or_default();
+    if let Some(path) = &data_file {
+        options.data = load_data_file(path)?;
     }
 
-    // Komentar: Set timeout
-    if let Some(timeout) = options.timeout {
-        req_builder = req_builder.timeout(Duration::from_millis(timeout));
+    let run = prepare_run(environment_id, &state, |store, vault| match &folder_id {
+        Some(folder_id) => RunPlan::for_folder(store, folder_id, vault),
+        None => RunPlan::for_collection(store, &collection_id, vault),
+    })?;
+    if run.plan.collection_id != collection_id {
+        return Err(format!("Folder {} does not belong to collection {}", folder_id.unwrap_or_default(), collection_id));
     }
 
-    // Komentar: Send request
-    let response = req_builder.send().await.map_err(|e| e.to_string())?;
+    let result = runner(&run, options, &state)
+        .run(&run.plan, run.environment.clone(), |event| {
+            let _ = window.emit("collection-run-progress", event);
+        })
+        .await;
+    apply_variable_changes(&result.changes, &run, &state)?;
+    report::write_reports(&result, &reporters.unwrap_or_default())?;
 
-    // Komentar: Process response
-    let status = response.status().as_u16();
-    let headers = headers_to_map(response.headers());
+    Ok(result)
+}
 
-    // Komentar: Parse response body
-    let body = response.json::<Value>().await.unwrap_or(Value::Null);
+// Komentar: Load test untuk satu request, satu folder atau seluruh collection.
+// Komentar: Progress dikirim setiap detik sebagai event "load-test-progress". Perubahan variabel tidak ditulis balik.
+#[command]
+pub async fn run_load_test(
+    collection_id: String,
+    folder_id: Option<String>,
+    request_id: Option<String>,
+    environment_id: Option<String>,
+    options: LoadOptions,
+    window: Window,
+    state: State<'_, AppState>
+) -> Result<LoadResult, String> {
+    let run = prepare_run(environment_id, &state, |store, vault| match (&request_id, &folder_id) {
+        (Some(request_id), _) => RunPlan::for_request(store, request_id, vault),
+        (None, Some(folder_id)) => RunPlan::for_folder(store, folder_id, vault),
+        (None, None) => RunPlan::for_collection(store, &collection_id, vault),
+    })?;
+    if run.plan.collection_id != collection_id {
+        return Err(format!("Load test target does not belong to collection {}", collection_id));
+    }
 
-    let elapsed = start_time.elapsed().as_millis() as u64;
+    LoadTest::new(Arc::clone(&state.http_client), options)
+        .run(&run.plan, run.environment, |progress| {
+            let _ = window.emit("load-test-progress", progress);
+        })
+        .await
+}
 
-    Ok(HttpResponse {
-        status,
-        headers,
-        body,
-        time: elapsed,
-    })
+// Komentar: Rate limit untuk semua request dari aplikasi, null untuk menghapus batas
+#[command]
+pub fn set_rate_limit(config: Option<RateLimitConfig>, state: State<'_, AppState>) -> Result<Option<RateLimitConfig>, String> {
+    state.http_client.set_rate_limit(config)?;
+    Ok(state.http_client.rate_limit())
+}
+
+// Komentar: Rate limit yang sedang aktif
+#[command]
+pub fn get_rate_limit(state: State<'_, AppState>) -> Option<RateLimitConfig> {
+    state.http_client.rate_limit()
 }
 
 // Komentar: Struktur untuk GraphQL request
@@ -180,12 +482,7 @@ pub struct GraphQLRequest {
 
 // Komentar: Fungsi untuk mengirim GraphQL request
 #[command]
-pub async fn graphql_request(url: String, request: GraphQLRequest, options: RequestOptions) -> Result<HttpResponse, String> {
-    let start_time = Instant::now();
-
-    // Komentar: Setup HTTP client
-    let client = reqwest::Client::new();
-
+pub async fn graphql_request(url: String, request: GraphQLRequest, options: RequestOptions, state: State<'_, AppState>) -> Result<HttpResponse, String> {
     // Komentar: Build request body
     let mut body = json!({
         "query": request.query
@@ -199,50 +496,13 @@ pub async fn graphql_request(url: String, request: GraphQLRequest, options: Requ
         body["operationName"] = Value::String(operation_name);
     }
 
-    // Komentar: Setup headers
-    let mut headers = HeaderMap::new();
-    headers.insert(
-        reqwest::header::CONTENT_TYPE,
-        HeaderValue::from_static("application/json"),
-    );
-
-    for (key, value) in options.headers {
-        if let Ok(header_name) = HeaderName::from_bytes(key.as_bytes()) {
-            if let Ok(header_value) = HeaderValue::from_str(&value) {
-                headers.insert(header_name, header_value);
-            }
-        }
-    }
+    // Komentar: Body JSON otomatis mendapat Content-Type application/json
+    let mut params = options.into_params("POST", url);
+    params.body = Some(body);
 
-    // Komentar: Apply authentication if provided
-    if let Some(auth) = &options.auth {
-        apply_auth(&mut headers, auth)?;
-    }
-
-    // Komentar: Build request
-    let mut req_builder = client.post(url).headers(headers).json(&body);
+    let response = state.http_client.request(params).await?;
 
-    // Komentar: Set timeout
-    if let Some(timeout) = options.timeout {
-        req_builder = req_builder.timeout(Duration::from_millis(timeout));
-    }
-
-    // Komentar: Send request
-    let response = req_builder.send().await.map_err(|e| e.to_string())?;
-
-    // Komentar: Process response
-    let status = response.status().as_u16();
-    let headers = headers_to_map(response.headers());
-    let body = response.json::<Value>().await.unwrap_or(Value::Null);
-
-    let elapsed = start_time.elapsed().as_millis() as u64;
-
-    Ok(HttpResponse {
-        status,
-        headers,
-        body,
-        time: elapsed,
-    })
+    Ok(build_response(response, &state))
 }
 
 // Komentar: Struktur untuk WebSocket message
@@ -1,174 +1,21 @@
-use serde::{Deserialize, Serialize};
+use fmus_core::assertions::Assertion;
+use fmus_core::capture::Capture;
+use fmus_core::collections::curl;
+use fmus_core::codec::BodyFormat;
+use fmus_core::flow::{self, FlowRule};
+use fmus_core::schema::JsonSchema;
+use fmus_core::scripting::Scripts;
+use fmus_core::snapshot::SnapshotConfig;
+use serde::Deserialize;
 use serde_json::Value;
 use std::collections::HashMap;
-use std::sync::Mutex;
 use tauri::{command, State};
 use uuid::Uuid;
 
 use crate::AppState;
 
-// Komentar: Struktur untuk request dalam collection
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Request {
-    pub id: String,
-    pub name: String,
-    pub description: Option<String>,
-    pub method: String,
-    pub url: String,
-    pub headers: HashMap<String, String>,
-    pub params: HashMap<String, String>,
-    pub body: Option<Value>,
-    pub auth: Option<Value>,
-}
-
-// Komentar: Struktur untuk folder dalam collection
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Folder {
-    pub id: String,
-    pub name: String,
-    pub description: Option<String>,
-    pub requests: Vec<String>, // Komentar: Request IDs
-    pub folders: Vec<String>,  // Komentar: Sub-folder IDs
-}
-
-// Komentar: Struktur untuk collection
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Collection {
-    pub id: String,
-    pub name: String,
-    pub description: Option<String>,
-    pub requests: Vec<String>, // Komentar: Request IDs
-    pub folders: Vec<String>,  // Komentar: Folder IDs
-    pub variables: HashMap<String, String>,
-}
-
-// Komentar: Store untuk semua collections, requests, dan folders
-#[derive(Debug, Default)]
-pub struct CollectionStore {
-    collections: HashMap<String, Collection>,
-    requests: HashMap<String, Request>,
-    folders: HashMap<String, Folder>,
-}
-
-impl CollectionStore {
-    pub fn new() -> Self {
-        CollectionStore {
-            collections: HashMap::new(),
-            requests: HashMap::new(),
-            folders: HashMap::new(),
-        }
-    }
-
-    // Komentar: Membuat collection baru
-    pub fn create_collection(&mut self, name: String, description: Option<String>) -> Collection {
-        let id = Uuid::new_v4().to_string();
-        let collection = Collection {
-            id: id.clone(),
-            name,
-            description,
-            requests: Vec::new(),
-            folders: Vec::new(),
-            variables: HashMap::new(),
-        };
-
-        self.collections.insert(id, collection.clone());
-        collection
-    }
-
-    // Komentar: Mendapatkan collection berdasarkan ID
-    pub fn get_collection(&self, id: &str) -> Option<&Collection> {
-        self.collections.get(id)
-    }
-
-    // Komentar: Mendapatkan semua collections
-    pub fn list_collections(&self) -> Vec<Collection> {
-        self.collections.values().cloned().collect()
-    }
-
-    // Komentar: Update collection
-    pub fn update_collection(&mut self, id: &str, name: Option<String>, description: Option<String>) -> Option<Collection> {
-        if let Some(collection) = self.collections.get_mut(id) {
-            if let Some(new_name) = name {
-                collection.name = new_name;
-            }
-
-            if description.is_some() {
-                collection.description = description;
-            }
-
-            return Some(collection.clone());
-        }
-
-        None
-    }
-
-    // Komentar: Menghapus collection
-    pub fn delete_collection(&mut self, id: &str) -> bool {
-        if self.collections.contains_key(id) {
-            // Komentar: Hapus requests dan folders yang terkait
-            if let Some(collection) = self.collections.get(id) {
-                for request_id in &collection.requests {
-                    self.requests.remove(request_id);
-                }
-
-                for folder_id in &collection.folders {
-                    self.remove_folder_recursively(folder_id);
-                }
-            }
-
-            self.collections.remove(id);
-            return true;
-        }
-
-        false
-    }
-
-    // Komentar: Hapus folder dan semua kontennya secara rekursif
-    fn remove_folder_recursively(&mut self, folder_id: &str) {
-        if let Some(folder) = self.folders.get(folder_id) {
-            let request_ids = folder.requests.clone();
-            let folder_ids = folder.folders.clone();
-
-            for request_id in request_ids {
-                self.requests.remove(&request_id);
-            }
-
-            for sub_folder_id in folder_ids {
-                self.remove_folder_recursively(&sub_folder_id);
-            }
-
-            self.folders.remove(folder_id);
-        }
-    }
-
-    // Komentar: Menambahkan request ke collection
-    pub fn add_request(&mut self, collection_id: &str, request: Request) -> Option<String> {
-        if !self.collections.contains_key(collection_id) {
-            return None;
-        }
-
-        let request_id = request.id.clone();
-        self.requests.insert(request_id.clone(), request);
-
-        if let Some(collection) = self.collections.get_mut(collection_id) {
-            collection.requests.push(request_id.clone());
-            return Some(request_id);
-        }
-
-        None
-    }
-
-    // Komentar: Menghapus request dari collection
-    pub fn remove_request(&mut self, collection_id: &str, request_id: &str) -> bool {
-        if let Some(collection) = self.collections.get_mut(collection_id) {
-            collection.requests.retain(|id| id != request_id);
-            self.requests.remove(request_id);
-            return true;
-        }
-
-        false
-    }
-}
+// Komentar: Model collection ada di fmus-core agar bisa dijalankan tanpa UI
+pub use fmus_core::collections::{Collection, CollectionStore, ExecutionMode, Folder, Request};
 
 // Komentar: Command untuk mendapatkan daftar collections
 #[command]
@@ -198,11 +45,36 @@ pub fn update_collection(id: String, name: Option<String>, description: Option<S
     store.update_collection(&id, name, description)
 }
 
-// Komentar: Command untuk menghapus collection
+// Komentar: Command untuk menandai collection atau folder sequential/parallel di collection runner
 #[command]
-pub fn delete_collection(id: String, state: State<AppState>) -> bool {
+pub fn set_execution_mode(id: String, mode: Option<ExecutionMode>, state: State<AppState>) -> Result<(), String> {
     let mut store = state.collections.lock().unwrap();
-    store.delete_collection(&id)
+    if store.set_execution(&id, mode) {
+        Ok(())
+    } else {
+        Err(format!("Collection or folder not found: {}", id))
+    }
+}
+
+// Komentar: Command untuk menghapus collection beserta secret-nya di vault
+#[command]
+pub fn delete_collection(id: String, state: State<AppState>) -> Result<bool, String> {
+    let mut vault_state = state.vault.lock().unwrap();
+    let mut store = state.collections.lock().unwrap();
+
+    let secret_refs = store.collection_secret_refs(&id);
+    if !store.delete_collection(&id) {
+        return Ok(false);
+    }
+
+    if let Some(vault) = vault_state.vault.as_mut() {
+        for secret_ref in &secret_refs {
+            vault.remove_secret(secret_ref);
+        }
+        vault_state.persist()?;
+    }
+
+    Ok(true)
 }
 
 // Komentar: Struktur untuk request dalam collection
@@ -216,14 +88,34 @@ pub struct RequestInput {
     pub params: HashMap<String, String>,
     pub body: Option<Value>,
     pub auth: Option<Value>,
+    #[serde(default)]
+    pub schema: Option<JsonSchema>,
+    #[serde(default)]
+    pub assertions: Vec<Assertion>,
+    #[serde(default)]
+    pub scripts: Scripts,
+    #[serde(default)]
+    pub snapshot: Option<SnapshotConfig>,
+    #[serde(default)]
+    pub captures: Vec<Capture>,
+    #[serde(default)]
+    pub body_format: Option<BodyFormat>,
+    #[serde(default)]
+    pub response_format: Option<BodyFormat>,
+    #[serde(default)]
+    pub flow: Vec<FlowRule>,
 }
 
 // Komentar: Command untuk menambahkan request ke collection
 #[command]
-pub fn add_request_to_collection(collection_id: String, input: RequestInput, state: State<AppState>) -> Option<String> {
+pub fn add_request_to_collection(collection_id: String, input: RequestInput, state: State<AppState>) -> Result<Option<String>, String> {
+    // Komentar: Kondisi flow dicek sekarang agar salah ketik tidak baru ketahuan saat run
+    flow::validate(&input.flow)?;
+
+    let mut vault_state = state.vault.lock().unwrap();
     let mut store = state.collections.lock().unwrap();
 
-    let request = Request {
+    let mut request = Request {
         id: Uuid::new_v4().to_string(),
         name: input.name,
         description: input.description,
@@ -233,14 +125,70 @@ pub fn add_request_to_collection(collection_id: String, input: RequestInput, sta
         params: input.params,
         body: input.body,
         auth: input.auth,
+        schema: input.schema,
+        assertions: input.assertions,
+        scripts: input.scripts,
+        snapshot: input.snapshot,
+        captures: input.captures,
+        body_format: input.body_format,
+        response_format: input.response_format,
+        flow: input.flow,
     };
 
-    store.add_request(&collection_id, request)
+    if store.get_collection(&collection_id).is_none() {
+        return Ok(None);
+    }
+
+    // Komentar: Secret auth langsung disegel jika vault terbuka, jika tidak akan disegel saat unlock
+    if let Some(vault) = vault_state.unlocked_mut() {
+        if request.seal_secrets(vault)? {
+            vault_state.persist()?;
+        }
+    }
+
+    Ok(store.add_request(&collection_id, request))
+}
+
+// Komentar: Command untuk membuat request di collection dari perintah curl, misalnya hasil "Copy as cURL" browser
+// Komentar: Perintah yang membaca file lokal (-d @file, -F f=@file) ditolak agar perintah yang ditempel tidak mengirim file diam-diam
+#[command]
+pub fn import_curl(collection_id: String, command: String, name: Option<String>, state: State<AppState>) -> Result<Option<Request>, String> {
+    let mut request = curl::parse(&command, false)?.into_request(name);
+
+    let mut vault_state = state.vault.lock().unwrap();
+    let mut store = state.collections.lock().unwrap();
+
+    if store.get_collection(&collection_id).is_none() {
+        return Ok(None);
+    }
+
+    // Komentar: Password dari -u langsung disegel jika vault terbuka, jika tidak akan disegel saat unlock
+    if let Some(vault) = vault_state.unlocked_mut() {
+        if request.seal_secrets(vault)? {
+            vault_state.persist()?;
+        }
+    }
+
+    Ok(store.add_request(&collection_id, request.clone()).map(|_| request))
 }
 
 // Komentar: Command untuk menghapus request dari collection
 #[command]
-pub fn remove_request_from_collection(collection_id: String, request_id: String, state: State<AppState>) -> bool {
+pub fn remove_request_from_collection(collection_id: String, request_id: String, state: State<AppState>) -> Result<bool, String> {
+    let mut vault_state = state.vault.lock().unwrap();
     let mut store = state.collections.lock().unwrap();
-    store.remove_request(&collection_id, &request_id)
+
+    let secret_refs = store.get_request(&request_id).map(|r| r.secret_refs()).unwrap_or_default();
+    if !store.remove_request(&collection_id, &request_id) {
+        return Ok(false);
+    }
+
+    if let Some(vault) = vault_state.vault.as_mut() {
+        for secret_ref in &secret_refs {
+            vault.remove_secret(secret_ref);
+        }
+        vault_state.persist()?;
+    }
+
+    Ok(true)
 }
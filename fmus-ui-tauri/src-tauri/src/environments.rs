@@ -1,4 +1,4 @@
-use serde::{Deserialize, Serialize};
+use fmus_core::vault::{SecretRef, Vault};
 use std::collections::HashMap;
 use std::sync::Mutex;
 use tauri::{command, State};
@@ -6,25 +6,8 @@ use uuid::Uuid;
 
 use crate::AppState;
 
-// Komentar: Struktur untuk environment variable
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct EnvVariable {
-    pub key: String,
-    pub value: String,
-    pub enabled: bool,
-    #[serde(default)]
-    pub is_secret: bool,
-}
-
-// Komentar: Struktur untuk environment
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Environment {
-    pub id: String,
-    pub name: String,
-    pub description: Option<String>,
-    pub variables: Vec<EnvVariable>,
-    pub is_active: bool,
-}
+// Komentar: Model environment ada di fmus-core agar file yang sama bisa dipakai oleh CLI
+pub use fmus_core::environment::{EnvVariable, Environment};
 
 // Komentar: Store untuk environments
 #[derive(Debug, Default)]
@@ -147,6 +130,76 @@ impl EnvironmentStore {
         None
     }
 
+    // Komentar: Referensi vault milik variable tertentu
+    pub fn variable_secret_ref(&self, env_id: &str, key: &str) -> Option<SecretRef> {
+        self.environments.get(env_id)
+            .and_then(|env| env.variables.iter().find(|v| v.key == key))
+            .and_then(|v| v.secret_ref.clone())
+    }
+
+    // Komentar: Segel semua secret yang masih tersimpan polos
+    pub fn seal_secrets(&mut self, vault: &mut Vault) -> Result<usize, String> {
+        let mut sealed = 0;
+        for env in self.environments.values_mut() {
+            for variable in env.variables.iter_mut() {
+                if variable.seal(vault, &env.name)? {
+                    sealed += 1;
+                }
+            }
+        }
+
+        Ok(sealed)
+    }
+
+    // Komentar: Nilai semua variable aktif dalam environment, secret dibuka lewat vault
+    pub fn resolve_variables(&self, env_id: &str, vault: Option<&Vault>) -> Result<HashMap<String, String>, String> {
+        self.environments.get(env_id)
+            .ok_or_else(|| format!("Environment not found: {}", env_id))?
+            .resolve_variables(vault)
+    }
+
+    // Komentar: Nilai variable secret dalam environment, untuk disensor dari laporan run
+    pub fn secret_values(&self, env_id: &str, vault: Option<&Vault>) -> Result<Vec<String>, String> {
+        self.environments.get(env_id)
+            .ok_or_else(|| format!("Environment not found: {}", env_id))?
+            .secret_values(vault)
+    }
+
+    // Komentar: Terapkan perubahan pm.environment dari script (None berarti unset).
+    // Komentar: Return referensi vault lama yang tidak dipakai lagi agar bisa dihapus dari vault.
+    pub fn apply_script_changes(&mut self, env_id: &str, changes: &HashMap<String, Option<String>>) -> Vec<SecretRef> {
+        let mut stale_refs = Vec::new();
+        let env = match self.environments.get_mut(env_id) {
+            Some(env) => env,
+            None => return stale_refs,
+        };
+
+        for (key, value) in changes {
+            let index = env.variables.iter().position(|v| &v.key == key);
+            match (index, value) {
+                (Some(index), Some(value)) => {
+                    // Komentar: Secret tetap ditandai secret dan disegel ulang oleh vault
+                    let variable = &mut env.variables[index];
+                    stale_refs.extend(variable.secret_ref.take());
+                    variable.value = value.clone();
+                },
+                (None, Some(value)) => env.variables.push(EnvVariable {
+                    key: key.clone(),
+                    value: value.clone(),
+                    enabled: true,
+                    is_secret: false,
+                    secret_ref: None,
+                }),
+                (Some(index), None) => {
+                    stale_refs.extend(env.variables.remove(index).secret_ref);
+                },
+                (None, None) => {},
+            }
+        }
+
+        stale_refs
+    }
+
     // Komentar: Mendapatkan active environment
     pub fn get_active_environment(&self) -> Option<&Environment> {
         if let Some(id) = &self.active_environment_id {
@@ -214,11 +267,25 @@ pub fn update_environment(
     store.update_environment(&id, name, description, is_active)
 }
 
-// Komentar: Command untuk menghapus environment
+// Komentar: Command untuk menghapus environment beserta secret-nya di vault
 #[command]
-pub fn delete_environment(id: String, state: State<AppState>) -> bool {
+pub fn delete_environment(id: String, state: State<AppState>) -> Result<bool, String> {
+    let mut vault_state = state.vault.lock().unwrap();
     let mut store = state.environments.lock().unwrap();
-    store.delete_environment(&id)
+
+    let secret_refs = store.get_environment(&id).map(|env| env.secret_refs()).unwrap_or_default();
+    if !store.delete_environment(&id) {
+        return Ok(false);
+    }
+
+    if let Some(vault) = vault_state.vault.as_mut() {
+        for secret_ref in &secret_refs {
+            vault.remove_secret(secret_ref);
+        }
+        vault_state.persist()?;
+    }
+
+    Ok(true)
 }
 
 // Komentar: Command untuk menambahkan variable ke environment
@@ -230,22 +297,63 @@ pub fn add_environment_variable(
     enabled: bool,
     is_secret: bool,
     state: State<AppState>
-) -> Option<Environment> {
+) -> Result<Option<Environment>, String> {
+    let mut vault_state = state.vault.lock().unwrap();
     let mut store = state.environments.lock().unwrap();
 
-    let variable = EnvVariable {
+    let env_name = match store.get_environment(&env_id) {
+        Some(env) => env.name.clone(),
+        None => return Ok(None),
+    };
+    let previous_ref = store.variable_secret_ref(&env_id, &key);
+
+    let mut variable = EnvVariable {
         key,
         value,
         enabled,
         is_secret,
+        secret_ref: None,
     };
 
-    store.add_variable(&env_id, variable)
+    // Komentar: Secret langsung disegel jika vault terbuka, jika tidak akan disegel saat unlock
+    if let Some(vault) = vault_state.unlocked_mut() {
+        variable.seal(vault, &env_name)?;
+    }
+
+    let environment = store.add_variable(&env_id, variable);
+
+    if let (Some(vault), Some(previous_ref)) = (vault_state.vault.as_mut(), previous_ref) {
+        vault.remove_secret(&previous_ref);
+    }
+    vault_state.persist()?;
+
+    Ok(environment)
 }
 
 // Komentar: Command untuk menghapus variable dari environment
 #[command]
-pub fn remove_environment_variable(env_id: String, key: String, state: State<AppState>) -> Option<Environment> {
+pub fn remove_environment_variable(env_id: String, key: String, state: State<AppState>) -> Result<Option<Environment>, String> {
+    let mut vault_state = state.vault.lock().unwrap();
     let mut store = state.environments.lock().unwrap();
-    store.remove_variable(&env_id, &key)
+
+    let secret_ref = store.variable_secret_ref(&env_id, &key);
+    let environment = store.remove_variable(&env_id, &key);
+
+    if let (Some(vault), Some(secret_ref)) = (vault_state.vault.as_mut(), secret_ref) {
+        vault.remove_secret(&secret_ref);
+        vault_state.persist()?;
+    }
+
+    Ok(environment)
+}
+
+// Komentar: Command untuk export environment, nilai secret tidak pernah diekspor
+#[command]
+pub fn export_environment(id: String, state: State<AppState>) -> Result<String, String> {
+    let store = state.environments.lock().unwrap();
+    let environment = store.get_environment(&id)
+        .ok_or_else(|| format!("Environment not found: {}", id))?;
+
+    serde_json::to_string_pretty(&environment.redacted())
+        .map_err(|e| format!("Failed to serialize environment: {}", e))
 }
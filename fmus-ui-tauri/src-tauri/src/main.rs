@@ -4,32 +4,73 @@
 mod api;
 mod collections;
 mod environments;
+mod monitors;
 mod utils;
+mod vault;
 
 use serde::{Deserialize, Serialize};
-use std::sync::Mutex;
-use tauri::State;
+use std::sync::{Arc, Mutex};
+use tauri::{Manager, State};
+
+// Komentar: Allocator yang mencatat memori per thread agar batas memori script berlaku
+#[global_allocator]
+static ALLOCATOR: fmus_core::scripting::ScriptAllocator = fmus_core::scripting::ScriptAllocator;
 
 // Komentar: AppState untuk menyimpan data aplikasi
 struct AppState {
+    // Komentar: Satu HTTP client untuk semua command agar koneksi bisa di-pool
+    http_client: Arc<fmus_core::HttpClient>,
+    // Komentar: Response terakhir untuk mengambil body yang terpotong
+    responses: Mutex<api::ResponseCache>,
     collections: Mutex<collections::CollectionStore>,
     environments: Mutex<environments::EnvironmentStore>,
+    vault: Mutex<vault::VaultState>,
+    // Komentar: Lokasi snapshot response, ditentukan saat setup aplikasi
+    snapshots: Mutex<Option<fmus_core::snapshot::SnapshotStore>>,
+    // Komentar: Monitor terjadwal, scheduler dijalankan saat setup aplikasi
+    monitors: Mutex<monitors::MonitorService>,
 }
 
 // Komentar: Ini adalah file utama untuk aplikasi Tauri FMUS-POST
 fn main() {
     // Komentar: Inisialisasi app state
     let app_state = AppState {
+        http_client: Arc::new(fmus_core::HttpClient::new(None)),
+        responses: Mutex::new(api::ResponseCache::default()),
         collections: Mutex::new(collections::CollectionStore::new()),
         environments: Mutex::new(environments::EnvironmentStore::new()),
+        vault: Mutex::new(vault::VaultState::default()),
+        snapshots: Mutex::new(None),
+        monitors: Mutex::new(monitors::MonitorService::default()),
     };
 
     // Komentar: Inisialisasi aplikasi Tauri
     tauri::Builder::default()
         .manage(app_state)
+        // Komentar: Muat vault, lokasi snapshot dan monitor dari app data directory
+        .setup(|app| {
+            let data_dir = app.path_resolver().app_data_dir();
+            let vault_state = vault::VaultState::load(data_dir.as_ref().map(|dir| dir.join("vault.json")))?;
+            let state = app.state::<AppState>();
+            *state.vault.lock().unwrap() = vault_state;
+            *state.snapshots.lock().unwrap() = data_dir.as_ref().map(|dir| fmus_core::snapshot::SnapshotStore::new(dir.join("snapshots")));
+            if let Some(dir) = data_dir {
+                *state.monitors.lock().unwrap() = monitors::MonitorService::start(&app.app_handle(), dir)?;
+            }
+            Ok(())
+        })
         // Komentar: API commands
         .invoke_handler(tauri::generate_handler![
             api::http_request,
+            api::get_response_body,
+            api::get_pretty_body,
+            api::evaluate_assertions,
+            api::run_request,
+            api::compare_environments,
+            api::run_collection,
+            api::run_load_test,
+            api::set_rate_limit,
+            api::get_rate_limit,
             api::websocket_connect,
             api::graphql_request,
 
@@ -39,7 +80,9 @@ fn main() {
             collections::create_collection,
             collections::update_collection,
             collections::delete_collection,
+            collections::set_execution_mode,
             collections::add_request_to_collection,
+            collections::import_curl,
             collections::remove_request_from_collection,
 
             // Komentar: Environment commands
@@ -48,6 +91,24 @@ fn main() {
             environments::create_environment,
             environments::update_environment,
             environments::delete_environment,
+            environments::add_environment_variable,
+            environments::remove_environment_variable,
+            environments::export_environment,
+
+            // Komentar: Monitor commands
+            monitors::list_monitors,
+            monitors::save_monitor,
+            monitors::delete_monitor,
+            monitors::run_monitor,
+            monitors::get_monitor_history,
+
+            // Komentar: Vault commands
+            vault::vault_status,
+            vault::vault_create,
+            vault::vault_unlock,
+            vault::vault_lock,
+            vault::vault_change_passphrase,
+            vault::vault_list_entries,
 
             // Komentar: Utility commands
             utils::import_collection,
@@ -0,0 +1,112 @@
+use fmus_core::monitor::{Monitor, MonitorHistory, MonitorRun, MonitorState, PreparedMonitor, Scheduler};
+use fmus_core::{HttpClient, RunPlan};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tauri::{command, AppHandle, Manager, State};
+use uuid::Uuid;
+
+use crate::api;
+use crate::AppState;
+
+// Komentar: Scheduler monitor, dibuat saat setup aplikasi jika app data directory tersedia
+#[derive(Default)]
+pub struct MonitorService {
+    pub scheduler: Option<Arc<Scheduler>>,
+    // Komentar: File definisi monitor, riwayat run disimpan di folder monitor-history di sebelahnya
+    pub path: Option<PathBuf>,
+}
+
+impl MonitorService {
+    // Komentar: Muat monitor dari disk dan jalankan scheduler selama aplikasi berjalan.
+    // Komentar: Setiap run dikirim sebagai event "monitor-run".
+    pub fn start(app: &AppHandle, data_dir: PathBuf) -> Result<Self, String> {
+        let path = data_dir.join("monitors.json");
+        let handle = app.clone();
+        let scheduler = Arc::new(Scheduler::new(
+            HttpClient::new(None),
+            MonitorHistory::new(data_dir.join("monitor-history")),
+            move |monitor| {
+                let state = handle.state::<AppState>();
+                let run = api::prepare_run(monitor.environment_id.clone(), &state, |store, vault| match &monitor.folder_id {
+                    Some(folder_id) => RunPlan::for_folder(store, folder_id, vault),
+                    None => RunPlan::for_collection(store, &monitor.collection_id, vault),
+                })?;
+                Ok(PreparedMonitor { plan: run.plan, environment: run.environment })
+            },
+        ));
+
+        if path.exists() {
+            let content = std::fs::read_to_string(&path)
+                .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+            let monitors: Vec<Monitor> = serde_json::from_str(&content)
+                .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?;
+            scheduler.set_monitors(monitors)?;
+        }
+
+        let handle = app.clone();
+        tauri::async_runtime::spawn(scheduler.clone().run(move |run| {
+            let _ = handle.emit_all("monitor-run", run);
+        }));
+
+        Ok(MonitorService { scheduler: Some(scheduler), path: Some(path) })
+    }
+
+    fn scheduler(&self) -> Result<Arc<Scheduler>, String> {
+        self.scheduler.clone().ok_or_else(|| "Monitors are not available".to_string())
+    }
+
+    // Komentar: Simpan definisi monitor ke disk setelah ada perubahan
+    fn persist(&self, scheduler: &Scheduler) -> Result<(), String> {
+        let Some(path) = &self.path else { return Ok(()) };
+        let content = serde_json::to_string_pretty(&scheduler.monitors())
+            .map_err(|e| format!("Failed to serialize monitors: {}", e))?;
+        std::fs::write(path, content).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+}
+
+// Komentar: Semua monitor beserta jadwal berikutnya dan run terakhir
+#[command]
+pub fn list_monitors(state: State<AppState>) -> Result<Vec<MonitorState>, String> {
+    let scheduler = state.monitors.lock().unwrap().scheduler()?;
+    Ok(scheduler.states())
+}
+
+// Komentar: Tambah atau ubah monitor, ID dibuat otomatis jika kosong
+#[command]
+pub fn save_monitor(mut monitor: Monitor, state: State<AppState>) -> Result<Monitor, String> {
+    if monitor.id.is_empty() {
+        monitor.id = Uuid::new_v4().to_string();
+    }
+
+    let service = state.monitors.lock().unwrap();
+    let scheduler = service.scheduler()?;
+    scheduler.save_monitor(monitor.clone())?;
+    service.persist(&scheduler)?;
+    Ok(monitor)
+}
+
+// Komentar: Hapus monitor beserta riwayat run-nya
+#[command]
+pub fn delete_monitor(id: String, state: State<AppState>) -> Result<bool, String> {
+    let service = state.monitors.lock().unwrap();
+    let scheduler = service.scheduler()?;
+    if !scheduler.remove_monitor(&id) {
+        return Ok(false);
+    }
+    service.persist(&scheduler)?;
+    scheduler.history().remove(&id)
+}
+
+// Komentar: Jalankan monitor sekarang di luar jadwal
+#[command]
+pub async fn run_monitor(id: String, state: State<'_, AppState>) -> Result<MonitorRun, String> {
+    let scheduler = state.monitors.lock().unwrap().scheduler()?;
+    scheduler.run_now(&id).await
+}
+
+// Komentar: Riwayat run monitor, yang paling baru lebih dulu
+#[command]
+pub fn get_monitor_history(id: String, limit: Option<usize>, state: State<AppState>) -> Result<Vec<MonitorRun>, String> {
+    let scheduler = state.monitors.lock().unwrap().scheduler()?;
+    scheduler.history().recent(&id, limit.unwrap_or(100))
+}
@@ -0,0 +1,144 @@
+use fmus_core::vault::{Vault, VaultEntryInfo};
+use serde::Serialize;
+use std::path::PathBuf;
+use tauri::{command, State};
+
+use crate::AppState;
+
+// Komentar: State vault, path file vault ditentukan saat setup aplikasi
+#[derive(Default)]
+pub struct VaultState {
+    pub vault: Option<Vault>,
+    pub path: Option<PathBuf>,
+}
+
+impl VaultState {
+    // Komentar: Memuat vault dari disk jika file sudah ada
+    pub fn load(path: Option<PathBuf>) -> Result<Self, String> {
+        let vault = match &path {
+            Some(path) if path.exists() => Some(Vault::load(path)?),
+            _ => None,
+        };
+
+        Ok(VaultState { vault, path })
+    }
+
+    // Komentar: Vault yang sudah di-unlock, untuk membuka atau menyegel secret
+    pub fn unlocked(&self) -> Option<&Vault> {
+        self.vault.as_ref().filter(|vault| !vault.is_locked())
+    }
+
+    pub fn unlocked_mut(&mut self) -> Option<&mut Vault> {
+        self.vault.as_mut().filter(|vault| !vault.is_locked())
+    }
+
+    // Komentar: Simpan vault ke disk setelah ada perubahan
+    pub fn persist(&self) -> Result<(), String> {
+        match (&self.vault, &self.path) {
+            (Some(vault), Some(path)) => vault.save(path),
+            _ => Ok(()),
+        }
+    }
+
+    fn status(&self) -> VaultStatus {
+        VaultStatus {
+            exists: self.vault.is_some(),
+            locked: self.vault.as_ref().map(|vault| vault.is_locked()).unwrap_or(true),
+            entries: self.vault.as_ref().map(|vault| vault.list_entries().len()).unwrap_or(0),
+        }
+    }
+}
+
+// Komentar: Status vault untuk UI, tanpa data rahasia
+#[derive(Debug, Serialize)]
+pub struct VaultStatus {
+    pub exists: bool,
+    pub locked: bool,
+    pub entries: usize,
+}
+
+// Komentar: Segel semua secret yang masih polos di environments dan collections
+fn seal_pending_secrets(state: &AppState, vault_state: &mut VaultState) -> Result<(), String> {
+    let vault = match vault_state.unlocked_mut() {
+        Some(vault) => vault,
+        None => return Ok(()),
+    };
+
+    let sealed_env = state.environments.lock().unwrap().seal_secrets(vault)?;
+    let sealed_req = state.collections.lock().unwrap().seal_secrets(vault)?;
+
+    if sealed_env + sealed_req > 0 {
+        vault_state.persist()?;
+    }
+
+    Ok(())
+}
+
+// Komentar: Command untuk mendapatkan status vault
+#[command]
+pub fn vault_status(state: State<AppState>) -> VaultStatus {
+    state.vault.lock().unwrap().status()
+}
+
+// Komentar: Command untuk membuat vault baru dengan master passphrase
+#[command]
+pub fn vault_create(passphrase: String, state: State<AppState>) -> Result<VaultStatus, String> {
+    let mut vault_state = state.vault.lock().unwrap();
+    if vault_state.vault.is_some() {
+        return Err("Vault already exists".to_string());
+    }
+
+    vault_state.vault = Some(Vault::create(&passphrase)?);
+    vault_state.persist()?;
+    seal_pending_secrets(&state, &mut vault_state)?;
+
+    Ok(vault_state.status())
+}
+
+// Komentar: Command untuk membuka vault
+#[command]
+pub fn vault_unlock(passphrase: String, state: State<AppState>) -> Result<VaultStatus, String> {
+    let mut vault_state = state.vault.lock().unwrap();
+    vault_state.vault
+        .as_mut()
+        .ok_or_else(|| "Vault has not been created".to_string())?
+        .unlock(&passphrase)?;
+
+    seal_pending_secrets(&state, &mut vault_state)?;
+
+    Ok(vault_state.status())
+}
+
+// Komentar: Command untuk mengunci vault
+#[command]
+pub fn vault_lock(state: State<AppState>) -> VaultStatus {
+    let mut vault_state = state.vault.lock().unwrap();
+    if let Some(vault) = vault_state.vault.as_mut() {
+        vault.lock();
+    }
+
+    vault_state.status()
+}
+
+// Komentar: Command untuk mengganti master passphrase
+#[command]
+pub fn vault_change_passphrase(current: String, new_passphrase: String, state: State<AppState>) -> Result<VaultStatus, String> {
+    let mut vault_state = state.vault.lock().unwrap();
+    vault_state.vault
+        .as_mut()
+        .ok_or_else(|| "Vault has not been created".to_string())?
+        .change_passphrase(&current, &new_passphrase)?;
+    vault_state.persist()?;
+
+    Ok(vault_state.status())
+}
+
+// Komentar: Command untuk melihat daftar entry vault (tanpa nilai)
+#[command]
+pub fn vault_list_entries(state: State<AppState>) -> Result<Vec<VaultEntryInfo>, String> {
+    let vault_state = state.vault.lock().unwrap();
+    vault_state.vault
+        .as_ref()
+        .map(|vault| vault.list_entries())
+        .ok_or_else(|| "Vault has not been created".to_string())
+}
@@ -1,3 +1,4 @@
+import { invoke } from '@tauri-apps/api/tauri';
 import { get as getRequest, post as postRequest, put as putRequest, del as deleteRequest } from 'fmus-post';
 
 // Komentar: Interface untuk HTTP request options
@@ -65,3 +66,39 @@ export const del = async (url: string, options?: RequestOptions): Promise<ApiRes
   const response = await deleteRequest(url, adaptRequestOptions(options));
   return adaptResponse(response);
 };
+
+// Komentar: Potongan body response dari command get_response_body
+export interface ResponseBodyChunk {
+  body: string;
+  encoding: 'text' | 'base64';
+  charset: string;
+  offset: number;
+  // Komentar: Awal potongan berikutnya, bisa lebih kecil dari offset + panjang potongan jika karakter multibyte terpotong
+  next_offset: number;
+  size: number;
+  truncated: boolean;
+}
+
+// Komentar: Mengambil sebagian body response yang terpotong, mulai dari offset
+export const getResponseBody = (responseId: string, offset: number, length?: number): Promise<ResponseBodyChunk> => {
+  return invoke<ResponseBodyChunk>('get_response_body', { responseId, offset, length });
+};
+
+// Komentar: Mengambil sisa body mulai dari next_offset response (atau potongan sebelumnya) sampai habis.
+// Komentar: Potongan berikutnya selalu diminta dari next_offset agar karakter multibyte di batas potongan tidak hilang.
+export const getRemainingResponseBody = async (
+  responseId: string,
+  nextOffset: number,
+  chunkSize = 1024 * 1024
+): Promise<ResponseBodyChunk[]> => {
+  const chunks: ResponseBodyChunk[] = [];
+  let offset = nextOffset;
+  for (;;) {
+    const chunk = await getResponseBody(responseId, offset, chunkSize);
+    chunks.push(chunk);
+    if (!chunk.truncated || chunk.next_offset <= offset) {
+      return chunks;
+    }
+    offset = chunk.next_offset;
+  }
+};